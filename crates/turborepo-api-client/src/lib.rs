@@ -19,8 +19,9 @@ use serde::Deserialize;
 use turborepo_ci::{Vendor, is_ci};
 use turborepo_types::SecretString;
 use turborepo_vercel_api::{
-    APIError, CachingStatus, CachingStatusResponse, PreflightResponse, Team, TeamsResponse, User,
-    UserResponse, VerificationResponse, VerifiedSsoUser,
+    APIError, ArtifactQueryRequest, ArtifactQueryResponse, CachingStatus, CachingStatusResponse,
+    PreflightResponse, Team, TeamsResponse, User, UserResponse, VerificationResponse,
+    VerifiedSsoUser,
     token::{ResponseTokenMetadata, Scope},
 };
 use url::Url;
@@ -113,6 +114,15 @@ pub trait CacheClient {
         team_id: Option<&str>,
         team_slug: Option<&str>,
     ) -> impl Future<Output = Result<Option<Response>>> + Send;
+    /// Queries many artifacts with a single request. Only supported by
+    /// servers that set `artifactQuery` in their caching status.
+    fn query_artifacts(
+        &self,
+        hashes: &[String],
+        token: &SecretString,
+        team_id: Option<&str>,
+        team_slug: Option<&str>,
+    ) -> impl Future<Output = Result<ArtifactQueryResponse>> + Send;
     fn get_caching_status(
         &self,
         token: &SecretString,
@@ -363,6 +373,56 @@ impl CacheClient for APIClient {
             .await
     }
 
+    #[tracing::instrument(skip_all)]
+    async fn query_artifacts(
+        &self,
+        hashes: &[String],
+        token: &SecretString,
+        team_id: Option<&str>,
+        team_slug: Option<&str>,
+    ) -> Result<ArtifactQueryResponse> {
+        let mut request_url = self.make_url("/v8/artifacts")?;
+        let mut allow_auth = true;
+
+        if self.use_preflight {
+            let preflight_response = self
+                .do_preflight(
+                    token,
+                    request_url.clone(),
+                    "POST",
+                    "Authorization, Content-Type, User-Agent",
+                )
+                .await?;
+
+            allow_auth = preflight_response.allow_authorization_header;
+            request_url = preflight_response.location;
+        }
+
+        let mut request_builder = self
+            .api_request(Method::POST, request_url)
+            .header("User-Agent", self.user_agent.clone())
+            .json(&ArtifactQueryRequest {
+                hashes: hashes.to_vec(),
+            });
+
+        if allow_auth {
+            request_builder = request_builder.bearer_auth(token.expose());
+        }
+
+        request_builder = Self::add_team_params(request_builder, team_id, team_slug);
+
+        let response =
+            retry::make_retryable_request(request_builder, retry::RetryStrategy::Timeout)
+                .await?
+                .into_response();
+
+        if response.status() == StatusCode::FORBIDDEN {
+            return Err(Self::handle_403(response).await);
+        }
+
+        Ok(response.error_for_status()?.json().await?)
+    }
+
     #[tracing::instrument(skip_all)]
     async fn put_artifact(
         &self,
@@ -1568,6 +1628,7 @@ mod test {
             response.status,
             turborepo_vercel_api::CachingStatus::Enabled
         ));
+        assert!(response.artifact_query);
 
         handle.abort();
         Ok(())
@@ -1626,6 +1687,63 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_query_artifacts() -> Result<()> {
+        let port = port_scanner::request_open_port().unwrap();
+        let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+        let handle = tokio::spawn(start_test_server(port, Some(ready_tx)));
+        tokio::time::timeout(Duration::from_secs(5), ready_rx).await??;
+
+        let base_url = format!("http://localhost:{port}");
+        let client = APIClient::new(
+            &base_url,
+            Some(Duration::from_secs(5)),
+            None,
+            "2.0.0",
+            false,
+        )?;
+        let token = SecretString::new("test-token".to_string());
+
+        let body = b"cached artifact data";
+        let artifact_body = tokio_stream::once(Ok(Bytes::copy_from_slice(body)));
+        client
+            .put_artifact(
+                "queried-hash",
+                artifact_body,
+                body.len(),
+                789,
+                None,
+                &token,
+                None,
+                None,
+                Some("abc123"),
+                None,
+            )
+            .await?;
+
+        let response = client
+            .query_artifacts(
+                &["queried-hash".to_string(), "missing-hash".to_string()],
+                &token,
+                None,
+                None,
+            )
+            .await?;
+        assert_eq!(response.len(), 2);
+        let Some(Some(turborepo_vercel_api::ArtifactQueryResult::Found(info))) =
+            response.get("queried-hash")
+        else {
+            panic!("expected queried-hash to be found: {response:?}");
+        };
+        assert_eq!(info.task_duration_ms, 789);
+        assert_eq!(info.size, body.len() as u64);
+        assert_eq!(info.sha.as_deref(), Some("abc123"));
+        assert!(matches!(response.get("missing-hash"), Some(None)));
+
+        handle.abort();
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_api_client_with_upload_timeout() -> Result<()> {
         let port = port_scanner::request_open_port().unwrap();
@@ -743,6 +743,16 @@ mod tests {
             unimplemented!("artifact_exists")
         }
 
+        async fn query_artifacts(
+            &self,
+            _hashes: &[String],
+            _token: &SecretString,
+            _team_id: Option<&str>,
+            _team_slug: Option<&str>,
+        ) -> turborepo_api_client::Result<turborepo_vercel_api::ArtifactQueryResponse> {
+            unimplemented!("query_artifacts")
+        }
+
         async fn get_caching_status(
             &self,
             token: &SecretString,
@@ -761,6 +771,7 @@ mod tests {
 
             Ok(CachingStatusResponse {
                 status: CachingStatus::Enabled,
+                artifact_query: false,
             })
         }
     }
@@ -1225,6 +1225,17 @@ mod tests {
             unimplemented!()
         }
 
+        async fn query_artifacts(
+            &self,
+            _hashes: &[String],
+            _token: &SecretString,
+            _team_id: Option<&str>,
+            _team_slug: Option<&str>,
+        ) -> Result<turborepo_vercel_api::ArtifactQueryResponse, turborepo_api_client::Error>
+        {
+            unimplemented!()
+        }
+
         async fn get_caching_status(
             &self,
             _token: &SecretString,
@@ -1240,6 +1251,7 @@ mod tests {
                     };
                     Ok(CachingStatusResponse {
                         status: caching_status,
+                        artifact_query: false,
                     })
                 }
                 MockCachingResponse::Error(MockErrorType::Error) => {
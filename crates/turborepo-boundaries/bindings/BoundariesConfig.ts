@@ -21,6 +21,13 @@ tags?: { [key in string]?: TagRules },
  * a testing library.
  */
 implicitDependencies?: Array<string>, 
+/**
+ * Reports packages in `dependencies` that no file in the package imports.
+ *
+ * When set in the root `turbo.json`, applies to every package that doesn't
+ * set it in its own `turbo.json`.
+ */
+unusedDependencies?: boolean, 
 /**
  * Rules for a package's dependencies.
  *
@@ -32,6 +32,14 @@ pub struct BoundariesConfig {
     #[ts(optional)]
     pub implicit_dependencies: Option<Spanned<Vec<Spanned<String>>>>,
 
+    /// Reports packages in `dependencies` that no file in the package imports.
+    ///
+    /// When set in the root `turbo.json`, applies to every package that doesn't
+    /// set it in its own `turbo.json`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub unused_dependencies: Option<bool>,
+
     /// Rules for a package's dependencies.
     ///
     /// Restricts which packages this package can import.
@@ -0,0 +1,184 @@
+//! Automatic fixes for imports of workspace packages that aren't declared as
+//! dependencies.
+//!
+//! Only `PackageNotFound` diagnostics naming a package in the workspace can be
+//! fixed. Relative imports that leave the package (`ImportLeavesPackage`)
+//! still need to be rewritten by hand, since declaring the dependency doesn't
+//! make a path import into another package valid.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_json_rewrite::RewriteError;
+use turborepo_repository::{
+    package_graph::PackageName, package_json::PackageJson, package_manager::PackageManager,
+};
+
+use crate::{
+    BoundariesChecker, BoundariesDiagnostic, BoundariesResult, Error, PackageGraphProvider,
+};
+
+/// A workspace package to add to the `dependencies` of a package
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DependencyFix {
+    /// The package that imports `dependency`
+    pub package: PackageName,
+    pub package_json_path: AbsoluteSystemPathBuf,
+    pub dependency: PackageName,
+    pub specifier: &'static str,
+    /// Whether `dependency` can't be resolved from `package` until the
+    /// package manager links it into `node_modules`
+    pub needs_install: bool,
+}
+
+impl BoundariesResult {
+    /// Removes the diagnostics for undeclared workspace dependencies and
+    /// returns the fixes for them, one per missing dependency.
+    pub fn take_dependency_fixes<G: PackageGraphProvider>(
+        &mut self,
+        repo_root: &AbsoluteSystemPath,
+        pkg_dep_graph: &G,
+        package_manager: &PackageManager,
+    ) -> Vec<DependencyFix> {
+        let packages: BTreeMap<_, _> = pkg_dep_graph
+            .packages()
+            .filter(|(name, _)| !matches!(name, PackageName::Root))
+            .collect();
+
+        let mut missing = BTreeSet::new();
+        self.diagnostics.retain(|diagnostic| {
+            let BoundariesDiagnostic::PackageNotFound {
+                name, package_name, ..
+            } = diagnostic
+            else {
+                return true;
+            };
+            let dependency = PackageName::from(name.as_str());
+            if &dependency == package_name || !packages.contains_key(&dependency) {
+                return true;
+            }
+            missing.insert((package_name.clone(), dependency));
+            false
+        });
+
+        missing
+            .into_iter()
+            .filter_map(|(package, dependency)| {
+                let info = packages.get(&package)?;
+                let package_root = repo_root.resolve(info.package_path());
+                let needs_install = !is_linked(repo_root, &package_root, &dependency);
+                Some(DependencyFix {
+                    package_json_path: repo_root.resolve(info.package_json_path()),
+                    package,
+                    dependency,
+                    specifier: package_manager.workspace_dependency_specifier(),
+                    needs_install,
+                })
+            })
+            .collect()
+    }
+}
+
+impl BoundariesChecker {
+    /// Adds each fix's dependency to its package's `package.json`, preserving
+    /// the existing formatting of the file.
+    pub fn apply_dependency_fixes(fixes: &[DependencyFix]) -> Result<(), Error> {
+        let mut by_file: BTreeMap<&AbsoluteSystemPathBuf, Vec<&DependencyFix>> = BTreeMap::new();
+        for fix in fixes {
+            by_file.entry(&fix.package_json_path).or_default().push(fix);
+        }
+
+        for (path, fixes) in by_file {
+            let mut contents = path
+                .read_to_string()
+                .map_err(|_| Error::FileNotFound(path.to_owned()))?;
+            let has_dependencies = PackageJson::load_from_str(&contents, path.as_str())
+                .is_ok_and(|package_json| package_json.dependencies.is_some());
+            if has_dependencies {
+                for fix in fixes {
+                    contents = turborepo_json_rewrite::set_path(
+                        &contents,
+                        &["dependencies", fix.dependency.as_str()],
+                        &format!("\"{}\"", fix.specifier),
+                    )
+                    .map_err(|e| Error::PackageJsonRewrite(path.to_owned(), e))?;
+                }
+            } else {
+                contents = insert_dependencies(&contents, &fixes).ok_or_else(|| {
+                    Error::PackageJsonRewrite(path.to_owned(), RewriteError::NoRoot)
+                })?;
+            }
+            path.create_with_contents(contents)
+                .map_err(|_| Error::FileWrite(path.to_owned()))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Adds a `dependencies` object holding the fixes' dependencies after the last
+/// top-level key of a `package.json`, indented like the keys before it.
+/// Returns `None` if the document isn't an object.
+fn insert_dependencies(contents: &str, fixes: &[&DependencyFix]) -> Option<String> {
+    let closing_brace = contents.trim_end().strip_suffix('}')?.len();
+    let (body, rest) = contents.split_at(closing_brace);
+    let body = body.trim_end();
+    let separator = if body.ends_with('{') { "" } else { "," };
+
+    let member = match indentation(contents) {
+        Some(indent) => {
+            let entries = fixes
+                .iter()
+                .map(|fix| {
+                    format!(
+                        "{indent}{indent}\"{}\": \"{}\"",
+                        fix.dependency, fix.specifier
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",\n");
+            format!("\n{indent}\"dependencies\": {{\n{entries}\n{indent}}}\n")
+        }
+        None => {
+            let entries = fixes
+                .iter()
+                .map(|fix| format!("\"{}\":\"{}\"", fix.dependency, fix.specifier))
+                .collect::<Vec<_>>()
+                .join(",");
+            format!("\"dependencies\":{{{entries}}}")
+        }
+    };
+
+    Some(format!("{body}{separator}{member}{rest}"))
+}
+
+/// The indentation of the top-level keys of a JSON document, or `None` if it
+/// is on a single line
+fn indentation(contents: &str) -> Option<&str> {
+    if !contents.trim_end().contains('\n') {
+        return None;
+    }
+    let indent = contents
+        .lines()
+        .skip(1)
+        .find(|line| line.trim_start().starts_with('"'))
+        .map_or("  ", |line| &line[..line.len() - line.trim_start().len()]);
+    Some(indent)
+}
+
+/// Checks whether `dependency` is already in a `node_modules` directory
+/// between the package and the repository root, as it is when the package
+/// manager hoists workspace packages.
+fn is_linked(
+    repo_root: &AbsoluteSystemPath,
+    package_root: &AbsoluteSystemPath,
+    dependency: &PackageName,
+) -> bool {
+    let segments: Vec<_> = std::iter::once("node_modules")
+        .chain(dependency.as_str().split('/'))
+        .collect();
+    package_root
+        .ancestors()
+        .take_while(|dir| repo_root.contains(dir))
+        .any(|dir| dir.join_components(&segments).exists())
+}
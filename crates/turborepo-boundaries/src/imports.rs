@@ -428,6 +428,7 @@ pub(crate) fn check_package_import(
         return Some(BoundariesDiagnostic::PackageNotFound {
             path: file_path.to_owned(),
             name: package_node.to_string(),
+            package_name: dependency_locations.package.to_owned(),
             span,
             text: NamedSource::new(file_path.as_str(), file_content.to_string()),
         });
@@ -436,6 +437,67 @@ pub(crate) fn check_package_import(
     None
 }
 
+/// Returns the package that `@types/{types_name}` provides types for, e.g.
+/// `react` for `@types/react` and `@babel/core` for `@types/babel__core`.
+fn typed_package_name(types_name: &str) -> String {
+    match types_name.split_once("__") {
+        Some((scope, name)) => format!("@{scope}/{name}"),
+        None => types_name.to_string(),
+    }
+}
+
+/// Reports packages in `dependencies` that none of the package's files import.
+///
+/// Type-only imports count as uses, as does importing the package that an
+/// `@types/` dependency provides types for. Implicit dependencies are never
+/// reported since they're used without being imported.
+pub(crate) fn check_unused_dependencies(
+    dependency_locations: DependencyLocations<'_>,
+    imported_packages: &HashSet<String>,
+    package_json_path: &AbsoluteSystemPath,
+) -> Vec<BoundariesDiagnostic> {
+    let Some(dependencies) = &dependency_locations.package_json.dependencies else {
+        return Vec::new();
+    };
+
+    let is_used = |dependency: &str| {
+        imported_packages.contains(dependency)
+            || dependency
+                .strip_prefix("@types/")
+                .is_some_and(|types_name| {
+                    imported_packages.contains(&typed_package_name(types_name))
+                })
+            || dependency_locations
+                .implicit_dependencies
+                .contains_key(dependency)
+            || dependency_locations
+                .global_implicit_dependencies
+                .contains_key(dependency)
+    };
+
+    let package_json_contents = package_json_path.read_to_string().unwrap_or_default();
+    let dependencies_start = package_json_contents.find("\"dependencies\"");
+
+    dependencies
+        .keys()
+        .filter(|dependency| !is_used(dependency.as_str()))
+        .map(|dependency| {
+            let quoted = format!("\"{dependency}\"");
+            let span = dependencies_start.and_then(|start| {
+                package_json_contents[start..]
+                    .find(&quoted)
+                    .map(|offset| SourceSpan::new((start + offset).into(), quoted.len()))
+            });
+            BoundariesDiagnostic::UnusedDependency {
+                package_name: dependency_locations.package.to_owned(),
+                name: dependency.to_owned(),
+                span,
+                text: NamedSource::new(package_json_path.as_str(), package_json_contents.clone()),
+            }
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use test_case::test_case;
@@ -476,6 +538,12 @@ mod test {
         assert_eq!(get_package_name(import), expected);
     }
 
+    #[test_case("react", "react" ; "unscoped")]
+    #[test_case("babel__core", "@babel/core" ; "scoped")]
+    fn test_typed_package_name(types_name: &str, expected: &str) {
+        assert_eq!(typed_package_name(types_name), expected);
+    }
+
     fn make_tsconfig_alias_test_args(
         import: &str,
     ) -> (Resolver, PackageName, SourceSpan, String, BoundariesResult) {
@@ -3,6 +3,7 @@
 #![allow(unused_assignments)]
 
 mod config;
+mod fix;
 mod imports;
 mod tags;
 
@@ -13,6 +14,7 @@ use std::{
 };
 
 pub use config::{BoundariesConfig, Permissions, Rule, RulesMap};
+pub use fix::DependencyFix;
 use globwalk::{Settings, ValidatedGlob};
 use indicatif::ProgressBar;
 use miette::{Diagnostic, NamedSource, Report, SourceSpan};
@@ -178,6 +180,7 @@ pub enum BoundariesDiagnostic {
     PackageNotFound {
         path: AbsoluteSystemPathBuf,
         name: String,
+        package_name: PackageName,
         #[label("package imported here")]
         span: SourceSpan,
         #[source_code]
@@ -201,6 +204,19 @@ pub enum BoundariesDiagnostic {
     ParseError(AbsoluteSystemPathBuf, String),
     #[error("Circular package dependency detected: {cycle_path}")]
     CircularDependency { cycle_path: String },
+    #[error("package `{package_name}` depends on `{name}` but never imports it")]
+    #[diagnostic(help(
+        "remove `{name}` from `dependencies`, or add it to `boundaries.implicitDependencies` if \
+         it's used without being imported"
+    ))]
+    UnusedDependency {
+        package_name: PackageName,
+        name: String,
+        #[label("declared here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
 }
 
 #[derive(Debug, Error, Diagnostic)]
@@ -219,6 +235,8 @@ pub enum Error {
     FileNotFound(AbsoluteSystemPathBuf),
     #[error("failed to write to file: {0}")]
     FileWrite(AbsoluteSystemPathBuf),
+    #[error("failed to add dependencies to {0}: {1}")]
+    PackageJsonRewrite(AbsoluteSystemPathBuf, turborepo_json_rewrite::RewriteError),
 }
 
 impl BoundariesDiagnostic {
@@ -540,12 +558,22 @@ impl BoundariesChecker {
         let mut result = BoundariesResult::default();
 
         let implicit_dependencies = ctx.turbo_json_provider.implicit_dependencies(package_name);
+        let check_unused_dependencies = ctx
+            .turbo_json_provider
+            .boundaries_config(package_name)
+            .and_then(|config| config.unused_dependencies)
+            .or_else(|| {
+                ctx.root_boundaries_config
+                    .and_then(|config| config.unused_dependencies)
+            })
+            .unwrap_or(false);
         let file_result = Self::check_package_files(
             ctx,
             package_name,
             package_info,
             &implicit_dependencies,
             global_implicit_dependencies,
+            check_unused_dependencies,
         )?;
         result.merge(file_result);
 
@@ -573,6 +601,7 @@ impl BoundariesChecker {
         package_info: &PackageInfo,
         implicit_dependencies: &HashMap<String, Spanned<()>>,
         global_implicit_dependencies: &HashMap<String, Spanned<()>>,
+        check_unused_dependencies: bool,
     ) -> Result<BoundariesResult, Error>
     where
         G: PackageGraphProvider,
@@ -636,7 +665,14 @@ impl BoundariesChecker {
                 .as_ref(),
         };
 
-        type FileResult = Result<(Vec<BoundariesDiagnostic>, Vec<String>), Error>;
+        type FileResult = Result<
+            (
+                Vec<BoundariesDiagnostic>,
+                Vec<String>,
+                Option<HashSet<String>>,
+            ),
+            Error,
+        >;
         let file_results: Vec<FileResult> = {
             let _span = info_span!(
                 "process_files",
@@ -659,10 +695,19 @@ impl BoundariesChecker {
         };
 
         let mut result = BoundariesResult::default();
+        // `None` once a file's imports are unknown, since any dependency could
+        // be imported there
+        let mut imported_packages = Some(HashSet::new());
         for file_result in file_results {
-            let (diagnostics, warnings) = file_result?;
+            let (diagnostics, warnings, file_imports) = file_result?;
             result.diagnostics.extend(diagnostics);
             result.warnings.extend(warnings);
+            match (&mut imported_packages, file_imports) {
+                (Some(imported_packages), Some(file_imports)) => {
+                    imported_packages.extend(file_imports)
+                }
+                _ => imported_packages = None,
+            }
         }
 
         for ext in &not_supported_extensions {
@@ -671,6 +716,24 @@ impl BoundariesChecker {
             ));
         }
 
+        if check_unused_dependencies {
+            match imported_packages {
+                Some(imported_packages) if not_supported_extensions.is_empty() => {
+                    result
+                        .diagnostics
+                        .extend(imports::check_unused_dependencies(
+                            dependency_locations,
+                            &imported_packages,
+                            &ctx.repo_root.resolve(package_info.package_json_path()),
+                        ));
+                }
+                _ => result.warnings.push(format!(
+                    "not checking {package_name} for unused dependencies because some of its \
+                     files could not be checked"
+                )),
+            }
+        }
+
         result.files_checked = files.len();
 
         Ok(result)
@@ -682,7 +745,14 @@ impl BoundariesChecker {
         file_path: &AbsoluteSystemPath,
         dependency_locations: DependencyLocations<'_>,
         resolver: &Resolver,
-    ) -> Result<(Vec<BoundariesDiagnostic>, Vec<String>), Error> {
+    ) -> Result<
+        (
+            Vec<BoundariesDiagnostic>,
+            Vec<String>,
+            Option<HashSet<String>>,
+        ),
+        Error,
+    > {
         let file_content = file_path
             .read_to_string()
             .map_err(|_| Error::FileNotFound(file_path.to_owned()))?;
@@ -696,6 +766,7 @@ impl BoundariesChecker {
                         "parser panicked".to_string(),
                     )],
                     Vec::new(),
+                    None,
                 ));
             }
         };
@@ -722,7 +793,13 @@ impl BoundariesChecker {
             )?;
         }
 
-        Ok((diagnostics, warnings))
+        let imported_packages = imports
+            .iter()
+            .filter(|import_result| !import_result.specifier.starts_with('.'))
+            .map(|import_result| imports::get_package_name(&import_result.specifier).to_string())
+            .collect();
+
+        Ok((diagnostics, warnings, Some(imported_packages)))
     }
 }
 
@@ -923,6 +1000,180 @@ mod tests {
         );
     }
 
+    fn package_info(path: &str) -> PackageInfo {
+        PackageInfo {
+            package_json: Default::default(),
+            package_json_path: turbopath::AnchoredSystemPathBuf::from_raw(path).unwrap(),
+            unresolved_external_dependencies: None,
+            transitive_dependencies: None,
+        }
+    }
+
+    #[test]
+    fn missing_workspace_dependency_is_fixed() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::new(tmp.path().to_str().unwrap()).unwrap();
+
+        let app_dir = repo_root.join_components(&["apps", "web"]);
+        app_dir.create_dir_all().unwrap();
+        app_dir
+            .join_component("package.json")
+            .create_with_contents("{\n  \"name\": \"web\"\n}\n")
+            .unwrap();
+        app_dir
+            .join_component("index.ts")
+            .create_with_contents("import { a } from '@repo/ui';\nimport 'react';\n")
+            .unwrap();
+        // `@repo/ui` is hoisted, so it resolves without reinstalling
+        repo_root
+            .join_components(&["node_modules", "@repo", "ui"])
+            .create_dir_all()
+            .unwrap();
+
+        let graph = MockGraph::new(vec![
+            (
+                PackageName::Other("web".into()),
+                package_info("apps/web/package.json"),
+            ),
+            (
+                PackageName::Other("@repo/ui".into()),
+                package_info("packages/ui/package.json"),
+            ),
+        ]);
+        let filtered: HashSet<PackageName> = [PackageName::Other("web".into())].into();
+        let ctx = BoundariesContext {
+            repo_root,
+            pkg_dep_graph: &graph,
+            turbo_json_provider: &MockTurboJson,
+            root_boundaries_config: None,
+            filtered_pkgs: &filtered,
+        };
+
+        let mut result = BoundariesChecker::check_boundaries(&ctx, false).unwrap();
+        assert_eq!(result.diagnostics.len(), 2);
+
+        let fixes = result.take_dependency_fixes(
+            repo_root,
+            &graph,
+            &turborepo_repository::package_manager::PackageManager::Pnpm9,
+        );
+        assert_eq!(
+            fixes,
+            vec![DependencyFix {
+                package: PackageName::Other("web".into()),
+                package_json_path: app_dir.join_component("package.json"),
+                dependency: PackageName::Other("@repo/ui".into()),
+                specifier: "workspace:*",
+                needs_install: false,
+            }]
+        );
+        // `react` isn't a workspace package, so it's left for the user
+        assert!(matches!(
+            result.diagnostics.as_slice(),
+            [BoundariesDiagnostic::PackageNotFound { name, .. }] if name == "react"
+        ));
+
+        BoundariesChecker::apply_dependency_fixes(&fixes).unwrap();
+        assert_eq!(
+            app_dir
+                .join_component("package.json")
+                .read_to_string()
+                .unwrap(),
+            r#"{
+  "name": "web",
+  "dependencies": {
+    "@repo/ui": "workspace:*"
+  }
+}
+"#
+        );
+    }
+
+    #[test]
+    fn unlinked_workspace_dependency_needs_install() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::new(tmp.path().to_str().unwrap()).unwrap();
+        let graph = MockGraph::new(vec![
+            (
+                PackageName::Other("web".into()),
+                package_info("apps/web/package.json"),
+            ),
+            (
+                PackageName::Other("ui".into()),
+                package_info("packages/ui/package.json"),
+            ),
+        ]);
+        let mut result = BoundariesResult {
+            diagnostics: vec![BoundariesDiagnostic::PackageNotFound {
+                path: repo_root.join_components(&["apps", "web", "index.ts"]),
+                name: "ui".into(),
+                package_name: PackageName::Other("web".into()),
+                span: SourceSpan::new(0.into(), 0),
+                text: NamedSource::new("index.ts", String::new()),
+            }],
+            ..Default::default()
+        };
+
+        let fixes = result.take_dependency_fixes(
+            repo_root,
+            &graph,
+            &turborepo_repository::package_manager::PackageManager::Npm,
+        );
+
+        assert!(result.is_ok());
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].specifier, "*");
+        assert!(fixes[0].needs_install);
+    }
+
+    #[test]
+    fn unused_dependencies_are_reported_when_enabled() {
+        let tmp = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::new(tmp.path().to_str().unwrap()).unwrap();
+
+        let app_dir = repo_root.join_components(&["apps", "web"]);
+        app_dir.create_dir_all().unwrap();
+        app_dir
+            .join_component("index.ts")
+            .create_with_contents(
+                "import { Button } from '@repo/ui/button';\nimport type { FC } from 'react';\n",
+            )
+            .unwrap();
+
+        let mut info = package_info("apps/web/package.json");
+        info.package_json.dependencies = Some(
+            ["@repo/ui", "@types/react", "lodash", "react", "react-dom"]
+                .into_iter()
+                .map(|name| (name.to_string(), "*".to_string()))
+                .collect(),
+        );
+        let graph = MockGraph::new(vec![(PackageName::Other("web".into()), info)]);
+        let filtered: HashSet<PackageName> = [PackageName::Other("web".into())].into();
+        let root_boundaries_config = BoundariesConfig {
+            unused_dependencies: Some(true),
+            ..Default::default()
+        };
+        let ctx = BoundariesContext {
+            repo_root,
+            pkg_dep_graph: &graph,
+            turbo_json_provider: &MockTurboJson,
+            root_boundaries_config: Some(&root_boundaries_config),
+            filtered_pkgs: &filtered,
+        };
+
+        let result = BoundariesChecker::check_boundaries(&ctx, false).unwrap();
+
+        let unused: Vec<_> = result
+            .diagnostics
+            .iter()
+            .map(|diagnostic| match diagnostic {
+                BoundariesDiagnostic::UnusedDependency { name, .. } => name.as_str(),
+                other => panic!("unexpected diagnostic: {other}"),
+            })
+            .collect();
+        assert_eq!(unused, vec!["lodash", "react-dom"]);
+    }
+
     #[test]
     fn merge_preserves_ordering() {
         let mut a = BoundariesResult {
@@ -0,0 +1,570 @@
+//! Turborepo Cache Server
+//!
+//! A self-hosted Remote Cache backed by a local directory. It implements the
+//! artifact routes of the Remote Cache API that `turborepo-api-client` uses,
+//! so `turbo` can point at it with `--api` and a token. Started with
+//! `turbo cache serve`.
+
+#![deny(clippy::all)]
+
+mod store;
+
+use std::{future::Future, io, net::SocketAddr, sync::Arc};
+
+use axum::{
+    Json, Router,
+    body::Body,
+    extract::{Path, Request, State},
+    http::{
+        HeaderMap, HeaderValue, StatusCode,
+        header::{self, CONTENT_LENGTH, CONTENT_TYPE},
+    },
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use thiserror::Error;
+use tokio::net::TcpListener;
+use tokio_util::io::ReaderStream;
+use tracing::{debug, warn};
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_cache::{CacheError, fs::CacheMetadata};
+use turborepo_vercel_api::{
+    ArtifactInfo, ArtifactQueryError, ArtifactQueryRequest, ArtifactQueryResponse,
+    ArtifactQueryResult, CachingStatus, CachingStatusResponse,
+};
+
+use crate::store::{ArtifactStore, StoreError};
+
+/// Default port for the cache server
+pub const DEFAULT_PORT: u16 = 8080;
+
+/// The most hashes accepted in a single artifact query
+const MAX_QUERY_HASHES: usize = 1000;
+
+/// Errors that can occur in the cache server
+#[derive(Debug, Error)]
+pub enum ServerError {
+    #[error("At least one token is required to authenticate clients.")]
+    NoTokens,
+    #[error("Failed to open cache directory: {0}")]
+    Store(#[from] CacheError),
+    #[error("Failed to bind to {addr}: {source}")]
+    Bind {
+        addr: SocketAddr,
+        #[source]
+        source: io::Error,
+    },
+    #[error("Server error: {0}")]
+    Server(#[from] io::Error),
+}
+
+#[derive(Clone)]
+struct AppState {
+    store: Arc<ArtifactStore>,
+    tokens: Arc<[String]>,
+}
+
+/// The cache server
+pub struct CacheServer {
+    store: Arc<ArtifactStore>,
+    tokens: Arc<[String]>,
+}
+
+impl CacheServer {
+    /// Opens the cache directory, creating it if needed. Clients must send
+    /// one of `tokens` as a bearer token. Once the directory grows beyond
+    /// `max_size` bytes, the least recently used artifacts are evicted.
+    pub fn new(
+        dir: AbsoluteSystemPathBuf,
+        tokens: Vec<String>,
+        max_size: Option<u64>,
+    ) -> Result<Self, ServerError> {
+        let tokens: Vec<String> = tokens
+            .into_iter()
+            .filter(|token| !token.is_empty())
+            .collect();
+        if tokens.is_empty() {
+            return Err(ServerError::NoTokens);
+        }
+
+        Ok(Self {
+            store: Arc::new(ArtifactStore::open(dir, max_size)?),
+            tokens: tokens.into(),
+        })
+    }
+
+    /// The directory artifacts are stored in
+    pub fn dir(&self) -> &AbsoluteSystemPath {
+        self.store.dir()
+    }
+
+    /// The size of the stored artifacts, in bytes
+    pub fn size(&self) -> u64 {
+        self.store.size()
+    }
+
+    /// Binds the listener that the server accepts connections on
+    pub async fn bind(addr: SocketAddr) -> Result<TcpListener, ServerError> {
+        TcpListener::bind(addr)
+            .await
+            .map_err(|source| ServerError::Bind { addr, source })
+    }
+
+    /// Serves requests on `listener` until `shutdown` completes
+    pub async fn serve(
+        self,
+        listener: TcpListener,
+        shutdown: impl Future<Output = ()> + Send + 'static,
+    ) -> Result<(), ServerError> {
+        axum::serve(listener, self.router())
+            .with_graceful_shutdown(shutdown)
+            .await?;
+        Ok(())
+    }
+
+    fn router(self) -> Router {
+        let state = AppState {
+            store: self.store,
+            tokens: self.tokens,
+        };
+
+        Router::new()
+            .route("/v8/artifacts", post(query_artifacts))
+            .route("/v8/artifacts/status", get(caching_status))
+            .route("/v8/artifacts/events", post(record_events))
+            .route(
+                "/v8/artifacts/{hash}",
+                get(fetch_artifact).head(artifact_exists).put(put_artifact),
+            )
+            .route_layer(middleware::from_fn_with_state(state.clone(), authenticate))
+            .with_state(state)
+    }
+}
+
+async fn authenticate(State(state): State<AppState>, request: Request, next: Next) -> Response {
+    let token = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+
+    match token {
+        Some(token)
+            if state
+                .tokens
+                .iter()
+                .any(|expected| tokens_match(expected.as_bytes(), token.as_bytes())) =>
+        {
+            next.run(request).await
+        }
+        _ => {
+            // The client reads the error from a 403 body in this shape
+            let body = serde_json::json!({
+                "error": {
+                    "code": "forbidden",
+                    "message": "The request is missing a valid token for this cache server",
+                }
+            });
+            (StatusCode::FORBIDDEN, Json(body)).into_response()
+        }
+    }
+}
+
+async fn caching_status() -> Json<CachingStatusResponse> {
+    Json(CachingStatusResponse {
+        status: CachingStatus::Enabled,
+        artifact_query: true,
+    })
+}
+
+/// Clients report cache hits and misses after every run. They aren't
+/// collected, but are accepted so clients don't log failures.
+async fn record_events() -> StatusCode {
+    StatusCode::OK
+}
+
+async fn artifact_exists(State(state): State<AppState>, Path(hash): Path<String>) -> Response {
+    if !is_valid_hash(&hash) {
+        return invalid_hash(&hash);
+    }
+
+    match state.store.metadata(&hash).await {
+        Some(meta) => (StatusCode::OK, artifact_headers(&meta)).into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// Answers `exists` checks for many artifacts at once. Invalid hashes get a
+/// per-hash error rather than failing the whole query.
+async fn query_artifacts(
+    State(state): State<AppState>,
+    Json(request): Json<ArtifactQueryRequest>,
+) -> Response {
+    if request.hashes.len() > MAX_QUERY_HASHES {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("at most {MAX_QUERY_HASHES} hashes can be queried at once"),
+        )
+            .into_response();
+    }
+
+    let mut response = ArtifactQueryResponse::new();
+    for hash in request.hashes {
+        let result = if !is_valid_hash(&hash) {
+            Some(ArtifactQueryResult::Error {
+                error: ArtifactQueryError {
+                    message: format!("invalid artifact hash `{hash}`"),
+                },
+            })
+        } else if let Some(meta) = state.store.metadata(&hash).await {
+            Some(ArtifactQueryResult::Found(ArtifactInfo {
+                size: state.store.archive_size(&hash).await.unwrap_or_default(),
+                task_duration_ms: meta.duration,
+                tag: meta.tag,
+                sha: meta.sha,
+                dirty_hash: meta.dirty_hash,
+            }))
+        } else {
+            None
+        };
+        response.insert(hash, result);
+    }
+    Json(response).into_response()
+}
+
+async fn fetch_artifact(State(state): State<AppState>, Path(hash): Path<String>) -> Response {
+    if !is_valid_hash(&hash) {
+        return invalid_hash(&hash);
+    }
+
+    let Some((file, len, meta)) = state.store.open_archive(&hash).await else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+    let mut headers = artifact_headers(&meta);
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/octet-stream"),
+    );
+    headers.insert(CONTENT_LENGTH, HeaderValue::from(len));
+
+    (
+        StatusCode::OK,
+        headers,
+        Body::from_stream(ReaderStream::new(file)),
+    )
+        .into_response()
+}
+
+async fn put_artifact(
+    State(state): State<AppState>,
+    Path(hash): Path<String>,
+    headers: HeaderMap,
+    body: Body,
+) -> Response {
+    if !is_valid_hash(&hash) {
+        return invalid_hash(&hash);
+    }
+
+    let meta = CacheMetadata {
+        duration: header_string(&headers, "x-artifact-duration")
+            .and_then(|duration| duration.parse().ok())
+            .unwrap_or_default(),
+        sha: header_string(&headers, "x-artifact-sha"),
+        dirty_hash: header_string(&headers, "x-artifact-dirty-hash"),
+        tag: header_string(&headers, "x-artifact-tag"),
+        hash,
+        task_id: None,
+    };
+    let expected_len =
+        header_string(&headers, CONTENT_LENGTH.as_str()).and_then(|len| len.parse().ok());
+
+    debug!("storing artifact {}", meta.hash);
+    match state
+        .store
+        .put(meta, expected_len, body.into_data_stream())
+        .await
+    {
+        Ok(()) => StatusCode::ACCEPTED.into_response(),
+        Err(err @ (StoreError::Body(_) | StoreError::LengthMismatch { .. })) => {
+            (StatusCode::BAD_REQUEST, err.to_string()).into_response()
+        }
+        Err(err) => {
+            warn!("failed to store artifact: {err}");
+            (StatusCode::INTERNAL_SERVER_ERROR, err.to_string()).into_response()
+        }
+    }
+}
+
+fn artifact_headers(meta: &CacheMetadata) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    headers.insert("x-artifact-duration", HeaderValue::from(meta.duration));
+    for (name, value) in [
+        ("x-artifact-sha", &meta.sha),
+        ("x-artifact-dirty-hash", &meta.dirty_hash),
+        ("x-artifact-tag", &meta.tag),
+    ] {
+        if let Some(value) = value
+            && let Ok(value) = HeaderValue::from_str(value)
+        {
+            headers.insert(name, value);
+        }
+    }
+    headers
+}
+
+fn header_string(headers: &HeaderMap, name: &str) -> Option<String> {
+    headers
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+/// Hashes become file names, so anything that could escape the cache
+/// directory is rejected
+fn is_valid_hash(hash: &str) -> bool {
+    !hash.is_empty()
+        && hash.len() <= 128
+        && hash
+            .bytes()
+            .all(|byte| byte.is_ascii_alphanumeric() || byte == b'-' || byte == b'_')
+}
+
+fn invalid_hash(hash: &str) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        format!("invalid artifact hash `{hash}`"),
+    )
+        .into_response()
+}
+
+/// Compares tokens without returning early at the first differing byte
+fn tokens_match(expected: &[u8], actual: &[u8]) -> bool {
+    expected.len() == actual.len()
+        && expected
+            .iter()
+            .zip(actual)
+            .fold(0, |acc, (expected, actual)| acc | (expected ^ actual))
+            == 0
+}
+
+#[cfg(test)]
+mod test {
+    use std::{net::SocketAddr, time::Duration};
+
+    use anyhow::Result;
+    use tempfile::tempdir;
+    use test_case::test_case;
+    use tokio::sync::oneshot;
+    use turbopath::AbsoluteSystemPathBuf;
+    use turborepo_api_client::{APIClient, Bytes, CacheClient};
+    use turborepo_types::SecretString;
+    use turborepo_vercel_api::{ArtifactQueryResult, CachingStatus};
+
+    use super::{CacheServer, is_valid_hash};
+
+    const TOKEN: &str = "test-token";
+
+    async fn start_server(dir: AbsoluteSystemPathBuf) -> Result<(APIClient, oneshot::Sender<()>)> {
+        let server = CacheServer::new(dir, vec![TOKEN.to_string()], None)?;
+        let listener = CacheServer::bind(SocketAddr::from(([127, 0, 0, 1], 0))).await?;
+        let addr = listener.local_addr()?;
+        let (shutdown_tx, shutdown_rx) = oneshot::channel::<()>();
+        tokio::spawn(server.serve(listener, async {
+            let _ = shutdown_rx.await;
+        }));
+
+        let client = APIClient::new(
+            format!("http://{addr}"),
+            Some(Duration::from_secs(10)),
+            None,
+            "0.0.0",
+            false,
+        )?;
+        Ok((client, shutdown_tx))
+    }
+
+    async fn put(
+        client: &APIClient,
+        token: &SecretString,
+        hash: &str,
+        tag: &str,
+        body: &'static [u8],
+    ) {
+        let stream = tokio_stream::once(Ok::<_, turborepo_api_client::Error>(Bytes::from_static(
+            body,
+        )));
+        client
+            .put_artifact(
+                hash,
+                stream,
+                body.len(),
+                1234,
+                Some(tag),
+                token,
+                None,
+                None,
+                Some("deadbeef"),
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_artifact_round_trip() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = AbsoluteSystemPathBuf::try_from(dir.path())?;
+        let (client, _shutdown) = start_server(dir.clone()).await?;
+        let token = SecretString::new(TOKEN.to_string());
+
+        assert!(
+            client
+                .artifact_exists("abc123", &token, None, None)
+                .await?
+                .is_none()
+        );
+
+        put(
+            &client,
+            &token,
+            "abc123",
+            "artifact-tag",
+            b"archive contents",
+        )
+        .await;
+
+        let response = client
+            .artifact_exists("abc123", &token, Some("team"), None)
+            .await?
+            .unwrap();
+        let headers = response.headers();
+        assert_eq!(headers["x-artifact-duration"], "1234");
+        assert_eq!(headers["x-artifact-tag"], "artifact-tag");
+        assert_eq!(headers["x-artifact-sha"], "deadbeef");
+        assert!(headers.get("x-artifact-dirty-hash").is_none());
+
+        let response = client
+            .fetch_artifact("abc123", &token, None, None)
+            .await?
+            .unwrap();
+        assert_eq!(response.headers()["x-artifact-tag"], "artifact-tag");
+        assert_eq!(response.bytes().await?.as_ref(), b"archive contents");
+
+        assert!(dir.join_component("abc123.tar.zst").exists());
+        assert!(dir.join_component("abc123-meta.json").exists());
+
+        let status = client.get_caching_status(&token, None, None).await?;
+        assert!(matches!(status.status, CachingStatus::Enabled));
+        assert!(status.artifact_query);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_query_artifacts() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = AbsoluteSystemPathBuf::try_from(dir.path())?;
+        let (client, _shutdown) = start_server(dir).await?;
+        let token = SecretString::new(TOKEN.to_string());
+
+        put(
+            &client,
+            &token,
+            "abc123",
+            "artifact-tag",
+            b"archive contents",
+        )
+        .await;
+
+        let hashes = ["abc123", "def456", "../escape"].map(String::from);
+        let response = client.query_artifacts(&hashes, &token, None, None).await?;
+
+        let Some(Some(ArtifactQueryResult::Found(info))) = response.get("abc123") else {
+            panic!("expected abc123 to be found: {response:?}");
+        };
+        assert_eq!(info.size, b"archive contents".len() as u64);
+        assert_eq!(info.task_duration_ms, 1234);
+        assert_eq!(info.tag.as_deref(), Some("artifact-tag"));
+        assert_eq!(info.sha.as_deref(), Some("deadbeef"));
+        assert!(matches!(response.get("def456"), Some(None)));
+        assert!(matches!(
+            response.get("../escape"),
+            Some(Some(ArtifactQueryResult::Error { .. }))
+        ));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_uploads_keep_tag_with_archive() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = AbsoluteSystemPathBuf::try_from(dir.path())?;
+        let (client, _shutdown) = start_server(dir).await?;
+        let token = SecretString::new(TOKEN.to_string());
+        let uploads = [("tag-a", b"first contents"), ("tag-b", b"other contents")];
+
+        put(&client, &token, "abc123", uploads[0].0, uploads[0].1).await;
+
+        let mut tasks = tokio::task::JoinSet::new();
+        for i in 0..20 {
+            let (tag, body) = uploads[i % 2];
+            let client = client.clone();
+            let token = token.clone();
+            tasks.spawn(async move {
+                put(&client, &token, "abc123", tag, body).await;
+            });
+            let client = client.clone();
+            let token = token.clone();
+            tasks.spawn(async move {
+                let response = client
+                    .fetch_artifact("abc123", &token, None, None)
+                    .await
+                    .unwrap()
+                    .unwrap();
+                let tag = response.headers()["x-artifact-tag"]
+                    .to_str()
+                    .unwrap()
+                    .to_owned();
+                let body = response.bytes().await.unwrap();
+                let expected = uploads.iter().find(|(t, _)| *t == tag).unwrap().1;
+                assert_eq!(body.as_ref(), expected, "archive doesn't match {tag}");
+            });
+        }
+        while let Some(result) = tasks.join_next().await {
+            result?;
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_token() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = AbsoluteSystemPathBuf::try_from(dir.path())?;
+        let (client, _shutdown) = start_server(dir).await?;
+
+        let wrong_token = SecretString::new("wrong-token".to_string());
+        assert!(
+            client
+                .artifact_exists("abc123", &wrong_token, None, None)
+                .await
+                .is_err()
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_requires_a_token() -> Result<()> {
+        let dir = tempdir()?;
+        let dir = AbsoluteSystemPathBuf::try_from(dir.path())?;
+        assert!(CacheServer::new(dir, vec![String::new()], None).is_err());
+        Ok(())
+    }
+
+    #[test_case("a1b2c3d4e5f60718", true ; "task hash")]
+    #[test_case("incremental-0123abcd", true ; "incremental key")]
+    #[test_case("..", false ; "parent directory")]
+    #[test_case("a/b", false ; "separator")]
+    #[test_case("", false ; "empty")]
+    fn test_is_valid_hash(hash: &str, expected: bool) {
+        assert_eq!(is_valid_hash(hash), expected);
+    }
+}
@@ -0,0 +1,279 @@
+//! Artifact storage for the cache server.
+//!
+//! Artifacts are stored in the same layout as turbo's local cache
+//! (`{hash}.tar.zst` next to `{hash}-meta.json`), so the directory can be
+//! inspected with `turbo cache ls --cache-dir` and evicted with the same
+//! logic as `cacheMaxSize`.
+
+use std::{
+    hash::{DefaultHasher, Hash, Hasher},
+    io,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::SystemTime,
+};
+
+use axum::body::Bytes;
+use futures::{Stream, StreamExt};
+use tokio::{fs::File, io::AsyncWriteExt, sync::RwLock};
+use tracing::{debug, warn};
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_cache::{CacheError, fs::CacheMetadata, inspect};
+
+/// Eviction stops once the store is below this share of `max_size`, so that
+/// every upload after reaching the limit doesn't trigger another eviction.
+const EVICTION_TARGET_PERCENT: u64 = 90;
+
+/// Number of locks that artifact hashes are spread over. Uploads of
+/// different hashes rarely share one, and the count stays fixed no matter how
+/// many artifacts are stored.
+const HASH_LOCK_COUNT: usize = 64;
+
+#[derive(Debug, thiserror::Error)]
+pub enum StoreError {
+    #[error("failed to read artifact body: {0}")]
+    Body(axum::Error),
+    #[error("artifact body was {actual} bytes, expected {expected}")]
+    LengthMismatch { expected: u64, actual: u64 },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("failed to serialize artifact metadata: {0}")]
+    Metadata(#[from] serde_json::Error),
+}
+
+pub struct ArtifactStore {
+    dir: AbsoluteSystemPathBuf,
+    max_size: Option<u64>,
+    /// Approximate size of the stored artifacts. Recomputed after each
+    /// eviction.
+    size: AtomicU64,
+    evicting: AtomicBool,
+    next_upload_id: AtomicU64,
+    /// Held for writing while an upload moves an archive and its metadata
+    /// into place, and for reading while both are read, so that readers never
+    /// pair an archive with the metadata of another upload of the same hash.
+    hash_locks: [RwLock<()>; HASH_LOCK_COUNT],
+}
+
+impl ArtifactStore {
+    pub fn open(dir: AbsoluteSystemPathBuf, max_size: Option<u64>) -> Result<Self, CacheError> {
+        dir.create_dir_all()?;
+        let size = total_size(&dir)?;
+        Ok(Self {
+            dir,
+            max_size,
+            size: AtomicU64::new(size),
+            evicting: AtomicBool::new(false),
+            next_upload_id: AtomicU64::new(0),
+            hash_locks: std::array::from_fn(|_| RwLock::new(())),
+        })
+    }
+
+    pub fn dir(&self) -> &AbsoluteSystemPath {
+        &self.dir
+    }
+
+    pub fn size(&self) -> u64 {
+        self.size.load(Ordering::Relaxed)
+    }
+
+    /// Reads the metadata for `hash`. Returns `None` if the artifact isn't
+    /// stored.
+    pub async fn metadata(&self, hash: &str) -> Option<CacheMetadata> {
+        let _guard = self.hash_lock(hash).read().await;
+        self.read_metadata(hash).await
+    }
+
+    async fn read_metadata(&self, hash: &str) -> Option<CacheMetadata> {
+        if !self.archive_path(hash).exists() {
+            return None;
+        }
+        let contents = tokio::fs::read_to_string(self.meta_path(hash).as_std_path())
+            .await
+            .ok()?;
+        match serde_json::from_str(&contents) {
+            Ok(meta) => Some(meta),
+            Err(err) => {
+                warn!("invalid metadata for artifact {hash}: {err}");
+                None
+            }
+        }
+    }
+
+    /// The length of the archive for `hash`, if it's stored
+    pub async fn archive_size(&self, hash: &str) -> Option<u64> {
+        tokio::fs::metadata(self.archive_path(hash).as_std_path())
+            .await
+            .ok()
+            .map(|metadata| metadata.len())
+    }
+
+    /// Opens the archive for `hash` along with its metadata and length.
+    /// Marks the artifact as recently used so that eviction keeps it.
+    pub async fn open_archive(&self, hash: &str) -> Option<(File, u64, CacheMetadata)> {
+        let archive_path = self.archive_path(hash);
+        let (file, meta) = {
+            let _guard = self.hash_lock(hash).read().await;
+            let meta = self.read_metadata(hash).await?;
+            // The open file keeps reading this archive even if another upload
+            // replaces it afterwards.
+            let file = File::open(archive_path.as_std_path()).await.ok()?;
+            (file, meta)
+        };
+        let len = file.metadata().await.ok()?.len();
+
+        // Eviction removes the artifacts with the oldest modification time
+        // first, so bumping it on reads evicts the least recently used ones.
+        tokio::task::spawn_blocking(move || {
+            let touched = std::fs::File::options()
+                .append(true)
+                .open(archive_path.as_std_path())
+                .and_then(|file| file.set_modified(SystemTime::now()));
+            if let Err(err) = touched {
+                debug!("failed to update modification time of {archive_path}: {err}");
+            }
+        });
+
+        Some((file, len, meta))
+    }
+
+    /// Stores the archive for `meta.hash`, streaming `body` to a temporary
+    /// file first so that readers never see a partial archive. Concurrent
+    /// uploads of the same hash each replace the archive and its metadata
+    /// together.
+    pub async fn put(
+        self: &Arc<Self>,
+        meta: CacheMetadata,
+        expected_len: Option<u64>,
+        mut body: impl Stream<Item = Result<Bytes, axum::Error>> + Unpin,
+    ) -> Result<(), StoreError> {
+        let hash = meta.hash.clone();
+        let temp_path = self.temp_path(&format!("{hash}.tar.zst"));
+
+        let written = async {
+            let mut file = File::create(temp_path.as_std_path()).await?;
+            let mut written = 0;
+            while let Some(chunk) = body.next().await {
+                let chunk = chunk.map_err(StoreError::Body)?;
+                file.write_all(&chunk).await?;
+                written += chunk.len() as u64;
+            }
+            file.flush().await?;
+            if let Some(expected) = expected_len
+                && expected != written
+            {
+                return Err(StoreError::LengthMismatch {
+                    expected,
+                    actual: written,
+                });
+            }
+            Ok::<_, StoreError>(written)
+        }
+        .await;
+        let written = match written {
+            Ok(written) => written,
+            Err(err) => {
+                let _ = tokio::fs::remove_file(temp_path.as_std_path()).await;
+                return Err(err);
+            }
+        };
+        let meta_json = match self.move_into_place(&meta, &temp_path).await {
+            Ok(meta_json) => meta_json,
+            Err(err) => {
+                let _ = tokio::fs::remove_file(temp_path.as_std_path()).await;
+                return Err(err);
+            }
+        };
+
+        let added = written + meta_json.len() as u64;
+        let size = self.size.fetch_add(added, Ordering::Relaxed) + added;
+        if let Some(max_size) = self.max_size
+            && size > max_size
+        {
+            self.evict(max_size);
+        }
+        Ok(())
+    }
+
+    /// Writes the metadata for `meta.hash` and moves it and the archive at
+    /// `archive_temp_path` into place. The metadata goes first, since
+    /// readers treat an artifact without an archive as missing. Returns the
+    /// serialized metadata.
+    async fn move_into_place(
+        &self,
+        meta: &CacheMetadata,
+        archive_temp_path: &AbsoluteSystemPath,
+    ) -> Result<String, StoreError> {
+        let hash = &meta.hash;
+        let meta_json = serde_json::to_string(meta)?;
+        let meta_temp_path = self.temp_path(&format!("{hash}-meta.json"));
+        tokio::fs::write(meta_temp_path.as_std_path(), &meta_json).await?;
+
+        let _guard = self.hash_lock(hash).write().await;
+        let renamed = async {
+            tokio::fs::rename(
+                meta_temp_path.as_std_path(),
+                self.meta_path(hash).as_std_path(),
+            )
+            .await?;
+            tokio::fs::rename(
+                archive_temp_path.as_std_path(),
+                self.archive_path(hash).as_std_path(),
+            )
+            .await
+        }
+        .await;
+        if let Err(err) = renamed {
+            let _ = tokio::fs::remove_file(meta_temp_path.as_std_path()).await;
+            return Err(err.into());
+        }
+        Ok(meta_json)
+    }
+
+    /// Evicts the least recently used artifacts in the background, unless an
+    /// eviction is already running.
+    fn evict(self: &Arc<Self>, max_size: u64) {
+        if self.evicting.swap(true, Ordering::AcqRel) {
+            return;
+        }
+        let store = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let target = max_size / 100 * EVICTION_TARGET_PERCENT;
+            let (removed, reclaimed) = inspect::prune(&store.dir, None, Some(target));
+            debug!("evicted {removed} artifacts, reclaiming {reclaimed} bytes");
+            match total_size(&store.dir) {
+                Ok(size) => store.size.store(size, Ordering::Relaxed),
+                Err(err) => warn!("failed to measure cache directory: {err}"),
+            }
+            store.evicting.store(false, Ordering::Release);
+        });
+    }
+
+    fn hash_lock(&self, hash: &str) -> &RwLock<()> {
+        let mut hasher = DefaultHasher::new();
+        hash.hash(&mut hasher);
+        &self.hash_locks[hasher.finish() as usize % HASH_LOCK_COUNT]
+    }
+
+    fn archive_path(&self, hash: &str) -> AbsoluteSystemPathBuf {
+        self.dir.join_component(&format!("{hash}.tar.zst"))
+    }
+
+    fn meta_path(&self, hash: &str) -> AbsoluteSystemPathBuf {
+        self.dir.join_component(&format!("{hash}-meta.json"))
+    }
+
+    fn temp_path(&self, file_name: &str) -> AbsoluteSystemPathBuf {
+        let upload_id = self.next_upload_id.fetch_add(1, Ordering::Relaxed);
+        self.dir.join_component(&format!(
+            ".{file_name}.{}.{upload_id}.tmp",
+            std::process::id()
+        ))
+    }
+}
+
+fn total_size(dir: &AbsoluteSystemPath) -> Result<u64, CacheError> {
+    Ok(inspect::list(dir)?.iter().map(|entry| entry.size).sum())
+}
@@ -8,7 +8,7 @@ use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{APIAuth, APIClient};
 
 use crate::{
-    CacheError, CacheHitMetadata, CacheOpts, LazyScmState, http::UploadMap,
+    ArtifactDetails, CacheError, CacheHitMetadata, CacheOpts, LazyScmState, http::UploadMap,
     multiplexer::CacheMultiplexer,
 };
 
@@ -26,6 +26,7 @@ enum WorkerRequest {
         key: String,
         duration: u64,
         files: Vec<AnchoredSystemPathBuf>,
+        details: Option<ArtifactDetails>,
     },
     Flush(oneshot::Sender<()>),
     /// Shutdown the cache. The first oneshot notifies when shutdown starts and
@@ -73,6 +74,7 @@ impl AsyncCache {
                         key,
                         duration,
                         files,
+                        details,
                     } => {
                         let Ok(permit) = semaphore.clone().acquire_owned().await else {
                             break;
@@ -82,8 +84,9 @@ impl AsyncCache {
                         let worker_span = tracing::span!(Level::TRACE, "cache worker: cache PUT");
                         workers.push(tokio::spawn(
                             async move {
-                                if let Err(err) =
-                                    real_cache.put(&anchor, &key, &files, duration).await
+                                if let Err(err) = real_cache
+                                    .put(&anchor, &key, &files, duration, details.as_ref())
+                                    .await
                                 {
                                     let num_warnings =
                                         warnings.load(std::sync::atomic::Ordering::Acquire);
@@ -153,6 +156,7 @@ impl AsyncCache {
         key: String,
         files: Vec<AnchoredSystemPathBuf>,
         duration: u64,
+        details: Option<ArtifactDetails>,
     ) -> Result<(), CacheError> {
         if self
             .writer_sender
@@ -161,6 +165,7 @@ impl AsyncCache {
                 key,
                 duration,
                 files,
+                details,
             })
             .await
             .is_err()
@@ -295,9 +300,11 @@ mod tests {
                 unused_team_id: Some("my-team".to_string()),
                 signature: false,
                 enforce_signature_key_length: false,
+                public_key: None,
             }),
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
 
         let api_client = APIClient::new(
@@ -337,6 +344,7 @@ mod tests {
                     .map(|f| f.path().to_owned())
                     .collect(),
                 test_case.duration,
+                None,
             )
             .await
             .unwrap();
@@ -396,9 +404,11 @@ mod tests {
                 unused_team_id: Some("my-team".to_string()),
                 signature: false,
                 enforce_signature_key_length: false,
+                public_key: None,
             }),
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
 
         let api_auth = Some(APIAuth {
@@ -431,6 +441,7 @@ mod tests {
                     .map(|f| f.path().to_owned())
                     .collect(),
                 test_case.duration,
+                None,
             )
             .await
             .unwrap();
@@ -499,9 +510,11 @@ mod tests {
                 unused_team_id: Some("my-team".to_string()),
                 signature: false,
                 enforce_signature_key_length: false,
+                public_key: None,
             }),
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
 
         let api_auth = Some(APIAuth {
@@ -530,6 +543,7 @@ mod tests {
                     .map(|f| f.path().to_owned())
                     .collect(),
                 test_case.duration,
+                None,
             )
             .await?;
 
@@ -574,9 +588,11 @@ mod tests {
                 unused_team_id: Some("my-team".to_string()),
                 signature: false,
                 enforce_signature_key_length: false,
+                public_key: None,
             }),
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
 
         let api_client = APIClient::new(
@@ -616,6 +632,7 @@ mod tests {
                     .map(|f| f.path().to_owned())
                     .collect(),
                 test_case.duration,
+                None,
             )
             .await
             .unwrap();
@@ -0,0 +1,324 @@
+//! Content-addressed storage for the files in local cache artifacts.
+//!
+//! When deduplication is enabled, the contents of regular files are stored
+//! once in `blobs/{digest[..2]}/{digest}`, keyed by their SHA-256. Archives
+//! still contain an entry for every file, but regular files are written with
+//! an empty body and a [`BLOB_PAX_KEY`] extension naming the blob, so
+//! restoring them goes through the same path checks as any other archive.
+//!
+//! Each artifact lists the blobs it references in `{hash}-blobs.json`, which
+//! lets eviction find unreferenced blobs without reading every archive.
+
+use std::{
+    backtrace::Backtrace,
+    collections::{BTreeSet, HashMap},
+    fs,
+    io::{self, Read, Seek},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use sha2::{Digest, Sha256};
+use tar::Entry;
+use tracing::debug;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+
+use crate::CacheError;
+
+pub(crate) const BLOBS_DIR: &str = "blobs";
+/// The PAX extension naming the blob that holds a regular file's contents
+pub(crate) const BLOB_PAX_KEY: &str = "TURBO.blob";
+
+const DIGEST_LEN: usize = 64;
+/// Unreferenced blobs younger than this are kept, since a concurrent `put`
+/// may be about to reference them.
+pub(crate) const GARBAGE_GRACE_PERIOD: Duration = Duration::from_secs(3600);
+
+static TEMP_FILE_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) struct BlobStore {
+    dir: AbsoluteSystemPathBuf,
+}
+
+impl BlobStore {
+    pub fn new(cache_directory: &AbsoluteSystemPath) -> Self {
+        Self {
+            dir: cache_directory.join_component(BLOBS_DIR),
+        }
+    }
+
+    pub fn path(&self, digest: &str) -> Result<AbsoluteSystemPathBuf, CacheError> {
+        if digest.len() != DIGEST_LEN
+            || !digest
+                .bytes()
+                .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        {
+            return Err(CacheError::InvalidBlob(
+                digest.to_string(),
+                Backtrace::capture(),
+            ));
+        }
+        Ok(self.dir.join_components(&[&digest[..2], digest]))
+    }
+
+    /// Adds the contents of `file` to the store and returns their digest.
+    /// The contents are only copied if no artifact has stored them yet.
+    pub fn insert(&self, file: &mut fs::File) -> Result<String, CacheError> {
+        let mut hasher = Sha256::new();
+        io::copy(file, &mut hasher)?;
+        let digest = format!("{:x}", hasher.finalize());
+
+        let path = self.path(&digest)?;
+        if path.exists() {
+            // Mark the blob as recently used so that garbage collection
+            // running in another process leaves it alone until this
+            // artifact's references are written.
+            let _ = fs::File::options()
+                .append(true)
+                .open(path.as_std_path())
+                .and_then(|blob| blob.set_modified(SystemTime::now()));
+            return Ok(digest);
+        }
+
+        path.ensure_dir()?;
+        let temp_path = self.dir.join_components(&[
+            &digest[..2],
+            &format!(
+                ".{digest}.{}.{}.tmp",
+                std::process::id(),
+                TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+            ),
+        ]);
+        file.rewind()?;
+        let copied = fs::File::create(temp_path.as_std_path())
+            .and_then(|mut blob| io::copy(file, &mut blob))
+            .and_then(|_| fs::rename(temp_path.as_std_path(), path.as_std_path()));
+        if let Err(e) = copied {
+            let _ = fs::remove_file(temp_path.as_std_path());
+            return Err(e.into());
+        }
+        Ok(digest)
+    }
+
+    /// Writes the blob's contents to `dest`.
+    ///
+    /// On Linux this first asks the filesystem for a copy-on-write clone, and
+    /// `io::copy` falls back to `copy_file_range`, so filesystems with reflink
+    /// support share the data with the store. Blobs are never hard-linked:
+    /// tools often rewrite their outputs in place, which would corrupt every
+    /// artifact sharing the blob.
+    pub fn restore(&self, digest: &str, dest: &mut fs::File) -> Result<(), CacheError> {
+        let path = self.path(digest)?;
+        let mut blob = match fs::File::open(path.as_std_path()) {
+            Ok(blob) => blob,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                return Err(CacheError::MissingBlob(
+                    digest.to_string(),
+                    Backtrace::capture(),
+                ));
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        #[cfg(target_os = "linux")]
+        if reflink(&blob, dest) {
+            return Ok(());
+        }
+
+        io::copy(&mut blob, dest)?;
+        Ok(())
+    }
+
+    pub fn size(&self, digest: &str) -> Option<u64> {
+        let path = self.path(digest).ok()?;
+        fs::metadata(path.as_std_path()).ok().map(|m| m.len())
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn reflink(source: &fs::File, dest: &fs::File) -> bool {
+    use std::os::fd::AsRawFd;
+
+    // SAFETY: both file descriptors stay open for the duration of the call
+    unsafe { libc::ioctl(dest.as_raw_fd(), libc::FICLONE as _, source.as_raw_fd()) == 0 }
+}
+
+/// Reads the blob digest from a regular file entry. Returns `None` for
+/// entries that store their contents in the archive.
+pub(crate) fn blob_digest(entry: &mut Entry<impl Read>) -> Result<Option<String>, CacheError> {
+    let Some(extensions) = entry.pax_extensions()? else {
+        return Ok(None);
+    };
+    for extension in extensions {
+        let extension = extension?;
+        if extension.key() == Ok(BLOB_PAX_KEY) {
+            return Ok(Some(
+                String::from_utf8_lossy(extension.value_bytes()).into_owned(),
+            ));
+        }
+    }
+    Ok(None)
+}
+
+pub(crate) fn refs_file_name(hash: &str) -> String {
+    format!("{hash}-blobs.json")
+}
+
+/// Records the blobs that the artifact for `hash` references. Must be
+/// written before the archive so that garbage collection never sees an
+/// archive without its references.
+pub(crate) fn write_refs(
+    cache_directory: &AbsoluteSystemPath,
+    hash: &str,
+    digests: &BTreeSet<String>,
+) -> Result<(), CacheError> {
+    let contents = serde_json::to_string(digests)
+        .map_err(|e| CacheError::MetadataWriteFailure(e, Backtrace::capture()))?;
+    let path = cache_directory.join_component(&refs_file_name(hash));
+    let temp_path = cache_directory.join_component(&format!(
+        ".{}.{}.{}.tmp",
+        refs_file_name(hash),
+        std::process::id(),
+        TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    temp_path.create_with_contents(contents)?;
+    temp_path.rename(&path)?;
+    Ok(())
+}
+
+struct StoredBlob {
+    path: AbsoluteSystemPathBuf,
+    size: u64,
+    modified: SystemTime,
+}
+
+/// Reference counts for the blobs in a cache directory, used by eviction.
+pub(crate) struct BlobRefs {
+    by_artifact: HashMap<String, Vec<String>>,
+    ref_counts: HashMap<String, usize>,
+    blobs: HashMap<String, StoredBlob>,
+    stale_temp_files: Vec<(AbsoluteSystemPathBuf, u64)>,
+}
+
+impl BlobRefs {
+    /// Loads the references of the given artifacts and scans the blobs they
+    /// could point to. Directories without a blob store are skipped cheaply.
+    pub fn load<'a>(
+        cache_directory: &AbsoluteSystemPath,
+        hashes: impl Iterator<Item = &'a str>,
+        now: SystemTime,
+    ) -> Self {
+        let mut refs = Self {
+            by_artifact: HashMap::new(),
+            ref_counts: HashMap::new(),
+            blobs: HashMap::new(),
+            stale_temp_files: Vec::new(),
+        };
+        let blobs_dir = cache_directory.join_component(BLOBS_DIR);
+        let Ok(shards) = fs::read_dir(blobs_dir.as_std_path()) else {
+            return refs;
+        };
+
+        for hash in hashes {
+            let path = cache_directory.join_component(&refs_file_name(hash));
+            let Ok(contents) = path.read_to_string() else {
+                continue;
+            };
+            let digests: Vec<String> = match serde_json::from_str(&contents) {
+                Ok(digests) => digests,
+                Err(e) => {
+                    debug!("ignoring invalid blob references for {hash}: {e}");
+                    continue;
+                }
+            };
+            for digest in &digests {
+                *refs.ref_counts.entry(digest.clone()).or_default() += 1;
+            }
+            refs.by_artifact.insert(hash.to_owned(), digests);
+        }
+
+        for shard in shards.filter_map(|e| e.ok()) {
+            let Ok(files) = fs::read_dir(shard.path()) else {
+                continue;
+            };
+            for file in files.filter_map(|e| e.ok()) {
+                let Ok(metadata) = file.metadata() else {
+                    continue;
+                };
+                let Ok(path) = AbsoluteSystemPathBuf::try_from(file.path()) else {
+                    continue;
+                };
+                let modified = metadata.modified().unwrap_or(now);
+                let name = file.file_name().to_string_lossy().into_owned();
+                if name.ends_with(".tmp") {
+                    if now
+                        .duration_since(modified)
+                        .is_ok_and(|age| age >= GARBAGE_GRACE_PERIOD)
+                    {
+                        refs.stale_temp_files.push((path, metadata.len()));
+                    }
+                    continue;
+                }
+                refs.blobs.insert(
+                    name,
+                    StoredBlob {
+                        path,
+                        size: metadata.len(),
+                        modified,
+                    },
+                );
+            }
+        }
+        refs
+    }
+
+    /// The size of the blobs referenced by at least one artifact
+    pub fn referenced_size(&self) -> u64 {
+        self.ref_counts
+            .iter()
+            .filter(|(_, count)| **count > 0)
+            .filter_map(|(digest, _)| self.blobs.get(digest))
+            .map(|blob| blob.size)
+            .sum()
+    }
+
+    /// Drops the references of an evicted artifact and returns the size of
+    /// the blobs that are no longer referenced.
+    pub fn release(&mut self, hash: &str) -> u64 {
+        let Some(digests) = self.by_artifact.remove(hash) else {
+            return 0;
+        };
+        let mut freed = 0;
+        for digest in digests {
+            if let Some(count) = self.ref_counts.get_mut(&digest) {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    freed += self.blobs.get(&digest).map_or(0, |blob| blob.size);
+                }
+            }
+        }
+        freed
+    }
+
+    /// Removes blobs that no artifact references, along with temporary files
+    /// left behind by interrupted writes. Returns the number of bytes
+    /// reclaimed.
+    pub fn collect_garbage(self, now: SystemTime) -> u64 {
+        let mut reclaimed = 0;
+        for (digest, blob) in &self.blobs {
+            let referenced = self.ref_counts.get(digest).is_some_and(|count| *count > 0);
+            let is_stale = now
+                .duration_since(blob.modified)
+                .is_ok_and(|age| age >= GARBAGE_GRACE_PERIOD);
+            if !referenced && is_stale && fs::remove_file(blob.path.as_std_path()).is_ok() {
+                reclaimed += blob.size;
+            }
+        }
+        for (path, size) in self.stale_temp_files {
+            if fs::remove_file(path.as_std_path()).is_ok() {
+                reclaimed += size;
+            }
+        }
+        reclaimed
+    }
+}
@@ -10,7 +10,10 @@ use std::{
 use tar::{EntryType, Header};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath, IntoUnix};
 
-use crate::CacheError;
+use crate::{
+    CacheError,
+    blobs::{BLOB_PAX_KEY, BlobStore},
+};
 
 /// Atomic counter to ensure unique temp filenames within a single process.
 /// Combined with PID, this guarantees uniqueness across concurrent tasks.
@@ -209,6 +212,34 @@ impl<'a> CacheWriter<'a> {
         Ok(())
     }
 
+    /// Like [`CacheWriter::add_file`], but stores the contents of regular
+    /// files in `blobs` and only references them from the archive. Returns
+    /// the digest of the blob for regular files.
+    pub(crate) fn add_file_to_blob_store(
+        &mut self,
+        anchor: &AbsoluteSystemPath,
+        file_path: &AnchoredSystemPath,
+        blobs: &BlobStore,
+    ) -> Result<Option<String>, CacheError> {
+        let ArchiveSource::Regular {
+            mut file,
+            mut header,
+        } = archive_source(anchor, file_path)?
+        else {
+            self.add_file(anchor, file_path)?;
+            return Ok(None);
+        };
+
+        let digest = blobs.insert(&mut file)?;
+        let mut file_path = file_path.to_unix();
+        file_path.make_canonical_for_tar(false);
+        header.set_size(0);
+        self.builder
+            .append_pax_extensions([(BLOB_PAX_KEY, digest.as_bytes())])?;
+        self.append_data(&mut header, file_path.as_str(), std::io::empty())?;
+        Ok(Some(digest))
+    }
+
     fn create_header(file_info: &fs::Metadata) -> Result<Header, CacheError> {
         let mut header = Header::new_gnu();
 
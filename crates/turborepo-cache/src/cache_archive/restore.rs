@@ -7,6 +7,7 @@ use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf
 
 use crate::{
     CacheError,
+    blobs::{BlobStore, blob_digest},
     cache_archive::{
         restore_directory::{CachedDirTree, restore_directory},
         restore_manifest::RestoreManifest,
@@ -15,10 +16,12 @@ use crate::{
             canonicalize_linkname, restore_symlink, restore_symlink_allow_missing_target,
         },
     },
+    inspect::{ArchiveEntry, ArchiveEntryKind},
 };
 
 pub struct CacheReader<'a> {
     reader: Box<dyn Read + 'a>,
+    blobs: Option<&'a BlobStore>,
 }
 
 impl<'a> CacheReader<'a> {
@@ -29,7 +32,10 @@ impl<'a> CacheReader<'a> {
             Box::new(reader)
         };
 
-        Ok(CacheReader { reader })
+        Ok(CacheReader {
+            reader,
+            blobs: None,
+        })
     }
 
     pub fn open(path: &AbsoluteSystemPathBuf) -> Result<Self, CacheError> {
@@ -43,7 +49,17 @@ impl<'a> CacheReader<'a> {
             Box::new(file)
         };
 
-        Ok(CacheReader { reader })
+        Ok(CacheReader {
+            reader,
+            blobs: None,
+        })
+    }
+
+    /// Reads the contents of files stored outside of the archive from
+    /// `blobs`.
+    pub(crate) fn with_blobs(mut self, blobs: &'a BlobStore) -> Self {
+        self.blobs = Some(blobs);
+        self
     }
 
     pub fn get_sha(mut self) -> Result<Vec<u8>, CacheError> {
@@ -60,6 +76,38 @@ impl<'a> CacheReader<'a> {
         Ok(hasher.finalize().to_vec())
     }
 
+    /// Lists the entries in the archive without restoring them. The whole
+    /// stream is read, so truncated or corrupt archives result in an error.
+    pub fn entries(&mut self) -> Result<Vec<ArchiveEntry>, CacheError> {
+        let mut tr = tar::Archive::new(&mut self.reader);
+        let mut entries = Vec::new();
+        for entry in tr.entries()? {
+            let mut entry = entry?;
+            let blob = blob_digest(&mut entry)?;
+            let kind = match entry.header().entry_type() {
+                tar::EntryType::Regular => ArchiveEntryKind::File,
+                tar::EntryType::Directory => ArchiveEntryKind::Directory,
+                tar::EntryType::Symlink => ArchiveEntryKind::Symlink,
+                _ => ArchiveEntryKind::Other,
+            };
+            entries.push(ArchiveEntry {
+                path: String::from_utf8_lossy(&entry.path_bytes()).into_owned(),
+                kind,
+                size: entry.header().size()?,
+                link_target: entry
+                    .link_name_bytes()
+                    .map(|target| String::from_utf8_lossy(&target).into_owned()),
+                blob,
+            });
+        }
+
+        // Reading entries stops at the end of archive marker, so read the rest
+        // of the stream to make sure that the compressed data is complete
+        std::io::copy(&mut tr.into_inner(), &mut std::io::sink())?;
+
+        Ok(entries)
+    }
+
     pub fn restore(
         &mut self,
         anchor: &AbsoluteSystemPath,
@@ -80,6 +128,7 @@ impl<'a> CacheReader<'a> {
             anchor,
             previous_manifest,
             &mut new_manifest,
+            self.blobs,
         )?;
         Ok((restored, new_manifest))
     }
@@ -91,13 +140,14 @@ impl<'a> CacheReader<'a> {
         anchor: &AbsoluteSystemPath,
         previous_manifest: Option<&RestoreManifest>,
         new_manifest: &mut RestoreManifest,
+        blobs: Option<&BlobStore>,
     ) -> Result<(), CacheError> {
         let mut symlinks = Vec::new();
 
         for entry in tr.entries()? {
             let mut entry = entry?;
             let entry_type = entry.header().entry_type();
-            match restore_entry(&mut dir_cache, anchor, &mut entry, previous_manifest) {
+            match restore_entry(&mut dir_cache, anchor, &mut entry, previous_manifest, blobs) {
                 Err(CacheError::LinkTargetDoesNotExist(_, _)) => {
                     symlinks.push(entry);
                 }
@@ -185,6 +235,7 @@ fn restore_entry<T: Read>(
     anchor: &AbsoluteSystemPath,
     entry: &mut Entry<T>,
     manifest: Option<&RestoreManifest>,
+    blobs: Option<&BlobStore>,
 ) -> Result<(AnchoredSystemPathBuf, bool), CacheError> {
     let header = entry.header();
 
@@ -192,7 +243,7 @@ fn restore_entry<T: Read>(
         tar::EntryType::Directory => {
             restore_directory(dir_cache, anchor, entry).map(|p| (p, false))
         }
-        tar::EntryType::Regular => restore_regular(dir_cache, anchor, entry, manifest),
+        tar::EntryType::Regular => restore_regular(dir_cache, anchor, entry, manifest, blobs),
         tar::EntryType::Symlink => restore_symlink(dir_cache, anchor, entry).map(|p| (p, false)),
         ty => Err(CacheError::RestoreUnsupportedFileType(
             ty,
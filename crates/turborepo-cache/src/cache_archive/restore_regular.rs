@@ -1,4 +1,5 @@
 use std::{
+    backtrace::Backtrace,
     fs::{File, OpenOptions},
     io,
     io::Read,
@@ -8,7 +9,11 @@ use std::{
 use tar::Entry;
 use turbopath::{AbsoluteSystemPath, AnchoredSystemPath, AnchoredSystemPathBuf};
 
-use crate::{CacheError, cache_archive::restore_directory::CachedDirTree};
+use crate::{
+    CacheError,
+    blobs::{BlobStore, blob_digest},
+    cache_archive::restore_directory::CachedDirTree,
+};
 
 /// Returns `(path, true)` when the file was skipped (matched manifest),
 /// or `(path, false)` when it was written to disk.
@@ -17,6 +22,7 @@ pub fn restore_regular(
     anchor: &AbsoluteSystemPath,
     entry: &mut Entry<impl Read>,
     manifest: Option<&super::restore_manifest::RestoreManifest>,
+    blobs: Option<&BlobStore>,
 ) -> Result<(AnchoredSystemPathBuf, bool), CacheError> {
     let processed_name = AnchoredSystemPathBuf::from_system_path(&entry.path()?)?;
     let resolved_path = anchor.resolve(&processed_name);
@@ -43,7 +49,24 @@ pub fn restore_regular(
     let mode = 0;
 
     let mut file = open_for_restore(&resolved_path, sanitized_mode(mode))?;
-    io::copy(entry, &mut file)?;
+    match blob_digest(entry)? {
+        Some(digest) => {
+            let restored = match blobs {
+                Some(blobs) => blobs.restore(&digest, &mut file),
+                None => Err(CacheError::MissingBlob(digest, Backtrace::capture())),
+            };
+            if let Err(e) = restored {
+                // Don't leave an empty or truncated file behind in place of
+                // the output.
+                drop(file);
+                let _ = std::fs::remove_file(resolved_path.as_std_path());
+                return Err(e);
+            }
+        }
+        None => {
+            io::copy(entry, &mut file)?;
+        }
+    }
 
     Ok((processed_name, false))
 }
@@ -1,4 +1,8 @@
-use std::{backtrace::Backtrace, time::Duration};
+use std::{
+    backtrace::Backtrace,
+    collections::{BTreeSet, HashSet},
+    time::Duration,
+};
 
 use camino::Utf8Path;
 use serde::{Deserialize, Serialize};
@@ -8,7 +12,8 @@ use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{analytics, analytics::AnalyticsEvent};
 
 use crate::{
-    CacheError, CacheHitMetadata, CacheSource, LazyScmState,
+    ArtifactDetails, CacheError, CacheHitMetadata, CacheSource, LazyScmState,
+    blobs::{self, BlobRefs, BlobStore, GARBAGE_GRACE_PERIOD},
     cache_archive::{CacheReader, CacheWriter},
 };
 
@@ -16,20 +21,31 @@ pub struct FSCache {
     cache_directory: AbsoluteSystemPathBuf,
     analytics_recorder: Option<AnalyticsSender>,
     scm_state: LazyScmState,
+    /// Always used for reads, since the cache may contain deduplicated
+    /// artifacts from earlier runs
+    blobs: BlobStore,
+    deduplicate: bool,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
-struct CacheMetadata {
-    hash: String,
-    duration: u64,
+/// The `{hash}-meta.json` file written next to each artifact
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct CacheMetadata {
+    pub hash: String,
+    pub duration: u64,
+    #[serde(default)]
+    pub sha: Option<String>,
     #[serde(default)]
-    sha: Option<String>,
+    pub dirty_hash: Option<String>,
+    /// The task that produced the entry, if it was written by a run
     #[serde(default)]
-    dirty_hash: Option<String>,
+    pub task_id: Option<String>,
+    /// The signature of an artifact stored by a Remote Cache server
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
 }
 
 impl CacheMetadata {
-    fn read(path: &AbsoluteSystemPath) -> Result<CacheMetadata, CacheError> {
+    pub fn read(path: &AbsoluteSystemPath) -> Result<CacheMetadata, CacheError> {
         serde_json::from_str(&path.read_to_string()?)
             .map_err(|e| CacheError::InvalidMetadata(e, Backtrace::capture()))
     }
@@ -59,12 +75,21 @@ impl FSCache {
         cache_directory.create_dir_all()?;
 
         Ok(FSCache {
+            blobs: BlobStore::new(&cache_directory),
             cache_directory,
             analytics_recorder,
             scm_state,
+            deduplicate: false,
         })
     }
 
+    /// Stores the contents of files once in a content-addressed blob store
+    /// shared by every artifact, instead of in each artifact's archive.
+    pub fn with_deduplication(mut self, deduplicate: bool) -> Self {
+        self.deduplicate = deduplicate;
+        self
+    }
+
     pub(crate) fn cache_directory(&self) -> &AbsoluteSystemPath {
         &self.cache_directory
     }
@@ -90,15 +115,11 @@ impl FSCache {
         anchor: &AbsoluteSystemPath,
         hash: &str,
     ) -> Result<Option<(CacheHitMetadata, Vec<AnchoredSystemPathBuf>)>, CacheError> {
-        let cache_path = self
-            .cache_directory
-            .join_component(&format!("{hash}.tar.zst"));
-
         // Check if the archive exists before doing any work.
-        if !cache_path.as_path().exists() {
+        let Some(cache_path) = find_archive(&self.cache_directory, hash) else {
             self.log_fetch(analytics::CacheEvent::Miss, hash, 0);
             return Ok(None);
-        }
+        };
 
         let manifest_path = self
             .cache_directory
@@ -132,7 +153,7 @@ impl FSCache {
         // manifest so that individual files still matching on disk can be
         // skipped, avoiding unnecessary writes and filesystem notifications.
         let mut cache_reader = match CacheReader::open(&cache_path) {
-            Ok(reader) => reader,
+            Ok(reader) => reader.with_blobs(&self.blobs),
             Err(CacheError::IO(ref e, _)) if e.kind() == std::io::ErrorKind::NotFound => {
                 self.log_fetch(analytics::CacheEvent::Miss, hash, 0);
                 return Ok(None);
@@ -141,7 +162,16 @@ impl FSCache {
         };
 
         let (restored_files, new_manifest) =
-            cache_reader.restore(anchor, previous_manifest.as_ref())?;
+            match cache_reader.restore(anchor, previous_manifest.as_ref()) {
+                Err(CacheError::MissingBlob(digest, _)) => {
+                    // Another process may have garbage collected the blob, so
+                    // the task needs to run again.
+                    debug!("cache entry {hash} references missing blob {digest}");
+                    self.log_fetch(analytics::CacheEvent::Miss, hash, 0);
+                    return Ok(None);
+                }
+                result => result?,
+            };
 
         let manifest_path_owned = manifest_path.to_owned();
         std::thread::spawn(move || {
@@ -176,9 +206,13 @@ impl FSCache {
         buf.push_str(hash);
         let prefix_len = buf.len();
 
-        buf.push_str(".tar.zst");
+        buf.push_str(ARCHIVE_SUFFIX);
         if !std::path::Path::new(&buf).exists() {
-            return Ok(None);
+            buf.truncate(prefix_len);
+            buf.push_str(DEDUPLICATED_ARCHIVE_SUFFIX);
+            if !std::path::Path::new(&buf).exists() {
+                return Ok(None);
+            }
         }
 
         buf.truncate(prefix_len);
@@ -209,16 +243,26 @@ impl FSCache {
         hash: &str,
         files: &[AnchoredSystemPathBuf],
         duration: u64,
+        details: Option<&ArtifactDetails>,
     ) -> Result<(), CacheError> {
         let cache_path = self
             .cache_directory
-            .join_component(&format!("{hash}.tar.zst"));
+            .join_component(&archive_file_name(hash, self.deduplicate));
 
         let mut cache_item = CacheWriter::create(&cache_path)?;
         let mut manifest = crate::cache_archive::RestoreManifest::new();
+        let mut blob_digests = BTreeSet::new();
 
         for file in files {
-            cache_item.add_file(anchor, file)?;
+            if self.deduplicate {
+                if let Some(digest) =
+                    cache_item.add_file_to_blob_store(anchor, file, &self.blobs)?
+                {
+                    blob_digests.insert(digest);
+                }
+            } else {
+                cache_item.add_file(anchor, file)?;
+            }
 
             let source_path = anchor.resolve(file);
             let unix_path = file.to_unix();
@@ -231,9 +275,26 @@ impl FSCache {
             }
         }
 
+        if self.deduplicate {
+            blobs::write_refs(&self.cache_directory, hash, &blob_digests)?;
+        }
+
         // Finish the archive (performs atomic rename from temp to final path)
         cache_item.finish()?;
 
+        // Drop an archive for the same hash written with the other setting,
+        // so that each entry has a single archive.
+        let other_archive = self
+            .cache_directory
+            .join_component(&archive_file_name(hash, !self.deduplicate));
+        let _ = std::fs::remove_file(other_archive.as_std_path());
+        if !self.deduplicate {
+            let refs_path = self
+                .cache_directory
+                .join_component(&blobs::refs_file_name(hash));
+            let _ = std::fs::remove_file(refs_path.as_std_path());
+        }
+
         // Write manifest alongside the archive so the first fetch() can
         // skip decompression when outputs are still on disk.
         let manifest_path = self
@@ -241,49 +302,114 @@ impl FSCache {
             .join_component(&format!("{hash}-manifest.json"));
         let _ = manifest.write_atomic(&manifest_path);
 
-        // Write metadata file atomically using write-to-temp-then-rename pattern
-        let metadata_path = self
-            .cache_directory
-            .join_component(&format!("{hash}-meta.json"));
+        // Like the manifest, the hash inputs are only used to explain later
+        // cache misses, so failing to write them doesn't fail the put.
+        if let Some(hash_inputs) = details.and_then(|details| details.hash_inputs.as_deref()) {
+            let _ = self.write_atomic(&format!("{hash}-inputs.json"), hash_inputs);
+        }
 
+        // Write metadata file atomically using write-to-temp-then-rename pattern
         let resolved = self.scm_state.get();
         let meta = CacheMetadata {
             hash: hash.to_string(),
             duration,
             sha: resolved.and_then(|s| s.sha.clone()),
             dirty_hash: resolved.and_then(|s| s.dirty_hash.clone()),
+            task_id: details.map(|details| details.task_id.clone()),
+            tag: None,
         };
 
         let meta_json = serde_json::to_string(&meta)
             .map_err(|e| CacheError::InvalidMetadata(e, Backtrace::capture()))?;
 
-        // Write to temporary file then atomically rename
-        let temp_metadata_path = self
-            .cache_directory
-            .join_component(&format!(".{hash}-meta.json.{}.tmp", std::process::id()));
-
-        temp_metadata_path.create_with_contents(&meta_json)?;
-        temp_metadata_path.rename(&metadata_path)?;
+        self.write_atomic(&format!("{hash}-meta.json"), &meta_json)?;
 
         Ok(())
     }
 
+    fn write_atomic(&self, file_name: &str, contents: &str) -> Result<(), CacheError> {
+        write_atomic(&self.cache_directory, file_name, contents)
+    }
+
     pub fn evict(&self, max_age: Option<Duration>, max_size: Option<u64>) -> (u64, u64) {
         evict_cache_dir(&self.cache_directory, max_age, max_size)
     }
 }
 
+/// Archives whose regular files are stored in the blob store use a distinct
+/// name, so that versions of turbo that can't resolve blob references don't
+/// restore them as empty files.
+const DEDUPLICATED_ARCHIVE_SUFFIX: &str = ".dedup.tar.zst";
+const ARCHIVE_SUFFIX: &str = ".tar.zst";
+
+pub(crate) fn archive_file_name(hash: &str, deduplicated: bool) -> String {
+    if deduplicated {
+        format!("{hash}{DEDUPLICATED_ARCHIVE_SUFFIX}")
+    } else {
+        format!("{hash}{ARCHIVE_SUFFIX}")
+    }
+}
+
+const GLOBAL_INPUTS_SUFFIX: &str = "-global-inputs.json";
+
+/// The name of the file recording the global hash inputs shared by the
+/// entries whose `-inputs.json` refers to `global_hash`
+pub(crate) fn global_inputs_file_name(global_hash: &str) -> String {
+    format!("{global_hash}{GLOBAL_INPUTS_SUFFIX}")
+}
+
+/// The hash of the entry that an archive belongs to, or `None` if
+/// `file_name` isn't an archive
+pub(crate) fn archive_hash(file_name: &str) -> Option<&str> {
+    file_name
+        .strip_suffix(DEDUPLICATED_ARCHIVE_SUFFIX)
+        .or_else(|| file_name.strip_suffix(ARCHIVE_SUFFIX))
+}
+
+/// The path of the archive for `hash`, whether or not it was deduplicated
+pub(crate) fn find_archive(
+    cache_directory: &AbsoluteSystemPath,
+    hash: &str,
+) -> Option<AbsoluteSystemPathBuf> {
+    [false, true]
+        .into_iter()
+        .map(|deduplicated| cache_directory.join_component(&archive_file_name(hash, deduplicated)))
+        .find(|path| path.exists())
+}
+
+/// Writes a file in the cache directory using write-to-temp-then-rename
+pub(crate) fn write_atomic(
+    cache_directory: &AbsoluteSystemPath,
+    file_name: &str,
+    contents: &str,
+) -> Result<(), CacheError> {
+    let path = cache_directory.join_component(file_name);
+    let temp_path =
+        cache_directory.join_component(&format!(".{file_name}.{}.tmp", std::process::id()));
+
+    temp_path.create_with_contents(contents)?;
+    temp_path.rename(&path)?;
+    Ok(())
+}
+
 /// Evicts cache entries from the given directory based on age and/or
 /// total size constraints.
 ///
 /// Phase 1 (TTL): If `max_age` is `Some`, removes entries whose
-/// `.tar.zst` archive mtime is older than the cutoff. Orphaned `.tmp`
-/// files from crashed writes are cleaned up if older than 1 hour.
+/// `.tar.zst` (or `.dedup.tar.zst`) archive mtime is older than the cutoff.
+/// Orphaned `.tmp` files from crashed writes are cleaned up if older than 1
+/// hour.
 ///
 /// Phase 2 (LRU): If `max_size` is `Some` and the remaining cache
 /// exceeds the limit, deletes the oldest entries first until the
 /// total size is under the cap. Size includes sidecar files
-/// (`-meta.json`, `-manifest.json`).
+/// (`-meta.json`, `-manifest.json`, `-inputs.json`, `-blobs.json`) and
+/// deduplicated blobs, which only count towards the budget until the
+/// last entry referencing them is evicted.
+///
+/// Phase 3 (GC): Removes deduplicated blobs that no remaining entry
+/// references, and recorded global hash inputs (`-global-inputs.json`) that
+/// no remaining entry's `-inputs.json` refers to.
 ///
 /// Returns the number of entries removed and bytes reclaimed.
 /// Eviction is best-effort: individual file removal failures are
@@ -309,11 +435,24 @@ pub(crate) fn evict_cache_dir(
         mtime: std::time::SystemTime,
     }
     let mut remaining: Vec<ArchiveEntry> = Vec::new();
+    let mut global_inputs: Vec<GlobalInputsFile> = Vec::new();
 
     for entry in entries.filter_map(|e| e.ok()) {
         let name = entry.file_name();
         let name_str = name.to_string_lossy();
 
+        // Shared by many entries, so only removed once none refer to them
+        if let Some(global_hash) = name_str.strip_suffix(GLOBAL_INPUTS_SUFFIX) {
+            if let Ok(metadata) = entry.metadata() {
+                global_inputs.push(GlobalInputsFile {
+                    global_hash: global_hash.to_owned(),
+                    size: metadata.len(),
+                    mtime: metadata.modified().ok(),
+                });
+            }
+            continue;
+        }
+
         if name_str.ends_with(".tmp") {
             // Only clean up orphaned .tmp files older than 1 hour to avoid
             // racing with in-progress writes from concurrent processes.
@@ -332,9 +471,9 @@ pub(crate) fn evict_cache_dir(
             continue;
         }
 
-        if !name_str.ends_with(".tar.zst") {
+        let Some(hash) = archive_hash(&name_str) else {
             continue;
-        }
+        };
 
         let metadata = match entry.metadata() {
             Ok(m) => m,
@@ -346,18 +485,9 @@ pub(crate) fn evict_cache_dir(
             Err(_) => continue,
         };
 
-        let hash = name_str.trim_end_matches(".tar.zst").to_owned();
-        let mut entry_size = metadata.len();
-
+        let hash = hash.to_owned();
         // Include sidecar sizes for accurate budget tracking
-        let meta_path = cache_directory.join_component(&format!("{hash}-meta.json"));
-        if let Ok(m) = std::fs::symlink_metadata(meta_path.as_std_path()) {
-            entry_size += m.len();
-        }
-        let manifest_path = cache_directory.join_component(&format!("{hash}-manifest.json"));
-        if let Ok(m) = std::fs::symlink_metadata(manifest_path.as_std_path()) {
-            entry_size += m.len();
-        }
+        let entry_size = entry_size(cache_directory, &hash, metadata.len());
 
         // Phase 1: TTL eviction
         if let Some(max_age) = max_age {
@@ -376,13 +506,21 @@ pub(crate) fn evict_cache_dir(
         });
     }
 
+    let mut blob_refs = BlobRefs::load(
+        cache_directory,
+        remaining.iter().map(|e| e.hash.as_str()),
+        now,
+    );
+
     // Phase 2: LRU eviction by size
     if let Some(max_size) = max_size {
-        let mut total_size: u64 = remaining.iter().map(|e| e.size).sum();
+        let mut total_size: u64 =
+            remaining.iter().map(|e| e.size).sum::<u64>() + blob_refs.referenced_size();
 
         if total_size > max_size {
             remaining.sort_by_key(|a| a.mtime);
 
+            let mut evicted = 0;
             for entry in &remaining {
                 if total_size <= max_size {
                     break;
@@ -394,11 +532,23 @@ pub(crate) fn evict_cache_dir(
                     &mut reclaimed_bytes,
                 );
                 removed_count += 1;
-                total_size = total_size.saturating_sub(entry.size);
+                evicted += 1;
+                let freed = entry.size + blob_refs.release(&entry.hash);
+                total_size = total_size.saturating_sub(freed);
             }
+            remaining.drain(..evicted);
         }
     }
 
+    // Phase 3: blob and global hash inputs garbage collection
+    reclaimed_bytes += blob_refs.collect_garbage(now);
+    reclaimed_bytes += collect_global_inputs(
+        cache_directory,
+        global_inputs,
+        remaining.iter().map(|e| e.hash.as_str()),
+        now,
+    );
+
     if removed_count > 0 {
         info!(
             "cache eviction: removed {} entries, reclaimed {} bytes",
@@ -411,14 +561,88 @@ pub(crate) fn evict_cache_dir(
     (removed_count, reclaimed_bytes)
 }
 
+struct GlobalInputsFile {
+    global_hash: String,
+    size: u64,
+    mtime: Option<std::time::SystemTime>,
+}
+
+/// The part of an entry's `-inputs.json` that refers to the global inputs
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RecordedGlobalHash {
+    global_hash: String,
+}
+
+/// Removes the recorded global hash inputs that none of `hashes` refer to,
+/// returning the bytes reclaimed. Recently written files are kept as a run
+/// records its global inputs before the inputs of its tasks.
+fn collect_global_inputs<'a>(
+    cache_directory: &AbsoluteSystemPath,
+    global_inputs: Vec<GlobalInputsFile>,
+    hashes: impl Iterator<Item = &'a str>,
+    now: std::time::SystemTime,
+) -> u64 {
+    if global_inputs.is_empty() {
+        return 0;
+    }
+    let referenced: HashSet<String> = hashes
+        .filter_map(|hash| {
+            let path = cache_directory.join_component(&format!("{hash}-inputs.json"));
+            let contents = path.read_to_string().ok()?;
+            let recorded: RecordedGlobalHash = serde_json::from_str(&contents).ok()?;
+            Some(recorded.global_hash)
+        })
+        .collect();
+
+    let mut reclaimed = 0;
+    for file in global_inputs {
+        let is_stale = file
+            .mtime
+            .and_then(|mtime| now.duration_since(mtime).ok())
+            .is_some_and(|age| age >= GARBAGE_GRACE_PERIOD);
+        if referenced.contains(&file.global_hash) || !is_stale {
+            continue;
+        }
+        let path = cache_directory.join_component(&global_inputs_file_name(&file.global_hash));
+        if std::fs::remove_file(path.as_std_path()).is_ok() {
+            reclaimed += file.size;
+        }
+    }
+    reclaimed
+}
+
+/// The size of a cache entry's archive along with its sidecar files
+pub(crate) fn entry_size(
+    cache_directory: &AbsoluteSystemPath,
+    hash: &str,
+    archive_size: u64,
+) -> u64 {
+    let mut size = archive_size;
+    for sidecar in [
+        format!("{hash}-meta.json"),
+        format!("{hash}-manifest.json"),
+        format!("{hash}-inputs.json"),
+        blobs::refs_file_name(hash),
+    ] {
+        let path = cache_directory.join_component(&sidecar);
+        if let Ok(m) = std::fs::symlink_metadata(path.as_std_path()) {
+            size += m.len();
+        }
+    }
+    size
+}
+
 fn remove_cache_entry(
     cache_directory: &AbsoluteSystemPath,
     hash: &str,
     entry_size: u64,
     reclaimed_bytes: &mut u64,
 ) {
-    let archive_path = cache_directory.join_component(&format!("{hash}.tar.zst"));
-    let _ = std::fs::remove_file(archive_path.as_std_path());
+    for deduplicated in [false, true] {
+        let archive_path = cache_directory.join_component(&archive_file_name(hash, deduplicated));
+        let _ = std::fs::remove_file(archive_path.as_std_path());
+    }
 
     let meta_path = cache_directory.join_component(&format!("{hash}-meta.json"));
     let _ = std::fs::remove_file(meta_path.as_std_path());
@@ -426,6 +650,12 @@ fn remove_cache_entry(
     let manifest_path = cache_directory.join_component(&format!("{hash}-manifest.json"));
     let _ = std::fs::remove_file(manifest_path.as_std_path());
 
+    let inputs_path = cache_directory.join_component(&format!("{hash}-inputs.json"));
+    let _ = std::fs::remove_file(inputs_path.as_std_path());
+
+    let blob_refs_path = cache_directory.join_component(&blobs::refs_file_name(hash));
+    let _ = std::fs::remove_file(blob_refs_path.as_std_path());
+
     *reclaimed_bytes += entry_size;
 }
 
@@ -507,7 +737,13 @@ mod test {
             .iter()
             .map(|f| f.path().to_owned())
             .collect();
-        cache.put(repo_root_path, test_case.hash, &files, test_case.duration)?;
+        cache.put(
+            repo_root_path,
+            test_case.hash,
+            &files,
+            test_case.duration,
+            None,
+        )?;
 
         let (status, files) = cache.fetch(repo_root_path, test_case.hash)?.unwrap();
 
@@ -573,7 +809,7 @@ mod test {
             AnchoredSystemPathBuf::from_raw("packages/app/dist/file.txt")?,
         ];
 
-        cache.put(repo_root_path, "symlinked-parent", &files, 50)?;
+        cache.put(repo_root_path, "symlinked-parent", &files, 50, None)?;
         let result = cache.fetch(repo_root_path, "symlinked-parent")?;
 
         assert!(result.is_some());
@@ -621,17 +857,17 @@ mod test {
         let handle1 = {
             let files = files.clone();
             let repo_root = repo_root_path.to_owned();
-            tokio::spawn(async move { cache1.put(&repo_root, hash, &files, duration) })
+            tokio::spawn(async move { cache1.put(&repo_root, hash, &files, duration, None) })
         };
         let handle2 = {
             let files = files.clone();
             let repo_root = repo_root_path.to_owned();
-            tokio::spawn(async move { cache2.put(&repo_root, hash, &files, duration) })
+            tokio::spawn(async move { cache2.put(&repo_root, hash, &files, duration, None) })
         };
         let handle3 = {
             let files = files.clone();
             let repo_root = repo_root_path.to_owned();
-            tokio::spawn(async move { cache3.put(&repo_root, hash, &files, duration) })
+            tokio::spawn(async move { cache3.put(&repo_root, hash, &files, duration, None) })
         };
 
         // All writes should succeed (or at least not corrupt the cache)
@@ -678,7 +914,7 @@ mod test {
             None,
             LazyScmState::resolved(None),
         )?;
-        cache.put(repo_root_path, hash, &files, duration)?;
+        cache.put(repo_root_path, hash, &files, duration, None)?;
 
         // Update the source file
         test_file.create_with_contents("updated content")?;
@@ -700,7 +936,9 @@ mod test {
         let write_handle = {
             let files = files.clone();
             let repo_root = repo_root_path.to_owned();
-            tokio::spawn(async move { cache_write.put(&repo_root, hash, &files, duration + 1) })
+            tokio::spawn(
+                async move { cache_write.put(&repo_root, hash, &files, duration + 1, None) },
+            )
         };
 
         // Perform multiple reads while write is happening
@@ -743,7 +981,7 @@ mod test {
             None,
             LazyScmState::resolved(None),
         )?;
-        cache.put(repo_root_path, hash, &files, duration)?;
+        cache.put(repo_root_path, hash, &files, duration, None)?;
 
         // Perform concurrent reads
         let mut handles = Vec::new();
@@ -806,17 +1044,17 @@ mod test {
         let handle1 = {
             let files = files.clone();
             let repo_root = repo_root_path.to_owned();
-            tokio::spawn(async move { cache1.put(&repo_root, hash, &files, duration) })
+            tokio::spawn(async move { cache1.put(&repo_root, hash, &files, duration, None) })
         };
         let handle2 = {
             let files = files.clone();
             let repo_root = repo_root_path.to_owned();
-            tokio::spawn(async move { cache2.put(&repo_root, hash, &files, duration) })
+            tokio::spawn(async move { cache2.put(&repo_root, hash, &files, duration, None) })
         };
         let handle3 = {
             let files = files.clone();
             let repo_root = repo_root_path.to_owned();
-            tokio::spawn(async move { cache3.put(&repo_root, hash, &files, duration) })
+            tokio::spawn(async move { cache3.put(&repo_root, hash, &files, duration, None) })
         };
 
         // Wait for all writes to complete
@@ -868,7 +1106,16 @@ mod test {
         }));
         let cache = FSCache::new(Utf8Path::new("cache"), repo_root_path, None, scm_state)?;
         let files = vec![AnchoredSystemPathBuf::from_raw("test.txt")?];
-        cache.put(repo_root_path, "scm-test-hash", &files, 42)?;
+        cache.put(
+            repo_root_path,
+            "scm-test-hash",
+            &files,
+            42,
+            Some(&ArtifactDetails {
+                task_id: "web#build".to_string(),
+                hash_inputs: Some(r#"{"hash":"scm-test-hash"}"#.to_string()),
+            }),
+        )?;
 
         let meta_path = repo_root_path
             .join_component("cache")
@@ -878,6 +1125,11 @@ mod test {
         assert_eq!(meta_json["dirty_hash"], "fedcba654321");
         assert_eq!(meta_json["duration"], 42);
         assert_eq!(meta_json["hash"], "scm-test-hash");
+        assert_eq!(meta_json["task_id"], "web#build");
+        let inputs_path = repo_root_path
+            .join_component("cache")
+            .join_component("scm-test-hash-inputs.json");
+        assert_eq!(inputs_path.read_to_string()?, r#"{"hash":"scm-test-hash"}"#);
 
         Ok(())
     }
@@ -897,7 +1149,7 @@ mod test {
             LazyScmState::resolved(None),
         )?;
         let files = vec![AnchoredSystemPathBuf::from_raw("test.txt")?];
-        cache.put(repo_root_path, "no-scm-hash", &files, 10)?;
+        cache.put(repo_root_path, "no-scm-hash", &files, 10, None)?;
 
         let meta_path = repo_root_path
             .join_component("cache")
@@ -926,6 +1178,8 @@ mod test {
             duration: 99,
             sha: Some("deadbeef".to_string()),
             dirty_hash: Some("cafebabe".to_string()),
+            task_id: None,
+            tag: None,
         };
         let json = serde_json::to_string(&meta).unwrap();
         let deserialized: CacheMetadata = serde_json::from_str(&json).unwrap();
@@ -1182,7 +1436,7 @@ mod test {
             None,
             LazyScmState::resolved(None),
         )?;
-        cache.put(repo_root_path, hash, &files, 50)?;
+        cache.put(repo_root_path, hash, &files, 50, None)?;
 
         let stable_mtime_before = stable_file.symlink_metadata()?.modified()?;
 
@@ -1211,4 +1465,161 @@ mod test {
 
         Ok(())
     }
+
+    fn deduplicated_cache_with_outputs(
+        repo_root: &AbsoluteSystemPath,
+    ) -> Result<(FSCache, Vec<AnchoredSystemPathBuf>)> {
+        let dist = repo_root.join_component("dist");
+        dist.create_dir_all()?;
+        dist.join_component("vendor.js")
+            .create_with_contents("shared vendor chunk")?;
+        dist.join_component("index.js")
+            .create_with_contents("console.log('a')")?;
+        let files = vec![
+            AnchoredSystemPathBuf::from_raw("dist")?,
+            AnchoredSystemPathBuf::from_raw("dist/vendor.js")?,
+            AnchoredSystemPathBuf::from_raw("dist/index.js")?,
+        ];
+        let cache = FSCache::new(
+            Utf8Path::new("cache"),
+            repo_root,
+            None,
+            LazyScmState::resolved(None),
+        )?
+        .with_deduplication(true);
+        Ok((cache, files))
+    }
+
+    fn blob_count(cache_dir: &AbsoluteSystemPath) -> Result<usize> {
+        let mut count = 0;
+        for shard in std::fs::read_dir(cache_dir.join_component(blobs::BLOBS_DIR).as_std_path())? {
+            count += std::fs::read_dir(shard?.path())?.count();
+        }
+        Ok(count)
+    }
+
+    #[test]
+    fn test_deduplicated_round_trip() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        let (cache, files) = deduplicated_cache_with_outputs(repo_root)?;
+        let cache_dir = repo_root.join_component("cache");
+
+        cache.put(repo_root, "first", &files, 10, None)?;
+        repo_root
+            .join_components(&["dist", "index.js"])
+            .create_with_contents("console.log('b')")?;
+        cache.put(repo_root, "second", &files, 10, None)?;
+
+        // The vendor chunk is shared, so there are three blobs for four files
+        assert_eq!(blob_count(&cache_dir)?, 3);
+        // Deduplicated archives aren't named like complete archives, which
+        // older versions would restore as empty files
+        assert!(cache_dir.join_component("first.dedup.tar.zst").exists());
+        assert!(!cache_dir.join_component("first.tar.zst").exists());
+        let entries = crate::inspect::archive_entries(&cache_dir, "first")?.unwrap();
+        let vendor = entries
+            .iter()
+            .find(|entry| entry.path == "dist/vendor.js")
+            .unwrap();
+        assert!(vendor.blob.is_some());
+        assert_eq!(vendor.size, "shared vendor chunk".len() as u64);
+        crate::inspect::verify(&cache_dir, "first")?;
+
+        repo_root.join_component("dist").remove_dir_all()?;
+        let (_, restored) = cache.fetch(repo_root, "first")?.unwrap();
+        assert_eq!(restored.len(), 3);
+        assert_eq!(
+            repo_root
+                .join_components(&["dist", "vendor.js"])
+                .read_to_string()?,
+            "shared vendor chunk"
+        );
+        assert_eq!(
+            repo_root
+                .join_components(&["dist", "index.js"])
+                .read_to_string()?,
+            "console.log('a')"
+        );
+
+        // Artifacts written without deduplication can still be read
+        let plain = FSCache::new(
+            Utf8Path::new("cache"),
+            repo_root,
+            None,
+            LazyScmState::resolved(None),
+        )?;
+        repo_root.join_component("dist").remove_dir_all()?;
+        assert!(plain.fetch(repo_root, "second")?.is_some());
+        assert!(plain.exists("second")?.is_some());
+
+        // Rewriting an entry without deduplication replaces its archive
+        plain.put(repo_root, "second", &files, 10, None)?;
+        assert!(cache_dir.join_component("second.tar.zst").exists());
+        assert!(!cache_dir.join_component("second.dedup.tar.zst").exists());
+        assert!(!cache_dir.join_component("second-blobs.json").exists());
+        assert_eq!(crate::inspect::list(&cache_dir)?.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_deduplicated_fetch_with_missing_blob_is_a_miss() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        let (cache, files) = deduplicated_cache_with_outputs(repo_root)?;
+        let cache_dir = repo_root.join_component("cache");
+        cache.put(repo_root, "hash", &files, 10, None)?;
+
+        cache_dir
+            .join_component(blobs::BLOBS_DIR)
+            .remove_dir_all()?;
+        repo_root.join_component("dist").remove_dir_all()?;
+
+        assert!(crate::inspect::verify(&cache_dir, "hash").is_err());
+        assert!(cache.fetch(repo_root, "hash")?.is_none());
+        // The file whose blob is missing isn't left behind empty
+        for file in ["vendor.js", "index.js"] {
+            assert!(!repo_root.join_components(&["dist", file]).exists());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_evict_collects_unreferenced_blobs() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        let (cache, files) = deduplicated_cache_with_outputs(repo_root)?;
+        let cache_dir = repo_root.join_component("cache");
+
+        cache.put(repo_root, "stale", &files, 10, None)?;
+        repo_root
+            .join_components(&["dist", "index.js"])
+            .create_with_contents("console.log('b')")?;
+        cache.put(repo_root, "fresh", &files, 10, None)?;
+
+        // Backdate the stale entry and every blob past the grace period
+        let ten_days_ago = filetime::FileTime::from_system_time(
+            std::time::SystemTime::now() - Duration::from_secs(86400 * 10),
+        );
+        filetime::set_file_mtime(
+            cache_dir
+                .join_component("stale.dedup.tar.zst")
+                .as_std_path(),
+            ten_days_ago,
+        )?;
+        for shard in std::fs::read_dir(cache_dir.join_component(blobs::BLOBS_DIR).as_std_path())? {
+            for blob in std::fs::read_dir(shard?.path())? {
+                filetime::set_file_mtime(blob?.path(), ten_days_ago)?;
+            }
+        }
+
+        let (removed, _) = cache.evict(Some(Duration::from_secs(86400 * 7)), None);
+        assert_eq!(removed, 1);
+        assert!(!cache_dir.join_component("stale-blobs.json").exists());
+
+        // Only the blob unique to the stale entry is collected
+        assert_eq!(blob_count(&cache_dir)?, 2);
+        crate::inspect::verify(&cache_dir, "fresh")?;
+        Ok(())
+    }
 }
@@ -3,8 +3,11 @@ use std::{
     collections::HashMap,
     io::Write,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 
+use futures::future::join_all;
+use tokio::sync::{OnceCell, oneshot};
 use tracing::{debug, warn};
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
 use turborepo_analytics::AnalyticsSender;
@@ -13,16 +16,22 @@ use turborepo_api_client::{
     analytics::{self, AnalyticsEvent},
 };
 use turborepo_types::SecretString;
+use turborepo_vercel_api::{ArtifactQueryResponse, ArtifactQueryResult};
 
 use crate::{
     CacheError, CacheHitMetadata, CacheOpts, CacheSource, LazyScmState,
     cache_archive::{CacheReader, CacheWriter},
-    signature_authentication::{ArtifactSignatureAuthenticator, SignatureError},
+    signature_authentication::{
+        ArtifactSignatureAuthenticator, ArtifactSigner, PublicKeyAuthenticator, SignatureError,
+    },
     upload_progress::{UploadProgress, UploadProgressQuery},
 };
 
 pub type UploadMap = HashMap<String, UploadProgressQuery<10, 100>>;
 
+/// An `exists` call waiting for the artifact query that will answer it
+type PendingExists = (String, oneshot::Sender<Option<CacheHitMetadata>>);
+
 fn replace_api_auth_token(api_auth: &mut APIAuth, token: SecretString) -> bool {
     if api_auth.token.expose() == token.expose() {
         return false;
@@ -34,12 +43,46 @@ fn replace_api_auth_token(api_auth: &mut APIAuth, token: SecretString) -> bool {
 
 pub struct HTTPCache {
     client: APIClient,
-    signer_verifier: Option<ArtifactSignatureAuthenticator>,
+    signer_verifier: Option<ArtifactSigner>,
     repo_root: AbsoluteSystemPathBuf,
     api_auth: Arc<Mutex<APIAuth>>,
     analytics_recorder: Option<AnalyticsSender>,
     uploads: Arc<Mutex<UploadMap>>,
     scm_state: LazyScmState,
+    /// Whether the server supports `POST /v8/artifacts`, checked on first use
+    artifact_query: OnceCell<bool>,
+    pending_exists: Mutex<Vec<PendingExists>>,
+}
+
+/// Owns the batch of pending `exists` calls until it is sent. If the call
+/// collecting the batch is cancelled, the pending calls are dropped so that
+/// they fall back to individual requests instead of waiting forever.
+struct PendingExistsBatch<'a> {
+    pending: &'a Mutex<Vec<PendingExists>>,
+    taken: bool,
+}
+
+impl PendingExistsBatch<'_> {
+    fn take(mut self) -> Vec<PendingExists> {
+        self.taken = true;
+        std::mem::take(
+            &mut *self
+                .pending
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner()),
+        )
+    }
+}
+
+impl Drop for PendingExistsBatch<'_> {
+    fn drop(&mut self) {
+        if !self.taken {
+            self.pending
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clear();
+        }
+    }
 }
 
 impl HTTPCache {
@@ -57,32 +100,47 @@ impl HTTPCache {
         let enforce_key_length =
             remote_cache_opts.is_some_and(|o| o.enforce_signature_key_length());
 
-        let signer_verifier = if wants_signature {
-            let authenticator = ArtifactSignatureAuthenticator {
-                team_id: api_auth
-                    .team_id
-                    .as_deref()
-                    .unwrap_or_default()
-                    .as_bytes()
-                    .to_vec(),
-                secret_key_override: None,
-            };
-
-            if let Err(e) = authenticator.validate_key_length()
-                && matches!(e, SignatureError::SignatureKeyTooShort { .. })
-            {
-                if enforce_key_length {
-                    return Err(e.into());
+        let team_id = api_auth
+            .team_id
+            .as_deref()
+            .unwrap_or_default()
+            .as_bytes()
+            .to_vec();
+        let public_key = remote_cache_opts.and_then(|o| o.public_key());
+
+        let signer_verifier = match (wants_signature, public_key) {
+            (false, _) => None,
+            (true, Some(public_key)) => {
+                let authenticator = PublicKeyAuthenticator::from_env(team_id, public_key)?;
+                if !authenticator.can_sign() {
+                    debug!(
+                        "no private key for remoteCache.publicKey, artifacts will be verified but \
+                         not uploaded"
+                    );
                 }
-                warn!(
-                    "{e} This will become a fatal error in the next major version of Turborepo. \
-                     Enable `futureFlags.longerSignatureKey` in turbo.json to enforce this now."
-                );
+                Some(ArtifactSigner::PublicKey(authenticator))
             }
+            (true, None) => {
+                let authenticator = ArtifactSignatureAuthenticator {
+                    team_id,
+                    secret_key_override: None,
+                };
+
+                if let Err(e) = authenticator.validate_key_length()
+                    && matches!(e, SignatureError::SignatureKeyTooShort { .. })
+                {
+                    if enforce_key_length {
+                        return Err(e.into());
+                    }
+                    warn!(
+                        "{e} This will become a fatal error in the next major version of \
+                         Turborepo. Enable `futureFlags.longerSignatureKey` in turbo.json to \
+                         enforce this now."
+                    );
+                }
 
-            Some(authenticator)
-        } else {
-            None
+                Some(ArtifactSigner::SharedKey(authenticator))
+            }
         };
 
         Ok(HTTPCache {
@@ -93,6 +151,8 @@ impl HTTPCache {
             api_auth: Arc::new(Mutex::new(api_auth)),
             analytics_recorder,
             scm_state,
+            artifact_query: OnceCell::new(),
+            pending_exists: Mutex::new(Vec::new()),
         })
     }
 
@@ -188,6 +248,17 @@ impl HTTPCache {
         files: &[AnchoredSystemPathBuf],
         duration: u64,
     ) -> Result<(), CacheError> {
+        if self
+            .signer_verifier
+            .as_ref()
+            .is_some_and(|signer| !signer.can_sign())
+        {
+            // Uploading an unsigned artifact would only produce cache entries
+            // that every other machine rejects.
+            debug!("skipping upload of {hash}, no private key to sign it with");
+            return Ok(());
+        }
+
         let mut artifact_body = Vec::new();
         self.write(&mut artifact_body, anchor, files).await?;
         let body_len = artifact_body.len();
@@ -270,8 +341,140 @@ impl HTTPCache {
         Ok(())
     }
 
+    /// How long the first `exists` call of a batch waits for others to join
+    const EXISTS_BATCH_WINDOW: Duration = Duration::from_millis(5);
+    /// The most hashes sent in a single artifact query
+    const MAX_ARTIFACT_QUERY_HASHES: usize = 100;
+
+    /// Checks whether an artifact exists without downloading it.
+    ///
+    /// If the server supports artifact queries, concurrent calls are
+    /// batched into a single `POST /v8/artifacts` request. Hashes the query
+    /// can't answer are checked individually.
     #[tracing::instrument(skip_all)]
     pub async fn exists(&self, hash: &str) -> Result<Option<CacheHitMetadata>, CacheError> {
+        if !self.supports_artifact_query().await {
+            return self.exists_single(hash).await;
+        }
+
+        let (tx, rx) = oneshot::channel();
+        let is_first = {
+            let mut pending = self
+                .pending_exists
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner());
+            pending.push((hash.to_string(), tx));
+            pending.len() == 1
+        };
+
+        if is_first {
+            let batch = PendingExistsBatch {
+                pending: &self.pending_exists,
+                taken: false,
+            };
+            tokio::time::sleep(Self::EXISTS_BATCH_WINDOW).await;
+            self.query_artifacts(batch.take()).await;
+        }
+
+        match rx.await {
+            Ok(hit) => Ok(hit),
+            Err(_) => self.exists_single(hash).await,
+        }
+    }
+
+    /// Checks once whether the server advertises support for artifact
+    /// queries. If it doesn't, or the check fails, every `exists` call sends
+    /// its own `HEAD` request.
+    async fn supports_artifact_query(&self) -> bool {
+        *self
+            .artifact_query
+            .get_or_init(|| async {
+                let api_auth = self
+                    .api_auth
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner())
+                    .clone();
+                match self
+                    .client
+                    .get_caching_status(
+                        &api_auth.token,
+                        api_auth.team_id.as_deref(),
+                        api_auth.team_slug.as_deref(),
+                    )
+                    .await
+                {
+                    Ok(status) => status.artifact_query,
+                    Err(err) => {
+                        debug!("failed to check for artifact query support: {:?}", err);
+                        false
+                    }
+                }
+            })
+            .await
+    }
+
+    /// Answers a batch of `exists` calls. Senders for hashes that the server
+    /// couldn't answer are dropped, so that those calls fall back to
+    /// `exists_single`.
+    async fn query_artifacts(&self, batch: Vec<PendingExists>) {
+        let hashes: Vec<String> = batch.iter().map(|(hash, _)| hash.clone()).collect();
+        let responses = join_all(
+            hashes
+                .chunks(Self::MAX_ARTIFACT_QUERY_HASHES)
+                .map(|chunk| self.query_artifact_chunk(chunk)),
+        )
+        .await;
+
+        let mut results = ArtifactQueryResponse::new();
+        for response in responses {
+            match response {
+                Ok(response) => results.extend(response),
+                Err(err) => debug!("failed to query artifacts: {:?}", err),
+            }
+        }
+
+        for (hash, tx) in batch {
+            let hit = match results.get(&hash) {
+                Some(None) => None,
+                Some(Some(ArtifactQueryResult::Found(info))) => Some(CacheHitMetadata {
+                    source: CacheSource::Remote,
+                    time_saved: info.task_duration_ms,
+                    sha: info.sha.clone(),
+                    dirty_hash: info.dirty_hash.clone(),
+                }),
+                Some(Some(ArtifactQueryResult::Error { error })) => {
+                    debug!("failed to query artifact {}: {}", hash, error.message);
+                    continue;
+                }
+                None => continue,
+            };
+            let _ = tx.send(hit);
+        }
+    }
+
+    async fn query_artifact_chunk(
+        &self,
+        hashes: &[String],
+    ) -> Result<ArtifactQueryResponse, CacheError> {
+        let first_hash = hashes.first().map_or("", String::as_str);
+        self.execute_with_token_refresh(first_hash, |api_auth| {
+            let client = &self.client;
+            async move {
+                client
+                    .query_artifacts(
+                        hashes,
+                        &api_auth.token,
+                        api_auth.team_id.as_deref(),
+                        api_auth.team_slug.as_deref(),
+                    )
+                    .await
+            }
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn exists_single(&self, hash: &str) -> Result<Option<CacheHitMetadata>, CacheError> {
         let response = self
             .execute_with_token_refresh(hash, |api_auth| {
                 let client = &self.client;
@@ -391,7 +594,12 @@ impl HTTPCache {
             let is_valid = signer_verifier.validate(hash.as_bytes(), &body, &expected_tag)?;
 
             if !is_valid {
-                return Err(CacheError::InvalidTag(Backtrace::capture()));
+                return Err(match signer_verifier {
+                    ArtifactSigner::SharedKey(_) => CacheError::InvalidTag(Backtrace::capture()),
+                    ArtifactSigner::PublicKey(_) => {
+                        CacheError::UntrustedArtifact(hash.to_string(), Backtrace::capture())
+                    }
+                });
             }
 
             body
@@ -534,6 +742,7 @@ mod test {
             remote_cache_opts: None,
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
         let api_auth = APIAuth {
             team_id: Some("my-team".to_string()),
@@ -617,6 +826,7 @@ mod test {
             remote_cache_opts: None,
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
         let api_auth = APIAuth {
             team_id: Some("my-team".to_string()),
@@ -664,6 +874,77 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_http_cache_batches_exists() -> Result<()> {
+        let port = port_scanner::request_open_port().unwrap();
+        let (ready_tx, ready_rx) = tokio::sync::oneshot::channel();
+        let handle = tokio::spawn(start_test_server(port, Some(ready_tx)));
+
+        tokio::time::timeout(Duration::from_secs(5), ready_rx)
+            .await
+            .map_err(|_| anyhow::anyhow!("Test server failed to start within timeout"))??;
+
+        let test_case = &get_test_cases()[0];
+        let repo_root = tempdir()?;
+        let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path())?;
+        test_case.initialize(&repo_root_path)?;
+
+        let api_client = APIClient::new(
+            format!("http://localhost:{port}"),
+            Some(Duration::from_secs(200)),
+            None,
+            "2.0.0",
+            false,
+        )?;
+        let opts = CacheOpts {
+            cache_dir: ".turbo/cache".into(),
+            cache: Default::default(),
+            workers: 0,
+            remote_cache_opts: None,
+            cache_max_age: None,
+            cache_max_size: None,
+            cache_deduplication: false,
+        };
+        let api_auth = APIAuth {
+            team_id: Some("my-team".to_string()),
+            token: SecretString::new("my-token".to_string()),
+            team_slug: None,
+        };
+        let cache = HTTPCache::new(
+            api_client,
+            &opts,
+            repo_root_path.to_owned(),
+            api_auth,
+            None,
+            LazyScmState::resolved(None),
+        )
+        .unwrap();
+
+        let anchored_files: Vec<_> = test_case
+            .files
+            .iter()
+            .map(|f| f.path().to_owned())
+            .collect();
+        let hashes: Vec<_> = (0..3)
+            .map(|i| format!("{}-batch-{i}", test_case.hash))
+            .collect();
+        for (i, hash) in hashes.iter().take(2).enumerate() {
+            cache
+                .put(&repo_root_path, hash, &anchored_files, 100 + i as u64)
+                .await?;
+        }
+
+        let responses = try_join_all(hashes.iter().map(|hash| cache.exists(hash))).await?;
+        assert!(cache.artifact_query.get().copied().unwrap_or_default());
+        assert_eq!(responses[0].as_ref().map(|hit| hit.time_saved), Some(100));
+        assert_eq!(responses[1].as_ref().map(|hit| hit.time_saved), Some(101));
+        assert!(responses[2].is_none());
+        assert!(cache.pending_exists.lock().unwrap().is_empty());
+
+        handle.abort();
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_http_cache_no_scm_metadata() -> Result<()> {
         let port = port_scanner::request_open_port().unwrap();
@@ -695,6 +976,7 @@ mod test {
             remote_cache_opts: None,
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
         let api_auth = APIAuth {
             team_id: Some("my-team".to_string()),
@@ -797,6 +1079,7 @@ mod test {
             remote_cache_opts: None,
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
 
         let api_auth = APIAuth {
@@ -847,6 +1130,7 @@ mod test {
             remote_cache_opts: None,
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
 
         let initial_api_auth = APIAuth {
@@ -910,6 +1194,7 @@ mod test {
             remote_cache_opts: None,
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
 
         let api_auth = APIAuth {
@@ -994,9 +1279,11 @@ mod test {
             remote_cache_opts: Some(crate::RemoteCacheOpts::new(
                 None, true, // signature enabled
                 true, // enforce key length
+                None, // no public key
             )),
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
 
         let api_auth = APIAuth {
@@ -1053,9 +1340,11 @@ mod test {
             remote_cache_opts: Some(crate::RemoteCacheOpts::new(
                 None, true,  // signature enabled
                 false, // enforcement OFF (no future flag)
+                None,  // no public key
             )),
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
 
         let api_auth = APIAuth {
@@ -1104,9 +1393,11 @@ mod test {
             remote_cache_opts: Some(crate::RemoteCacheOpts::new(
                 None, true, // signature enabled
                 true, // enforce key length
+                None, // no public key
             )),
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
 
         let api_auth = APIAuth {
@@ -1158,9 +1449,11 @@ mod test {
             remote_cache_opts: Some(crate::RemoteCacheOpts::new(
                 None, true, // signature enabled
                 true, // enforce key length
+                None, // no public key
             )),
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
 
         let api_auth = APIAuth {
@@ -1186,4 +1479,58 @@ mod test {
         // It only enforces minimum length on keys that ARE set.
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn test_invalid_public_key_rejected() {
+        let repo_root = tempfile::tempdir().unwrap();
+        let repo_root_path = AbsoluteSystemPathBuf::try_from(repo_root.path()).unwrap();
+
+        let api_client = APIClient::new(
+            "http://localhost:8000",
+            Some(Duration::from_secs(200)),
+            None,
+            "2.0.0",
+            false,
+        )
+        .unwrap();
+
+        let opts = CacheOpts {
+            cache_dir: ".turbo/cache".into(),
+            cache: Default::default(),
+            workers: 0,
+            remote_cache_opts: Some(crate::RemoteCacheOpts::new(
+                None,
+                true,
+                false,
+                Some("not-a-public-key".to_string()),
+            )),
+            cache_max_age: None,
+            cache_max_size: None,
+            cache_deduplication: false,
+        };
+
+        let api_auth = APIAuth {
+            team_id: Some("my-team".to_string()),
+            token: SecretString::new("my-token".to_string()),
+            team_slug: None,
+        };
+
+        let result = HTTPCache::new(
+            api_client,
+            &opts,
+            repo_root_path,
+            api_auth,
+            None,
+            LazyScmState::resolved(None),
+        );
+
+        let err = result
+            .err()
+            .expect("expected an error for an invalid public key");
+        let source = std::error::Error::source(&err).expect("should have a source error");
+        assert_snapshot!(
+            source.to_string(),
+            @"remoteCache.publicKey is not a valid Ed25519 public key. Expected a base64-encoded 32-byte key or a PEM-encoded public key"
+        );
+    }
 }
@@ -0,0 +1,348 @@
+use std::{
+    backtrace::Backtrace,
+    time::{Duration, SystemTime},
+};
+
+use serde::Serialize;
+use turbopath::AbsoluteSystemPath;
+
+use crate::{
+    CacheError,
+    blobs::BlobStore,
+    cache_archive::CacheReader,
+    fs::{
+        CacheMetadata, archive_file_name, archive_hash, entry_size, evict_cache_dir, find_archive,
+        global_inputs_file_name, write_atomic,
+    },
+};
+
+/// An artifact in the local cache directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub hash: String,
+    /// The size of the archive along with its sidecar files
+    pub size: u64,
+    /// When the archive was last written
+    pub modified: SystemTime,
+    /// How long the task that produced the entry took, in milliseconds
+    pub duration: Option<u64>,
+    /// The task that produced the entry. Only known for entries written by
+    /// versions of turbo that record it.
+    pub task_id: Option<String>,
+    /// The commit that the entry was produced from
+    pub sha: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveEntry {
+    pub path: String,
+    pub kind: ArchiveEntryKind,
+    pub size: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_target: Option<String>,
+    /// The digest of the deduplicated contents of a regular file
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blob: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArchiveEntryKind {
+    File,
+    Directory,
+    Symlink,
+    Other,
+}
+
+/// Lists the artifacts in `cache_directory`, most recently written first.
+/// A missing directory is treated as an empty cache.
+pub fn list(cache_directory: &AbsoluteSystemPath) -> Result<Vec<CacheEntry>, CacheError> {
+    let dir_entries = match std::fs::read_dir(cache_directory.as_std_path()) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut entries = Vec::new();
+    for dir_entry in dir_entries.filter_map(|e| e.ok()) {
+        let name = dir_entry.file_name();
+        let Some(hash) = name.to_str().and_then(archive_hash) else {
+            continue;
+        };
+        let Ok(metadata) = dir_entry.metadata() else {
+            continue;
+        };
+
+        let meta =
+            CacheMetadata::read(&cache_directory.join_component(&format!("{hash}-meta.json"))).ok();
+        entries.push(CacheEntry {
+            hash: hash.to_owned(),
+            size: entry_size(cache_directory, hash, metadata.len()),
+            modified: metadata.modified()?,
+            duration: meta.as_ref().map(|meta| meta.duration),
+            task_id: meta.as_ref().and_then(|meta| meta.task_id.clone()),
+            sha: meta.and_then(|meta| meta.sha),
+        });
+    }
+
+    entries.sort_by(|a, b| {
+        b.modified
+            .cmp(&a.modified)
+            .then_with(|| a.hash.cmp(&b.hash))
+    });
+    Ok(entries)
+}
+
+/// Lists the files in the artifact for `hash` without restoring them.
+/// Returns `None` if there is no artifact for `hash`.
+pub fn archive_entries(
+    cache_directory: &AbsoluteSystemPath,
+    hash: &str,
+) -> Result<Option<Vec<ArchiveEntry>>, CacheError> {
+    let Some(archive_path) = find_archive(cache_directory, hash) else {
+        return Ok(None);
+    };
+    let mut entries = CacheReader::open(&archive_path)?.entries()?;
+
+    let blobs = BlobStore::new(cache_directory);
+    for entry in &mut entries {
+        if let Some(size) = entry.blob.as_deref().and_then(|blob| blobs.size(blob)) {
+            entry.size = size;
+        }
+    }
+    Ok(Some(entries))
+}
+
+/// Reads the hash inputs recorded for the artifact, as JSON. Returns `None`
+/// if the artifact doesn't exist or was written without them.
+pub fn hash_inputs(
+    cache_directory: &AbsoluteSystemPath,
+    hash: &str,
+) -> Result<Option<String>, CacheError> {
+    read_if_exists(&cache_directory.join_component(&format!("{hash}-inputs.json")))
+}
+
+/// Records the global hash inputs of a run, as JSON. Every artifact that the
+/// run writes shares them, so they're stored once per global hash rather than
+/// with each artifact.
+pub fn write_global_hash_inputs(
+    cache_directory: &AbsoluteSystemPath,
+    global_hash: &str,
+    contents: &str,
+) -> Result<(), CacheError> {
+    cache_directory.create_dir_all()?;
+    write_atomic(
+        cache_directory,
+        &global_inputs_file_name(global_hash),
+        contents,
+    )
+}
+
+/// Reads the global hash inputs recorded for `global_hash`, as JSON
+pub fn global_hash_inputs(
+    cache_directory: &AbsoluteSystemPath,
+    global_hash: &str,
+) -> Result<Option<String>, CacheError> {
+    read_if_exists(&cache_directory.join_component(&global_inputs_file_name(global_hash)))
+}
+
+fn read_if_exists(path: &AbsoluteSystemPath) -> Result<Option<String>, CacheError> {
+    match path.read_to_string() {
+        Ok(contents) => Ok(Some(contents)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Checks that the artifact for `hash` can be restored by reading its
+/// metadata, the whole archive, and checking that any deduplicated file
+/// contents it references are present.
+pub fn verify(cache_directory: &AbsoluteSystemPath, hash: &str) -> Result<(), CacheError> {
+    CacheMetadata::read(&cache_directory.join_component(&format!("{hash}-meta.json")))?;
+    let archive_path = find_archive(cache_directory, hash)
+        .unwrap_or_else(|| cache_directory.join_component(&archive_file_name(hash, false)));
+    let entries = CacheReader::open(&archive_path)?.entries()?;
+
+    let blobs = BlobStore::new(cache_directory);
+    for blob in entries.into_iter().filter_map(|entry| entry.blob) {
+        if !blobs.path(&blob)?.exists() {
+            return Err(CacheError::MissingBlob(blob, Backtrace::capture()));
+        }
+    }
+    Ok(())
+}
+
+/// Removes artifacts older than `max_age`, and then the oldest artifacts
+/// until the cache is smaller than `max_size`. Returns the number of
+/// artifacts removed and the bytes reclaimed.
+pub fn prune(
+    cache_directory: &AbsoluteSystemPath,
+    max_age: Option<Duration>,
+    max_size: Option<u64>,
+) -> (u64, u64) {
+    evict_cache_dir(cache_directory, max_age, max_size)
+}
+
+#[cfg(test)]
+mod test {
+    use anyhow::Result;
+    use camino::Utf8Path;
+    use tempfile::tempdir;
+    use turbopath::AnchoredSystemPathBuf;
+
+    use super::*;
+    use crate::{ArtifactDetails, LazyScmState, fs::FSCache};
+
+    fn cache_with_entry(repo_root: &AbsoluteSystemPath) -> Result<FSCache> {
+        repo_root
+            .join_components(&["dist", "index.js"])
+            .ensure_dir()?;
+        repo_root
+            .join_components(&["dist", "index.js"])
+            .create_with_contents("console.log('hi')")?;
+        let cache = FSCache::new(
+            Utf8Path::new("cache"),
+            repo_root,
+            None,
+            LazyScmState::resolved(None),
+        )?;
+        let files = vec![
+            AnchoredSystemPathBuf::from_raw("dist")?,
+            AnchoredSystemPathBuf::from_raw("dist/index.js")?,
+        ];
+        cache.put(
+            repo_root,
+            "abc123",
+            &files,
+            120,
+            Some(&ArtifactDetails {
+                task_id: "web#build".to_string(),
+                hash_inputs: Some(r#"{"hash":"abc123"}"#.to_string()),
+            }),
+        )?;
+        Ok(cache)
+    }
+
+    #[test]
+    fn test_list_and_show() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        cache_with_entry(repo_root)?;
+        let cache_dir = repo_root.join_component("cache");
+
+        let entries = list(&cache_dir)?;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].hash, "abc123");
+        assert_eq!(entries[0].task_id.as_deref(), Some("web#build"));
+        assert_eq!(entries[0].duration, Some(120));
+        assert!(entries[0].size > 0);
+
+        let files = archive_entries(&cache_dir, "abc123")?.unwrap();
+        assert_eq!(
+            files
+                .iter()
+                .map(|entry| (entry.path.as_str(), entry.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                ("dist/", ArchiveEntryKind::Directory),
+                ("dist/index.js", ArchiveEntryKind::File),
+            ]
+        );
+        assert_eq!(archive_entries(&cache_dir, "missing")?, None);
+
+        assert_eq!(
+            hash_inputs(&cache_dir, "abc123")?.as_deref(),
+            Some(r#"{"hash":"abc123"}"#)
+        );
+        assert_eq!(hash_inputs(&cache_dir, "missing")?, None);
+
+        write_global_hash_inputs(&cache_dir, "global123", r#"{"hash":"global123"}"#)?;
+        assert_eq!(
+            global_hash_inputs(&cache_dir, "global123")?.as_deref(),
+            Some(r#"{"hash":"global123"}"#)
+        );
+        assert_eq!(global_hash_inputs(&cache_dir, "missing")?, None);
+        // the global inputs aren't an artifact
+        assert_eq!(list(&cache_dir)?.len(), 1);
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune_removes_unreferenced_global_inputs() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        cache_with_entry(repo_root)?;
+        let cache_dir = repo_root.join_component("cache");
+        cache_dir
+            .join_component("abc123-inputs.json")
+            .create_with_contents(r#"{"hash":"abc123","globalHash":"kept"}"#)?;
+
+        let two_hours_ago =
+            filetime::FileTime::from_system_time(SystemTime::now() - Duration::from_secs(7200));
+        for global_hash in ["kept", "orphaned", "recent"] {
+            write_global_hash_inputs(&cache_dir, global_hash, r#"{"files":{}}"#)?;
+            if global_hash != "recent" {
+                filetime::set_file_mtime(
+                    cache_dir
+                        .join_component(&global_inputs_file_name(global_hash))
+                        .as_std_path(),
+                    two_hours_ago,
+                )?;
+            }
+        }
+
+        let (removed, reclaimed) = prune(&cache_dir, Some(Duration::from_secs(86400)), None);
+
+        assert_eq!(removed, 0);
+        assert!(reclaimed > 0);
+        assert!(global_hash_inputs(&cache_dir, "kept")?.is_some());
+        assert_eq!(global_hash_inputs(&cache_dir, "orphaned")?, None);
+        // it may belong to a run that hasn't recorded its task inputs yet
+        assert!(global_hash_inputs(&cache_dir, "recent")?.is_some());
+
+        // once the entry referring to them is gone, so are the global inputs
+        prune(&cache_dir, None, Some(1));
+        assert_eq!(list(&cache_dir)?.len(), 0);
+        assert_eq!(global_hash_inputs(&cache_dir, "kept")?, None);
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_missing_directory() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+
+        assert!(list(&repo_root.join_component("cache"))?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_detects_truncated_archive() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        cache_with_entry(repo_root)?;
+        let cache_dir = repo_root.join_component("cache");
+        verify(&cache_dir, "abc123")?;
+
+        let archive = cache_dir.join_component("abc123.tar.zst");
+        let bytes = std::fs::read(archive.as_std_path())?;
+        std::fs::write(archive.as_std_path(), &bytes[..bytes.len() / 2])?;
+
+        assert!(verify(&cache_dir, "abc123").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify_detects_missing_metadata() -> Result<()> {
+        let repo_root = tempdir()?;
+        let repo_root = AbsoluteSystemPath::from_std_path(repo_root.path())?;
+        cache_with_entry(repo_root)?;
+        let cache_dir = repo_root.join_component("cache");
+
+        cache_dir.join_component("abc123-meta.json").remove_file()?;
+
+        assert!(verify(&cache_dir, "abc123").is_err());
+        Ok(())
+    }
+}
@@ -12,6 +12,8 @@
 
 /// A wrapper for the cache that uses a worker pool to perform cache operations
 mod async_cache;
+/// Content-addressed storage that deduplicates files across local artifacts
+mod blobs;
 /// The core cache creation and restoration logic.
 pub mod cache_archive;
 pub mod config;
@@ -21,6 +23,8 @@ pub mod duration;
 pub mod fs;
 /// Remote cache
 pub mod http;
+/// Read-only inspection and on-demand pruning of the local cache directory
+pub mod inspect;
 /// A wrapper that allows reads and writes from the file system and remote
 /// cache.
 mod multiplexer;
@@ -58,6 +62,11 @@ pub enum CacheError {
     ArtifactTagMissing(#[backtrace] Backtrace),
     #[error("invalid artifact verification tag")]
     InvalidTag(#[backtrace] Backtrace),
+    #[error(
+        "artifact verification failed: {0} was not signed by the private key matching \
+         remoteCache.publicKey"
+    )]
+    UntrustedArtifact(String, #[backtrace] Backtrace),
     #[error("cannot untar file to {0}")]
     InvalidFilePath(String, #[backtrace] Backtrace),
     #[error("failed to contact remote cache: {0}")]
@@ -98,6 +107,10 @@ pub enum CacheError {
     MetadataWriteFailure(serde_json::Error, #[backtrace] Backtrace),
     #[error("Unable to perform write as cache is shutting down")]
     CacheShuttingDown,
+    #[error("cache artifact references missing file contents {0}")]
+    MissingBlob(String, #[backtrace] Backtrace),
+    #[error("cache artifact references invalid file contents {0}")]
+    InvalidBlob(String, #[backtrace] Backtrace),
     #[error("Invalid restore manifest: {0}")]
     InvalidManifest(String),
     #[error("Unable to determine config cache base")]
@@ -206,6 +219,15 @@ pub enum CacheSource {
     Remote,
 }
 
+/// Information about the task that produced an artifact, stored next to the
+/// artifact in the local cache
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArtifactDetails {
+    pub task_id: String,
+    /// The inputs to the task's hash, serialized as JSON
+    pub hash_inputs: Option<String>,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct CacheHitMetadata {
     pub source: CacheSource,
@@ -293,6 +315,10 @@ pub struct CacheOpts {
     /// `None` or `0` means no size limit.
     #[serde(skip)]
     pub cache_max_size: Option<u64>,
+    /// Store the contents of files in the local cache once, shared between
+    /// every artifact that contains them.
+    #[serde(skip)]
+    pub cache_deduplication: bool,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -300,6 +326,9 @@ pub struct RemoteCacheOpts {
     unused_team_id: Option<String>,
     signature: bool,
     enforce_signature_key_length: bool,
+    /// Ed25519 public key used to verify artifacts instead of the shared
+    /// HMAC key
+    public_key: Option<String>,
 }
 
 impl RemoteCacheOpts {
@@ -307,15 +336,21 @@ impl RemoteCacheOpts {
         unused_team_id: Option<String>,
         signature: bool,
         enforce_signature_key_length: bool,
+        public_key: Option<String>,
     ) -> Self {
         Self {
             unused_team_id,
             signature,
             enforce_signature_key_length,
+            public_key,
         }
     }
 
     pub fn enforce_signature_key_length(&self) -> bool {
         self.enforce_signature_key_length
     }
+
+    pub fn public_key(&self) -> Option<&str> {
+        self.public_key.as_deref()
+    }
 }
@@ -9,7 +9,7 @@ use turborepo_analytics::AnalyticsSender;
 use turborepo_api_client::{APIAuth, APIClient};
 
 use crate::{
-    CacheConfig, CacheError, CacheHitMetadata, CacheOpts, LazyScmState,
+    ArtifactDetails, CacheConfig, CacheError, CacheHitMetadata, CacheOpts, LazyScmState,
     fs::FSCache,
     http::{HTTPCache, UploadMap},
 };
@@ -65,6 +65,7 @@ impl CacheMultiplexer {
                     analytics_recorder.clone(),
                     scm_state.clone(),
                 )
+                .map(|cache| cache.with_deduplication(opts.cache_deduplication))
             })
             .transpose()?;
 
@@ -131,6 +132,7 @@ impl CacheMultiplexer {
         key: &str,
         files: &[AnchoredSystemPathBuf],
         duration: u64,
+        details: Option<&ArtifactDetails>,
     ) -> Result<(), CacheError> {
         // Wait for the background SCM computation to finish so that both
         // the FS sidecar metadata and the HTTP headers carry provenance
@@ -140,7 +142,7 @@ impl CacheMultiplexer {
         if self.cache_config.local.write {
             self.fs
                 .as_ref()
-                .map(|fs| fs.put(anchor, key, files, duration))
+                .map(|fs| fs.put(anchor, key, files, duration, details))
                 .transpose()?;
         }
 
@@ -209,7 +211,7 @@ impl CacheMultiplexer {
             if self.cache_config.local.write
                 && let Some(fs) = &self.fs
             {
-                let _ = fs.put(anchor, key, &files, hit_metadata.time_saved);
+                let _ = fs.put(anchor, key, &files, hit_metadata.time_saved, None);
             }
 
             return Ok(Some((hit_metadata, files)));
@@ -3,13 +3,23 @@ use std::env;
 use base64::{Engine, prelude::BASE64_STANDARD};
 use hmac::{Hmac, Mac};
 use os_str_bytes::OsStringBytes;
-use sha2::Sha256;
+use ring::signature::{ED25519, Ed25519KeyPair, KeyPair, UnparsedPublicKey};
+use sha2::{Digest, Sha256, Sha512};
 use thiserror::Error;
 
 type HmacSha256 = Hmac<Sha256>;
 
 pub const MIN_SIGNATURE_KEY_LENGTH: usize = 32;
 const SIGNATURE_MESSAGE_PREFIX: &[u8] = b"artifact-signature:v2";
+const PUBLIC_KEY_SIGNATURE_MESSAGE_PREFIX: &[u8] = b"artifact-signature:ed25519:v1";
+const PRIVATE_KEY_ENV_VAR: &str = "TURBO_REMOTE_CACHE_PRIVATE_KEY";
+
+const ED25519_KEY_LENGTH: usize = 32;
+// DER prefix of a SubjectPublicKeyInfo holding an Ed25519 key, which is what
+// `openssl pkey -pubout` produces. The raw key follows it.
+const ED25519_SPKI_PREFIX: &[u8] = &[
+    0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00,
+];
 
 #[derive(Debug, Error)]
 pub enum SignatureError {
@@ -33,6 +43,72 @@ pub enum SignatureError {
     Base64EncodingError(#[from] base64::DecodeError),
     #[error(transparent)]
     Hmac(#[from] hmac::digest::InvalidLength),
+    #[error(
+        "remoteCache.publicKey is not a valid Ed25519 public key. Expected a base64-encoded \
+         32-byte key or a PEM-encoded public key"
+    )]
+    InvalidPublicKey,
+    #[error("{PRIVATE_KEY_ENV_VAR} is not a valid Ed25519 private key: {0}")]
+    InvalidPrivateKey(String),
+    #[error("{PRIVATE_KEY_ENV_VAR} does not match the public key in remoteCache.publicKey")]
+    PrivateKeyMismatch,
+    #[error(
+        "cannot sign artifact because {PRIVATE_KEY_ENV_VAR} is not set. Only machines with the \
+         private key can upload artifacts when remoteCache.publicKey is set"
+    )]
+    NoPrivateKey,
+}
+
+/// How artifacts are signed and verified when `remoteCache.signature` is
+/// enabled.
+#[derive(Debug)]
+pub enum ArtifactSigner {
+    /// HMAC-SHA256 with a secret key shared by every machine
+    SharedKey(ArtifactSignatureAuthenticator),
+    /// Ed25519 with a private key for signing and the public key from
+    /// `remoteCache.publicKey` for verifying
+    PublicKey(PublicKeyAuthenticator),
+}
+
+impl ArtifactSigner {
+    /// Whether this machine holds the key needed to sign uploads
+    pub fn can_sign(&self) -> bool {
+        match self {
+            ArtifactSigner::SharedKey(_) => true,
+            ArtifactSigner::PublicKey(authenticator) => authenticator.can_sign(),
+        }
+    }
+
+    pub fn generate_tag(
+        &self,
+        hash: &[u8],
+        artifact_body: &[u8],
+    ) -> Result<String, SignatureError> {
+        match self {
+            ArtifactSigner::SharedKey(authenticator) => {
+                authenticator.generate_tag(hash, artifact_body)
+            }
+            ArtifactSigner::PublicKey(authenticator) => {
+                authenticator.generate_tag(hash, artifact_body)
+            }
+        }
+    }
+
+    pub fn validate(
+        &self,
+        hash: &[u8],
+        artifact_body: &[u8],
+        expected_tag: &str,
+    ) -> Result<bool, SignatureError> {
+        match self {
+            ArtifactSigner::SharedKey(authenticator) => {
+                authenticator.validate(hash, artifact_body, expected_tag)
+            }
+            ArtifactSigner::PublicKey(authenticator) => {
+                Ok(authenticator.validate(hash, artifact_body, expected_tag))
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -132,6 +208,123 @@ fn update_message_field(mac: &mut HmacSha256, field: &[u8]) {
     mac.update(field);
 }
 
+/// Signs artifacts with an Ed25519 private key and verifies them with the
+/// matching public key. Unlike [`ArtifactSignatureAuthenticator`], machines
+/// that can verify artifacts can't forge them.
+#[derive(Debug)]
+pub struct PublicKeyAuthenticator {
+    team_id: Vec<u8>,
+    public_key: Vec<u8>,
+    key_pair: Option<Ed25519KeyPair>,
+}
+
+impl PublicKeyAuthenticator {
+    /// Creates an authenticator for `public_key`, reading the private key
+    /// from `TURBO_REMOTE_CACHE_PRIVATE_KEY` if it is set.
+    pub fn from_env(team_id: Vec<u8>, public_key: &str) -> Result<Self, SignatureError> {
+        let private_key = match env::var(PRIVATE_KEY_ENV_VAR) {
+            Ok(private_key) if !private_key.trim().is_empty() => Some(private_key),
+            Ok(_) | Err(env::VarError::NotPresent) => None,
+            Err(env::VarError::NotUnicode(_)) => {
+                return Err(SignatureError::InvalidPrivateKey(
+                    "key is not valid unicode".to_string(),
+                ));
+            }
+        };
+        Self::new(team_id, public_key, private_key.as_deref())
+    }
+
+    /// Keys are either base64-encoded raw keys or PEM documents, as produced
+    /// by `openssl genpkey -algorithm ed25519`.
+    pub fn new(
+        team_id: Vec<u8>,
+        public_key: &str,
+        private_key: Option<&str>,
+    ) -> Result<Self, SignatureError> {
+        let public_key = decode_key(public_key).map_err(|_| SignatureError::InvalidPublicKey)?;
+        let public_key = match public_key.strip_prefix(ED25519_SPKI_PREFIX) {
+            Some(raw) => raw.to_vec(),
+            None => public_key,
+        };
+        if public_key.len() != ED25519_KEY_LENGTH {
+            return Err(SignatureError::InvalidPublicKey);
+        }
+
+        let key_pair = private_key
+            .map(|private_key| {
+                let private_key = decode_key(private_key)
+                    .map_err(|e| SignatureError::InvalidPrivateKey(e.to_string()))?;
+                if private_key.len() == ED25519_KEY_LENGTH {
+                    return Ed25519KeyPair::from_seed_and_public_key(&private_key, &public_key)
+                        .map_err(|_| SignatureError::PrivateKeyMismatch);
+                }
+                let key_pair = Ed25519KeyPair::from_pkcs8_maybe_unchecked(&private_key)
+                    .map_err(|e| SignatureError::InvalidPrivateKey(e.to_string()))?;
+                if key_pair.public_key().as_ref() != public_key.as_slice() {
+                    return Err(SignatureError::PrivateKeyMismatch);
+                }
+                Ok(key_pair)
+            })
+            .transpose()?;
+
+        Ok(Self {
+            team_id,
+            public_key,
+            key_pair,
+        })
+    }
+
+    pub fn can_sign(&self) -> bool {
+        self.key_pair.is_some()
+    }
+
+    // The artifact body can be large, so sign a digest of the message rather
+    // than copying every field into a single buffer.
+    fn message_digest(&self, hash: &[u8], artifact_body: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha512::new();
+        for field in [
+            PUBLIC_KEY_SIGNATURE_MESSAGE_PREFIX,
+            hash,
+            self.team_id.as_slice(),
+            artifact_body,
+        ] {
+            hasher.update((field.len() as u64).to_le_bytes());
+            hasher.update(field);
+        }
+        hasher.finalize().to_vec()
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn generate_tag(
+        &self,
+        hash: &[u8],
+        artifact_body: &[u8],
+    ) -> Result<String, SignatureError> {
+        let key_pair = self.key_pair.as_ref().ok_or(SignatureError::NoPrivateKey)?;
+        let signature = key_pair.sign(&self.message_digest(hash, artifact_body));
+        Ok(BASE64_STANDARD.encode(signature))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub fn validate(&self, hash: &[u8], artifact_body: &[u8], expected_tag: &str) -> bool {
+        let Ok(signature) = BASE64_STANDARD.decode(expected_tag) else {
+            return false;
+        };
+        UnparsedPublicKey::new(&ED25519, &self.public_key)
+            .verify(&self.message_digest(hash, artifact_body), &signature)
+            .is_ok()
+    }
+}
+
+fn decode_key(key: &str) -> Result<Vec<u8>, base64::DecodeError> {
+    let encoded = key
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("-----"))
+        .collect::<String>();
+    BASE64_STANDARD.decode(encoded)
+}
+
 #[cfg(test)]
 mod tests {
     use anyhow::Result;
@@ -349,6 +542,95 @@ mod tests {
         assert!(auth.validate(b"hash", b"body", &tag).unwrap());
     }
 
+    fn generate_key_pair() -> (String, String) {
+        let pkcs8 = Ed25519KeyPair::generate_pkcs8(&ring::rand::SystemRandom::new()).unwrap();
+        let key_pair = Ed25519KeyPair::from_pkcs8(pkcs8.as_ref()).unwrap();
+        (
+            BASE64_STANDARD.encode(key_pair.public_key()),
+            BASE64_STANDARD.encode(pkcs8),
+        )
+    }
+
+    #[test]
+    fn test_public_key_signatures() -> Result<()> {
+        let (public_key, private_key) = generate_key_pair();
+        let signer =
+            PublicKeyAuthenticator::new(b"team".to_vec(), &public_key, Some(&private_key))?;
+        let verifier = PublicKeyAuthenticator::new(b"team".to_vec(), &public_key, None)?;
+        assert!(signer.can_sign());
+        assert!(!verifier.can_sign());
+
+        let tag = signer.generate_tag(b"hash", b"body")?;
+        assert!(verifier.validate(b"hash", b"body", &tag));
+        assert!(!verifier.validate(b"hash", b"other body", &tag));
+        assert!(!verifier.validate(b"other hash", b"body", &tag));
+        assert!(!verifier.validate(b"hash", b"body", "not a signature"));
+
+        let other_team = PublicKeyAuthenticator::new(b"other".to_vec(), &public_key, None)?;
+        assert!(!other_team.validate(b"hash", b"body", &tag));
+
+        assert!(matches!(
+            verifier.generate_tag(b"hash", b"body"),
+            Err(SignatureError::NoPrivateKey)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_public_key_rejects_other_signers() -> Result<()> {
+        let (public_key, _) = generate_key_pair();
+        let (other_public_key, other_private_key) = generate_key_pair();
+        let forger = PublicKeyAuthenticator::new(
+            b"team".to_vec(),
+            &other_public_key,
+            Some(&other_private_key),
+        )?;
+        let verifier = PublicKeyAuthenticator::new(b"team".to_vec(), &public_key, None)?;
+
+        let tag = forger.generate_tag(b"hash", b"body")?;
+        assert!(!verifier.validate(b"hash", b"body", &tag));
+        Ok(())
+    }
+
+    #[test]
+    fn test_private_key_must_match_public_key() {
+        let (public_key, _) = generate_key_pair();
+        let (_, other_private_key) = generate_key_pair();
+
+        let err =
+            PublicKeyAuthenticator::new(b"team".to_vec(), &public_key, Some(&other_private_key))
+                .unwrap_err();
+        assert!(
+            matches!(err, SignatureError::PrivateKeyMismatch),
+            "got: {err:?}"
+        );
+    }
+
+    #[test]
+    fn test_public_key_formats() -> Result<()> {
+        let (public_key, private_key) = generate_key_pair();
+        let raw_public_key = BASE64_STANDARD.decode(&public_key)?;
+        let spki = BASE64_STANDARD.encode([ED25519_SPKI_PREFIX, &raw_public_key].concat());
+        let pem = format!("-----BEGIN PUBLIC KEY-----\n{spki}\n-----END PUBLIC KEY-----\n");
+
+        let signer =
+            PublicKeyAuthenticator::new(b"team".to_vec(), &public_key, Some(&private_key))?;
+        let tag = signer.generate_tag(b"hash", b"body")?;
+        for key in [&spki, &pem] {
+            let verifier = PublicKeyAuthenticator::new(b"team".to_vec(), key, None)?;
+            assert!(verifier.validate(b"hash", b"body", &tag));
+        }
+
+        for key in ["", "not base64!", &BASE64_STANDARD.encode(b"too short")] {
+            let err = PublicKeyAuthenticator::new(b"team".to_vec(), key, None).unwrap_err();
+            assert!(
+                matches!(err, SignatureError::InvalidPublicKey),
+                "got: {err:?}"
+            );
+        }
+        Ok(())
+    }
+
     #[test]
     fn test_signature_fields_are_separated() {
         let secret_key = b"shared signing key".to_vec();
@@ -8,7 +8,7 @@ use itertools::Itertools;
 use tracing::warn;
 use turbopath::AbsoluteSystemPathBuf;
 use turborepo_cache::CacheConfig;
-use turborepo_types::{EnvMode, LogOrder, UIMode};
+use turborepo_types::{EnvMode, LogOrder, UIMode, UndeclaredFilesMode};
 
 use crate::{
     ConfigurationOptions, Error, ExperimentalObservabilityOptions, ExperimentalOtelOptions,
@@ -33,12 +33,15 @@ const TURBO_MAPPING: &[(&str, &str)] = [
     ("turbo_cache_dir", "cache_dir"),
     ("turbo_cache_max_age", "cache_max_age"),
     ("turbo_cache_max_size", "cache_max_size"),
+    ("turbo_cache_deduplication", "cache_deduplication"),
     ("turbo_preflight", "preflight"),
     ("turbo_scm_base", "scm_base"),
     ("turbo_scm_head", "scm_head"),
     ("turbo_root_turbo_json", "root_turbo_json_path"),
     ("turbo_force", "force"),
     ("turbo_log_order", "log_order"),
+    ("turbo_undeclared_outputs", "undeclared_outputs"),
+    ("turbo_undeclared_inputs", "undeclared_inputs"),
     ("turbo_remote_only", "remote_only"),
     ("turbo_remote_cache_read_only", "remote_cache_read_only"),
     ("turbo_run_summary", "run_summary"),
@@ -101,6 +104,7 @@ const TURBO_MAPPING: &[(&str, &str)] = [
         "turbo_experimental_otel_metrics_task_attributes_hashes",
         "experimental_otel_metrics_task_attributes_hashes",
     ),
+    ("turbo_experimental_otel_traces", "experimental_otel_traces"),
     (
         "turbo_experimental_otel_use_remote_cache_token",
         "experimental_otel_use_remote_cache_token",
@@ -232,6 +236,8 @@ impl ResolvedConfigurationOptions for EnvVars {
         // Process daemon
         let daemon = self.truthy_value("daemon").flatten();
 
+        let cache_deduplication = self.truthy_value("cache_deduplication").flatten();
+
         let env_mode = self
             .output_map
             .get("env_mode")
@@ -268,6 +274,36 @@ impl ResolvedConfigurationOptions for EnvVars {
                 )
             })?;
 
+        let undeclared_outputs = self
+            .output_map
+            .get("undeclared_outputs")
+            .filter(|s| !s.is_empty())
+            .map(|s| UndeclaredFilesMode::from_str(s, true))
+            .transpose()
+            .map_err(|_| {
+                Error::InvalidUndeclaredOutputs(
+                    UndeclaredFilesMode::value_variants()
+                        .iter()
+                        .map(|v| v.to_string())
+                        .join(", "),
+                )
+            })?;
+
+        let undeclared_inputs = self
+            .output_map
+            .get("undeclared_inputs")
+            .filter(|s| !s.is_empty())
+            .map(|s| UndeclaredFilesMode::from_str(s, true))
+            .transpose()
+            .map_err(|_| {
+                Error::InvalidUndeclaredInputs(
+                    UndeclaredFilesMode::value_variants()
+                        .iter()
+                        .map(|v| v.to_string())
+                        .join(", "),
+                )
+            })?;
+
         let concurrency = self
             .output_map
             .get("concurrency")
@@ -314,6 +350,8 @@ impl ResolvedConfigurationOptions for EnvVars {
             cache,
             // Processed booleans
             signature,
+            // The public key is only read from turbo.json
+            signature_public_key: None,
             preflight,
             enabled: None,
             ui,
@@ -336,8 +374,11 @@ impl ResolvedConfigurationOptions for EnvVars {
             cache_dir,
             cache_max_age,
             cache_max_size,
+            cache_deduplication,
             root_turbo_json_path,
             log_order,
+            undeclared_outputs,
+            undeclared_inputs,
             // Do not allow future flags to be set by env var
             future_flags: None,
             experimental_observability,
@@ -384,7 +425,7 @@ fn map_environment<'a>(
 #[cfg(test)]
 mod test {
     use camino::Utf8PathBuf;
-    use turborepo_types::LogOrder;
+    use turborepo_types::{LogOrder, UndeclaredFilesMode};
 
     use super::*;
     use crate::{DEFAULT_API_URL, DEFAULT_LOGIN_URL, DEFAULT_TUI_SCROLLBACK_LENGTH};
@@ -477,6 +518,32 @@ mod test {
         assert_eq!(config.log_order(), LogOrder::Stream);
     }
 
+    #[test]
+    fn test_undeclared_outputs_env_setting() {
+        let mut env: HashMap<OsString, OsString> = HashMap::new();
+        env.insert("turbo_undeclared_outputs".into(), "error".into());
+
+        let config = EnvVars::new(&env)
+            .unwrap()
+            .get_configuration_options(&ConfigurationOptions::default())
+            .unwrap();
+
+        assert_eq!(config.undeclared_outputs(), UndeclaredFilesMode::Error);
+    }
+
+    #[test]
+    fn test_undeclared_inputs_env_setting() {
+        let mut env: HashMap<OsString, OsString> = HashMap::new();
+        env.insert("turbo_undeclared_inputs".into(), "warn".into());
+
+        let config = EnvVars::new(&env)
+            .unwrap()
+            .get_configuration_options(&ConfigurationOptions::default())
+            .unwrap();
+
+        assert_eq!(config.undeclared_inputs(), UndeclaredFilesMode::Warn);
+    }
+
     #[test]
     fn test_empty_env_setting() {
         let mut env: HashMap<OsString, OsString> = HashMap::new();
@@ -63,6 +63,8 @@ pub struct ExperimentalOtelOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metrics: Option<ExperimentalOtelMetricsOptions>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub traces: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub use_remote_cache_token: Option<bool>,
 }
 
@@ -77,7 +79,8 @@ pub struct ExperimentalOtelOptions {
 /// different endpoint set by a higher-priority source.
 ///
 /// Non-credential fields (`enabled`, `protocol`, `timeout_ms`, `interval_ms`,
-/// `resource`, `metrics`) always merge independently across all sources.
+/// `resource`, `metrics`, `traces`) always merge independently across all
+/// sources.
 impl Merge for ExperimentalOtelOptions {
     fn merge(&mut self, other: Self) {
         let endpoint_locked = self.endpoint.is_some();
@@ -97,6 +100,7 @@ impl Merge for ExperimentalOtelOptions {
         merge::option::overwrite_none(&mut self.interval_ms, other.interval_ms);
         merge::option::overwrite_none(&mut self.resource, other.resource);
         merge::option::recurse(&mut self.metrics, other.metrics);
+        merge::option::overwrite_none(&mut self.traces, other.traces);
     }
 }
 
@@ -110,6 +114,7 @@ impl ExperimentalOtelOptions {
             && self.interval_ms.is_none()
             && self.resource.as_ref().map(|m| m.is_empty()).unwrap_or(true)
             && self.use_remote_cache_token.is_none()
+            && self.traces.is_none()
             && self
                 .metrics
                 .as_ref()
@@ -248,6 +253,11 @@ impl ExperimentalOtelOptions {
             &mut options,
         )?;
 
+        if let Some(raw) = get_non_empty(map, "experimental_otel_traces") {
+            options.traces = Some(parse_bool_flag(raw, "TURBO_EXPERIMENTAL_OTEL_TRACES")?);
+            touched = true;
+        }
+
         if let Some(raw) = get_non_empty(map, "experimental_otel_use_remote_cache_token") {
             options.use_remote_cache_token = Some(parse_bool_flag(
                 raw,
@@ -723,6 +733,27 @@ mod tests {
         assert_eq!(opts.metrics.unwrap().run_summary, Some(true));
     }
 
+    #[test]
+    fn test_from_env_map_traces() {
+        let map = build_env_map(&[("experimental_otel_traces", "1")]);
+        let result = ExperimentalOtelOptions::from_env_map(&map).unwrap();
+        assert!(result.is_some());
+        assert_eq!(result.unwrap().traces, Some(true));
+    }
+
+    #[test]
+    fn test_from_env_map_traces_invalid() {
+        let map = build_env_map(&[("experimental_otel_traces", "invalid")]);
+        let result = ExperimentalOtelOptions::from_env_map(&map);
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            Error::InvalidExperimentalOtelConfig { message } => {
+                assert!(message.contains("TURBO_EXPERIMENTAL_OTEL_TRACES"));
+            }
+            _ => panic!("Expected InvalidExperimentalOtelConfig"),
+        }
+    }
+
     #[test]
     fn test_from_env_map_use_remote_cache_token_enabled() {
         let map = build_env_map(&[("experimental_otel_use_remote_cache_token", "1")]);
@@ -776,6 +807,15 @@ mod tests {
         assert!(!opts.is_empty());
     }
 
+    #[test]
+    fn test_is_empty_with_traces() {
+        let opts = ExperimentalOtelOptions {
+            traces: Some(false),
+            ..Default::default()
+        };
+        assert!(!opts.is_empty());
+    }
+
     #[test]
     fn test_is_empty_with_empty_headers() {
         let opts = ExperimentalOtelOptions {
@@ -50,7 +50,7 @@ use turborepo_cache::CacheConfig;
 use turborepo_repository::package_graph::PackageName;
 use turborepo_scm::WorktreeInfo;
 use turborepo_turbo_json::FutureFlags;
-use turborepo_types::{ConfigurationSource, EnvMode, LogOrder, UIMode};
+use turborepo_types::{ConfigurationSource, EnvMode, LogOrder, UIMode, UndeclaredFilesMode};
 
 pub const CONFIG_FILE: &str = "turbo.json";
 pub const CONFIG_FILE_JSONC: &str = "turbo.jsonc";
@@ -197,6 +197,10 @@ pub enum Error {
     InvalidPreflight,
     #[error("TURBO_LOG_ORDER should be one of: {0}")]
     InvalidLogOrder(String),
+    #[error("TURBO_UNDECLARED_OUTPUTS should be one of: {0}")]
+    InvalidUndeclaredOutputs(String),
+    #[error("TURBO_UNDECLARED_INPUTS should be one of: {0}")]
+    InvalidUndeclaredInputs(String),
     #[error(
         "TURBO_TUI_SCROLLBACK_LENGTH: Invalid value. Use a number for how many lines to keep in \
          scrollback."
@@ -290,6 +294,9 @@ pub struct ConfigurationOptions {
     /// corresponds to env var TURBO_TOKEN
     pub token: Option<String>,
     pub signature: Option<bool>,
+    /// Only read from turbo.json so that the key is reviewed alongside the
+    /// rest of the repository
+    pub signature_public_key: Option<String>,
     pub preflight: Option<bool>,
     pub timeout: Option<u64>,
     pub upload_timeout: Option<u64>,
@@ -309,11 +316,15 @@ pub struct ConfigurationOptions {
     pub cache_max_age: Option<String>,
     #[serde(rename = "cacheMaxSize")]
     pub cache_max_size: Option<String>,
+    #[serde(rename = "cacheDeduplication")]
+    pub cache_deduplication: Option<bool>,
     // This is skipped as we never want this to be stored in a file
     #[serde(skip)]
     pub root_turbo_json_path: Option<AbsoluteSystemPathBuf>,
     pub force: Option<bool>,
     pub log_order: Option<LogOrder>,
+    pub undeclared_outputs: Option<UndeclaredFilesMode>,
+    pub undeclared_inputs: Option<UndeclaredFilesMode>,
     #[serde(skip)]
     pub cache: Option<CacheConfig>,
     pub remote_only: Option<bool>,
@@ -393,6 +404,10 @@ impl ConfigurationOptions {
         self.signature.unwrap_or_default()
     }
 
+    pub fn signature_public_key(&self) -> Option<&str> {
+        non_empty_str(self.signature_public_key.as_deref())
+    }
+
     pub fn enabled(&self) -> bool {
         self.enabled.unwrap_or(true)
     }
@@ -480,6 +495,10 @@ impl ConfigurationOptions {
         self.cache_max_size.as_deref()
     }
 
+    pub fn cache_deduplication(&self) -> bool {
+        self.cache_deduplication.unwrap_or_default()
+    }
+
     /// Resolves the cache directory, taking Git worktrees into account.
     ///
     /// If no explicit `cacheDir` is configured and we're in a linked Git
@@ -583,6 +602,14 @@ impl ConfigurationOptions {
         self.log_order.unwrap_or_default()
     }
 
+    pub fn undeclared_outputs(&self) -> UndeclaredFilesMode {
+        self.undeclared_outputs.unwrap_or_default()
+    }
+
+    pub fn undeclared_inputs(&self) -> UndeclaredFilesMode {
+        self.undeclared_inputs.unwrap_or_default()
+    }
+
     pub fn remote_only(&self) -> bool {
         self.remote_only.unwrap_or_default()
     }
@@ -37,6 +37,10 @@ impl From<&RawRemoteCacheOptions> for ConfigurationOptions {
                 .as_ref()
                 .map(|s| s.as_inner().clone()),
             signature: remote_cache_opts.signature.as_ref().map(|s| *s.as_inner()),
+            signature_public_key: remote_cache_opts
+                .public_key
+                .as_ref()
+                .map(|s| s.as_inner().clone()),
             preflight: remote_cache_opts.preflight.as_ref().map(|s| *s.as_inner()),
             timeout: remote_cache_opts.timeout.as_ref().map(|s| *s.as_inner()),
             upload_timeout: remote_cache_opts
@@ -97,6 +101,9 @@ impl<'a> TurboJsonReader<'a> {
         opts.cache_max_size = turbo_json
             .cache_max_size
             .map(|size| size.into_inner().to_string());
+        opts.cache_deduplication = turbo_json
+            .cache_deduplication
+            .map(|deduplicate| *deduplicate.as_inner());
         opts.concurrency = turbo_json.concurrency.map(|c| c.as_inner().clone());
 
         opts.future_flags = turbo_json.future_flags.map(|f| *f.as_inner());
@@ -192,6 +199,7 @@ fn convert_raw_observability_otel(
         interval_ms: raw.interval_ms.map(|interval| *interval.as_inner()),
         resource: raw.resource,
         metrics,
+        traces: raw.traces.map(|flag| *flag.as_inner()),
         use_remote_cache_token: raw.use_remote_cache_token.map(|flag| *flag.as_inner()),
     })
 }
@@ -282,6 +290,7 @@ mod test {
                     "teamSlug": team_slug,
                     "teamId": team_id,
                     "signature": true,
+                    "publicKey": "MCowBQYDK2VwAyEA",
                     "preflight": true
                 }
             }))
@@ -300,6 +309,7 @@ mod test {
         assert_eq!(config.team_slug(), Some(team_slug));
         assert_eq!(config.team_id(), Some(team_id));
         assert!(config.signature());
+        assert_eq!(config.signature_public_key(), Some("MCowBQYDK2VwAyEA"));
         assert!(config.preflight());
     }
 
@@ -512,6 +522,7 @@ mod test {
                             "hashes": true
                         }
                     },
+                    "traces": true,
                     "useRemoteCacheToken": true
                 }
             }
@@ -543,6 +554,7 @@ mod test {
             .and_then(|obs| obs.otel.as_ref())
             .expect("otel config should survive round-trip");
         assert_eq!(otel.enabled, Some(true));
+        assert_eq!(otel.traces, Some(true));
         assert_eq!(otel.headers, None);
         assert_eq!(
             otel.endpoint.as_deref(),
@@ -636,4 +648,20 @@ mod test {
         let config = TurboJsonReader::turbo_json_to_config_options(turbo_json).unwrap();
         assert_eq!(config.cache_max_size(), Some("5GB"));
     }
+
+    #[test]
+    fn test_cache_deduplication_from_turbo_json() {
+        let turbo_json = RawRootTurboJson::parse(
+            &serde_json::to_string_pretty(&json!({
+                "cacheDeduplication": true
+            }))
+            .unwrap(),
+            "turbo.json",
+        )
+        .unwrap()
+        .try_into()
+        .unwrap();
+        let config = TurboJsonReader::turbo_json_to_config_options(turbo_json).unwrap();
+        assert!(config.cache_deduplication());
+    }
 }
@@ -23,6 +23,7 @@ mod validate;
 use std::{
     collections::{HashMap, HashSet},
     fmt,
+    time::Duration,
 };
 
 pub use affected::match_tasks_against_changed_files;
@@ -816,6 +817,12 @@ pub enum TaskErrorCause {
     Spawn { msg: String },
     #[error("command {command} exited ({exit_code})")]
     Exit { command: String, exit_code: i32 },
+    #[error("command {command} timed out after {}s", .timeout.as_secs())]
+    Timeout { command: String, timeout: Duration },
+    #[error("wrote files not covered by its `outputs`: {}", .files.join(", "))]
+    UndeclaredOutputs { files: Vec<String> },
+    #[error("read files not covered by its `inputs`: {}", .files.join(", "))]
+    UndeclaredInputs { files: Vec<String> },
     #[error("turbo has internal error processing task")]
     Internal,
 }
@@ -871,6 +878,27 @@ impl TaskError {
             cause: TaskErrorCause::Exit { command, exit_code },
         }
     }
+
+    pub fn from_timeout(task_id: String, command: String, timeout: Duration) -> Self {
+        Self {
+            task_id,
+            cause: TaskErrorCause::Timeout { command, timeout },
+        }
+    }
+
+    pub fn from_undeclared_outputs(task_id: String, files: Vec<String>) -> Self {
+        Self {
+            task_id,
+            cause: TaskErrorCause::UndeclaredOutputs { files },
+        }
+    }
+
+    pub fn from_undeclared_inputs(task_id: String, files: Vec<String>) -> Self {
+        Self {
+            task_id,
+            cause: TaskErrorCause::UndeclaredInputs { files },
+        }
+    }
 }
 
 impl TaskErrorCause {
@@ -1302,6 +1330,27 @@ impl turborepo_task_executor::TaskErrorCollector for TaskErrorCollectorWrapper {
             .unwrap_or_else(|poisoned| poisoned.into_inner())
             .push(TaskError::from_execution(task_id, command, exit_code));
     }
+
+    fn push_timeout_error(&self, task_id: String, command: String, timeout: Duration) {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(TaskError::from_timeout(task_id, command, timeout));
+    }
+
+    fn push_undeclared_outputs_error(&self, task_id: String, files: Vec<String>) {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(TaskError::from_undeclared_outputs(task_id, files));
+    }
+
+    fn push_undeclared_inputs_error(&self, task_id: String, files: Vec<String>) {
+        self.0
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .push(TaskError::from_undeclared_inputs(task_id, files));
+    }
 }
 
 /// A wrapper around `Arc<Mutex<Vec<TaskWarning>>>` that implements
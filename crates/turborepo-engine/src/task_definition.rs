@@ -4,13 +4,14 @@
 //! `ProcessedTaskDefinition` to `TaskDefinition`.
 
 use turbopath::RelativeUnixPath;
+use turborepo_cache::duration::parse_human_duration;
 use turborepo_errors::Spanned;
 use turborepo_task_id::TaskName;
 use turborepo_turbo_json::{
-    ProcessedTaskDefinition, TOPOLOGICAL_PIPELINE_DELIMITER, TaskInputsFromProcessed,
-    incremental_partitions_from_processed, task_outputs_from_processed,
+    ProcessedTaskDefinition, RawTaskCommand, TOPOLOGICAL_PIPELINE_DELIMITER,
+    TaskInputsFromProcessed, incremental_partitions_from_processed, task_outputs_from_processed,
 };
-use turborepo_types::{TaskDefinition, TaskInputs};
+use turborepo_types::{TaskCommand, TaskDefinition, TaskInputs};
 
 use crate::BuilderError;
 
@@ -71,6 +72,52 @@ impl TaskDefinitionFromProcessed for TaskDefinition {
             ));
         }
 
+        // A zero timeout disables the limit, matching `cacheMaxAge` semantics.
+        let timeout = processed
+            .timeout
+            .map(|timeout| {
+                parse_human_duration(timeout.as_str()).map_err(|err| {
+                    let (span, text) = timeout.span_and_text("turbo.json");
+                    BuilderError::TurboJson(turborepo_turbo_json::Error::InvalidTaskTimeout {
+                        reason: err.to_string(),
+                        span,
+                        text,
+                    })
+                })
+            })
+            .transpose()?
+            .filter(|timeout| !timeout.is_zero());
+
+        let retries = processed
+            .retries
+            .map(|retries| *retries)
+            .unwrap_or_default();
+
+        let command = processed
+            .command
+            .map(|command| {
+                let task_command = match command.as_inner() {
+                    RawTaskCommand::String(command) => TaskCommand::Shell(command.to_string()),
+                    RawTaskCommand::Argv(argv) => {
+                        TaskCommand::Argv(argv.iter().map(|arg| arg.to_string()).collect())
+                    }
+                };
+                let is_empty = match &task_command {
+                    TaskCommand::Shell(command) => command.trim().is_empty(),
+                    TaskCommand::Argv(argv) => {
+                        argv.first().is_none_or(|program| program.is_empty())
+                    }
+                };
+                if is_empty {
+                    let (span, text) = command.span_and_text("turbo.json");
+                    return Err(BuilderError::TurboJson(
+                        turborepo_turbo_json::Error::EmptyTaskCommand { span, text },
+                    ));
+                }
+                Ok(task_command)
+            })
+            .transpose()?;
+
         let mut topological_dependencies: Vec<Spanned<TaskName>> = Vec::new();
         let mut task_dependencies: Vec<Spanned<TaskName>> = Vec::new();
         if let Some(depends_on) = processed.depends_on {
@@ -121,6 +168,9 @@ impl TaskDefinitionFromProcessed for TaskDefinition {
             persistent,
             interruptible: *interruptible,
             interactive,
+            timeout,
+            retries,
+            command,
             env_mode: processed.env_mode.map(|mode| *mode.as_inner()),
             with,
             incremental,
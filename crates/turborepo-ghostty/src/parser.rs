@@ -1,9 +1,12 @@
+use std::ops::Range;
+
 use crate::{
     Error, RenderState, Result,
     error::Error as GhosttyInnerError,
     fmt::{Format, Formatter, FormatterOptions},
+    screen::TrackedGridRef,
     selection::{FormatOptions, Selection},
-    terminal::{Point, PointCoordinate, ScrollViewport, Terminal},
+    terminal::{Point, PointCoordinate, PointSpace, ScrollViewport, Terminal},
 };
 
 const DEFAULT_CELL_WIDTH_PX: u32 = 8;
@@ -18,8 +21,19 @@ pub struct Parser {
     /// copy.
     selection_range: Option<(u16, u16, u16, u16)>,
     max_scrollback: usize,
+    /// A row followed as output scrolls, see [`Parser::mark_row`]
+    row_mark: Option<RowMark>,
 }
 
+/// A tracked reference to the start of a row.
+struct RowMark(TrackedGridRef);
+
+// SAFETY: The tracked reference is owned by the `Parser` holding the terminal
+// it was created from and is only used through that `Parser`, so it moves
+// between threads together with its terminal, which is itself `Send + Sync`.
+unsafe impl Send for RowMark {}
+unsafe impl Sync for RowMark {}
+
 impl Parser {
     pub fn new(rows: u16, cols: u16, scrollback_len: usize) -> Self {
         Self::try_new(rows, cols, scrollback_len).expect("failed to initialize ghostty terminal")
@@ -39,6 +53,7 @@ impl Parser {
             selection_start: None,
             selection_range: None,
             max_scrollback: scrollback_len,
+            row_mark: None,
         })
     }
 
@@ -93,6 +108,99 @@ impl Parser {
         Ok(bytes.to_vec())
     }
 
+    /// Plain text of every row on the screen, starting from the top of the
+    /// scrollback. Soft-wrapped rows are kept separate so indices line up
+    /// with [`Parser::scroll_to_row`].
+    pub fn screen_lines(&self) -> Result<Vec<String>> {
+        let mut formatter = Formatter::new(
+            &self.terminal,
+            FormatterOptions::new()
+                .with_format(Format::Plain)
+                .with_trim(true)
+                .with_unwrap(false)
+                .with_cursor(false),
+        )?;
+        let bytes = formatter.format_alloc(None)?;
+        Ok(String::from_utf8_lossy(bytes.as_ref())
+            .lines()
+            .map(str::to_owned)
+            .collect())
+    }
+
+    /// Plain text of the rows in `rows`, counted from the top of the
+    /// scrollback. Rows past the end of the screen are left out.
+    pub fn screen_lines_range(&self, rows: Range<usize>) -> Result<Vec<String>> {
+        let end = rows.end.min(self.terminal.total_rows()?);
+        if rows.start >= end {
+            return Ok(Vec::new());
+        }
+        let last_col = self.terminal.cols()?.saturating_sub(1);
+        let start = self.terminal.grid_ref(screen_point(rows.start, 0)?)?;
+        let last = self.terminal.grid_ref(screen_point(end - 1, last_col)?)?;
+        let selection = Selection::new(start, last, false);
+        let bytes = self.terminal.format_selection_alloc(
+            None,
+            FormatOptions::new()
+                .with_emit_format(Format::Plain)
+                .with_trim(true)
+                .with_unwrap(false)
+                .with_selection(&selection),
+        )?;
+        let mut lines = bytes
+            .map(|bytes| {
+                String::from_utf8_lossy(bytes.as_ref())
+                    .lines()
+                    .map(str::to_owned)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
+        // Blank rows at the end of the range are trimmed by the formatter
+        lines.resize(end - rows.start, String::new());
+        Ok(lines)
+    }
+
+    /// The number of rows on the screen, scrollback included.
+    pub fn total_rows(&self) -> Result<usize> {
+        Ok(self.terminal.total_rows()?)
+    }
+
+    /// The number of rows in the scrollback, above the active screen.
+    pub fn scrollback_rows(&self) -> Result<usize> {
+        Ok(self.terminal.scrollback_rows()?)
+    }
+
+    /// Mark `row`, counted from the top of the scrollback, so its position
+    /// can be found with [`Parser::marked_row`] after more output is written.
+    /// Replaces any previous mark.
+    pub fn mark_row(&mut self, row: usize) -> Result<()> {
+        let tracked = self.terminal.track_grid_ref(screen_point(row, 0)?)?;
+        self.row_mark = Some(RowMark(tracked));
+        Ok(())
+    }
+
+    /// The current row of the row marked with [`Parser::mark_row`], counted
+    /// from the top of the scrollback. Returns `None` if nothing is marked or
+    /// the row has since been pruned from the scrollback.
+    pub fn marked_row(&self) -> Result<Option<usize>> {
+        let Some(RowMark(tracked)) = &self.row_mark else {
+            return Ok(None);
+        };
+        Ok(tracked
+            .point(PointSpace::Screen)?
+            .map(|point| point.y as usize))
+    }
+
+    pub fn clear_row_mark(&mut self) {
+        self.row_mark = None;
+    }
+
+    /// Scroll the viewport so that `row`, counted from the top of the
+    /// scrollback, is the first visible row.
+    pub fn scroll_to_row(&mut self, row: usize) -> Result<()> {
+        self.scroll_to_top()?;
+        self.scroll_by(false, row)
+    }
+
     pub fn clear_selection(&mut self) -> Result<()> {
         self.selection_start = None;
         self.selection_range = None;
@@ -170,6 +278,7 @@ impl Parser {
         self.terminal.reset();
         self.selection_start = None;
         self.selection_range = None;
+        self.row_mark = None;
     }
 
     pub fn max_scrollback(&self) -> usize {
@@ -177,6 +286,11 @@ impl Parser {
     }
 }
 
+fn screen_point(row: usize, col: u16) -> Result<Point> {
+    let y = u32::try_from(row).map_err(|_| Error::Ghostty(GhosttyInnerError::InvalidValue))?;
+    Ok(Point::Screen(PointCoordinate { x: col, y }))
+}
+
 fn viewport_point(row: u16, col: u16) -> Point {
     Point::Viewport(PointCoordinate {
         x: col,
@@ -198,6 +312,51 @@ mod tests {
         assert!(output.contains("world"));
     }
 
+    #[test]
+    fn screen_lines_include_scrollback() {
+        let mut parser = Parser::new(2, 20, 100);
+        parser.process(b"first\r\nsecond\r\nthird\r\nfourth");
+        let lines = parser.screen_lines().expect("screen lines");
+        assert_eq!(lines, vec!["first", "second", "third", "fourth"]);
+    }
+
+    #[test]
+    fn screen_lines_range_selects_rows() {
+        let mut parser = Parser::new(2, 20, 100);
+        parser.process(b"first\r\n\r\nthird\r\nfourth");
+        assert_eq!(parser.total_rows().expect("total rows"), 4);
+        assert_eq!(parser.scrollback_rows().expect("scrollback rows"), 2);
+        let lines = parser.screen_lines_range(1..3).expect("screen lines");
+        assert_eq!(lines, vec!["", "third"]);
+        let lines = parser.screen_lines_range(3..10).expect("screen lines");
+        assert_eq!(lines, vec!["fourth"]);
+        assert!(
+            parser
+                .screen_lines_range(4..10)
+                .expect("screen lines")
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn marked_row_follows_pruned_scrollback() {
+        let mut parser = Parser::new(2, 20, 3);
+        parser.process(b"one\r\ntwo\r\nthree");
+        parser.mark_row(1).expect("mark row");
+        parser.process(b"\r\nfour\r\nfive\r\nsix");
+        let row = parser
+            .marked_row()
+            .expect("marked row")
+            .expect("still marked");
+        let lines = parser
+            .screen_lines_range(row..row + 1)
+            .expect("screen lines");
+        assert_eq!(lines, vec!["two"]);
+
+        parser.reset();
+        assert_eq!(parser.marked_row().expect("marked row"), None);
+    }
+
     #[test]
     fn resize_reflows_existing_output() {
         let mut parser = Parser::new(5, 10, 0);
@@ -45,6 +45,44 @@ pub fn transitive_closure<N: Hash + Eq + PartialEq, E, I: IntoIterator<Item = No
     visited
 }
 
+/// Like [`transitive_closure`], but only follows edges up to `depth` levels
+/// away from the starting nodes. The starting nodes are always included.
+pub fn transitive_closure_within_depth<
+    N: Hash + Eq + PartialEq,
+    E,
+    I: IntoIterator<Item = NodeIndex>,
+>(
+    graph: &Graph<N, E>,
+    indices: I,
+    direction: petgraph::Direction,
+    depth: usize,
+) -> HashSet<&N> {
+    let mut seen = HashSet::new();
+    let mut frontier = indices
+        .into_iter()
+        .filter(|index| seen.insert(*index))
+        .collect::<Vec<_>>();
+
+    for _ in 0..depth {
+        let mut next = Vec::new();
+        for index in frontier {
+            for neighbor in graph.neighbors_directed(index, direction) {
+                if seen.insert(neighbor) {
+                    next.push(neighbor);
+                }
+            }
+        }
+        if next.is_empty() {
+            break;
+        }
+        frontier = next;
+    }
+
+    seen.into_iter()
+        .filter_map(|index| graph.node_weight(index))
+        .collect()
+}
+
 pub struct Cycle<N> {
     pub nodes: Vec<NodeIndex>,
     pub cuts: Vec<HashSet<(N, N)>>,
@@ -270,6 +308,50 @@ mod test {
         "###);
     }
 
+    #[test]
+    fn test_transitive_closure_within_depth() {
+        // a -> b -> c -> d
+        //  \_________/
+        let mut g = Graph::new();
+        let a = g.add_node("a");
+        let b = g.add_node("b");
+        let c = g.add_node("c");
+        let d = g.add_node("d");
+
+        g.add_edge(a, b, ());
+        g.add_edge(b, c, ());
+        g.add_edge(c, d, ());
+        g.add_edge(a, c, ());
+
+        let closure = |index, direction, depth| {
+            transitive_closure_within_depth(&g, Some(index), direction, depth)
+                .into_iter()
+                .copied()
+                .collect::<HashSet<_>>()
+        };
+
+        assert_eq!(closure(a, Direction::Outgoing, 0), HashSet::from(["a"]));
+        assert_eq!(
+            closure(a, Direction::Outgoing, 1),
+            HashSet::from(["a", "b", "c"])
+        );
+        assert_eq!(
+            closure(a, Direction::Outgoing, 2),
+            HashSet::from(["a", "b", "c", "d"])
+        );
+        assert_eq!(
+            closure(d, Direction::Incoming, 1),
+            HashSet::from(["d", "c"])
+        );
+        assert_eq!(
+            closure(d, Direction::Incoming, 5),
+            transitive_closure(&g, Some(d), Direction::Incoming)
+                .into_iter()
+                .copied()
+                .collect::<HashSet<_>>()
+        );
+    }
+
     #[test]
     fn test_basic_cycle_break() {
         // Simple cycle where any edge would break the cycle
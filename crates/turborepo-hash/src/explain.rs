@@ -0,0 +1,674 @@
+//! Records the inputs that went into a task's hash so that a cache miss can be
+//! explained by comparing them with the inputs recorded for an earlier run of
+//! the same task.
+//!
+//! Environment variable values are never recorded, only hashes of them. The
+//! global inputs are the same for every task in a run, so they're recorded
+//! once per run and each task only records the global hash.
+
+use std::{collections::BTreeMap, fmt, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+use turborepo_types::{EnvMode, TaskCommand, TaskOutputs};
+
+use crate::{FileHashes, GlobalHashable, TaskHashable};
+
+/// The inputs to the global hash
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalHashInputs {
+    pub hash: String,
+    pub cache_key: String,
+    pub files: BTreeMap<String, String>,
+    pub root_external_dependencies_hash: Option<String>,
+    pub root_internal_dependencies_hash: Option<String>,
+    pub engines: BTreeMap<String, String>,
+    pub env: Vec<String>,
+    /// Resolved environment variables mapped to hashes of their values
+    pub env_vars: BTreeMap<String, String>,
+    pub pass_through_env: Vec<String>,
+    pub env_mode: EnvMode,
+    pub framework_inference: bool,
+    pub global_configuration: bool,
+}
+
+impl GlobalHashInputs {
+    /// `secret_env_vars` are the resolved global environment variables as
+    /// `NAME=HASH` pairs, as produced by `to_secret_hashable`.
+    pub fn new(hashable: &GlobalHashable, hash: &str, secret_env_vars: &[String]) -> Self {
+        Self {
+            hash: hash.to_owned(),
+            cache_key: hashable.global_cache_key.to_owned(),
+            files: hashable
+                .global_file_hash_map
+                .iter()
+                .map(|(path, hash)| (path.to_string(), hash.clone()))
+                .collect(),
+            root_external_dependencies_hash: hashable
+                .root_external_dependencies_hash
+                .map(str::to_owned),
+            root_internal_dependencies_hash: hashable
+                .root_internal_dependencies_hash
+                .map(str::to_owned),
+            engines: hashable
+                .engines
+                .iter()
+                .map(|(name, version)| (name.to_string(), version.to_string()))
+                .collect(),
+            env: hashable.env.to_vec(),
+            env_vars: env_var_map(secret_env_vars),
+            pass_through_env: hashable.pass_through_env.to_vec(),
+            env_mode: hashable.env_mode,
+            framework_inference: hashable.framework_inference,
+            global_configuration: hashable.global_configuration,
+        }
+    }
+}
+
+/// The inputs to a task's hash
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskHashInputs {
+    pub hash: String,
+    pub global_hash: String,
+    /// Not serialized, see [`TaskHashInputs::with_global`]
+    #[serde(skip)]
+    pub global: Option<Arc<GlobalHashInputs>>,
+    #[serde(with = "file_hashes")]
+    pub files: Arc<FileHashes>,
+    /// The hashes of the task's dependencies, keyed by task ID
+    pub dependencies: BTreeMap<String, String>,
+    pub external_deps_hash: Option<String>,
+    pub package_dir: Option<String>,
+    pub task: String,
+    pub outputs: TaskOutputs,
+    pub pass_through_args: Vec<String>,
+    pub command: Option<TaskCommand>,
+    pub env: Vec<String>,
+    /// Resolved environment variables mapped to hashes of their values
+    pub env_vars: BTreeMap<String, String>,
+    pub pass_through_env: Vec<String>,
+    pub env_mode: EnvMode,
+}
+
+impl TaskHashInputs {
+    /// `files` must be the file hashes that `hashable.hash_of_files` was
+    /// computed from, and `secret_env_vars` the resolved environment variables
+    /// as `NAME=HASH` pairs.
+    pub fn new(
+        hashable: &TaskHashable,
+        hash: &str,
+        global: Arc<GlobalHashInputs>,
+        files: Arc<FileHashes>,
+        dependencies: BTreeMap<String, String>,
+        secret_env_vars: &[String],
+    ) -> Self {
+        // Mirrors `TaskHashable::calculate_task_hash`
+        let pass_through_env = match hashable.env_mode {
+            EnvMode::Loose => Vec::new(),
+            EnvMode::Strict => hashable.pass_through_env.to_vec(),
+        };
+        Self {
+            hash: hash.to_owned(),
+            global_hash: global.hash.clone(),
+            global: Some(global),
+            files,
+            dependencies,
+            external_deps_hash: hashable.external_deps_hash.map(str::to_owned),
+            package_dir: hashable.package_dir.as_ref().map(|dir| dir.to_string()),
+            task: hashable.task.to_owned(),
+            outputs: hashable.outputs.clone(),
+            pass_through_args: hashable.pass_through_args.to_vec(),
+            command: hashable.command.cloned(),
+            env: hashable.env.to_vec(),
+            env_vars: env_var_map(secret_env_vars),
+            pass_through_env,
+            env_mode: hashable.env_mode,
+        }
+    }
+
+    /// Attaches the global inputs that were recorded separately, if they are
+    /// the ones that these inputs were hashed with
+    pub fn with_global(mut self, global: Arc<GlobalHashInputs>) -> Self {
+        if global.hash == self.global_hash {
+            self.global = Some(global);
+        }
+        self
+    }
+
+    /// Lists the inputs that differ between `previous` and these inputs
+    pub fn changes_since(&self, previous: &TaskHashInputs) -> Vec<InputChange> {
+        let mut changes = Vec::new();
+
+        if self.global_hash != previous.global_hash {
+            match (&self.global, &previous.global) {
+                (Some(current), Some(previous)) => {
+                    changes.extend(current.changes_since(previous));
+                }
+                // The global inputs of one of the runs weren't recorded
+                _ => changes.push(InputChange::GlobalHash),
+            }
+        }
+
+        if !Arc::ptr_eq(&self.files, &previous.files) {
+            let current = file_map(&self.files);
+            let previous = file_map(&previous.files);
+            changes.extend(
+                diff_maps(&previous, &current)
+                    .map(|(path, change)| InputChange::File { path, change }),
+            );
+        }
+        changes.extend(
+            diff_maps(&previous.env_vars, &self.env_vars)
+                .map(|(name, change)| InputChange::EnvVar { name, change }),
+        );
+        changes.extend(
+            diff_maps(&previous.dependencies, &self.dependencies)
+                .map(|(task_id, change)| InputChange::Dependency { task_id, change }),
+        );
+        if self.pass_through_args != previous.pass_through_args {
+            changes.push(InputChange::PassThroughArgs {
+                previous: previous.pass_through_args.clone(),
+                current: self.pass_through_args.clone(),
+            });
+        }
+
+        let config_fields = [
+            (
+                "externalDependencies",
+                self.external_deps_hash != previous.external_deps_hash,
+            ),
+            ("packageDir", self.package_dir != previous.package_dir),
+            ("task", self.task != previous.task),
+            ("outputs", self.outputs != previous.outputs),
+            ("command", self.command != previous.command),
+            ("env", self.env != previous.env),
+            (
+                "passThroughEnv",
+                self.pass_through_env != previous.pass_through_env,
+            ),
+            ("envMode", self.env_mode != previous.env_mode),
+        ];
+        changes.extend(
+            config_fields
+                .into_iter()
+                .filter(|(_, changed)| *changed)
+                .map(|(field, _)| InputChange::TaskConfig { field }),
+        );
+
+        changes
+    }
+}
+
+impl GlobalHashInputs {
+    fn changes_since(&self, previous: &GlobalHashInputs) -> Vec<InputChange> {
+        let mut changes: Vec<_> = diff_maps(&previous.files, &self.files)
+            .map(|(path, change)| InputChange::GlobalFile { path, change })
+            .collect();
+        changes.extend(
+            diff_maps(&previous.env_vars, &self.env_vars)
+                .map(|(name, change)| InputChange::GlobalEnvVar { name, change }),
+        );
+
+        let config_fields = [
+            ("cacheKey", self.cache_key != previous.cache_key),
+            (
+                "rootExternalDependencies",
+                self.root_external_dependencies_hash != previous.root_external_dependencies_hash,
+            ),
+            (
+                "rootInternalDependencies",
+                self.root_internal_dependencies_hash != previous.root_internal_dependencies_hash,
+            ),
+            ("engines", self.engines != previous.engines),
+            ("globalEnv", self.env != previous.env),
+            (
+                "globalPassThroughEnv",
+                self.pass_through_env != previous.pass_through_env,
+            ),
+            ("envMode", self.env_mode != previous.env_mode),
+            (
+                "frameworkInference",
+                self.framework_inference != previous.framework_inference,
+            ),
+            (
+                "globalConfiguration",
+                self.global_configuration != previous.global_configuration,
+            ),
+        ];
+        changes.extend(
+            config_fields
+                .into_iter()
+                .filter(|(_, changed)| *changed)
+                .map(|(field, _)| InputChange::GlobalConfig { field }),
+        );
+
+        changes
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+    Added,
+    Removed,
+    Modified,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Change::Added => "was added",
+            Change::Removed => "was removed",
+            Change::Modified => "changed",
+        })
+    }
+}
+
+/// A single difference between the hash inputs of two runs of a task
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum InputChange {
+    File {
+        path: String,
+        change: Change,
+    },
+    EnvVar {
+        name: String,
+        change: Change,
+    },
+    Dependency {
+        task_id: String,
+        change: Change,
+    },
+    PassThroughArgs {
+        previous: Vec<String>,
+        current: Vec<String>,
+    },
+    GlobalFile {
+        path: String,
+        change: Change,
+    },
+    GlobalEnvVar {
+        name: String,
+        change: Change,
+    },
+    /// Another part of the task's configuration, named as in `turbo.json`
+    TaskConfig {
+        field: &'static str,
+    },
+    /// Another part of the global configuration, named as in `turbo.json`
+    GlobalConfig {
+        field: &'static str,
+    },
+    /// The global hash changed, but the global inputs of one of the runs
+    /// weren't recorded
+    GlobalHash,
+}
+
+impl fmt::Display for InputChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputChange::File { path, change } => write!(f, "file `{path}` {change}"),
+            InputChange::EnvVar { name, change } => {
+                write!(f, "environment variable `{name}` {change}")
+            }
+            InputChange::Dependency {
+                task_id,
+                change: Change::Modified,
+            } => write!(f, "dependency `{task_id}` has a different hash"),
+            InputChange::Dependency { task_id, change } => {
+                write!(f, "dependency `{task_id}` {change}")
+            }
+            InputChange::PassThroughArgs { previous, current } => write!(
+                f,
+                "arguments passed to the task changed from `{}` to `{}`",
+                previous.join(" "),
+                current.join(" ")
+            ),
+            InputChange::GlobalFile { path, change } => {
+                write!(f, "global file `{path}` {change}")
+            }
+            InputChange::GlobalEnvVar { name, change } => {
+                write!(f, "global environment variable `{name}` {change}")
+            }
+            InputChange::TaskConfig { field } => write!(f, "task configuration `{field}` changed"),
+            InputChange::GlobalConfig { field } => {
+                write!(f, "global configuration `{field}` changed")
+            }
+            InputChange::GlobalHash => f.write_str("global inputs changed"),
+        }
+    }
+}
+
+fn env_var_map(pairs: &[String]) -> BTreeMap<String, String> {
+    pairs
+        .iter()
+        .map(|pair| match pair.split_once('=') {
+            Some((name, hash)) => (name.to_owned(), hash.to_owned()),
+            None => (pair.clone(), String::new()),
+        })
+        .collect()
+}
+
+fn file_map(files: &FileHashes) -> BTreeMap<&str, &str> {
+    files
+        .0
+        .iter()
+        .map(|(path, hash)| (path.as_str(), &**hash))
+        .collect()
+}
+
+fn diff_maps<'a, K, V>(
+    previous: &'a BTreeMap<K, V>,
+    current: &'a BTreeMap<K, V>,
+) -> impl Iterator<Item = (String, Change)> + 'a
+where
+    K: Ord + ToString,
+    V: PartialEq,
+{
+    let removed = previous
+        .keys()
+        .filter(|key| !current.contains_key(*key))
+        .map(|key| (key.to_string(), Change::Removed));
+    let added_or_modified = current
+        .iter()
+        .filter_map(|(key, value)| match previous.get(key) {
+            None => Some((key.to_string(), Change::Added)),
+            Some(previous_value) if previous_value != value => {
+                Some((key.to_string(), Change::Modified))
+            }
+            Some(_) => None,
+        });
+    removed.chain(added_or_modified)
+}
+
+/// (De)serializes file hashes as a map of path to hash
+mod file_hashes {
+    use std::{collections::BTreeMap, sync::Arc};
+
+    use serde::{Deserialize, Deserializer, Serializer, de::Error};
+    use turbopath::RelativeUnixPathBuf;
+
+    use crate::{FileHashes, OidHash};
+
+    pub fn serialize<S: Serializer>(
+        files: &Arc<FileHashes>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_map(files.0.iter().map(|(path, hash)| (path.as_str(), &**hash)))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Arc<FileHashes>, D::Error> {
+        let files = BTreeMap::<String, String>::deserialize(deserializer)?;
+        let files = files
+            .into_iter()
+            .map(|(path, hash)| {
+                if hash.len() != 40 || !hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+                    return Err(D::Error::custom(format!("invalid file hash `{hash}`")));
+                }
+                let path = RelativeUnixPathBuf::new(path).map_err(D::Error::custom)?;
+                Ok((path, OidHash::from_hex_str(&hash)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Arc::new(FileHashes(files)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{collections::HashMap, sync::Arc};
+
+    use turbopath::RelativeUnixPathBuf;
+
+    use super::*;
+    use crate::OidHash;
+
+    fn file_hashes(files: &[(&str, char)]) -> Arc<FileHashes> {
+        Arc::new(FileHashes(
+            files
+                .iter()
+                .map(|(path, hash)| {
+                    (
+                        RelativeUnixPathBuf::new(*path).unwrap(),
+                        OidHash::from_hex_str(&hash.to_string().repeat(40)),
+                    )
+                })
+                .collect(),
+        ))
+    }
+
+    fn global_inputs(files: &[(&str, &str)], env_vars: &[String]) -> Arc<GlobalHashInputs> {
+        let global_file_hash_map = files
+            .iter()
+            .map(|(path, hash)| (RelativeUnixPathBuf::new(*path).unwrap(), hash.to_string()))
+            .collect::<HashMap<_, _>>();
+        let hashable = GlobalHashable {
+            global_cache_key: "cache key",
+            global_file_hash_map: &global_file_hash_map,
+            root_external_dependencies_hash: Some("root-deps"),
+            root_internal_dependencies_hash: None,
+            engines: HashMap::new(),
+            env: &[],
+            resolved_env_vars: vec![],
+            pass_through_env: &[],
+            env_mode: EnvMode::Strict,
+            framework_inference: true,
+            global_configuration: false,
+        };
+        Arc::new(GlobalHashInputs::new(
+            &hashable,
+            &format!("{files:?}{env_vars:?}"),
+            env_vars,
+        ))
+    }
+
+    fn task_inputs(
+        global: Arc<GlobalHashInputs>,
+        files: Arc<FileHashes>,
+        dependencies: &[(&str, &str)],
+        pass_through_args: &[String],
+        env_vars: &[String],
+    ) -> TaskHashInputs {
+        let hashable = TaskHashable {
+            global_hash: &global.hash,
+            task_dependency_hashes: vec![],
+            hash_of_files: "files",
+            external_deps_hash: None,
+            package_dir: Some(RelativeUnixPathBuf::new("apps/web").unwrap()),
+            task: "build",
+            outputs: TaskOutputs {
+                inclusions: vec!["dist/**".to_string()],
+                exclusions: vec![],
+            },
+            pass_through_args,
+            command: None,
+            env: &[],
+            resolved_env_vars: vec![],
+            pass_through_env: &[],
+            env_mode: EnvMode::Strict,
+        };
+        TaskHashInputs::new(
+            &hashable,
+            "hash",
+            global,
+            files,
+            dependencies
+                .iter()
+                .map(|(task, hash)| (task.to_string(), hash.to_string()))
+                .collect(),
+            env_vars,
+        )
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let inputs = task_inputs(
+            global_inputs(&[("turbo.json", "abc")], &["CI=1a2b".to_string()]),
+            file_hashes(&[("apps/web/index.ts", 'a')]),
+            &[("ui#build", "123")],
+            &["--verbose".to_string()],
+            &["API_URL=3c4d".to_string()],
+        );
+
+        let json = serde_json::to_string(&inputs).unwrap();
+        let deserialized: TaskHashInputs = serde_json::from_str(&json).unwrap();
+
+        // the global inputs are recorded separately
+        assert_eq!(deserialized.global, None);
+        let global = inputs.global.clone().unwrap();
+        let global_json = serde_json::to_string(&global).unwrap();
+        let deserialized_global: GlobalHashInputs = serde_json::from_str(&global_json).unwrap();
+        assert_eq!(
+            deserialized.with_global(Arc::new(deserialized_global)),
+            inputs
+        );
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["files"]["apps/web/index.ts"], "a".repeat(40));
+        assert_eq!(value["envVars"]["API_URL"], "3c4d");
+        assert_eq!(value["globalHash"], global.hash.as_str());
+        assert!(value.get("global").is_none());
+        let global_value: serde_json::Value = serde_json::from_str(&global_json).unwrap();
+        assert_eq!(global_value["envVars"]["CI"], "1a2b");
+    }
+
+    #[test]
+    fn test_ignores_mismatched_global() {
+        let inputs = task_inputs(global_inputs(&[], &[]), file_hashes(&[]), &[], &[], &[]);
+        let recorded: TaskHashInputs =
+            serde_json::from_str(&serde_json::to_string(&inputs).unwrap()).unwrap();
+        let recorded = recorded.with_global(global_inputs(&[("turbo.json", "abc")], &[]));
+
+        assert_eq!(recorded.global, None);
+    }
+
+    #[test]
+    fn test_rejects_invalid_file_hash() {
+        let inputs = task_inputs(
+            global_inputs(&[], &[]),
+            file_hashes(&[("index.ts", 'a')]),
+            &[],
+            &[],
+            &[],
+        );
+        let mut value = serde_json::to_value(&inputs).unwrap();
+        value["files"]["index.ts"] = "not a hash".into();
+
+        assert!(serde_json::from_value::<TaskHashInputs>(value).is_err());
+    }
+
+    #[test]
+    fn test_no_changes() {
+        let global = global_inputs(&[("turbo.json", "abc")], &[]);
+        let files = file_hashes(&[("index.ts", 'a')]);
+        let previous = task_inputs(global.clone(), files.clone(), &[], &[], &[]);
+        let current = task_inputs(global, file_hashes(&[("index.ts", 'a')]), &[], &[], &[]);
+
+        assert_eq!(current.changes_since(&previous), vec![]);
+    }
+
+    #[test]
+    fn test_task_changes() {
+        let global = global_inputs(&[], &[]);
+        let previous = task_inputs(
+            global.clone(),
+            file_hashes(&[("a.ts", 'a'), ("b.ts", 'b')]),
+            &[("ui#build", "123"), ("utils#build", "456")],
+            &[],
+            &["API_URL=1111".to_string(), "OLD=2222".to_string()],
+        );
+        let current = task_inputs(
+            global,
+            file_hashes(&[("a.ts", 'c'), ("c.ts", 'c')]),
+            &[("ui#build", "789"), ("utils#build", "456")],
+            &["--watch".to_string()],
+            &["API_URL=3333".to_string()],
+        );
+
+        assert_eq!(
+            current.changes_since(&previous),
+            vec![
+                InputChange::File {
+                    path: "b.ts".to_string(),
+                    change: Change::Removed
+                },
+                InputChange::File {
+                    path: "a.ts".to_string(),
+                    change: Change::Modified
+                },
+                InputChange::File {
+                    path: "c.ts".to_string(),
+                    change: Change::Added
+                },
+                InputChange::EnvVar {
+                    name: "OLD".to_string(),
+                    change: Change::Removed
+                },
+                InputChange::EnvVar {
+                    name: "API_URL".to_string(),
+                    change: Change::Modified
+                },
+                InputChange::Dependency {
+                    task_id: "ui#build".to_string(),
+                    change: Change::Modified
+                },
+                InputChange::PassThroughArgs {
+                    previous: vec![],
+                    current: vec!["--watch".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_global_changes() {
+        let files = file_hashes(&[("index.ts", 'a')]);
+        let previous = task_inputs(
+            global_inputs(&[("tsconfig.json", "abc")], &["CI=1111".to_string()]),
+            files.clone(),
+            &[],
+            &[],
+            &[],
+        );
+        let current = task_inputs(
+            global_inputs(&[("tsconfig.json", "def")], &["CI=2222".to_string()]),
+            files,
+            &[],
+            &[],
+            &[],
+        );
+
+        let changes = current.changes_since(&previous);
+        assert_eq!(
+            changes,
+            vec![
+                InputChange::GlobalFile {
+                    path: "tsconfig.json".to_string(),
+                    change: Change::Modified
+                },
+                InputChange::GlobalEnvVar {
+                    name: "CI".to_string(),
+                    change: Change::Modified
+                },
+            ]
+        );
+        assert_eq!(
+            changes[0].to_string(),
+            "global file `tsconfig.json` changed"
+        );
+
+        let mut unrecorded = previous.clone();
+        unrecorded.global = None;
+        assert_eq!(
+            current.changes_since(&unrecorded),
+            vec![InputChange::GlobalHash]
+        );
+    }
+}
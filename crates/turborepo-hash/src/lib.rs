@@ -6,6 +6,7 @@
 
 #![allow(clippy::expect_used, clippy::unwrap_used)]
 
+pub mod explain;
 mod oid_hash;
 mod traits;
 
@@ -17,7 +18,7 @@ use capnp::{
 };
 pub use oid_hash::OidHash;
 pub use traits::TurboHash;
-use turborepo_types::{EnvMode, TaskOutputs};
+use turborepo_types::{EnvMode, TaskCommand, TaskOutputs};
 
 #[allow(dead_code)]
 mod proto_capnp {
@@ -51,7 +52,7 @@ pub type EnvVarPairs = Vec<String>;
 /// This matches the type from turborepo-env::EnvironmentVariablePairs.
 pub type EnvironmentVariablePairs = Vec<String>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct TaskHashable<'a> {
     // hashes
     pub global_hash: &'a str,
@@ -64,6 +65,7 @@ pub struct TaskHashable<'a> {
     pub task: &'a str,
     pub outputs: TaskOutputs,
     pub pass_through_args: &'a [String],
+    pub command: Option<&'a TaskCommand>,
 
     // env
     pub env: &'a [String],
@@ -105,7 +107,7 @@ pub struct LockFilePackages(pub Vec<turborepo_lockfiles::Package>);
 
 pub struct LockFilePackagesRef<'a>(pub Vec<&'a turborepo_lockfiles::Package>);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct FileHashes(pub Vec<(turbopath::RelativeUnixPathBuf, OidHash)>);
 
 /// Wrapper type for TaskOutputs to enable capnp serialization.
@@ -343,6 +345,19 @@ impl HashableMessage for TaskHashable<'_> {
         builder.set_task(task_hashable.task);
         builder.set_env_mode(task_hashable.env_mode.into());
 
+        // Unset fields are dropped from the canonical message, so tasks without
+        // an inline command keep the hashes they had before it was hashed.
+        match task_hashable.command {
+            Some(TaskCommand::Shell(command)) => builder.set_command(command),
+            Some(TaskCommand::Argv(argv)) => {
+                let mut argv_builder = builder.reborrow().init_command_argv(argv.len() as u32);
+                for (i, arg) in argv.iter().enumerate() {
+                    argv_builder.set(i as u32, arg);
+                }
+            }
+            None => {}
+        }
+
         {
             let output_builder: Builder<_> = HashableTaskOutputs(task_hashable.outputs).into();
             let output_reader = output_builder
@@ -503,7 +518,7 @@ mod test {
 
     use test_case::test_case;
     use turborepo_lockfiles::Package;
-    use turborepo_types::{EnvMode, TaskOutputs};
+    use turborepo_types::{EnvMode, TaskCommand, TaskOutputs};
 
     use super::{
         FileHashes, GlobalHashable, LockFilePackages, LockFilePackagesRef, OidHash, TaskHashable,
@@ -524,6 +539,7 @@ mod test {
                 exclusions: vec!["exclusions".to_string()],
             },
             pass_through_args: &["pass_thru_args".to_string()],
+            command: None,
             env: &["env".to_string()],
             resolved_env_vars: vec![],
             pass_through_env: &["pass_thru_env".to_string()],
@@ -551,6 +567,7 @@ mod test {
                 exclusions: vec!["exclusions".to_string()],
             },
             pass_through_args: &["pass_thru_args".to_string()],
+            command: None,
             env: &["env".to_string()],
             resolved_env_vars: vec![],
             pass_through_env: &["pass_thru_env".to_string()],
@@ -563,6 +580,43 @@ mod test {
         assert_eq!(hash, "7676d7bb7c86d257");
     }
 
+    #[test]
+    fn task_hashable_command() {
+        let hash_with_command = |command: Option<&TaskCommand>| {
+            TaskHashable {
+                global_hash: "global_hash",
+                task_dependency_hashes: vec![],
+                package_dir: None,
+                hash_of_files: "hash_of_files",
+                external_deps_hash: None,
+                task: "task",
+                outputs: TaskOutputs::default(),
+                pass_through_args: &[],
+                command,
+                env: &[],
+                resolved_env_vars: vec![],
+                pass_through_env: &[],
+                env_mode: EnvMode::Strict,
+            }
+            .hash()
+        };
+
+        let shell = TaskCommand::Shell("echo hello".to_string());
+        let edited = TaskCommand::Shell("echo goodbye".to_string());
+        let argv = TaskCommand::Argv(vec!["echo".to_string(), "hello".to_string()]);
+
+        let hashes = [
+            hash_with_command(None),
+            hash_with_command(Some(&shell)),
+            hash_with_command(Some(&edited)),
+            hash_with_command(Some(&argv)),
+        ];
+        for (i, hash) in hashes.iter().enumerate() {
+            assert!(!hashes[i + 1..].contains(hash), "duplicate hash {hash}");
+        }
+        assert_eq!(hashes[1], hash_with_command(Some(&shell)));
+    }
+
     #[test]
     fn global_hashable() {
         let global_file_hash_map = vec![(
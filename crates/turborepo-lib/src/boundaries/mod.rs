@@ -6,7 +6,7 @@
 use std::collections::HashMap;
 
 use turborepo_boundaries::{BoundariesChecker, BoundariesContext, TurboJsonProvider};
-pub use turborepo_boundaries::{BoundariesConfig, BoundariesResult, Error};
+pub use turborepo_boundaries::{BoundariesConfig, BoundariesResult, DependencyFix, Error};
 use turborepo_errors::Spanned;
 use turborepo_repository::package_graph::PackageName;
 
@@ -80,6 +80,22 @@ impl Run {
         BoundariesChecker::check_boundaries(&ctx, show_progress)
     }
 
+    /// Adds the workspace dependencies that `result` reports as missing to
+    /// their packages' `package.json`, removing those diagnostics from
+    /// `result`.
+    pub fn fix_missing_dependencies(
+        &self,
+        result: &mut BoundariesResult,
+    ) -> Result<Vec<DependencyFix>, Error> {
+        let fixes = result.take_dependency_fixes(
+            self.repo_root(),
+            self.pkg_dep_graph(),
+            self.pkg_dep_graph().package_manager(),
+        );
+        BoundariesChecker::apply_dependency_fixes(&fixes)?;
+        Ok(fixes)
+    }
+
     /// Patch a file with @boundaries-ignore comments
     pub fn patch_file(
         &self,
@@ -1,4 +1,4 @@
-use std::{env, ffi::OsString, fmt};
+use std::{env, ffi::OsString, fmt, net::IpAddr};
 
 use camino::{Utf8Path, Utf8PathBuf};
 use clap::{ArgAction, ArgGroup, CommandFactory, Parser, Subcommand, ValueEnum};
@@ -10,7 +10,8 @@ use turborepo_telemetry::{
     track_usage,
 };
 use turborepo_types::{
-    ContinueMode, DryRunMode, EnvMode, LogOrder, LogPrefix, OutputLogsMode, UIMode,
+    ContinueMode, DryRunMode, EnvMode, LogOrder, LogPrefix, OutputLogsMode, SummaryFormat, UIMode,
+    UndeclaredFilesMode,
 };
 
 use super::{exit_with_heap_profile, observability};
@@ -194,6 +195,73 @@ pub enum TelemetryCommand {
     Status,
 }
 
+#[derive(Subcommand, Clone, Debug, PartialEq)]
+pub enum CacheCommand {
+    /// Lists the artifacts in the local cache
+    Ls {
+        /// Output format
+        #[clap(long, value_enum)]
+        output: Option<OutputFormat>,
+    },
+    /// Lists the files in a cached artifact without restoring them
+    Show {
+        /// The hash of the artifact
+        hash: String,
+        /// Output format
+        #[clap(long, value_enum)]
+        output: Option<OutputFormat>,
+    },
+    /// Removes old artifacts from the local cache. Defaults to the configured
+    /// `cacheMaxAge` and `cacheMaxSize` when no limits are passed.
+    Prune {
+        /// Remove artifacts older than this (e.g. `7d`, `12h`)
+        #[clap(long)]
+        max_age: Option<String>,
+        /// Remove the oldest artifacts until the cache is smaller than this
+        /// (e.g. `10GB`, `500MB`)
+        #[clap(long)]
+        max_size: Option<String>,
+        /// Output format
+        #[clap(long, value_enum)]
+        output: Option<OutputFormat>,
+    },
+    /// Checks that cached artifacts are complete and can be restored
+    Verify {
+        /// The hashes of the artifacts to check. Checks every artifact if
+        /// none are given.
+        hashes: Vec<String>,
+        /// Output format
+        #[clap(long, value_enum)]
+        output: Option<OutputFormat>,
+    },
+    /// Runs a self-hosted Remote Cache that stores artifacts in a directory
+    Serve {
+        /// The directory to store artifacts in
+        #[clap(long)]
+        dir: Utf8PathBuf,
+        /// The address to listen on
+        #[clap(long, default_value = "127.0.0.1")]
+        host: IpAddr,
+        /// The port to listen on
+        #[clap(long, default_value_t = turborepo_cache_server::DEFAULT_PORT)]
+        port: u16,
+        /// A token that clients must send to use the cache. Pass it more than
+        /// once, or as a comma-separated list, to accept several tokens.
+        #[clap(
+            long = "token",
+            env = "TURBO_CACHE_SERVER_TOKENS",
+            hide_env_values = true,
+            value_delimiter = ',',
+            required = true
+        )]
+        tokens: Vec<String>,
+        /// Evict the least recently used artifacts once the directory is
+        /// larger than this (e.g. `50GB`)
+        #[clap(long)]
+        max_size: Option<String>,
+    },
+}
+
 /// Returns formatted RunArgs options derived from clap's command definition.
 /// These options aren't included in the usage line by clap because they're all
 /// optional.
@@ -570,6 +638,14 @@ pub enum Command {
         ignore: Option<BoundariesIgnore>,
         #[clap(long, requires = "ignore")]
         reason: Option<String>,
+        /// Add missing dependencies on workspace packages to package.json
+        #[clap(long, conflicts_with = "ignore")]
+        fix: bool,
+    },
+    /// Inspect and manage the local cache
+    Cache {
+        #[clap(subcommand)]
+        command: CacheCommand,
     },
     /// Generate the autocompletion script for the specified shell
     Completion { shell: Shell },
@@ -601,6 +677,15 @@ pub enum Command {
         #[clap(long)]
         docs_version: Option<String>,
     },
+    /// Explain which inputs changed between the two most recent cached runs
+    /// of a task
+    Explain {
+        /// The task to explain, as `<package>#<task>`
+        task: String,
+        /// Output format
+        #[clap(long, value_enum)]
+        output: Option<OutputFormat>,
+    },
     /// Generate a new app / package
     #[clap(aliases = ["g", "gen"])]
     Generate {
@@ -645,6 +730,10 @@ pub enum Command {
         /// turbo's documentation https://turborepo.dev/docs/reference/command-line-reference/run#--filter
         #[clap(short = 'F', long)]
         filter: Vec<String>,
+        /// Limit how many levels of dependencies or dependents `...` in
+        /// filters and `--affected` include
+        #[clap(long, value_name = "DEPTH", value_parser = parse_filter_depth)]
+        filter_depth: Option<usize>,
         /// Get insight into a specific package, such as
         /// its dependencies and tasks
         packages: Vec<String>,
@@ -840,6 +929,10 @@ pub struct LsArgs {
     /// turbo's documentation https://turborepo.dev/docs/reference/command-line-reference/run#--filter
     #[clap(short = 'F', long)]
     pub filter: Vec<String>,
+    /// Limit how many levels of dependencies or dependents `...` in
+    /// filters and `--affected` include
+    #[clap(long, value_name = "DEPTH", value_parser = parse_filter_depth)]
+    pub filter_depth: Option<usize>,
     /// Get insight into a specific package, such as
     /// its dependencies and tasks
     pub packages: Vec<String>,
@@ -873,6 +966,15 @@ pub struct AffectedArgs {
     pub exit_code: bool,
 }
 
+fn parse_filter_depth(s: &str) -> Result<usize, String> {
+    match s.parse::<usize>() {
+        Ok(depth) if depth > 0 => Ok(depth),
+        _ => Err(format!(
+            "Invalid filter depth: '{s}'. Must be a positive integer."
+        )),
+    }
+}
+
 fn validate_graph_extension(s: &str) -> Result<String, String> {
     match s.is_empty() {
         true => Ok(s.to_string()),
@@ -918,6 +1020,10 @@ pub struct ExecutionArgs {
     /// failed.
     #[clap(long = "continue", value_name = "CONTINUE", num_args = 0..=1, default_value = "never", default_missing_value = "always", require_equals = true)]
     pub continue_execution: ContinueMode,
+    /// Retry failed tasks up to the given number of times before reporting
+    /// them as failed. Overrides the `retries` setting in turbo.json.
+    #[clap(long, value_name = "COUNT")]
+    pub retries: Option<u32>,
     /// Run turbo in single-package mode
     #[clap(long)]
     pub single_package: bool,
@@ -945,6 +1051,13 @@ pub struct ExecutionArgs {
     #[clap(long, group = "scope-filter-group")]
     pub affected: bool,
 
+    /// Limit how many levels of dependencies or dependents `...` in
+    /// filters and `--affected` include. For example, `--filter=...lib
+    /// --filter-depth=1` selects `lib` and the packages that directly
+    /// depend on it.
+    #[clap(long, value_name = "DEPTH", value_parser = parse_filter_depth)]
+    pub filter_depth: Option<usize>,
+
     /// Set type of process output logging. Use "full" to show
     /// all output. Use "hash-only" to show only turbo-computed
     /// task hashes. Use "new-only" to show only new output with
@@ -958,6 +1071,18 @@ pub struct ExecutionArgs {
     /// turbo decide based on its own heuristics. (default auto)
     #[clap(long, value_enum)]
     pub log_order: Option<LogOrder>,
+    /// Check for files a task creates or modifies that are not covered by
+    /// its `outputs`. Use "warn" to print a warning listing them. Use
+    /// "error" to fail the task. Use "off" to skip the check. (default off)
+    #[clap(long, value_enum)]
+    pub undeclared_outputs: Option<UndeclaredFilesMode>,
+    /// Check for files a task reads that are not covered by its `inputs`,
+    /// its dependencies' `inputs` and `outputs`, or `globalDependencies`.
+    /// Requires Linux with `strace` installed. Use "warn" to print a warning
+    /// listing them. Use "error" to fail the task. Use "off" to skip the
+    /// check. (default off)
+    #[clap(long, value_enum)]
+    pub undeclared_inputs: Option<UndeclaredFilesMode>,
     /// Output machine-readable NDJSON to stdout instead of human-readable
     /// text. Disables the TUI and forces stream mode.
     #[clap(long)]
@@ -966,6 +1091,11 @@ pub struct ExecutionArgs {
     /// `.turbo/logs/<epoch_millis>.json`.
     #[clap(long)]
     pub log_file: Option<Option<String>>,
+    /// EXPERIMENTAL: Serve GraphQL queries and subscriptions to task events
+    /// for this run on localhost. Listens on PORT if given, otherwise on a
+    /// free port that's printed when the run starts.
+    #[clap(long, value_name = "PORT", num_args = 0..=1, default_missing_value = "0", require_equals = true)]
+    pub experimental_query_server: Option<u16>,
     /// Only executes the tasks specified, does not execute parent tasks.
     #[clap(long)]
     pub only: bool,
@@ -1003,8 +1133,13 @@ impl ExecutionArgs {
             EventType::NonSensitive,
         );
 
+        if let Some(retries) = self.retries {
+            telemetry.track_arg_value("retries", retries, EventType::NonSensitive);
+        }
+
         track_usage!(telemetry, self.single_package, |val| val);
         track_usage!(telemetry, self.only, |val| val);
+        track_usage!(telemetry, &self.experimental_query_server, Option::is_some);
         track_usage!(telemetry, &self.cache_dir, Option::is_some);
         track_usage!(telemetry, &self.pkg_inference_root, Option::is_some);
 
@@ -1032,10 +1167,30 @@ impl ExecutionArgs {
             telemetry.track_arg_value("log-order", log_order, EventType::NonSensitive);
         }
 
+        if let Some(undeclared_outputs) = self.undeclared_outputs {
+            telemetry.track_arg_value(
+                "undeclared-outputs",
+                undeclared_outputs,
+                EventType::NonSensitive,
+            );
+        }
+
+        if let Some(undeclared_inputs) = self.undeclared_inputs {
+            telemetry.track_arg_value(
+                "undeclared-inputs",
+                undeclared_inputs,
+                EventType::NonSensitive,
+            );
+        }
+
         if self.log_prefix != LogPrefix::default() {
             telemetry.track_arg_value("log-prefix", self.log_prefix, EventType::NonSensitive);
         }
 
+        if let Some(filter_depth) = self.filter_depth {
+            telemetry.track_arg_value("filter-depth", filter_depth, EventType::NonSensitive);
+        }
+
         // track sizes
         if !self.filter.is_empty() {
             telemetry.track_arg_value("filter:length", self.filter.len(), EventType::NonSensitive);
@@ -1070,6 +1225,10 @@ pub struct RunArgs {
     /// tasks. Equivalent to `--cache=local:r,remote:r`
     #[clap(long)]
     pub no_cache: bool,
+    /// When a task misses the cache, report which of its inputs changed since
+    /// the most recent local cache entry for that task
+    #[clap(long)]
+    pub explain_miss: bool,
 
     /// Set the number of concurrent cache operations (default 10)
     #[clap(long, default_value_t = DEFAULT_NUM_WORKERS)]
@@ -1107,6 +1266,11 @@ pub struct RunArgs {
     /// Generate a summary of the turbo run
     #[clap(long, default_missing_value = "true")]
     pub summarize: Option<Option<bool>>,
+    /// Formats to write the run summary in, separated by commas. Use "json"
+    /// for turbo's own summary. Use "junit" for a JUnit XML report with a
+    /// test case per task. Implies --summarize. (default json)
+    #[clap(long, value_enum, value_delimiter = ',')]
+    pub summary_format: Vec<SummaryFormat>,
 
     /// [DEPRECATED] Execute all tasks in parallel. Use task configuration
     /// (`persistent`, `with`) instead.
@@ -1124,12 +1288,14 @@ impl Default for RunArgs {
             dry_run: None,
             graph: None,
             no_cache: false,
+            explain_miss: false,
             daemon: false,
             no_daemon: false,
             profile: None,
             anon_profile: None,
             remote_cache_read_only: None,
             summarize: None,
+            summary_format: Vec::new(),
             parallel: false,
         }
     }
@@ -1186,6 +1352,7 @@ impl RunArgs {
     pub fn track(&self, telemetry: &CommandEventBuilder) {
         // default to true
         track_usage!(telemetry, self.no_cache, |val| val);
+        track_usage!(telemetry, self.explain_miss, |val| val);
         track_usage!(telemetry, self.remote_only().unwrap_or_default(), |val| val);
         track_usage!(telemetry, &self.force, Option::is_some);
         track_usage!(telemetry, self.daemon, |val| val);
@@ -1216,5 +1383,9 @@ impl RunArgs {
             let extension = Utf8Path::new(graph).extension().unwrap_or("stdout");
             telemetry.track_arg_value("graph", extension, EventType::NonSensitive);
         }
+
+        for summary_format in &self.summary_format {
+            telemetry.track_arg_value("summary-format", summary_format, EventType::NonSensitive);
+        }
     }
 }
@@ -9,7 +9,9 @@ use turborepo_telemetry::events::command::CommandEventBuilder;
 use turborepo_ui::{color, BOLD, GREY};
 
 use crate::{
-    commands::{bin, docs, generate, get_mfe_port, link, login, ls, prune, CommandBase},
+    commands::{
+        bin, cache, docs, explain, generate, get_mfe_port, link, login, ls, prune, CommandBase,
+    },
     run,
     run::{builder::RunBuilder, watch},
 };
@@ -23,6 +25,9 @@ pub enum Error {
     #[error("{0}")]
     Bin(#[from] bin::Error),
     #[error(transparent)]
+    #[diagnostic(transparent)]
+    Cache(#[from] cache::Error),
+    #[error(transparent)]
     Boundaries(#[from] crate::boundaries::Error),
     #[error(transparent)]
     Path(#[from] turbopath::PathError),
@@ -43,6 +48,9 @@ pub enum Error {
     #[error(transparent)]
     Docs(#[from] docs::Error),
     #[error(transparent)]
+    #[diagnostic(transparent)]
+    Explain(#[from] explain::Error),
+    #[error(transparent)]
     Generate(#[from] generate::Error),
     #[error(transparent)]
     GetMfePort(#[from] get_mfe_port::Error),
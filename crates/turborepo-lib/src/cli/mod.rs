@@ -18,8 +18,8 @@ use turborepo_ui::{ColorConfig, GREY};
 use crate::{
     cli::error::print_potential_tasks,
     commands::{
-        bin, boundaries, config, daemon, docs, generate, get_mfe_port, info, link, login, logout,
-        ls, prune, query, run, telemetry, unlink, CommandBase,
+        bin, boundaries, cache, config, daemon, docs, explain, generate, get_mfe_port, info, link,
+        login, logout, ls, prune, query, run, telemetry, unlink, CommandBase,
     },
     get_version,
     run::watch::WatchClient,
@@ -34,9 +34,9 @@ mod test;
 
 #[allow(unused_imports)]
 pub use args::{
-    AffectedArgs, Args, BoundariesIgnore, Command, DaemonCommand, ExecutionArgs, GenerateCommand,
-    GenerateWorkspaceArgs, GeneratorCustomArgs, LsArgs, OutputFormat, QuerySubcommand, RunArgs,
-    TelemetryCommand, Verbosity,
+    AffectedArgs, Args, BoundariesIgnore, CacheCommand, Command, DaemonCommand, ExecutionArgs,
+    GenerateCommand, GenerateWorkspaceArgs, GeneratorCustomArgs, LsArgs, OutputFormat,
+    QuerySubcommand, RunArgs, TelemetryCommand, Verbosity,
 };
 
 fn exit_with_heap_profile(code: i32) -> ! {
@@ -379,15 +379,28 @@ async fn run_main(
 
             Ok(0)
         }
-        Command::Boundaries { ignore, reason, .. } => {
+        Command::Boundaries {
+            ignore,
+            reason,
+            fix,
+            ..
+        } => {
             let event = CommandEventBuilder::new("boundaries").with_parent(&root_telemetry);
             let ignore = *ignore;
             let reason = reason.clone();
+            let fix = *fix;
 
             event.track_call();
             let base = CommandBase::new(cli_args.clone(), repo_root, version, color_config)?;
 
-            Ok(boundaries::run(base, event, ignore, reason).await?)
+            Ok(boundaries::run(base, event, ignore, reason, fix).await?)
+        }
+        Command::Cache { command } => {
+            let event = CommandEventBuilder::new("cache").with_parent(&root_telemetry);
+            event.track_call();
+            let base = CommandBase::new(cli_args.clone(), repo_root, version, color_config)?;
+
+            Ok(cache::run(&base, command).await?)
         }
         #[allow(unused_variables)]
         Command::Daemon {
@@ -428,6 +441,13 @@ async fn run_main(
             docs::run(query, docs_version.as_deref()).await?;
             Ok(0)
         }
+        Command::Explain { task, output } => {
+            let event = CommandEventBuilder::new("explain").with_parent(&root_telemetry);
+            event.track_call();
+            let base = CommandBase::new(cli_args.clone(), repo_root, version, color_config)?;
+
+            Ok(explain::run(&base, task, output.unwrap_or_default())?)
+        }
         Command::Generate {
             tag,
             generator_name,
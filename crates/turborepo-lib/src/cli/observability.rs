@@ -109,6 +109,17 @@ pub struct ExperimentalOtelCliArgs {
     )]
     pub metrics_task_details: Option<bool>,
 
+    /// Export a trace for each run, with a span per task and links between
+    /// tasks and their dependencies.
+    #[clap(
+        long = "experimental-otel-traces",
+        global = true,
+        num_args = 0..=1,
+        default_missing_value = "true",
+        help = "Emit a trace with a span per task (default: false)"
+    )]
+    pub traces: Option<bool>,
+
     /// Use the Vercel remote cache authentication token for OTLP requests.
     /// Automatically adds an Authorization header with the token.
     #[clap(
@@ -176,6 +187,10 @@ impl ExperimentalOtelCliArgs {
                 .task_details = Some(value);
             touched = true;
         }
+        if let Some(value) = self.traces {
+            options.traces = Some(value);
+            touched = true;
+        }
         if let Some(value) = self.use_remote_cache_token {
             options.use_remote_cache_token = Some(value);
             touched = true;
@@ -427,6 +442,17 @@ mod tests {
         assert_eq!(metrics.task_details, Some(false));
     }
 
+    #[test]
+    fn test_experimental_otel_cli_args_traces() {
+        let args = ExperimentalOtelCliArgs {
+            traces: Some(true),
+            ..Default::default()
+        };
+        let result = args.to_config();
+        assert!(result.is_some());
+        assert_eq!(result.unwrap().traces, Some(true));
+    }
+
     #[test]
     fn test_experimental_otel_cli_args_combined() {
         let args = ExperimentalOtelCliArgs {
@@ -439,6 +465,7 @@ mod tests {
             resource_attributes: vec![("service.name".to_string(), "test".to_string())],
             metrics_run_summary: Some(true),
             metrics_task_details: Some(false),
+            traces: Some(true),
             use_remote_cache_token: None,
         };
         let result = args.to_config();
@@ -460,6 +487,7 @@ mod tests {
         let metrics = opts.metrics.unwrap();
         assert_eq!(metrics.run_summary, Some(true));
         assert_eq!(metrics.task_details, Some(false));
+        assert_eq!(opts.traces, Some(true));
     }
 
     #[test]
@@ -6,7 +6,7 @@ use insta::assert_snapshot;
 use itertools::Itertools;
 use pretty_assertions::assert_eq;
 
-use crate::cli::{ExecutionArgs, RunArgs};
+use crate::cli::{CacheCommand, ExecutionArgs, OutputFormat, RunArgs};
 
 fn get_subcommand(name: &str) -> clap::Command {
     Args::command()
@@ -53,6 +53,18 @@ fn run_args_summarize_parses_optional_boolean(args: Vec<&str>, expected: Option<
     assert_eq!(run_args.summarize(), expected);
 }
 
+#[test_case::test_case(vec!["turbo", "run", "build"], vec![] ; "missing")]
+#[test_case::test_case(vec!["turbo", "run", "build", "--summary-format", "junit"], vec![SummaryFormat::Junit] ; "junit")]
+#[test_case::test_case(vec!["turbo", "run", "build", "--summary-format=json,junit"], vec![SummaryFormat::Json, SummaryFormat::Junit] ; "comma separated")]
+fn run_args_summary_format(args: Vec<&str>, expected: Vec<SummaryFormat>) {
+    let args = Args::try_parse_from(args).unwrap();
+    let Command::Run { run_args, .. } = args.command.unwrap() else {
+        panic!("expected run command");
+    };
+
+    assert_eq!(run_args.summary_format, expected);
+}
+
 #[test]
 fn turbo_short_help() {
     let mut cmd = Args::command();
@@ -154,7 +166,10 @@ impl CommandTestCase {
     }
 }
 
-use turborepo_types::{ContinueMode, DryRunMode, EnvMode, LogOrder, LogPrefix, OutputLogsMode};
+use turborepo_types::{
+    ContinueMode, DryRunMode, EnvMode, LogOrder, LogPrefix, OutputLogsMode, SummaryFormat,
+    UndeclaredFilesMode,
+};
 
 use crate::cli::{Args, Command};
 
@@ -369,6 +384,36 @@ use crate::cli::{Args, Command};
     } ;
     "continue option with no value before task"
 	)]
+#[test_case::test_case(
+		&["turbo", "run", "--experimental-query-server", "build"],
+    Args {
+        command: Some(Command::Run {
+            execution_args: Box::new(ExecutionArgs {
+                tasks: vec!["build".to_string()],
+                experimental_query_server: Some(0),
+                ..get_default_execution_args()
+            }),
+            run_args: Box::new(get_default_run_args())
+        }),
+        ..Args::default()
+    } ;
+    "query server with no port before task"
+	)]
+#[test_case::test_case(
+		&["turbo", "run", "build", "--experimental-query-server=4000"],
+    Args {
+        command: Some(Command::Run {
+            execution_args: Box::new(ExecutionArgs {
+                tasks: vec!["build".to_string()],
+                experimental_query_server: Some(4000),
+                ..get_default_execution_args()
+            }),
+            run_args: Box::new(get_default_run_args())
+        }),
+        ..Args::default()
+    } ;
+    "query server with port"
+	)]
 #[test_case::test_case(
 		&["turbo", "run", "build", "--continue=dependencies-successful"],
     Args {
@@ -384,6 +429,21 @@ use crate::cli::{Args, Command};
     } ;
     "continue option with explicit value"
 	)]
+#[test_case::test_case(
+		&["turbo", "run", "test", "--retries=2"],
+    Args {
+        command: Some(Command::Run {
+            execution_args: Box::new(ExecutionArgs {
+                tasks: vec!["test".to_string()],
+                retries: Some(2),
+                ..get_default_execution_args()
+            }),
+            run_args: Box::new(get_default_run_args())
+        }),
+        ..Args::default()
+    } ;
+    "retries"
+	)]
 #[test_case::test_case(
 		&["turbo", "run", "build", "--dry-run"],
     Args {
@@ -576,6 +636,23 @@ use crate::cli::{Args, Command};
     } ;
     "no cache"
 	)]
+#[test_case::test_case(
+		&["turbo", "run", "build", "--explain-miss"],
+    Args {
+        command: Some(Command::Run {
+            execution_args: Box::new(ExecutionArgs {
+                tasks: vec!["build".to_string()],
+                ..get_default_execution_args()
+            }),
+            run_args: Box::new(RunArgs {
+                explain_miss: true,
+                ..get_default_run_args()
+            })
+        }),
+        ..Args::default()
+    } ;
+    "explain miss"
+	)]
 #[test_case::test_case(
 		&["turbo", "run", "build", "--only"],
     Args {
@@ -700,6 +777,36 @@ use crate::cli::{Args, Command};
     };
     "log order grouped"
 	)]
+#[test_case::test_case(
+		&["turbo", "run", "build", "--undeclared-outputs", "error"],
+    Args {
+        command: Some(Command::Run {
+            execution_args: Box::new(ExecutionArgs {
+                tasks: vec!["build".to_string()],
+                undeclared_outputs: Some(UndeclaredFilesMode::Error),
+                ..get_default_execution_args()
+            }),
+            run_args: Box::new(get_default_run_args())
+        }),
+        ..Args::default()
+    };
+    "undeclared outputs error"
+	)]
+#[test_case::test_case(
+		&["turbo", "run", "build", "--undeclared-inputs", "warn"],
+    Args {
+        command: Some(Command::Run {
+            execution_args: Box::new(ExecutionArgs {
+                tasks: vec!["build".to_string()],
+                undeclared_inputs: Some(UndeclaredFilesMode::Warn),
+                ..get_default_execution_args()
+            }),
+            run_args: Box::new(get_default_run_args())
+        }),
+        ..Args::default()
+    };
+    "undeclared inputs warn"
+	)]
 #[test_case::test_case(
 		&["turbo", "run", "build", "--log-prefix", "auto"],
     Args {
@@ -1120,6 +1227,93 @@ fn test_parse_link() {
     .test();
 }
 
+#[test]
+fn test_parse_cache() {
+    assert_eq!(
+        Args::try_parse_from(["turbo", "cache", "ls", "--output", "json"]).unwrap(),
+        Args {
+            command: Some(Command::Cache {
+                command: CacheCommand::Ls {
+                    output: Some(OutputFormat::Json),
+                },
+            }),
+            ..Args::default()
+        }
+    );
+
+    assert_eq!(
+        Args::try_parse_from(["turbo", "cache", "prune", "--max-age", "7d"]).unwrap(),
+        Args {
+            command: Some(Command::Cache {
+                command: CacheCommand::Prune {
+                    max_age: Some("7d".to_string()),
+                    max_size: None,
+                    output: None,
+                },
+            }),
+            ..Args::default()
+        }
+    );
+
+    assert_eq!(
+        Args::try_parse_from(["turbo", "cache", "verify", "abc", "def"]).unwrap(),
+        Args {
+            command: Some(Command::Cache {
+                command: CacheCommand::Verify {
+                    hashes: vec!["abc".to_string(), "def".to_string()],
+                    output: None,
+                },
+            }),
+            ..Args::default()
+        }
+    );
+
+    assert!(Args::try_parse_from(["turbo", "cache", "show"]).is_err());
+
+    assert_eq!(
+        Args::try_parse_from([
+            "turbo",
+            "cache",
+            "serve",
+            "--dir",
+            "/srv/turbo",
+            "--token",
+            "one,two",
+            "--max-size",
+            "50GB"
+        ])
+        .unwrap(),
+        Args {
+            command: Some(Command::Cache {
+                command: CacheCommand::Serve {
+                    dir: Utf8PathBuf::from("/srv/turbo"),
+                    host: [127, 0, 0, 1].into(),
+                    port: turborepo_cache_server::DEFAULT_PORT,
+                    tokens: vec!["one".to_string(), "two".to_string()],
+                    max_size: Some("50GB".to_string()),
+                },
+            }),
+            ..Args::default()
+        }
+    );
+}
+
+#[test]
+fn test_parse_explain() {
+    assert_eq!(
+        Args::try_parse_from(["turbo", "explain", "web#build", "--output", "json"]).unwrap(),
+        Args {
+            command: Some(Command::Explain {
+                task: "web#build".to_string(),
+                output: Some(OutputFormat::Json),
+            }),
+            ..Args::default()
+        }
+    );
+
+    assert!(Args::try_parse_from(["turbo", "explain"]).is_err());
+}
+
 #[test]
 fn test_parse_login() {
     assert_eq!(
@@ -1687,6 +1881,17 @@ fn test_affected_and_filter_can_be_combined() {
     assert!(Args::try_parse_from(["turbo", "ls", "--filter", "foo", "--affected"]).is_ok(),);
 }
 
+#[test]
+fn test_filter_depth() {
+    assert!(
+        Args::try_parse_from(["turbo", "build", "--filter=...foo", "--filter-depth=1"]).is_ok()
+    );
+    assert!(Args::try_parse_from(["turbo", "build", "--affected", "--filter-depth", "2"]).is_ok());
+    assert!(Args::try_parse_from(["turbo", "ls", "--affected", "--filter-depth=1"]).is_ok());
+    assert!(Args::try_parse_from(["turbo", "build", "--filter-depth=0"]).is_err());
+    assert!(Args::try_parse_from(["turbo", "build", "--filter-depth=-1"]).is_err());
+}
+
 struct SinglePackageTestCase {
     args: &'static [&'static str],
     expected_is_single: bool,
@@ -5,15 +5,22 @@ use miette::{Report, SourceSpan};
 use turbopath::AbsoluteSystemPath;
 use turborepo_signals::{listeners::get_signal, SignalHandler};
 use turborepo_telemetry::events::command::CommandEventBuilder;
-use turborepo_ui::{color, BOLD_GREEN};
+use turborepo_ui::{color, BOLD, BOLD_GREEN};
 
-use crate::{cli, cli::BoundariesIgnore, commands::CommandBase, run::builder::RunBuilder};
+use crate::{
+    boundaries::DependencyFix,
+    cli,
+    cli::BoundariesIgnore,
+    commands::CommandBase,
+    run::{builder::RunBuilder, Run},
+};
 
 pub async fn run(
     base: CommandBase,
     telemetry: CommandEventBuilder,
     ignore: Option<BoundariesIgnore>,
     reason: Option<String>,
+    fix: bool,
 ) -> Result<i32, cli::Error> {
     let signal = get_signal()?;
     let handler = SignalHandler::new(signal);
@@ -23,9 +30,13 @@ pub async fn run(
         .build(&handler, telemetry)
         .await?;
 
-    let result = run.check_boundaries(true)?;
+    let mut result = run.check_boundaries(true)?;
 
-    if let Some(ignore) = ignore {
+    if fix {
+        let fixes = run.fix_missing_dependencies(&mut result)?;
+        print_dependency_fixes(&run, &fixes);
+        result.emit(run.color_config());
+    } else if let Some(ignore) = ignore {
         let mut patches: HashMap<&AbsoluteSystemPath, Vec<(SourceSpan, String)>> = HashMap::new();
         for diagnostic in &result.diagnostics {
             let Some((path, span)) = diagnostic.path_and_span() else {
@@ -92,3 +103,36 @@ pub async fn run(
         Ok(1)
     }
 }
+
+fn print_dependency_fixes(run: &Run, fixes: &[DependencyFix]) {
+    for fix in fixes {
+        let short_path = match run.repo_root().anchor(&fix.package_json_path) {
+            Ok(path) => path.to_string(),
+            Err(_) => fix.package_json_path.to_string(),
+        };
+        println!(
+            "{} {}@{} to {}",
+            color!(run.color_config(), BOLD_GREEN, "adding"),
+            fix.dependency,
+            fix.specifier,
+            short_path
+        );
+    }
+
+    let needs_install: Vec<_> = fixes.iter().filter(|fix| fix.needs_install).collect();
+    if !needs_install.is_empty() {
+        let install = format!(
+            "{} install",
+            run.pkg_dep_graph().package_manager().command()
+        );
+        println!();
+        println!(
+            "Run {} so these packages can resolve their new dependencies:",
+            color!(run.color_config(), BOLD, "{}", install)
+        );
+        for fix in needs_install {
+            println!("  {} -> {}", fix.package, fix.dependency);
+        }
+        println!();
+    }
+}
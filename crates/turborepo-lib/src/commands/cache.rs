@@ -0,0 +1,458 @@
+//! `turbo cache`: inspect and manage the local cache directory without
+//! running any tasks, or serve a directory as a Remote Cache.
+
+use std::{
+    net::SocketAddr,
+    time::{Duration, SystemTime},
+};
+
+use camino::Utf8Path;
+use chrono::{DateTime, Utc};
+use miette::Diagnostic;
+use serde::Serialize;
+use thiserror::Error;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_cache::{
+    duration::{parse_human_duration, DurationParseError},
+    inspect::{self, ArchiveEntry, ArchiveEntryKind, CacheEntry},
+    size::{parse_human_size, SizeParseError},
+    CacheError,
+};
+use turborepo_cache_server::{CacheServer, ServerError};
+use turborepo_ui::{color, cprintln, ColorConfig, BOLD, BOLD_GREEN, BOLD_RED, GREY};
+
+use super::CommandBase;
+use crate::cli::{CacheCommand, OutputFormat};
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum Error {
+    #[error("Invalid value for `--max-age`: {0}")]
+    MaxAge(#[from] DurationParseError),
+    #[error("Invalid value for `--max-size`: {0}")]
+    MaxSize(#[from] SizeParseError),
+    #[error("No limits to prune the cache with.")]
+    #[diagnostic(help(
+        "Pass `--max-age` or `--max-size`, or set `cacheMaxAge` or `cacheMaxSize` in turbo.json."
+    ))]
+    NoPruneLimits,
+    #[error("No artifact with hash `{0}` in the local cache.")]
+    NotFound(String),
+    #[error(transparent)]
+    Cache(#[from] CacheError),
+    #[error(transparent)]
+    Server(#[from] ServerError),
+    #[error(transparent)]
+    Path(#[from] turbopath::PathError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheEntryDisplay {
+    hash: String,
+    size: u64,
+    modified: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    duration_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    task_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sha: Option<String>,
+}
+
+impl From<CacheEntry> for CacheEntryDisplay {
+    fn from(entry: CacheEntry) -> Self {
+        Self {
+            hash: entry.hash,
+            size: entry.size,
+            modified: entry.modified.into(),
+            duration_ms: entry.duration,
+            task_id: entry.task_id,
+            sha: entry.sha,
+        }
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CacheListDisplay {
+    cache_dir: String,
+    total_size: u64,
+    entries: Vec<CacheEntryDisplay>,
+}
+
+#[derive(Serialize)]
+struct ArchiveDisplay {
+    hash: String,
+    entries: Vec<ArchiveEntry>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PruneDisplay {
+    removed: u64,
+    reclaimed_bytes: u64,
+}
+
+#[derive(Serialize)]
+struct VerifyResult {
+    hash: String,
+    valid: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Serialize)]
+struct VerifyDisplay {
+    entries: Vec<VerifyResult>,
+}
+
+/// Runs a `turbo cache` subcommand, returning the exit code
+pub async fn run(base: &CommandBase, command: &CacheCommand) -> Result<i32, Error> {
+    let cache_dir = AbsoluteSystemPathBuf::from_unknown(
+        &base.repo_root,
+        base.opts.cache_opts.cache_dir.clone(),
+    );
+    let color_config = base.color_config;
+
+    match command {
+        CacheCommand::Ls { output } => {
+            ls(&cache_dir, output.unwrap_or_default(), color_config)?;
+            Ok(0)
+        }
+        CacheCommand::Show { hash, output } => {
+            show(&cache_dir, hash, output.unwrap_or_default(), color_config)?;
+            Ok(0)
+        }
+        CacheCommand::Prune {
+            max_age,
+            max_size,
+            output,
+        } => {
+            let (max_age, max_size) = if max_age.is_none() && max_size.is_none() {
+                (
+                    base.opts.cache_opts.cache_max_age,
+                    base.opts.cache_opts.cache_max_size,
+                )
+            } else {
+                (
+                    max_age.as_deref().map(parse_human_duration).transpose()?,
+                    max_size.as_deref().map(parse_human_size).transpose()?,
+                )
+            };
+            prune(
+                &cache_dir,
+                max_age,
+                max_size,
+                output.unwrap_or_default(),
+                color_config,
+            )?;
+            Ok(0)
+        }
+        CacheCommand::Verify { hashes, output } => {
+            verify(&cache_dir, hashes, output.unwrap_or_default(), color_config)
+        }
+        CacheCommand::Serve {
+            dir,
+            host,
+            port,
+            tokens,
+            max_size,
+        } => {
+            let max_size = max_size.as_deref().map(parse_human_size).transpose()?;
+            serve(
+                dir,
+                SocketAddr::new(*host, *port),
+                tokens.clone(),
+                max_size,
+                color_config,
+            )
+            .await?;
+            Ok(0)
+        }
+    }
+}
+
+async fn serve(
+    dir: &Utf8Path,
+    addr: SocketAddr,
+    tokens: Vec<String>,
+    max_size: Option<u64>,
+    color_config: ColorConfig,
+) -> Result<(), Error> {
+    let dir = AbsoluteSystemPathBuf::from_unknown(&AbsoluteSystemPathBuf::cwd()?, dir);
+    let server = CacheServer::new(dir, tokens, max_size)?;
+    let listener = CacheServer::bind(addr).await?;
+    let addr = listener.local_addr().unwrap_or(addr);
+
+    cprintln!(
+        color_config,
+        BOLD,
+        "Remote Cache listening on http://{}",
+        addr
+    );
+    println!(
+        "  Storing artifacts in {} ({})",
+        server.dir(),
+        format_size(server.size())
+    );
+    println!(
+        "  {}",
+        color!(
+            color_config,
+            GREY,
+            "Use it with TURBO_API=http://{} TURBO_TOKEN=<token> TURBO_TEAM=<team>",
+            addr
+        )
+    );
+
+    server
+        .serve(listener, async {
+            let _ = tokio::signal::ctrl_c().await;
+        })
+        .await?;
+    Ok(())
+}
+
+fn ls(
+    cache_dir: &AbsoluteSystemPath,
+    output: OutputFormat,
+    color_config: ColorConfig,
+) -> Result<(), Error> {
+    let entries = inspect::list(cache_dir)?;
+    let total_size = entries.iter().map(|entry| entry.size).sum();
+
+    match output {
+        OutputFormat::Json => {
+            let list = CacheListDisplay {
+                cache_dir: cache_dir.to_string(),
+                total_size,
+                entries: entries.into_iter().map(CacheEntryDisplay::from).collect(),
+            };
+            println!("{}", serde_json::to_string_pretty(&list)?);
+        }
+        OutputFormat::Pretty => {
+            if entries.is_empty() {
+                println!("No artifacts in {cache_dir}");
+                return Ok(());
+            }
+            cprintln!(
+                color_config,
+                BOLD,
+                "{} artifacts ({}) in {}",
+                entries.len(),
+                format_size(total_size),
+                cache_dir
+            );
+            let now = SystemTime::now();
+            for entry in &entries {
+                let age = now.duration_since(entry.modified).unwrap_or(Duration::ZERO);
+                println!(
+                    "  {}  {:>10}  {:>4} ago  {}",
+                    entry.hash,
+                    format_size(entry.size),
+                    format_age(age),
+                    color!(
+                        color_config,
+                        GREY,
+                        "{}",
+                        entry.task_id.as_deref().unwrap_or("unknown task")
+                    )
+                );
+            }
+        }
+    }
+    Ok(())
+}
+
+fn show(
+    cache_dir: &AbsoluteSystemPath,
+    hash: &str,
+    output: OutputFormat,
+    color_config: ColorConfig,
+) -> Result<(), Error> {
+    let entries =
+        inspect::archive_entries(cache_dir, hash)?.ok_or_else(|| Error::NotFound(hash.into()))?;
+
+    match output {
+        OutputFormat::Json => {
+            let archive = ArchiveDisplay {
+                hash: hash.to_string(),
+                entries,
+            };
+            println!("{}", serde_json::to_string_pretty(&archive)?);
+        }
+        OutputFormat::Pretty => {
+            cprintln!(color_config, BOLD, "{} ({} entries)", hash, entries.len());
+            for entry in &entries {
+                match (&entry.kind, &entry.link_target) {
+                    (ArchiveEntryKind::Symlink, Some(target)) => println!(
+                        "  {} -> {}",
+                        entry.path,
+                        color!(color_config, GREY, "{}", target)
+                    ),
+                    (ArchiveEntryKind::File, _) => println!(
+                        "  {}  {}",
+                        entry.path,
+                        color!(color_config, GREY, "{}", format_size(entry.size))
+                    ),
+                    _ => println!("  {}", entry.path),
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn prune(
+    cache_dir: &AbsoluteSystemPath,
+    max_age: Option<Duration>,
+    max_size: Option<u64>,
+    output: OutputFormat,
+    color_config: ColorConfig,
+) -> Result<(), Error> {
+    let max_age = max_age.filter(|age| !age.is_zero());
+    let max_size = max_size.filter(|size| *size > 0);
+    if max_age.is_none() && max_size.is_none() {
+        return Err(Error::NoPruneLimits);
+    }
+
+    let (removed, reclaimed_bytes) = inspect::prune(cache_dir, max_age, max_size);
+
+    match output {
+        OutputFormat::Json => {
+            let pruned = PruneDisplay {
+                removed,
+                reclaimed_bytes,
+            };
+            println!("{}", serde_json::to_string_pretty(&pruned)?);
+        }
+        OutputFormat::Pretty => {
+            println!(
+                "{} Removed {removed} artifacts, reclaiming {}",
+                color!(color_config, BOLD_GREEN, "✓"),
+                format_size(reclaimed_bytes)
+            );
+        }
+    }
+    Ok(())
+}
+
+fn verify(
+    cache_dir: &AbsoluteSystemPath,
+    hashes: &[String],
+    output: OutputFormat,
+    color_config: ColorConfig,
+) -> Result<i32, Error> {
+    let known: Vec<String> = inspect::list(cache_dir)?
+        .into_iter()
+        .map(|entry| entry.hash)
+        .collect();
+    let hashes = if hashes.is_empty() {
+        known.clone()
+    } else {
+        hashes.to_vec()
+    };
+
+    let results: Vec<VerifyResult> = hashes
+        .into_iter()
+        .map(|hash| {
+            let error = if known.contains(&hash) {
+                inspect::verify(cache_dir, &hash)
+                    .err()
+                    .map(|e| e.to_string())
+            } else {
+                Some("not in the local cache".to_string())
+            };
+            VerifyResult {
+                valid: error.is_none(),
+                hash,
+                error,
+            }
+        })
+        .collect();
+    let exit_code = if results.iter().all(|result| result.valid) {
+        0
+    } else {
+        1
+    };
+
+    match output {
+        OutputFormat::Json => {
+            let verified = VerifyDisplay { entries: results };
+            println!("{}", serde_json::to_string_pretty(&verified)?);
+        }
+        OutputFormat::Pretty => {
+            for result in &results {
+                match &result.error {
+                    None => println!("{} {}", color!(color_config, BOLD_GREEN, "✓"), result.hash),
+                    Some(error) => println!(
+                        "{} {}  {}",
+                        color!(color_config, BOLD_RED, "✗"),
+                        result.hash,
+                        color!(color_config, GREY, "{}", error)
+                    ),
+                }
+            }
+            let invalid = results.iter().filter(|result| !result.valid).count();
+            println!(
+                "\nChecked {} artifacts, {invalid} corrupt or missing",
+                results.len()
+            );
+        }
+    }
+    Ok(exit_code)
+}
+
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+    for next_unit in UNITS.iter().skip(1) {
+        if value < 1024.0 {
+            break;
+        }
+        value /= 1024.0;
+        unit = next_unit;
+    }
+    format!("{value:.1} {unit}")
+}
+
+fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{secs}s"),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86_399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86_400),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use test_case::test_case;
+
+    use super::{format_age, format_size};
+
+    #[test_case(0, "0 B" ; "zero")]
+    #[test_case(1023, "1023 B" ; "bytes")]
+    #[test_case(1536, "1.5 KiB" ; "kibibytes")]
+    #[test_case(5 * 1024 * 1024 * 1024, "5.0 GiB" ; "gibibytes")]
+    fn test_format_size(bytes: u64, expected: &str) {
+        assert_eq!(format_size(bytes), expected);
+    }
+
+    #[test_case(Duration::from_secs(42), "42s" ; "seconds")]
+    #[test_case(Duration::from_secs(90), "1m" ; "minutes")]
+    #[test_case(Duration::from_secs(7200), "2h" ; "hours")]
+    #[test_case(Duration::from_secs(3 * 86_400 + 5), "3d" ; "days")]
+    fn test_format_age(age: Duration, expected: &str) {
+        assert_eq!(format_age(age), expected);
+    }
+}
@@ -0,0 +1,98 @@
+//! `turbo explain`: report which hash inputs changed between the two most
+//! recent local cache entries for a task.
+
+use miette::Diagnostic;
+use serde::Serialize;
+use thiserror::Error;
+use turbopath::AbsoluteSystemPathBuf;
+use turborepo_cache::CacheError;
+use turborepo_hash::explain::InputChange;
+use turborepo_run_cache::RecordedHashInputs;
+use turborepo_ui::{color, cprintln, BOLD, BOLD_GREEN, GREY};
+
+use super::CommandBase;
+use crate::cli::OutputFormat;
+
+#[derive(Debug, Error, Diagnostic)]
+pub enum Error {
+    #[error("`{0}` is not a task ID.")]
+    #[diagnostic(help("Pass the task as `<package>#<task>`, for example `web#build`."))]
+    InvalidTaskId(String),
+    #[error("Found {found} cache entries with recorded inputs for `{task_id}`, need at least 2.")]
+    #[diagnostic(help(
+        "Hash inputs are recorded when a task is cached by `turbo run --explain-miss`. Run the \
+         task again with `--explain-miss` after changing its inputs."
+    ))]
+    NotEnoughEntries { task_id: String, found: usize },
+    #[error(transparent)]
+    Cache(#[from] CacheError),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExplainDisplay {
+    task_id: String,
+    hash: String,
+    previous_hash: String,
+    changes: Vec<InputChange>,
+}
+
+/// Compares the hash inputs of the two most recent local cache entries for
+/// `task_id`
+pub fn run(base: &CommandBase, task_id: &str, output: OutputFormat) -> Result<i32, Error> {
+    if !task_id.contains('#') {
+        return Err(Error::InvalidTaskId(task_id.to_string()));
+    }
+    let cache_dir = AbsoluteSystemPathBuf::from_unknown(
+        &base.repo_root,
+        base.opts.cache_opts.cache_dir.clone(),
+    );
+    let color_config = base.color_config;
+
+    let recorded = RecordedHashInputs::read(&cache_dir)?
+        .for_task(task_id)
+        .take(2)
+        .collect::<Vec<_>>();
+    let [current, previous] = recorded.as_slice() else {
+        return Err(Error::NotEnoughEntries {
+            task_id: task_id.to_string(),
+            found: recorded.len(),
+        });
+    };
+
+    let explained = ExplainDisplay {
+        task_id: task_id.to_string(),
+        hash: current.hash.clone(),
+        previous_hash: previous.hash.clone(),
+        changes: current.changes_since(previous),
+    };
+
+    match output {
+        OutputFormat::Json => {
+            println!("{}", serde_json::to_string_pretty(&explained)?);
+        }
+        OutputFormat::Pretty => {
+            cprintln!(
+                color_config,
+                BOLD,
+                "{} {} since {}",
+                explained.task_id,
+                explained.hash,
+                explained.previous_hash
+            );
+            if explained.changes.is_empty() {
+                println!(
+                    "{} No recorded inputs changed. The hashes may differ because the entries \
+                     were written by different versions of turbo.",
+                    color!(color_config, BOLD_GREEN, "✓")
+                );
+            }
+            for change in &explained.changes {
+                println!("  {} {}", color!(color_config, GREY, "-"), change);
+            }
+        }
+    }
+    Ok(0)
+}
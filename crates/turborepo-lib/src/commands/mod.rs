@@ -18,10 +18,12 @@ use crate::{
 
 pub(crate) mod bin;
 pub(crate) mod boundaries;
+pub(crate) mod cache;
 pub(crate) mod config;
 pub(crate) mod daemon;
 pub(crate) mod devtools;
 pub(crate) mod docs;
+pub(crate) mod explain;
 pub(crate) mod generate;
 pub(crate) mod get_mfe_port;
 pub(crate) mod info;
@@ -9,7 +9,12 @@ use turborepo_telemetry::events::command::CommandEventBuilder;
 use turborepo_types::DryRunMode;
 use turborepo_ui::{sender::UISender, LogSinks};
 
-use crate::{commands::CommandBase, run, run::builder::RunBuilder, tracing::TurboSubscriber};
+use crate::{
+    commands::CommandBase,
+    run,
+    run::{builder::RunBuilder, task_control::TaskController},
+    tracing::TurboSubscriber,
+};
 
 #[derive(Debug, PartialEq, Eq)]
 enum RunOutcome<T> {
@@ -97,6 +102,9 @@ pub async fn run(
         }
     }
 
+    let (mut task_controller, task_action_handler) =
+        TaskController::new(base.clone(), handler.clone(), telemetry.clone());
+
     let mut run_builder = {
         let _span = tracing::info_span!("run_builder_new").entered();
         RunBuilder::new(base, Some(http_client))?
@@ -139,7 +147,7 @@ pub async fn run(
 
         let (sender, handle) = {
             let _span = tracing::info_span!("start_ui").entered();
-            run.start_ui()?.unzip()
+            run.start_ui(Some(task_action_handler))?.unzip()
         };
 
         if let Some(UISender::Tui(ref tui_sender)) = sender {
@@ -163,7 +171,14 @@ pub async fn run(
             }
         }
 
-        let result = run.run(sender.clone(), false).await;
+        task_controller.set_ui_sender(sender.clone());
+        task_controller.set_run_events(run.run_events());
+        task_controller.add_stopper(run.stopper());
+        let result = task_controller
+            .run_until(run.run(sender.clone(), false))
+            .await;
+        // Tasks started from the TUI can outlive the run that started them
+        task_controller.wait().await;
 
         if let Some(analytics_handle) = analytics_handle {
             analytics_handle.close_with_timeout().await;
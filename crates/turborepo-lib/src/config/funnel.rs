@@ -57,6 +57,12 @@ pub fn cli_overrides_from_args(args: &Args) -> Result<ConfigurationOptions, Erro
         log_order: args
             .execution_args()
             .and_then(|execution_args| execution_args.log_order),
+        undeclared_outputs: args
+            .execution_args()
+            .and_then(|execution_args| execution_args.undeclared_outputs),
+        undeclared_inputs: args
+            .execution_args()
+            .and_then(|execution_args| execution_args.undeclared_inputs),
         remote_only: args.run_args().and_then(|run_args| run_args.remote_only()),
         remote_cache_read_only: args
             .run_args()
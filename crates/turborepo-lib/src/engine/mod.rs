@@ -137,7 +137,8 @@ impl EngineExt for Engine<Built> {
                             package: dep_id.package().to_string(),
                         })?;
                     if task_definition.persistent
-                        && package_json.scripts.contains_key(dep_id.task())
+                        && (task_definition.command.is_some()
+                            || package_json.scripts.contains_key(dep_id.task()))
                     {
                         let (span, text) = self
                             .task_locations()
@@ -162,16 +163,18 @@ impl EngineExt for Engine<Built> {
                     }
                 })?;
 
-                let package_has_task = info
-                    .package_json
-                    .scripts
-                    .get(task_id.task())
-                    // handle legacy behaviour from go where an empty string may appear
-                    .is_some_and(|script| !script.is_empty());
-
-                let task_is_persistent = self
-                    .task_definition(task_id)
-                    .is_some_and(|task_def| task_def.persistent);
+                let task_definition = self.task_definition(task_id);
+                let package_has_task = task_definition
+                    .is_some_and(|task_def| task_def.command.is_some())
+                    || info
+                        .package_json
+                        .scripts
+                        .get(task_id.task())
+                        // handle legacy behaviour from go where an empty string may appear
+                        .is_some_and(|script| !script.is_empty());
+
+                let task_is_persistent =
+                    task_definition.is_some_and(|task_def| task_def.persistent);
 
                 Ok(task_is_persistent && package_has_task)
             })
@@ -7,8 +7,8 @@ use turborepo_api_client::APIAuth;
 use turborepo_cache::{CacheOpts, RemoteCacheOpts};
 use turborepo_types::{
     APIClientOpts, ContinueMode, DryRunMode, EnvMode, GraphOpts, LogOrder, LogPrefix, RepoOpts,
-    ResolvedLogOrder, ResolvedLogPrefix, RunCacheOpts, RunOptsInfo, ScopeOpts, TaskArgs, TuiOpts,
-    UIMode,
+    ResolvedLogOrder, ResolvedLogPrefix, RunCacheOpts, RunOptsInfo, ScopeOpts, SummaryFormat,
+    TaskArgs, TuiOpts, UIMode, UndeclaredFilesMode,
 };
 
 use crate::{
@@ -110,6 +110,10 @@ impl Opts {
             cmd.push_str(" --affected");
         }
 
+        if let Some(filter_depth) = self.scope_opts.filter_depth {
+            cmd.push_str(&format!(" --filter-depth={filter_depth}"));
+        }
+
         if self.run_opts.parallel {
             cmd.push_str(" --parallel");
         }
@@ -122,6 +126,10 @@ impl Opts {
             _ => (),
         }
 
+        if let Some(retries) = self.run_opts.retries {
+            cmd.push_str(&format!(" --retries={retries}"));
+        }
+
         if let Some(dry) = self.run_opts.dry_run {
             match dry {
                 DryRunMode::Json => cmd.push_str(" --dry=json"),
@@ -164,11 +172,15 @@ impl Opts {
             }) => (execution_args, run_args),
             Some(Command::Watch { execution_args, .. }) => (execution_args, &Box::default()),
             Some(Command::Ls {
-                affected, filter, ..
+                affected,
+                filter,
+                filter_depth,
+                ..
             }) => {
                 let execution_args = ExecutionArgs {
                     filter: filter.clone(),
                     affected: *affected,
+                    filter_depth: *filter_depth,
                     ..Default::default()
                 };
 
@@ -189,6 +201,7 @@ impl Opts {
                 let execution_args = ExecutionArgs {
                     filter: ls_args.filter.clone(),
                     affected: ls_args.affected,
+                    filter_depth: ls_args.filter_depth,
                     ..Default::default()
                 };
 
@@ -389,6 +402,7 @@ impl<'a> From<OptsInputs<'a>> for RunCacheOpts {
         RunCacheOpts {
             task_output_logs_override: inputs.execution_args.output_logs,
             errors_only_show_hash: inputs.config.future_flags().errors_only_show_hash,
+            explain_misses: inputs.run_args.explain_miss,
         }
     }
 }
@@ -406,6 +420,8 @@ pub struct RunOpts {
     pub(crate) framework_inference: bool,
     pub profile: Option<String>,
     pub(crate) continue_on_error: ContinueMode,
+    // Overrides the `retries` configured for each task
+    pub(crate) retries: Option<u32>,
     pub(crate) pass_through_args: Vec<String>,
     pub(crate) only: bool,
     pub(crate) dry_run: Option<DryRunMode>,
@@ -415,8 +431,18 @@ pub struct RunOpts {
     pub log_prefix: ResolvedLogPrefix,
     pub log_order: ResolvedLogOrder,
     pub summarize: bool,
+    pub(crate) summary_formats: Vec<SummaryFormat>,
     pub is_github_actions: bool,
     pub ui_mode: UIMode,
+    // Port to serve queries and subscriptions to task events on while the run
+    // is in progress. `0` picks a free port.
+    pub(crate) query_server: Option<u16>,
+    // Run package.json scripts through the shell instead of the package manager
+    pub(crate) direct_script_execution: bool,
+    // How to report files written outside of a task's declared outputs
+    pub(crate) undeclared_outputs: UndeclaredFilesMode,
+    // How to report files read outside of a task's declared inputs
+    pub(crate) undeclared_inputs: UndeclaredFilesMode,
 }
 
 impl RunOpts {
@@ -495,12 +521,23 @@ impl<'a> TryFrom<OptsInputs<'a>> for RunOpts {
             tasks: inputs.execution_args.tasks.clone(),
             log_prefix,
             log_order,
-            summarize: inputs.config.run_summary(),
+            // Choosing a summary format implies summarizing unless summaries were
+            // explicitly turned off
+            summarize: inputs
+                .config
+                .run_summary
+                .unwrap_or(!inputs.run_args.summary_format.is_empty()),
+            summary_formats: if inputs.run_args.summary_format.is_empty() {
+                vec![SummaryFormat::default()]
+            } else {
+                inputs.run_args.summary_format.clone()
+            },
             framework_inference: inputs.execution_args.framework_inference,
             concurrency,
             parallel: inputs.run_args.parallel,
             profile: inputs.run_args.profile.clone(),
             continue_on_error: inputs.execution_args.continue_execution,
+            retries: inputs.execution_args.retries,
             pass_through_args: inputs.execution_args.pass_through_args.clone(),
             only: inputs.execution_args.only,
             daemon: inputs.config.daemon(),
@@ -518,6 +555,10 @@ impl<'a> TryFrom<OptsInputs<'a>> for RunOpts {
             } else {
                 inputs.config.ui()
             },
+            query_server: inputs.execution_args.experimental_query_server,
+            direct_script_execution: inputs.config.future_flags().direct_script_execution,
+            undeclared_outputs: inputs.config.undeclared_outputs(),
+            undeclared_inputs: inputs.config.undeclared_inputs(),
         })
     }
 }
@@ -566,6 +607,7 @@ fn scope_opts_from_inputs(inputs: OptsInputs<'_>) -> Result<ScopeOpts, Error> {
         pkg_inference_root,
         affected_range,
         filter_patterns: inputs.execution_args.filter.clone(),
+        filter_depth: inputs.execution_args.filter_depth,
     })
 }
 
@@ -649,10 +691,12 @@ impl<'a> TryFrom<OptsInputs<'a>> for CacheOpts {
             inputs.config.team_id().map(|team_id| team_id.to_string());
         let signature = inputs.config.signature();
         let enforce_signature_key_length = inputs.config.future_flags().longer_signature_key;
+        let signature_public_key = inputs.config.signature_public_key().map(str::to_string);
         let remote_cache_opts = Some(RemoteCacheOpts::new(
             unused_remote_cache_opts_team_id,
             signature,
             enforce_signature_key_length,
+            signature_public_key,
         ));
 
         let cache_max_age = inputs
@@ -679,6 +723,7 @@ impl<'a> TryFrom<OptsInputs<'a>> for CacheOpts {
             remote_cache_opts,
             cache_max_age,
             cache_max_size,
+            cache_deduplication: inputs.config.cache_deduplication(),
         };
         debug!("CacheOpts created with cache_dir={}", cache_opts.cache_dir);
         Ok(cache_opts)
@@ -707,6 +752,10 @@ impl RunOptsInfo for RunOpts {
         self.summarize.then_some("true")
     }
 
+    fn summary_formats(&self) -> &[SummaryFormat] {
+        &self.summary_formats
+    }
+
     fn framework_inference(&self) -> bool {
         self.framework_inference
     }
@@ -757,7 +806,8 @@ mod test {
     use turborepo_cache::{CacheActions, CacheConfig, CacheOpts};
     use turborepo_task_id::TaskId;
     use turborepo_types::{
-        ContinueMode, DryRunMode, EnvMode, ResolvedLogOrder, ResolvedLogPrefix, TaskArgs, UIMode,
+        ContinueMode, DryRunMode, EnvMode, ResolvedLogOrder, ResolvedLogPrefix, SummaryFormat,
+        TaskArgs, UIMode, UndeclaredFilesMode,
     };
     use turborepo_ui::ColorConfig;
 
@@ -778,8 +828,10 @@ mod test {
         pass_through_args: Vec<String>,
         parallel: bool,
         continue_on_error: ContinueMode,
+        retries: Option<u32>,
         dry_run: Option<DryRunMode>,
         affected: Option<(String, String)>,
+        filter_depth: Option<usize>,
     }
 
     #[test_case(TestCaseOpts{
@@ -834,6 +886,15 @@ mod test {
             },
         "turbo run build --filter=my-app --parallel --continue=dependencies-successful"
     )]
+    #[test_case(
+        TestCaseOpts{
+            filter_patterns: vec!["my-app".to_string()],
+            tasks: vec!["test".to_string()],
+            retries: Some(2),
+            ..Default::default()
+            },
+        "turbo run test --filter=my-app --retries=2"
+    )]
     #[test_case(
         TestCaseOpts{
             filter_patterns: vec!["my-app".to_string()],
@@ -869,6 +930,15 @@ mod test {
             },
         "turbo run build --affected"
     )]
+    #[test_case(
+        TestCaseOpts{
+            filter_patterns: vec!["...lib".to_string()],
+            tasks: vec!["build".to_string()],
+            filter_depth: Some(1),
+            ..Default::default()
+            },
+        "turbo run build --filter=...lib --filter-depth=1"
+    )]
     fn test_synthesize_command(opts_input: TestCaseOpts, expected: &str) {
         let run_opts = RunOpts {
             tasks: opts_input.tasks,
@@ -880,6 +950,7 @@ mod test {
             framework_inference: true,
             profile: None,
             continue_on_error: opts_input.continue_on_error,
+            retries: opts_input.retries,
             pass_through_args: opts_input.pass_through_args,
             only: opts_input.only,
             dry_run: opts_input.dry_run,
@@ -889,8 +960,13 @@ mod test {
             log_prefix: ResolvedLogPrefix::Task,
             log_order: ResolvedLogOrder::Stream,
             summarize: false,
+            summary_formats: vec![SummaryFormat::Json],
             is_github_actions: false,
             daemon: None,
+            query_server: None,
+            direct_script_execution: false,
+            undeclared_outputs: UndeclaredFilesMode::Off,
+            undeclared_inputs: UndeclaredFilesMode::Off,
         };
         let cache_opts = CacheOpts {
             cache_dir: ".turbo/cache".into(),
@@ -899,6 +975,7 @@ mod test {
             remote_cache_opts: None,
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         };
         let runcache_opts = RunCacheOpts::default();
         let scope_opts = ScopeOpts {
@@ -908,6 +985,7 @@ mod test {
             affected_range: opts_input
                 .affected
                 .map(|(base, head)| (Some(base), Some(head))),
+            filter_depth: opts_input.filter_depth,
         };
         let config = ConfigurationOptions::default();
         let root_turbo_json_path = config
@@ -1104,6 +1182,44 @@ mod test {
         Ok(())
     }
 
+    #[test_case(
+        vec!["turbo", "run", "build"],
+        None,
+        false
+        ; "default"
+    )]
+    #[test_case(
+        vec!["turbo", "run", "build", "--summary-format", "junit"],
+        None,
+        true
+        ; "format implies summarize"
+    )]
+    #[test_case(
+        vec!["turbo", "run", "build", "--summary-format", "junit"],
+        Some(false),
+        false
+        ; "explicitly disabled"
+    )]
+    fn test_summarize(
+        args_str: Vec<&str>,
+        run_summary: Option<bool>,
+        expected: bool,
+    ) -> Result<(), anyhow::Error> {
+        let args = Args::try_parse_from(&args_str)?;
+        let opts = Opts::new(
+            &AbsoluteSystemPathBuf::default(),
+            &args,
+            ConfigurationOptions {
+                run_summary,
+                ..Default::default()
+            },
+        )?;
+
+        assert_eq!(opts.run_opts.summarize, expected);
+
+        Ok(())
+    }
+
     #[test_case(
         vec!["build".to_string()],
         vec!["passthrough".to_string()],
@@ -45,8 +45,8 @@ use crate::{
     microfrontends::MicrofrontendsConfigs,
     opts::Opts,
     run::{
-        scope, task_access::TaskAccess, Error, RemoteCacheStatus, RemoteCacheUnavailableReason,
-        Run, RunCache,
+        scope, subscriptions, task_access::TaskAccess, Error, RemoteCacheStatus,
+        RemoteCacheUnavailableReason, Run, RunCache,
     },
     turbo_json::{TurboJson, TurboJsonReader, UnifiedTurboJsonLoader},
 };
@@ -74,6 +74,9 @@ pub struct RunBuilder {
     // that trigger the file watcher, causing an infinite rebuild loop.
     output_watcher: Option<Arc<dyn turborepo_run_cache::OutputWatcher>>,
     query_server: Option<Arc<dyn turborepo_query_api::QueryServer>>,
+    // Runs started on behalf of another run publish their task events to the
+    // subscribers of that run's query server
+    run_events: Option<turborepo_query_api::RunEventSender>,
     // In watch mode with `watchUsingTaskInputs`, the file watcher provides
     // the set of changed files that triggered the rebuild. Used to filter
     // the engine down to only tasks whose declared inputs match.
@@ -117,6 +120,7 @@ impl RunBuilder {
             add_all_tasks: false,
             output_watcher: None,
             query_server: None,
+            run_events: None,
             changed_files_for_watch: None,
         })
     }
@@ -139,6 +143,11 @@ impl RunBuilder {
         self
     }
 
+    pub fn with_run_events(mut self, events: Option<turborepo_query_api::RunEventSender>) -> Self {
+        self.run_events = events;
+        self
+    }
+
     pub fn with_changed_files(mut self, files: HashSet<turbopath::AnchoredSystemPathBuf>) -> Self {
         self.changed_files_for_watch = Some(files);
         self
@@ -346,6 +355,7 @@ impl RunBuilder {
         pkg_dep_graph: &PackageGraph,
         scm: &SCM,
         root_turbo_json: &TurboJson,
+        turbo_json_loader: &UnifiedTurboJsonLoader,
     ) -> Result<HashMap<PackageName, PackageInclusionReason>, Error> {
         let (mut filtered_pkgs, filter_mode) = scope::resolve_packages(
             &opts.scope_opts,
@@ -353,6 +363,7 @@ impl RunBuilder {
             pkg_dep_graph,
             scm,
             root_turbo_json,
+            turbo_json_loader,
         )?;
 
         let should_include_root_tasks = match filter_mode {
@@ -689,6 +700,7 @@ impl RunBuilder {
                 &pkg_dep_graph,
                 &scm,
                 &root_turbo_json,
+                &turbo_json_loader,
             )?
         };
 
@@ -770,7 +782,13 @@ impl RunBuilder {
                 .scope_opts
                 .filter_patterns
                 .iter()
-                .map(|p| p.parse())
+                .map(|p| {
+                    p.parse::<turborepo_scope::TargetSelector>()
+                        .map(|selector| turborepo_scope::TargetSelector {
+                            depth: self.opts.scope_opts.filter_depth,
+                            ..selector
+                        })
+                })
                 .collect::<Result<_, _>>()
                 .map_err(ResolutionError::from)?;
 
@@ -779,6 +797,7 @@ impl RunBuilder {
                     Some(super::task_filter::resolve_affected_tasks(
                         &engine,
                         affected_range,
+                        self.opts.scope_opts.filter_depth,
                         &pkg_dep_graph,
                         &scm,
                         &self.repo_root,
@@ -793,6 +812,7 @@ impl RunBuilder {
                 &selectors,
                 affected_constraint.as_ref(),
                 &pkg_dep_graph,
+                &scope::package_tags(&pkg_dep_graph, &turbo_json_loader),
                 &scm,
                 &self.repo_root,
                 &root_turbo_json.global_deps,
@@ -877,6 +897,12 @@ impl RunBuilder {
             }
             None => None,
         });
+        let run_events = self.run_events.or_else(|| {
+            self.opts
+                .run_opts
+                .query_server
+                .map(|_| subscriptions::channel())
+        });
         Ok((
             Run {
                 version: self.version,
@@ -902,6 +928,7 @@ impl RunBuilder {
                 repo_index,
                 observability_handle,
                 query_server: self.query_server,
+                run_events,
                 shutdown_started_emitted: Arc::new(std::sync::atomic::AtomicBool::new(false)),
             },
             analytics_handle,
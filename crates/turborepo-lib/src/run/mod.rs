@@ -4,7 +4,9 @@ pub mod builder;
 mod error;
 pub(crate) mod package_discovery;
 pub(crate) mod scope;
+pub(crate) mod subscriptions;
 pub mod task_access;
+pub mod task_control;
 pub(crate) mod task_filter;
 pub mod watch;
 
@@ -43,7 +45,12 @@ use turborepo_task_hash::{
 };
 use turborepo_telemetry::events::generic::GenericEventBuilder;
 use turborepo_types::{EnvMode, UIMode};
-use turborepo_ui::{sender::UISender, tui, tui::TuiSender, ColorConfig, LIGHT_GREY};
+use turborepo_ui::{
+    sender::UISender,
+    tui,
+    tui::{event::TaskActionHandler, TuiSender},
+    ColorConfig, LIGHT_GREY,
+};
 
 pub use crate::run::error::Error;
 use crate::{
@@ -100,6 +107,8 @@ pub struct Run {
     repo_index: Arc<Option<RepoGitIndex>>,
     observability_handle: Option<ObservabilityHandle>,
     pub(crate) query_server: Option<Arc<dyn turborepo_query_api::QueryServer>>,
+    // Where task events are published for subscribers of the query server
+    run_events: Option<turborepo_query_api::RunEventSender>,
     shutdown_started_emitted: Arc<AtomicBool>,
 }
 
@@ -530,17 +539,76 @@ impl Run {
             && tui::terminal_big_enough()?)
     }
 
-    pub fn start_ui(self: &Arc<Self>) -> UIResult<UISender> {
+    /// Start the UI for the run. If a task action handler is provided, the TUI
+    /// allows the user to stop, restart and start individual tasks.
+    ///
+    /// Also starts the query server for the run if one was requested.
+    pub fn start_ui(
+        self: &Arc<Self>,
+        task_action_handler: Option<TaskActionHandler>,
+    ) -> UIResult<UISender> {
+        self.start_query_server();
         match self.opts.run_opts.ui_mode {
             UIMode::Tui => self
-                .start_terminal_ui()
+                .start_terminal_ui(task_action_handler)
                 .map(|res| res.map(|(sender, handle)| (UISender::Tui(sender), handle))),
             UIMode::Stream | UIMode::StreamWithTimestamps => Ok(None),
         }
     }
 
+    /// Where the events of this run are published for subscribers of the
+    /// query server. Runs started on behalf of this one should publish here
+    /// too.
+    pub(crate) fn run_events(&self) -> Option<turborepo_query_api::RunEventSender> {
+        self.run_events.clone()
+    }
+
+    /// Serve queries and subscriptions to task events for the run.
+    fn start_query_server(self: &Arc<Self>) {
+        let (Some(port), Some(events)) = (self.opts.run_opts.query_server, self.run_events())
+        else {
+            return;
+        };
+        let Some(query_server) = self.query_server.clone() else {
+            warn!("GraphQL server is unavailable in this build of turbo");
+            return;
+        };
+
+        // Bind before spawning the server so the address can be reported
+        // even when an ephemeral port was requested
+        let (listener, address) = match std::net::TcpListener::bind(("127.0.0.1", port))
+            .and_then(|listener| Ok((listener.local_addr()?, listener)))
+        {
+            Ok((address, listener)) => (listener, address),
+            Err(err) => {
+                turborepo_log::error(
+                    turborepo_log::Source::turbo(turborepo_log::Subsystem::Run),
+                    format!("Unable to start GraphQL server on port {port}: {err}"),
+                )
+                .emit();
+                return;
+            }
+        };
+
+        let run: Arc<dyn turborepo_query_api::QueryRun> = self.clone();
+        let signal_handler = self.signal_handler.clone();
+        tokio::spawn(async move {
+            if let Err(err) = query_server
+                .serve_run(run, signal_handler, events, listener)
+                .await
+            {
+                error!("GraphQL server error: {err}");
+            }
+        });
+        turborepo_log::info(
+            turborepo_log::Source::turbo(turborepo_log::Subsystem::Run),
+            format!("GraphQL server for this run: http://{address}"),
+        )
+        .emit();
+    }
+
     #[allow(clippy::type_complexity)]
-    fn start_terminal_ui(&self) -> TuiResult {
+    fn start_terminal_ui(&self, task_action_handler: Option<TaskActionHandler>) -> TuiResult {
         if !self.should_start_ui()? {
             return Ok(None);
         }
@@ -565,6 +633,7 @@ impl Run {
                 &repo_root,
                 scrollback_len,
                 Some(interrupt),
+                task_action_handler,
             )
             .await?)
         });
@@ -1118,11 +1187,24 @@ impl Run {
             ui_sender,
             is_watch,
             self.micro_frontend_configs.as_ref(),
+            global_hash_inputs
+                .global_file_hash_map
+                .keys()
+                .map(|file| file.to_anchored_system_path_buf())
+                .collect(),
         )
         .await;
 
+        if let Some(events) = self.run_events() {
+            visitor.publish_task_events(subscriptions::observer(events));
+        }
+
         if self.opts.run_opts.dry_run.is_some() {
             visitor.dry_run();
+        } else if self.opts.runcache_opts.explain_misses {
+            let hash_inputs = Arc::new(global_hash_inputs.hash_inputs(&global_hash));
+            self.run_cache.record_global_hash_inputs(&hash_inputs);
+            visitor.record_hash_inputs(hash_inputs);
         }
 
         debug!("running visitor");
@@ -1266,6 +1348,11 @@ impl RunStopper {
     pub async fn stop_tasks(&self, task_ids: &[turborepo_task_id::TaskId<'static>]) {
         self.manager.stop_tasks(task_ids).await;
     }
+
+    /// Wait for the tasks that are currently running to exit
+    pub async fn wait_for_tasks(&self) {
+        self.manager.wait_for_running().await;
+    }
 }
 
 // Graph visualizer helper types and functions
@@ -1327,6 +1414,7 @@ impl turborepo_query_api::QueryRun for Run {
         &self,
         base: Option<String>,
         head: Option<String>,
+        depth: Option<usize>,
     ) -> Result<
         std::collections::HashMap<
             turborepo_repository::package_graph::PackageName,
@@ -1336,12 +1424,14 @@ impl turborepo_query_api::QueryRun for Run {
     > {
         let mut opts = self.opts.as_ref().clone();
         opts.scope_opts.affected_range = Some((base, head));
+        opts.scope_opts.filter_depth = depth;
         builder::RunBuilder::calculate_filtered_packages(
             &self.repo_root,
             &opts,
             &self.pkg_dep_graph,
             &self.scm,
             &self.root_turbo_json,
+            &self.turbo_json_loader,
         )
         .map_err(|e| turborepo_query_api::AffectedPackagesError::Other(Box::new(e)))
     }
@@ -14,18 +14,19 @@ use turborepo_scm::SCM;
 use turborepo_scope::filter::ResolutionError;
 use turborepo_types::{FilterMode, ScopeOpts};
 
-use crate::turbo_json::TurboJson;
+use crate::turbo_json::{TurboJson, UnifiedTurboJsonLoader};
 
 /// Resolve which packages should be included in the run based on scope options.
 ///
 /// Delegates directly to `turborepo_scope::resolve_packages`.
-#[tracing::instrument(skip(opts, pkg_graph, scm))]
+#[tracing::instrument(skip(opts, pkg_graph, scm, turbo_json_loader))]
 pub fn resolve_packages(
     opts: &ScopeOpts,
     turbo_root: &AbsoluteSystemPath,
     pkg_graph: &PackageGraph,
     scm: &SCM,
     root_turbo_json: &TurboJson,
+    turbo_json_loader: &UnifiedTurboJsonLoader,
 ) -> Result<(HashMap<PackageName, PackageInclusionReason>, FilterMode), ResolutionError> {
     turborepo_scope::resolve_packages(
         opts,
@@ -33,5 +34,27 @@ pub fn resolve_packages(
         pkg_graph,
         scm,
         &root_turbo_json.global_deps,
+        package_tags(pkg_graph, turbo_json_loader),
     )
 }
+
+/// Collects the `tags` each package declares in its `turbo.json`, used to
+/// resolve `tag:` filters. Packages without a `turbo.json` have no tags.
+pub fn package_tags(
+    pkg_graph: &PackageGraph,
+    turbo_json_loader: &UnifiedTurboJsonLoader,
+) -> HashMap<PackageName, Vec<String>> {
+    pkg_graph
+        .packages()
+        .filter_map(|(name, _)| {
+            let tags = turbo_json_loader.load(name).ok()?.tags.as_ref()?;
+            Some((
+                name.clone(),
+                tags.as_inner()
+                    .iter()
+                    .map(|tag| tag.as_inner().clone())
+                    .collect(),
+            ))
+        })
+        .collect()
+}
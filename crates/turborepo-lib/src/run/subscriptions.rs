@@ -0,0 +1,298 @@
+//! Publishes the events of a run so that GraphQL subscribers can follow it
+//! while it's in progress, whichever UI is in use.
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::broadcast;
+use turborepo_log::grouping::TaskHandle;
+use turborepo_query_api::{RunEvent, RunEventSender, TaskOutcome};
+use turborepo_task_executor::TaskOutput;
+use turborepo_ui::tui::event::{CacheResult, TaskEvent, TaskEventObserver, TaskResult};
+
+// Number of events a subscriber can fall behind by before it starts missing
+// them
+const EVENT_CAPACITY: usize = 1024;
+
+// Output without a newline is published once this much of it has been
+// buffered, so that a task that never prints one can't grow the buffer
+// without bound
+const MAX_PARTIAL_LINE_LEN: usize = 64 * 1024;
+
+pub fn channel() -> RunEventSender {
+    broadcast::channel(EVENT_CAPACITY).0
+}
+
+/// Creates an observer that publishes the task events it sees to `events`.
+/// Task output is published one line at a time, except that a line longer
+/// than `MAX_PARTIAL_LINE_LEN` is split.
+pub fn observer(events: RunEventSender) -> TaskEventObserver {
+    let publisher = Mutex::new(Publisher {
+        events,
+        partial_lines: HashMap::new(),
+    });
+    Arc::new(move |event| {
+        publisher
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .publish(event)
+    })
+}
+
+/// Publishes the lifecycle events of `task` through `task_output` and its
+/// output through `task_handle` to `observer`.
+pub fn observe_task(
+    observer: &TaskEventObserver,
+    task: String,
+    task_output: TaskOutput,
+    task_handle: &mut TaskHandle,
+) -> TaskOutput {
+    let output_observer = observer.clone();
+    let output_task = task.clone();
+    task_handle.observe_output(Arc::new(move |output: &[u8]| {
+        output_observer(TaskEvent::Output {
+            task: output_task.clone(),
+            output: output.to_vec(),
+        })
+    }));
+    task_output.with_observer(task, observer.clone())
+}
+
+/// Publishes that the tasks in the run changed
+pub fn tasks_updated(events: &RunEventSender, tasks: Vec<String>) {
+    // Fails if nobody is subscribed, in which case there's no one to tell
+    events.send(RunEvent::TasksUpdated { tasks }).ok();
+}
+
+struct Publisher {
+    events: RunEventSender,
+    // Output of each task since its last newline
+    partial_lines: HashMap<String, Vec<u8>>,
+}
+
+impl Publisher {
+    fn publish(&mut self, event: TaskEvent) {
+        match event {
+            TaskEvent::Start { task } => {
+                // Output left over from a previous run of the task belongs to a
+                // process that has exited
+                self.partial_lines.remove(&task);
+                self.send(RunEvent::TaskStarted { task })
+            }
+            TaskEvent::Output { task, output } => self.output(task, &output),
+            TaskEvent::Status {
+                task,
+                status,
+                result,
+            } => self.send(RunEvent::TaskCacheStatus {
+                task,
+                status,
+                cache_hit: result == CacheResult::Hit,
+            }),
+            TaskEvent::End { task, result } => {
+                if let Some(line) = self.partial_lines.remove(&task) {
+                    if !line.is_empty() {
+                        self.send_line(task.clone(), &line);
+                    }
+                }
+                let outcome = match result {
+                    TaskResult::Success => TaskOutcome::Success,
+                    TaskResult::Failure => TaskOutcome::Failure,
+                    TaskResult::CacheHit => TaskOutcome::CacheHit,
+                    TaskResult::Stopped => TaskOutcome::Stopped,
+                };
+                self.send(RunEvent::TaskFinished { task, outcome });
+            }
+        }
+    }
+
+    fn output(&mut self, task: String, output: &[u8]) {
+        let buffer = self.partial_lines.entry(task.clone()).or_default();
+        buffer.extend_from_slice(output);
+        let complete = buffer.iter().rposition(|&byte| byte == b'\n').map(|end| {
+            let rest = buffer.split_off(end + 1);
+            let mut complete = std::mem::replace(buffer, rest);
+            complete.truncate(end);
+            complete
+        });
+        let overflow = (buffer.len() >= MAX_PARTIAL_LINE_LEN).then(|| std::mem::take(buffer));
+
+        if let Some(complete) = complete {
+            for line in complete.split(|&byte| byte == b'\n') {
+                self.send_line(task.clone(), line);
+            }
+        }
+        if let Some(line) = overflow {
+            self.send_line(task, &line);
+        }
+    }
+
+    fn send_line(&self, task: String, line: &[u8]) {
+        // Output from a PTY has `\r\n` line endings
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        self.send(RunEvent::TaskLog {
+            task,
+            line: String::from_utf8_lossy(line).into_owned(),
+        });
+    }
+
+    fn send(&self, event: RunEvent) {
+        // Fails if nobody is subscribed, in which case there's no one to tell
+        self.events.send(event).ok();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use turborepo_log::{
+        grouping::{GroupingLayer, GroupingMode},
+        Logger, OutputChannel,
+    };
+    use turborepo_ui::tui::event::OutputLogs;
+
+    use super::*;
+
+    fn received(receiver: &mut broadcast::Receiver<RunEvent>) -> Vec<RunEvent> {
+        let mut received = Vec::new();
+        while let Ok(event) = receiver.try_recv() {
+            received.push(event);
+        }
+        received
+    }
+
+    #[tokio::test]
+    async fn test_publishes_stream_mode_events() {
+        let events = channel();
+        let mut receiver = events.subscribe();
+        let observer = observer(events);
+
+        let layer = GroupingLayer::new(Arc::new(Logger::new(vec![])), GroupingMode::Grouped);
+        let mut task_handle = layer.task("web#build");
+        let task_output = observe_task(
+            &observer,
+            "web#build".into(),
+            TaskOutput::stream(),
+            &mut task_handle,
+        );
+
+        task_output.start(OutputLogs::Full);
+        task_output.cache_status(CacheResult::Miss);
+        task_handle.task_output(OutputChannel::Stdout, b"building\n");
+        task_output.finish(false, false);
+        task_handle.finish(false);
+
+        assert_eq!(
+            received(&mut receiver),
+            vec![
+                RunEvent::TaskStarted {
+                    task: "web#build".into()
+                },
+                RunEvent::TaskCacheStatus {
+                    task: "web#build".into(),
+                    status: "cache miss".into(),
+                    cache_hit: false,
+                },
+                RunEvent::TaskLog {
+                    task: "web#build".into(),
+                    line: "building".into(),
+                },
+                RunEvent::TaskFinished {
+                    task: "web#build".into(),
+                    outcome: TaskOutcome::Success,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publishes_output_as_lines() {
+        let events = channel();
+        let mut receiver = events.subscribe();
+        let observer = observer(events);
+
+        observer(TaskEvent::Start {
+            task: "web#build".into(),
+        });
+        observer(TaskEvent::Output {
+            task: "web#build".into(),
+            output: b"first\r\nsec".to_vec(),
+        });
+        observer(TaskEvent::Output {
+            task: "web#build".into(),
+            output: b"ond\r\nthird".to_vec(),
+        });
+        observer(TaskEvent::End {
+            task: "web#build".into(),
+            result: TaskResult::Success,
+        });
+
+        let log = |line: &str| RunEvent::TaskLog {
+            task: "web#build".into(),
+            line: line.into(),
+        };
+        assert_eq!(
+            received(&mut receiver),
+            vec![
+                RunEvent::TaskStarted {
+                    task: "web#build".into()
+                },
+                log("first"),
+                log("second"),
+                log("third"),
+                RunEvent::TaskFinished {
+                    task: "web#build".into(),
+                    outcome: TaskOutcome::Success,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publishes_long_partial_lines() {
+        let events = channel();
+        let mut receiver = events.subscribe();
+        let observer = observer(events);
+
+        observer(TaskEvent::Start {
+            task: "web#dev".into(),
+        });
+        for _ in 0..4 {
+            observer(TaskEvent::Output {
+                task: "web#dev".into(),
+                output: vec![b'.'; MAX_PARTIAL_LINE_LEN / 2],
+            });
+        }
+        observer(TaskEvent::Output {
+            task: "web#dev".into(),
+            output: b"done\nready".to_vec(),
+        });
+        observer(TaskEvent::End {
+            task: "web#dev".into(),
+            result: TaskResult::Stopped,
+        });
+
+        let log = |line: String| RunEvent::TaskLog {
+            task: "web#dev".into(),
+            line,
+        };
+        let dots = ".".repeat(MAX_PARTIAL_LINE_LEN);
+        assert_eq!(
+            received(&mut receiver),
+            vec![
+                RunEvent::TaskStarted {
+                    task: "web#dev".into()
+                },
+                log(dots.clone()),
+                log(dots),
+                log("done".into()),
+                log("ready".into()),
+                RunEvent::TaskFinished {
+                    task: "web#dev".into(),
+                    outcome: TaskOutcome::Stopped,
+                },
+            ]
+        );
+    }
+}
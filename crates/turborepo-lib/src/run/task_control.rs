@@ -0,0 +1,289 @@
+//! Stopping, restarting and starting individual tasks at the request of the
+//! TUI.
+
+use std::{collections::HashMap, future::Future, sync::Arc};
+
+use tokio::{
+    select,
+    sync::mpsc,
+    task::{JoinError, JoinSet},
+};
+use tracing::debug;
+use turborepo_signals::SignalHandler;
+use turborepo_task_id::TaskId;
+use turborepo_telemetry::events::command::CommandEventBuilder;
+use turborepo_ui::{
+    sender::UISender,
+    tui::event::{TaskAction, TaskActionHandler},
+};
+
+use crate::{
+    commands::CommandBase,
+    engine::EngineExt,
+    get_version,
+    opts::Opts,
+    run::{self, builder::RunBuilder, Run, RunStopper},
+};
+
+pub(crate) type TaskActions = mpsc::UnboundedReceiver<(TaskId<'static>, TaskAction)>;
+
+type RunResult = (usize, Result<i32, run::Error>);
+
+/// Creates the handler the TUI should invoke when the user requests a task
+/// action along with the receiving end of the requests.
+pub(crate) fn task_actions() -> (TaskActionHandler, TaskActions) {
+    let (tx, actions) = mpsc::unbounded_channel();
+    let handler: TaskActionHandler = Arc::new(move |task: String, action: TaskAction| {
+        let Ok(task_id) = TaskId::try_from(task.as_str()) else {
+            debug!("unable to parse task id from {task}");
+            return;
+        };
+        // If the receiver is gone there's nothing left to act on the task
+        tx.send((task_id.into_owned(), action)).ok();
+    });
+    (handler, actions)
+}
+
+/// Restricts `opts` to running `task_id` along with its dependencies.
+pub(crate) fn single_task_opts(mut opts: Opts, task_id: &TaskId) -> Opts {
+    opts.run_opts.tasks = vec![task_id.task().to_string()];
+    // Dependencies of the task are pulled in by the task graph, so only
+    // the task's own package needs to be selected.
+    if !opts.run_opts.single_package {
+        opts.scope_opts.filter_patterns = vec![task_id.package().to_string()];
+    }
+    opts.scope_opts.affected_range = None;
+    opts.scope_opts.pkg_inference_root = None;
+    opts
+}
+
+/// Stops `task_id` in whichever of the runs behind `stoppers` owns its
+/// process.
+pub(crate) async fn stop_task<'a>(
+    task_id: &TaskId<'static>,
+    stoppers: impl IntoIterator<Item = &'a RunStopper>,
+) {
+    let task_ids = [task_id.clone()];
+    for stopper in stoppers {
+        stopper.stop_tasks(&task_ids).await;
+    }
+}
+
+/// Builds a run of `task_id` and its dependencies from `opts`, and tells the
+/// UI that the tasks in it are being restarted.
+///
+/// `configure` adds anything else the caller's runs are built with.
+pub(crate) async fn build_task_run(
+    base: &CommandBase,
+    opts: Opts,
+    task_id: &TaskId<'static>,
+    configure: impl FnOnce(RunBuilder) -> RunBuilder,
+    signal_handler: &SignalHandler,
+    telemetry: CommandEventBuilder,
+    ui_sender: Option<&UISender>,
+) -> Result<Run, run::Error> {
+    let base = CommandBase::from_opts(
+        single_task_opts(opts, task_id),
+        base.repo_root.clone(),
+        get_version(),
+        base.color_config,
+    );
+    let (run, _analytics) = configure(RunBuilder::new(base, None)?)
+        .build(signal_handler, telemetry)
+        .await?;
+
+    if let Some(sender) = ui_sender {
+        let task_names = run.engine.tasks_with_command(&run.pkg_dep_graph);
+        if let Err(err) = sender.restart_tasks(task_names) {
+            tracing::warn!("failed to notify UI of restarted tasks: {err}");
+        }
+    }
+    Ok(run)
+}
+
+/// Reports that a task the user asked to start couldn't be.
+pub(crate) fn log_start_failure(task_id: &TaskId, err: impl std::fmt::Display) {
+    turborepo_log::error(
+        turborepo_log::Source::turbo(turborepo_log::Subsystem::Run),
+        format!("Failed to start {task_id}: {err}"),
+    )
+    .emit();
+}
+
+/// Carries out task actions requested from the TUI.
+///
+/// Stopping a task kills its process in whichever run owns it. Starting a
+/// task builds a new run for just that task and its dependencies, so
+/// dependencies only execute again if their hashes changed.
+pub struct TaskController {
+    base: CommandBase,
+    signal_handler: SignalHandler,
+    telemetry: CommandEventBuilder,
+    ui_sender: Option<UISender>,
+    run_events: Option<turborepo_query_api::RunEventSender>,
+    actions: TaskActions,
+    // Every run that might own the process of a task, keyed by run id. A run
+    // is dropped once it and every task it left running have exited.
+    stoppers: HashMap<usize, RunStopper>,
+    // Runs registered through `add_stopper`, which are driven by the caller
+    // and only tracked in `runs` once `wait` is called
+    external_runs: Vec<usize>,
+    // Runs along with the persistent tasks they left running in the background
+    runs: JoinSet<RunResult>,
+    next_run_id: usize,
+}
+
+impl TaskController {
+    /// Creates a controller along with the handler the TUI should invoke when
+    /// the user requests a task action.
+    pub fn new(
+        base: CommandBase,
+        signal_handler: SignalHandler,
+        telemetry: CommandEventBuilder,
+    ) -> (Self, TaskActionHandler) {
+        let (handler, actions) = task_actions();
+        (
+            Self {
+                base,
+                signal_handler,
+                telemetry,
+                ui_sender: None,
+                run_events: None,
+                actions,
+                stoppers: HashMap::new(),
+                external_runs: Vec::new(),
+                runs: JoinSet::new(),
+                next_run_id: 0,
+            },
+            handler,
+        )
+    }
+
+    pub fn set_ui_sender(&mut self, ui_sender: Option<UISender>) {
+        self.ui_sender = ui_sender;
+    }
+
+    /// Publish the task events of started runs to the subscribers of the run
+    /// they were started from
+    pub fn set_run_events(&mut self, run_events: Option<turborepo_query_api::RunEventSender>) {
+        self.run_events = run_events;
+    }
+
+    /// Register a run whose tasks can be stopped
+    pub fn add_stopper(&mut self, stopper: RunStopper) {
+        let id = self.insert_stopper(stopper);
+        self.external_runs.push(id);
+    }
+
+    /// Drive `fut` to completion, handling task actions in the meantime
+    pub async fn run_until<T>(&mut self, fut: impl Future<Output = T>) -> T {
+        tokio::pin!(fut);
+        loop {
+            select! {
+                output = &mut fut => return output,
+                Some((task_id, action)) = self.actions.recv() => {
+                    self.handle(task_id, action).await;
+                }
+                Some(result) = self.runs.join_next() => self.run_finished(result),
+            }
+        }
+    }
+
+    /// Wait for tasks started by the controller to exit, handling task actions
+    /// in the meantime.
+    ///
+    /// If a shutdown has started, all tasks are stopped instead.
+    pub async fn wait(&mut self) {
+        // Runs registered by the caller have finished by now, but they may
+        // have left persistent tasks running.
+        for id in self.external_runs.drain(..) {
+            if let Some(stopper) = self.stoppers.get(&id).cloned() {
+                self.runs.spawn(async move {
+                    stopper.wait_for_tasks().await;
+                    (id, Ok(0))
+                });
+            }
+        }
+        loop {
+            select! {
+                _ = self.signal_handler.started() => {
+                    self.stop().await;
+                    break;
+                }
+                Some((task_id, action)) = self.actions.recv() => {
+                    self.handle(task_id, action).await;
+                }
+                result = self.runs.join_next() => match result {
+                    Some(result) => self.run_finished(result),
+                    None => break,
+                },
+            }
+        }
+    }
+
+    /// Stop every task the controller knows about
+    pub async fn stop(&self) {
+        for stopper in self.stoppers.values() {
+            stopper.stop().await;
+        }
+    }
+
+    fn insert_stopper(&mut self, stopper: RunStopper) -> usize {
+        let id = self.next_run_id;
+        self.next_run_id += 1;
+        self.stoppers.insert(id, stopper);
+        id
+    }
+
+    fn run_finished(&mut self, result: Result<RunResult, JoinError>) {
+        match result {
+            Ok((id, result)) => {
+                self.stoppers.remove(&id);
+                if let Err(err) = result {
+                    debug!("task run failed: {err}");
+                }
+            }
+            Err(err) => debug!("task run panicked: {err}"),
+        }
+    }
+
+    async fn handle(&mut self, task_id: TaskId<'static>, action: TaskAction) {
+        debug!("handling {action:?} for {task_id}");
+
+        if matches!(action, TaskAction::Stop | TaskAction::Restart) {
+            stop_task(&task_id, self.stoppers.values()).await;
+        }
+
+        if matches!(action, TaskAction::Restart | TaskAction::Start) {
+            if let Err(err) = self.start_task(&task_id).await {
+                log_start_failure(&task_id, err);
+            }
+        }
+    }
+
+    async fn start_task(&mut self, task_id: &TaskId<'static>) -> Result<(), run::Error> {
+        let run_events = self.run_events.clone();
+        let run = build_task_run(
+            &self.base,
+            self.base.opts().clone(),
+            task_id,
+            |builder| builder.with_run_events(run_events),
+            &self.signal_handler,
+            self.telemetry.clone(),
+            self.ui_sender.as_ref(),
+        )
+        .await?;
+
+        let stopper = run.stopper();
+        let id = self.insert_stopper(stopper.clone());
+        let ui_sender = self.ui_sender.clone();
+        // Run as a watch run so persistent tasks are left running in the
+        // background instead of blocking the run from finishing.
+        self.runs.spawn(async move {
+            let result = run.run(ui_sender, true).await;
+            stopper.wait_for_tasks().await;
+            (id, result)
+        });
+        Ok(())
+    }
+}
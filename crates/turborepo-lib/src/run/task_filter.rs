@@ -9,16 +9,19 @@
 //! - The `...` dependency/dependent syntax traverses the task graph, picking up
 //!   cross-package task dependencies (e.g. `web#build -> schema#gen` where
 //!   `web` has no package-level dependency on `schema`).
+//! - `--filter-depth` limits `...` by package graph levels, like package-level
+//!   filters, so that tasks depending on other tasks in the same package don't
+//!   use up a level.
 //!
 //! The core matching logic reuses
 //! `turborepo_engine::match_tasks_against_changed_files`
 //! and `crate::task_change_detector::affected_task_ids`, sharing code with
 //! `--affected` + `affectedUsingTaskInputs`.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 use turbopath::{AbsoluteSystemPath, AnchoredSystemPathBuf};
-use turborepo_repository::package_graph::{PackageGraph, PackageName};
+use turborepo_repository::package_graph::{PackageGraph, PackageName, PackageNode};
 use turborepo_scm::SCM;
 use turborepo_scope::{target_selector::GitRange, TargetSelector};
 use turborepo_task_id::TaskId;
@@ -32,6 +35,7 @@ use crate::engine::Engine;
 pub fn resolve_affected_tasks(
     engine: &Engine,
     affected_range: &(Option<String>, Option<String>),
+    depth: Option<usize>,
     pkg_dep_graph: &PackageGraph,
     scm: &SCM,
     repo_root: &AbsoluteSystemPath,
@@ -46,12 +50,14 @@ pub fn resolve_affected_tasks(
             merge_base: true,
         }),
         include_dependents: true,
+        depth,
         ..Default::default()
     };
     resolve_selector_to_tasks(
         engine,
         &selector,
         pkg_dep_graph,
+        &HashMap::new(),
         scm,
         repo_root,
         global_deps,
@@ -69,6 +75,7 @@ pub fn filter_engine_to_tasks(
     selectors: &[TargetSelector],
     affected_constraint: Option<&HashSet<TaskId<'static>>>,
     pkg_dep_graph: &PackageGraph,
+    package_tags: &HashMap<PackageName, Vec<String>>,
     scm: &SCM,
     repo_root: &AbsoluteSystemPath,
     global_deps: &[String],
@@ -82,6 +89,7 @@ pub fn filter_engine_to_tasks(
             &engine,
             selector,
             pkg_dep_graph,
+            package_tags,
             scm,
             repo_root,
             global_deps,
@@ -103,6 +111,7 @@ pub fn filter_engine_to_tasks(
             &engine,
             selector,
             pkg_dep_graph,
+            package_tags,
             scm,
             repo_root,
             global_deps,
@@ -129,12 +138,14 @@ pub fn filter_engine_to_tasks(
 /// Resolves a single selector to the set of matching task IDs.
 ///
 /// Steps:
-/// 1. Find the "base" task set from name/directory/git-range
-/// 2. Expand via `...` (dependencies/dependents) in the task graph
+/// 1. Find the "base" task set from name/tag/directory/git-range
+/// 2. Expand via `...` (dependencies/dependents) in the task graph, keeping
+///    only tasks in packages within the selector's depth
 fn resolve_selector_to_tasks(
     engine: &Engine,
     selector: &TargetSelector,
     pkg_dep_graph: &PackageGraph,
+    package_tags: &HashMap<PackageName, Vec<String>>,
     scm: &SCM,
     repo_root: &AbsoluteSystemPath,
     global_deps: &[String],
@@ -144,24 +155,40 @@ fn resolve_selector_to_tasks(
             engine,
             selector,
             pkg_dep_graph,
+            package_tags,
             scm,
             repo_root,
             global_deps,
         );
     }
 
-    let base_tasks =
-        resolve_base_tasks(engine, selector, pkg_dep_graph, scm, repo_root, global_deps)?;
+    let base_tasks = resolve_base_tasks(
+        engine,
+        selector,
+        pkg_dep_graph,
+        package_tags,
+        scm,
+        repo_root,
+        global_deps,
+    )?;
 
     let mut result = HashSet::new();
 
     if selector.include_dependencies {
-        let deps = engine.collect_task_dependencies(&base_tasks);
+        let mut deps = engine.collect_task_dependencies(&base_tasks);
+        retain_within_depth(&mut deps, &base_tasks, pkg_dep_graph, selector.depth, false);
         result.extend(deps);
     }
 
     if selector.include_dependents {
-        let dependents = engine.collect_task_dependents(&base_tasks);
+        let mut dependents = engine.collect_task_dependents(&base_tasks);
+        retain_within_depth(
+            &mut dependents,
+            &base_tasks,
+            pkg_dep_graph,
+            selector.depth,
+            true,
+        );
         result.extend(dependents);
     }
 
@@ -182,20 +209,57 @@ fn resolve_selector_to_tasks(
     Ok(result)
 }
 
+/// Keeps only the tasks in packages at most `depth` package graph levels
+/// away from the packages of `base_tasks`, in the direction of their
+/// dependencies or, with `dependents`, their dependents. Mirrors how
+/// package-level filters resolve `--filter-depth`: the package set is found
+/// first, and tasks are picked from it.
+fn retain_within_depth(
+    tasks: &mut HashSet<TaskId<'static>>,
+    base_tasks: &HashSet<TaskId<'static>>,
+    pkg_dep_graph: &PackageGraph,
+    depth: Option<usize>,
+    dependents: bool,
+) {
+    let Some(depth) = depth else {
+        return;
+    };
+
+    let mut packages = HashSet::new();
+    for task in base_tasks {
+        let package = PackageName::from(task.package());
+        let node = PackageNode::Workspace(package.clone());
+        let reachable = if dependents {
+            pkg_dep_graph.ancestors_within_depth(&node, depth)
+        } else {
+            pkg_dep_graph.dependencies_within_depth(&node, depth)
+        };
+        packages.extend(
+            reachable
+                .into_iter()
+                .map(|node| node.as_package_name().clone()),
+        );
+        packages.insert(package);
+    }
+
+    tasks.retain(|task| packages.contains(&PackageName::from(task.package())));
+}
+
 /// The base task set before `...` expansion.
 ///
-/// Combines name/directory matching (package-level) with git-range matching
+/// Combines name/tag/directory matching (package-level) with git-range matching
 /// (task-level via inputs). When both are present, the result is their
 /// intersection (same semantics as the package-level filter).
 fn resolve_base_tasks(
     engine: &Engine,
     selector: &TargetSelector,
     pkg_dep_graph: &PackageGraph,
+    package_tags: &HashMap<PackageName, Vec<String>>,
     scm: &SCM,
     repo_root: &AbsoluteSystemPath,
     global_deps: &[String],
 ) -> Result<HashSet<TaskId<'static>>, crate::run::error::Error> {
-    let tasks_from_packages = resolve_name_and_dir(engine, selector, pkg_dep_graph);
+    let tasks_from_packages = resolve_name_and_dir(engine, selector, pkg_dep_graph, package_tags);
     let tasks_from_git_range =
         resolve_git_range(engine, selector, pkg_dep_graph, scm, repo_root, global_deps)?;
 
@@ -209,28 +273,31 @@ fn resolve_base_tasks(
     }
 }
 
-/// Matches tasks by package name pattern and/or directory.
-/// Returns None if the selector has no name/directory constraints.
+/// Matches tasks by package name pattern, tag, and/or directory.
+/// Returns None if the selector has no name/tag/directory constraints.
 fn resolve_name_and_dir(
     engine: &Engine,
     selector: &TargetSelector,
     pkg_dep_graph: &PackageGraph,
+    package_tags: &HashMap<PackageName, Vec<String>>,
 ) -> Option<HashSet<TaskId<'static>>> {
     let has_name = !selector.name_pattern.is_empty();
+    let has_tag = selector.tag.is_some();
     let has_dir = selector.parent_dir.is_some();
 
-    if !has_name && !has_dir {
+    if !has_name && !has_tag && !has_dir {
         return None;
     }
 
-    let matching_packages = find_matching_packages(selector, pkg_dep_graph);
+    let matching_packages = find_matching_packages(selector, pkg_dep_graph, package_tags);
     Some(engine.task_ids_for_packages(&matching_packages))
 }
 
-/// Finds packages matching a selector's name pattern and/or directory.
+/// Finds packages matching a selector's name pattern, tag, and/or directory.
 fn find_matching_packages(
     selector: &TargetSelector,
     pkg_dep_graph: &PackageGraph,
+    package_tags: &HashMap<PackageName, Vec<String>>,
 ) -> HashSet<PackageName> {
     let mut packages: HashSet<PackageName> = HashSet::new();
 
@@ -264,6 +331,13 @@ fn find_matching_packages(
         }
     }
 
+    // Tag matching
+    if let Some(tag) = &selector.tag {
+        if let Ok(tagged) = turborepo_scope::filter::packages_with_tag(tag, package_tags) {
+            packages.retain(|name| tagged.contains(name));
+        }
+    }
+
     packages
 }
 
@@ -313,6 +387,7 @@ fn resolve_match_dependencies(
     engine: &Engine,
     selector: &TargetSelector,
     pkg_dep_graph: &PackageGraph,
+    package_tags: &HashMap<PackageName, Vec<String>>,
     scm: &SCM,
     repo_root: &AbsoluteSystemPath,
     global_deps: &[String],
@@ -323,11 +398,18 @@ fn resolve_match_dependencies(
     };
 
     // Find all tasks in the named packages
-    let matching_packages = find_matching_packages(selector, pkg_dep_graph);
+    let matching_packages = find_matching_packages(selector, pkg_dep_graph, package_tags);
     let package_tasks = engine.task_ids_for_packages(&matching_packages);
 
     // Expand to include all task-graph dependencies
     let mut candidate_tasks = engine.collect_task_dependencies(&package_tasks);
+    retain_within_depth(
+        &mut candidate_tasks,
+        &package_tasks,
+        pkg_dep_graph,
+        selector.depth,
+        false,
+    );
     if !selector.exclude_self {
         candidate_tasks.extend(package_tasks);
     }
@@ -456,11 +538,29 @@ mod tests {
     }
 
     async fn make_pkg_graph(repo_root: &AbsoluteSystemPath, packages: &[&str]) -> PackageGraph {
+        let packages = packages
+            .iter()
+            .map(|name| (*name, &[] as &[&str]))
+            .collect::<Vec<_>>();
+        make_pkg_graph_with_dependencies(repo_root, &packages).await
+    }
+
+    async fn make_pkg_graph_with_dependencies(
+        repo_root: &AbsoluteSystemPath,
+        packages: &[(&str, &[&str])],
+    ) -> PackageGraph {
         let mut pkgs = HashMap::new();
-        for name in packages {
+        for (name, dependencies) in packages {
             let path = repo_root.join_components(&["packages", name, "package.json"]);
             let pkg = PackageJson {
                 name: Some(turborepo_errors::Spanned::new(name.to_string())),
+                version: Some("1.0.0".to_string()),
+                dependencies: Some(
+                    dependencies
+                        .iter()
+                        .map(|dependency| (dependency.to_string(), "workspace:*".to_string()))
+                        .collect(),
+                ),
                 ..Default::default()
             };
             pkgs.insert(path, pkg);
@@ -619,14 +719,69 @@ mod tests {
             ..Default::default()
         };
 
-        let tasks =
-            super::resolve_selector_to_tasks(&engine, &selector, &pkg_graph, &scm, root, &[])
-                .unwrap();
+        let tasks = super::resolve_selector_to_tasks(
+            &engine,
+            &selector,
+            &pkg_graph,
+            &HashMap::new(),
+            &scm,
+            root,
+            &[],
+        )
+        .unwrap();
 
         assert!(tasks.contains(&web_build));
         assert!(!tasks.contains(&api_build));
     }
 
+    /// resolve_selector_to_tasks with a tag matches tasks in packages that
+    /// declare the tag.
+    #[tokio::test]
+    async fn selector_tag_only() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = AbsoluteSystemPath::from_std_path(tmp.path()).unwrap();
+        let pkg_graph = make_pkg_graph(root, &["web", "api", "worker"]).await;
+        let scm = turborepo_scm::SCM::new(root);
+
+        let web_build = TaskId::new("web", "build");
+        let api_build = TaskId::new("api", "build");
+        let worker_build = TaskId::new("worker", "build");
+
+        let engine = make_engine(
+            &[
+                (web_build.clone(), TaskDefinition::default()),
+                (api_build.clone(), TaskDefinition::default()),
+                (worker_build.clone(), TaskDefinition::default()),
+            ],
+            &[],
+        );
+
+        let package_tags = HashMap::from([
+            (PackageName::from("api"), vec!["service".to_string()]),
+            (PackageName::from("worker"), vec!["service".to_string()]),
+            (PackageName::from("web"), vec!["frontend".to_string()]),
+        ]);
+        let selector = turborepo_scope::TargetSelector {
+            tag: Some("service".to_string()),
+            ..Default::default()
+        };
+
+        let tasks = super::resolve_selector_to_tasks(
+            &engine,
+            &selector,
+            &pkg_graph,
+            &package_tags,
+            &scm,
+            root,
+            &[],
+        )
+        .unwrap();
+
+        assert!(tasks.contains(&api_build));
+        assert!(tasks.contains(&worker_build));
+        assert!(!tasks.contains(&web_build));
+    }
+
     /// resolve_selector_to_tasks with include_dependencies traverses
     /// the task graph, not the package graph.
     #[tokio::test]
@@ -655,9 +810,16 @@ mod tests {
             ..Default::default()
         };
 
-        let tasks =
-            super::resolve_selector_to_tasks(&engine, &selector, &pkg_graph, &scm, root, &[])
-                .unwrap();
+        let tasks = super::resolve_selector_to_tasks(
+            &engine,
+            &selector,
+            &pkg_graph,
+            &HashMap::new(),
+            &scm,
+            root,
+            &[],
+        )
+        .unwrap();
 
         assert!(
             tasks.contains(&web_build),
@@ -695,9 +857,16 @@ mod tests {
             ..Default::default()
         };
 
-        let tasks =
-            super::resolve_selector_to_tasks(&engine, &selector, &pkg_graph, &scm, root, &[])
-                .unwrap();
+        let tasks = super::resolve_selector_to_tasks(
+            &engine,
+            &selector,
+            &pkg_graph,
+            &HashMap::new(),
+            &scm,
+            root,
+            &[],
+        )
+        .unwrap();
 
         assert!(
             tasks.contains(&schema_gen),
@@ -737,9 +906,17 @@ mod tests {
             ..Default::default()
         };
 
-        let result =
-            super::filter_engine_to_tasks(engine, &[selector], None, &pkg_graph, &scm, root, &[])
-                .unwrap();
+        let result = super::filter_engine_to_tasks(
+            engine,
+            &[selector],
+            None,
+            &pkg_graph,
+            &HashMap::new(),
+            &scm,
+            root,
+            &[],
+        )
+        .unwrap();
 
         let remaining: HashSet<_> = result.task_ids().cloned().collect();
         assert!(
@@ -788,6 +965,7 @@ mod tests {
             &[selector],
             Some(&affected),
             &pkg_graph,
+            &HashMap::new(),
             &scm,
             root,
             &[],
@@ -842,6 +1020,7 @@ mod tests {
             &[exclude_selector],
             Some(&affected),
             &pkg_graph,
+            &HashMap::new(),
             &scm,
             root,
             &[],
@@ -897,6 +1076,7 @@ mod tests {
             &[],
             Some(&affected),
             &pkg_graph,
+            &HashMap::new(),
             &scm,
             root,
             &[],
@@ -963,6 +1143,7 @@ mod tests {
             &[selector],
             Some(&affected),
             &pkg_graph,
+            &HashMap::new(),
             &scm,
             root,
             &[],
@@ -1020,6 +1201,7 @@ mod tests {
             &[selector],
             Some(&affected),
             &pkg_graph,
+            &HashMap::new(),
             &scm,
             root,
             &[],
@@ -1061,9 +1243,16 @@ mod tests {
             ..Default::default()
         };
 
-        let tasks =
-            super::resolve_selector_to_tasks(&engine, &selector, &pkg_graph, &scm, root, &[])
-                .unwrap();
+        let tasks = super::resolve_selector_to_tasks(
+            &engine,
+            &selector,
+            &pkg_graph,
+            &HashMap::new(),
+            &scm,
+            root,
+            &[],
+        )
+        .unwrap();
 
         assert!(
             !tasks.contains(&web_build),
@@ -1101,9 +1290,16 @@ mod tests {
             ..Default::default()
         };
 
-        let tasks =
-            super::resolve_selector_to_tasks(&engine, &selector, &pkg_graph, &scm, root, &[])
-                .unwrap();
+        let tasks = super::resolve_selector_to_tasks(
+            &engine,
+            &selector,
+            &pkg_graph,
+            &HashMap::new(),
+            &scm,
+            root,
+            &[],
+        )
+        .unwrap();
 
         assert!(
             tasks.contains(&a_build),
@@ -1255,9 +1451,17 @@ mod tests {
             ..Default::default()
         };
 
-        let result =
-            super::filter_engine_to_tasks(engine, &[selector], None, &pkg_graph, &scm, root, &[])
-                .unwrap();
+        let result = super::filter_engine_to_tasks(
+            engine,
+            &[selector],
+            None,
+            &pkg_graph,
+            &HashMap::new(),
+            &scm,
+            root,
+            &[],
+        )
+        .unwrap();
 
         let remaining: HashSet<_> = result.task_ids().cloned().collect();
         assert!(
@@ -1304,9 +1508,17 @@ mod tests {
             ..Default::default()
         };
 
-        let result =
-            super::filter_engine_to_tasks(engine, &[selector], None, &pkg_graph, &scm, root, &[])
-                .unwrap();
+        let result = super::filter_engine_to_tasks(
+            engine,
+            &[selector],
+            None,
+            &pkg_graph,
+            &HashMap::new(),
+            &scm,
+            root,
+            &[],
+        )
+        .unwrap();
 
         let remaining: HashSet<_> = result.task_ids().cloned().collect();
         assert!(
@@ -1322,4 +1534,77 @@ mod tests {
             "docs#dev should NOT be retained (not in filter): {remaining:?}"
         );
     }
+
+    /// `--filter-depth` counts package graph levels, so a task depending on
+    /// another task in the same package doesn't use up a level.
+    #[tokio::test]
+    async fn selector_depth_counts_package_levels() {
+        let tmp = tempfile::tempdir().unwrap();
+        let root = AbsoluteSystemPath::from_std_path(tmp.path()).unwrap();
+        let pkg_graph = make_pkg_graph_with_dependencies(
+            root,
+            &[("web", &["ui"]), ("ui", &["utils"]), ("utils", &[])],
+        )
+        .await;
+        let scm = turborepo_scm::SCM::new(root);
+
+        let web_build = TaskId::new("web", "build");
+        let web_codegen = TaskId::new("web", "codegen");
+        let ui_build = TaskId::new("ui", "build");
+        let utils_build = TaskId::new("utils", "build");
+
+        let engine = make_engine(
+            &[
+                (web_build.clone(), TaskDefinition::default()),
+                (web_codegen.clone(), TaskDefinition::default()),
+                (ui_build.clone(), TaskDefinition::default()),
+                (utils_build.clone(), TaskDefinition::default()),
+            ],
+            // web#build -> web#codegen -> ui#build -> utils#build
+            &[
+                (web_build.clone(), web_codegen.clone()),
+                (web_codegen.clone(), ui_build.clone()),
+                (ui_build.clone(), utils_build.clone()),
+            ],
+        );
+
+        let selector = turborepo_scope::TargetSelector {
+            name_pattern: "web".to_string(),
+            include_dependencies: true,
+            depth: Some(1),
+            ..Default::default()
+        };
+        let tasks = super::resolve_selector_to_tasks(
+            &engine,
+            &selector,
+            &pkg_graph,
+            &HashMap::new(),
+            &scm,
+            root,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(
+            tasks,
+            HashSet::from([web_build.clone(), web_codegen.clone(), ui_build.clone()])
+        );
+
+        let selector = turborepo_scope::TargetSelector {
+            name_pattern: "utils".to_string(),
+            include_dependents: true,
+            depth: Some(1),
+            ..Default::default()
+        };
+        let tasks = super::resolve_selector_to_tasks(
+            &engine,
+            &selector,
+            &pkg_graph,
+            &HashMap::new(),
+            &scm,
+            root,
+            &[],
+        )
+        .unwrap();
+        assert_eq!(tasks, HashSet::from([utils_build, ui_build]));
+    }
 }
@@ -25,8 +25,9 @@ use turborepo_run_cache::{OutputWatcher, OutputWatcherError};
 use turborepo_scm::SCM;
 use turborepo_scope::target_selector::InvalidSelectorError;
 use turborepo_signals::{listeners::get_signal, ShutdownReason, SignalHandler};
+use turborepo_task_id::TaskId;
 use turborepo_telemetry::events::command::CommandEventBuilder;
-use turborepo_ui::{sender::UISender, LogSinks};
+use turborepo_ui::{sender::UISender, tui::event::TaskAction, LogSinks};
 
 use crate::{
     commands::CommandBase,
@@ -34,7 +35,13 @@ use crate::{
     engine::{EngineExt, TaskNode},
     get_version, opts,
     package_changes_watcher::PackageChangesWatcher,
-    run::{self, builder::RunBuilder, Run},
+    run::{
+        self,
+        builder::RunBuilder,
+        subscriptions,
+        task_control::{self, TaskActions},
+        Run,
+    },
 };
 
 #[derive(Debug)]
@@ -152,6 +159,7 @@ pub struct WatchClient {
     handler: SignalHandler,
     ui_sender: Option<UISender>,
     ui_handle: Option<JoinHandle<Result<(), turborepo_ui::Error>>>,
+    task_actions: TaskActions,
     experimental_write_cache: bool,
     query_server: Option<Arc<dyn turborepo_query_api::QueryServer>>,
 }
@@ -324,7 +332,8 @@ impl WatchClient {
 
         sinks.disable_for_tui();
 
-        let (ui_sender, ui_handle) = run.start_ui()?.unzip();
+        let (task_action_handler, task_actions) = task_control::task_actions();
+        let (ui_sender, ui_handle) = run.start_ui(Some(task_action_handler))?.unzip();
 
         if let Some(UISender::Tui(ref tui_sender)) = ui_sender {
             sinks.tui.connect(tui_sender.clone());
@@ -358,6 +367,7 @@ impl WatchClient {
             active_runs: Vec::new(),
             ui_sender,
             ui_handle,
+            task_actions,
             query_server,
         })
     }
@@ -410,7 +420,13 @@ impl WatchClient {
 
         let run_fut = async {
             loop {
-                notify_run.notified().await;
+                select! {
+                    _ = notify_run.notified() => {}
+                    Some((task_id, action)) = self.task_actions.recv() => {
+                        self.handle_task_action(task_id, action).await;
+                        continue;
+                    }
+                }
                 let some_changed_packages = {
                     let mut guard = pending_changes
                         .lock()
@@ -460,9 +476,7 @@ impl WatchClient {
                         active_runs = self.active_runs.len(),
                         "waiting for runs to complete"
                     );
-                    for handle in &mut self.active_runs {
-                        let _ = (&mut handle.run_task).await;
-                    }
+                    self.wait_for_active_runs().await;
                     debug!("all runs completed, ready for next event");
                     // Save stoppers before retain drops them — their PMs may
                     // still track background persistent processes.
@@ -588,6 +602,88 @@ impl WatchClient {
         }
     }
 
+    /// Wait for the active runs to finish, handling task actions in the
+    /// meantime.
+    async fn wait_for_active_runs(&mut self) {
+        while let Some(handle) = self
+            .active_runs
+            .iter_mut()
+            .find(|handle| !handle.run_task.is_finished())
+        {
+            select! {
+                _ = &mut handle.run_task => {}
+                Some((task_id, action)) = self.task_actions.recv() => {
+                    self.handle_task_action(task_id, action).await;
+                }
+            }
+        }
+    }
+
+    /// Carry out a task action requested from the TUI.
+    ///
+    /// Stopping a task kills its process in whichever run owns it. Starting a
+    /// task adds a run for just that task and its dependencies.
+    async fn handle_task_action(&mut self, task_id: TaskId<'static>, action: TaskAction) {
+        debug!("handling {action:?} for {task_id}");
+
+        if matches!(action, TaskAction::Stop | TaskAction::Restart) {
+            let stoppers = self
+                .active_runs
+                .iter()
+                .map(|handle| &handle.stopper)
+                .chain(&self.background_stoppers);
+            task_control::stop_task(&task_id, stoppers).await;
+        }
+
+        if matches!(action, TaskAction::Restart | TaskAction::Start) {
+            match self.start_task(&task_id).await {
+                Ok(handle) => self.active_runs.push(handle),
+                Err(err) => task_control::log_start_failure(&task_id, err),
+            }
+        }
+    }
+
+    async fn start_task(&self, task_id: &TaskId<'static>) -> Result<RunHandle, Error> {
+        let output_watcher = self.output_watcher.clone();
+        let run_events = self.run.run_events();
+        let query_server = self.query_server.clone();
+        let run = task_control::build_task_run(
+            &self.base,
+            self.run_opts(),
+            task_id,
+            |builder| {
+                let builder = builder
+                    .with_output_watcher(output_watcher)
+                    .with_run_events(run_events);
+                match query_server {
+                    Some(qs) => builder.with_query_server(qs),
+                    None => builder,
+                }
+            },
+            &self.handler,
+            self.telemetry.clone(),
+            self.ui_sender.as_ref(),
+        )
+        .await?;
+
+        let ui_sender = self.ui_sender.clone();
+        Ok(RunHandle {
+            stopper: run.stopper(),
+            run_task: tokio::spawn(async move { run.run(ui_sender, true).await }),
+        })
+    }
+
+    /// Options for runs started by watch mode, which only use the remote cache
+    /// when cache writes were explicitly enabled.
+    fn run_opts(&self) -> opts::Opts {
+        let mut opts = self.base.opts().clone();
+        if !self.experimental_write_cache {
+            opts.cache_opts.cache.remote.write = false;
+            opts.cache_opts.cache.remote.read = false;
+        }
+        opts
+    }
+
     async fn stop_impacted_tasks(
         &self,
         pkgs: &HashSet<PackageName>,
@@ -679,14 +775,8 @@ impl WatchClient {
                 packages,
                 changed_files,
             } => {
-                let mut opts = self.base.opts().clone();
-                if !self.experimental_write_cache {
-                    opts.cache_opts.cache.remote.write = false;
-                    opts.cache_opts.cache.remote.read = false;
-                }
-
                 let new_base = CommandBase::from_opts(
-                    opts,
+                    self.run_opts(),
                     self.base.repo_root.clone(),
                     get_version(),
                     self.base.color_config,
@@ -697,6 +787,7 @@ impl WatchClient {
 
                 let mut run_builder = RunBuilder::new(new_base, None)?
                     .with_output_watcher(self.output_watcher.clone())
+                    .with_run_events(self.run.run_events())
                     .with_entrypoint_packages(packages)
                     .with_changed_files(changed_files);
                 if let Some(ref qs) = self.query_server {
@@ -726,21 +817,16 @@ impl WatchClient {
                 })
             }
             ChangedPackages::All => {
-                let mut opts = self.base.opts().clone();
-                if !self.experimental_write_cache {
-                    opts.cache_opts.cache.remote.write = false;
-                    opts.cache_opts.cache.remote.read = false;
-                }
-
                 let base = CommandBase::from_opts(
-                    opts,
+                    self.run_opts(),
                     self.base.repo_root.clone(),
                     get_version(),
                     self.base.color_config,
                 );
 
                 let mut run_builder = RunBuilder::new(base.clone(), None)?
-                    .with_output_watcher(self.output_watcher.clone());
+                    .with_output_watcher(self.output_watcher.clone())
+                    .with_run_events(self.run.run_events());
                 if let Some(ref qs) = self.query_server {
                     run_builder = run_builder.with_query_server(qs.clone());
                 }
@@ -751,8 +837,11 @@ impl WatchClient {
 
                 self.watched_packages = self.run.get_relevant_packages();
 
+                let task_names = self.run.engine.tasks_with_command(&self.run.pkg_dep_graph);
+                if let Some(events) = self.run.run_events() {
+                    subscriptions::tasks_updated(&events, task_names.clone());
+                }
                 if let Some(sender) = &self.ui_sender {
-                    let task_names = self.run.engine.tasks_with_command(&self.run.pkg_dep_graph);
                     if let Err(err) = sender.update_tasks(task_names) {
                         tracing::warn!("failed to notify UI of updated tasks: {err}");
                     }
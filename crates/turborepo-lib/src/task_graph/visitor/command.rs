@@ -9,6 +9,9 @@ pub type CommandFactory<'a> = turborepo_task_executor::CommandFactory<'a, Error>
 pub type PackageGraphCommandProvider<'a> =
     turborepo_task_executor::PackageGraphCommandProvider<'a, MicrofrontendsConfigs>;
 
+// Re-export InlineCommandProvider from turborepo-task-executor
+pub use turborepo_task_executor::InlineCommandProvider;
+
 // Re-export MicroFrontendProxyProvider from turborepo-task-executor with our
 // MicrofrontendsConfigs type
 pub type MicroFrontendProxyProvider<'a, T> =
@@ -3,25 +3,47 @@
 //! This module provides the factory for creating task executors. The actual
 //! execution logic is in `turborepo-task-executor`.
 
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
 use console::StyledObject;
+use turbopath::AnchoredSystemPathBuf;
 use turborepo_engine::{TaskError, TaskErrorCollectorWrapper, TaskWarningCollectorWrapper};
 use turborepo_env::{platform::PlatformEnv, EnvironmentVariableMap};
 use turborepo_process::ProcessManager;
-use turborepo_task_executor::{DryRunExecutor, TaskExecutor};
+use turborepo_repository::package_graph::PackageName;
+use turborepo_task_executor::{DryRunExecutor, InputTrace, TaskExecutor};
 use turborepo_task_hash::TaskHashTracker;
 use turborepo_task_id::TaskId;
+use turborepo_types::{TaskDefinitionExt, UndeclaredFilesMode};
 
 use super::{
-    command::{CommandFactory, MicroFrontendProxyProvider, PackageGraphCommandProvider},
+    command::{
+        CommandFactory, InlineCommandProvider, MicroFrontendProxyProvider,
+        PackageGraphCommandProvider,
+    },
     Visitor,
 };
 use crate::{
-    engine::Engine,
+    engine::{Engine, TaskNode},
     run::{task_access::TaskAccess, TaskCache},
 };
 
+/// Files read by the package manager when it runs a task's script, relative
+/// to the repository root.
+const PACKAGE_MANAGER_FILES: &[&str] = &[
+    "package.json",
+    ".npmrc",
+    ".yarnrc",
+    ".yarnrc.yml",
+    ".pnp.cjs",
+    ".pnp.loader.mjs",
+];
+
 /// Type alias for the concrete TaskExecutor used in turborepo-lib.
 pub type ExecContext = TaskExecutor<
     TaskHashTracker,
@@ -41,9 +63,12 @@ pub struct ExecContextFactory<'a> {
     visitor: &'a Visitor<'a>,
     errors: Arc<Mutex<Vec<TaskError>>>,
     manager: ProcessManager,
-    #[allow(dead_code)]
     engine: &'a Arc<Engine>,
     command_factory: CommandFactory<'a>,
+    // Set when tasks should be traced to find undeclared inputs
+    strace: Option<PathBuf>,
+    // Repo-relative files in the global hash, declared for every task
+    global_files: &'a HashSet<AnchoredSystemPathBuf>,
 }
 
 impl<'a> ExecContextFactory<'a> {
@@ -52,13 +77,17 @@ impl<'a> ExecContextFactory<'a> {
         errors: Arc<Mutex<Vec<TaskError>>>,
         manager: ProcessManager,
         engine: &'a Arc<Engine>,
+        global_files: &'a HashSet<AnchoredSystemPathBuf>,
     ) -> Result<Self, super::Error> {
-        let pkg_graph_provider = PackageGraphCommandProvider::new(
+        let mut pkg_graph_provider = PackageGraphCommandProvider::new(
             visitor.repo_root,
             &visitor.package_graph,
             visitor.run_opts.task_args(),
             visitor.micro_frontends_configs,
         );
+        if visitor.run_opts.direct_script_execution {
+            pkg_graph_provider = pkg_graph_provider.with_direct_script_execution();
+        }
         let mut command_factory = CommandFactory::new();
         if let Some(micro_frontends_configs) = visitor.micro_frontends_configs {
             command_factory.add_provider(MicroFrontendProxyProvider::new(
@@ -68,14 +97,44 @@ impl<'a> ExecContextFactory<'a> {
                 micro_frontends_configs,
             ));
         }
+        // Inline commands from turbo.json take precedence over package.json scripts
+        command_factory.add_provider(InlineCommandProvider::new(
+            visitor.repo_root,
+            visitor.package_graph.as_ref(),
+            engine
+                .task_definitions()
+                .iter()
+                .filter_map(|(task_id, definition)| Some((task_id, definition.command.as_ref()?))),
+            visitor.run_opts.task_args(),
+        ));
         command_factory.add_provider(pkg_graph_provider);
 
+        let strace = match visitor.run_opts.undeclared_inputs {
+            UndeclaredFilesMode::Off => None,
+            UndeclaredFilesMode::Warn | UndeclaredFilesMode::Error => {
+                let strace = cfg!(target_os = "linux")
+                    .then(|| which::which("strace").ok())
+                    .flatten();
+                if strace.is_none() {
+                    turborepo_log::warn(
+                        turborepo_log::Source::turbo(turborepo_log::Subsystem::Run),
+                        "--undeclared-inputs requires Linux with strace installed, tasks will not \
+                         be checked for undeclared inputs",
+                    )
+                    .emit();
+                }
+                strace
+            }
+        };
+
         Ok(Self {
             visitor,
             errors,
             manager,
             engine,
             command_factory,
+            strace,
+            global_files,
         })
     }
 
@@ -87,6 +146,8 @@ impl<'a> ExecContextFactory<'a> {
         task_cache: TaskCache,
         mut execution_env: EnvironmentVariableMap,
         takes_input: bool,
+        timeout: Option<Duration>,
+        retries: u32,
         task_access: TaskAccess,
     ) -> Result<Option<ExecContext>, super::Error> {
         let task_id_for_display = self.visitor.display_task_id(&task_id);
@@ -97,6 +158,11 @@ impl<'a> ExecContextFactory<'a> {
         };
 
         let pretty_prefix = self.prefix_with_color(&task_id);
+        let input_trace = self
+            .strace
+            .as_deref()
+            .map(|strace| self.input_trace(&task_id, &task_hash, strace))
+            .transpose()?;
 
         Ok(Some(TaskExecutor {
             task_id,
@@ -106,11 +172,15 @@ impl<'a> ExecContextFactory<'a> {
             execution_env,
             manager: self.manager.clone(),
             takes_input,
+            timeout,
+            retries: self.visitor.run_opts.retries.unwrap_or(retries),
             continue_on_error: self.visitor.run_opts.continue_on_error,
             ui_mode: self.visitor.run_opts.ui_mode,
             color_config: self.visitor.color_config,
             is_github_actions: self.visitor.run_opts.is_github_actions,
             pretty_prefix,
+            undeclared_outputs: self.visitor.run_opts.undeclared_outputs,
+            input_trace,
             task_cache,
             hash_tracker: self.visitor.task_hasher.task_hash_tracker(),
             errors: TaskErrorCollectorWrapper::from_arc(self.errors.clone()),
@@ -132,6 +202,70 @@ impl<'a> ExecContextFactory<'a> {
         }
     }
 
+    /// Collects the files a task may read without them being undeclared
+    /// inputs: those hashed for it and its dependencies, the outputs of its
+    /// dependencies, and the files read by the package manager.
+    fn input_trace(
+        &self,
+        task_id: &TaskId<'static>,
+        task_hash: &str,
+        strace: &Path,
+    ) -> Result<InputTrace, super::Error> {
+        let hash_tracker = self.visitor.task_hasher.task_hash_tracker();
+        let mut declared_files = HashSet::new();
+        let mut declared_outputs = Vec::new();
+
+        // The transitive dependencies include the task itself
+        for node in self.engine.transitive_dependencies(task_id) {
+            let TaskNode::Task(dependency) = node else {
+                continue;
+            };
+            let package_name = PackageName::from(dependency.package());
+            let Some(package_dir) = self.visitor.package_graph.package_dir(&package_name) else {
+                return Err(super::Error::MissingPackage {
+                    package_name,
+                    task_id: dependency.clone(),
+                });
+            };
+            if let Some(inputs) = hash_tracker.get_expanded_inputs(dependency) {
+                declared_files.extend(inputs.0.iter().map(|(file, _)| {
+                    package_dir
+                        .to_owned()
+                        .join(&file.to_anchored_system_path_buf())
+                }));
+            }
+            if let Some(definition) = self.engine.task_definition(dependency) {
+                declared_outputs
+                    .push(definition.repo_relative_hashable_outputs(dependency, package_dir));
+            }
+        }
+
+        declared_files.extend(self.global_files.iter().cloned());
+
+        let package_manager = self.visitor.package_graph.package_manager();
+        declared_files.extend(
+            PACKAGE_MANAGER_FILES
+                .iter()
+                .copied()
+                .chain(Some(package_manager.lockfile_name()))
+                .chain(package_manager.workspace_configuration_path())
+                .filter_map(|file| AnchoredSystemPathBuf::from_raw(file).ok()),
+        );
+
+        Ok(InputTrace {
+            mode: self.visitor.run_opts.undeclared_inputs,
+            strace: strace.to_owned(),
+            repo_root: self.visitor.repo_root.to_owned(),
+            trace_file: self.visitor.repo_root.join_components(&[
+                ".turbo",
+                "traces",
+                &format!("{task_hash}.log"),
+            ]),
+            declared_files,
+            declared_outputs,
+        })
+    }
+
     /// Get a colored prefix for the task.
     fn prefix_with_color(&self, task_id: &TaskId) -> StyledObject<String> {
         let task_id_string = &task_id.to_string();
@@ -18,6 +18,7 @@ use turbopath::{AbsoluteSystemPath, AnchoredSystemPath, AnchoredSystemPathBuf};
 use turborepo_engine::{TaskError, TaskWarning};
 use turborepo_env::{platform::PlatformEnv, EnvironmentVariableMap};
 use turborepo_errors::TURBO_SITE;
+use turborepo_hash::explain::GlobalHashInputs;
 use turborepo_log::grouping::{GroupingLayer, GroupingMode};
 use turborepo_process::ProcessManager;
 use turborepo_repository::package_graph::{PackageGraph, PackageName, ROOT_PKG_NAME};
@@ -32,14 +33,14 @@ use turborepo_telemetry::events::{
     generic::GenericEventBuilder, task::PackageTaskEventBuilder, EventBuilder, TrackedErrors,
 };
 use turborepo_types::{EnvMode, ResolvedLogOrder, ResolvedLogPrefix};
-use turborepo_ui::{sender::UISender, ColorConfig, ColorSelector};
+use turborepo_ui::{sender::UISender, tui::event::TaskEventObserver, ColorConfig, ColorSelector};
 use wax::Program;
 
 use crate::{
     engine::{Engine, ExecutionOptions, TaskNode},
     microfrontends::MicrofrontendsConfigs,
     opts::RunOpts,
-    run::{task_access::TaskAccess, RunCache},
+    run::{subscriptions, task_access::TaskAccess, RunCache},
     task_hash::TaskHasher,
 };
 
@@ -62,8 +63,12 @@ pub struct Visitor<'a> {
     color_config: ColorConfig,
     is_watch: bool,
     ui_sender: Option<UISender>,
+    // Receives the lifecycle events and output of every task, in any UI mode
+    task_events: Option<TaskEventObserver>,
     warnings: Arc<Mutex<Vec<TaskWarning>>>,
     micro_frontends_configs: Option<&'a MicrofrontendsConfigs>,
+    // Repo-relative files in the global hash, which any task may read
+    global_files: HashSet<AnchoredSystemPathBuf>,
 }
 
 #[derive(Debug, thiserror::Error, Diagnostic)]
@@ -150,6 +155,7 @@ impl<'a> Visitor<'a> {
         ui_sender: Option<UISender>,
         is_watch: bool,
         micro_frontends_configs: Option<&'a MicrofrontendsConfigs>,
+        global_files: HashSet<AnchoredSystemPathBuf>,
     ) -> Self {
         let (task_hasher, color_cache, grouping_layer) = {
             let _span = tracing::info_span!("visitor_new").entered();
@@ -208,8 +214,10 @@ impl<'a> Visitor<'a> {
             color_config,
             ui_sender,
             is_watch,
+            task_events: None,
             warnings: Default::default(),
             micro_frontends_configs,
+            global_files,
         }
     }
 
@@ -577,7 +585,13 @@ impl<'a> Visitor<'a> {
         let errors = Arc::new(Mutex::new(Vec::new()));
         let span = Span::current();
 
-        let factory = ExecContextFactory::new(self, errors.clone(), self.manager.clone(), &engine)?;
+        let factory = ExecContextFactory::new(
+            self,
+            errors.clone(),
+            self.manager.clone(),
+            &engine,
+            &self.global_files,
+        )?;
 
         // Errors from the dispatch loop are captured here rather than returned
         // immediately. This ensures we always drain the FuturesUnordered below,
@@ -739,8 +753,13 @@ impl<'a> Visitor<'a> {
 
             let task_cache = {
                 let _span = tracing::info_span!("task_cache_new").entered();
-                self.run_cache
-                    .task_cache(task_definition, workspace_info, info.clone(), &task_hash)
+                self.run_cache.task_cache(
+                    task_definition,
+                    workspace_info,
+                    info.clone(),
+                    &task_hash,
+                    self.task_hasher.task_hash_tracker().hash_inputs(&info),
+                )
             };
 
             // Drop to avoid holding the span across an await
@@ -760,7 +779,7 @@ impl<'a> Visitor<'a> {
                 false => {
                     let takes_input = task_definition.interactive || task_definition.persistent;
 
-                    let task_output = if let Some(handle) = &self.ui_sender {
+                    let mut task_output = if let Some(handle) = &self.ui_sender {
                         TaskOutput::tui(handle.task(info.to_string()))
                     } else {
                         TaskOutput::stream()
@@ -778,6 +797,8 @@ impl<'a> Visitor<'a> {
                             task_cache,
                             execution_env,
                             takes_input,
+                            task_definition.timeout,
+                            task_definition.retries,
                             self.task_access.clone(),
                         ) {
                             Ok(ctx) => ctx,
@@ -800,9 +821,17 @@ impl<'a> Visitor<'a> {
                     } else {
                         format!("{}:{}", info.package(), info.task())
                     };
-                    let task_handle = self
+                    let mut task_handle = self
                         .grouping_layer
                         .task_with_label(&task_id_str, display_label);
+                    if let Some(observer) = &self.task_events {
+                        task_output = subscriptions::observe_task(
+                            observer,
+                            task_id_str.clone(),
+                            task_output,
+                            &mut task_handle,
+                        );
+                    }
                     self.grouping_layer
                         .logger()
                         .register_task(&task_id_str, &task_prefix);
@@ -1010,11 +1039,22 @@ impl<'a> Visitor<'a> {
         self.task_hasher.into_task_hash_tracker_state()
     }
 
+    /// Publish the lifecycle events and output of every task to `observer`
+    pub fn publish_task_events(&mut self, observer: TaskEventObserver) {
+        self.task_events = Some(observer);
+    }
+
     pub fn dry_run(&mut self) {
         self.dry = true;
         // No need to start a UI on dry run
         self.ui_sender = None;
     }
+
+    /// Records the inputs of each task's hash so they can be stored alongside
+    /// its cache entry and used to explain cache misses
+    pub fn record_hash_inputs(&mut self, global_hash_inputs: Arc<GlobalHashInputs>) {
+        self.task_hasher.record_hash_inputs(global_hash_inputs);
+    }
 }
 
 fn filter_hashes_to_declared_outputs(
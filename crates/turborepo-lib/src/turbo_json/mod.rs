@@ -85,10 +85,10 @@ mod tests {
     use turbopath::RelativeUnixPath;
     use turborepo_engine::TaskDefinitionFromProcessed;
     use turborepo_errors::Spanned;
-    use turborepo_turbo_json::raw::RawTaskInput;
-    use turborepo_types::{TaskDefinition, TaskInputs, TaskOutputs};
+    use turborepo_turbo_json::raw::{RawTaskCommand, RawTaskInput};
+    use turborepo_types::{TaskCommand, TaskDefinition, TaskInputs, TaskOutputs};
 
-    use super::RawTaskDefinition;
+    use super::{RawRootTurboJson, RawTaskDefinition};
 
     fn raw_input(value: &str) -> RawTaskInput {
         RawTaskInput::String(turborepo_unescape::UnescapedString::from(value.to_string()))
@@ -137,6 +137,9 @@ mod tests {
             persistent: Some(Spanned::new(true).with_range(278..282)),
             interactive: Some(Spanned::new(true).with_range(309..313)),
             interruptible: Some(Spanned::new(true).with_range(342..346)),
+            timeout: None,
+            retries: None,
+            command: None,
             env_mode: None,
             with: None,
             experimental_ci: None,
@@ -157,6 +160,9 @@ mod tests {
           persistent: true,
           interactive: true,
           interruptible: true,
+          timeout: None,
+          retries: 0,
+          command: None,
           env_mode: None,
           with: None,
           incremental: None,
@@ -188,6 +194,9 @@ mod tests {
             persistent: Some(Spanned::new(true).with_range(315..319)),
             interruptible: Some(Spanned::new(true).with_range(352..356)),
             interactive: None,
+            timeout: None,
+            retries: None,
+            command: None,
             env_mode: None,
             with: None,
             experimental_ci: None,
@@ -208,6 +217,9 @@ mod tests {
             persistent: true,
             interruptible: true,
             interactive: false,
+            timeout: None,
+            retries: 0,
+            command: None,
             env_mode: None,
             with: None,
             incremental: None,
@@ -253,6 +265,77 @@ mod tests {
         }
     ; "with task"
     )]
+    #[test_case(
+        r#"{ "timeout": "15m" }"#,
+        RawTaskDefinition {
+            timeout: Some(Spanned::new(turborepo_unescape::UnescapedString::from("15m")).with_range(13..18)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            timeout: Some(std::time::Duration::from_secs(15 * 60)),
+            ..TaskDefinition::default()
+        }
+    ; "timeout"
+    )]
+    #[test_case(
+        r#"{ "timeout": "0" }"#,
+        RawTaskDefinition {
+            timeout: Some(Spanned::new(turborepo_unescape::UnescapedString::from("0")).with_range(13..16)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition::default()
+    ; "zero timeout is disabled"
+    )]
+    #[test_case(
+        r#"{ "retries": 2 }"#,
+        RawTaskDefinition {
+            retries: Some(Spanned::new(2).with_range(13..14)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            retries: 2,
+            ..TaskDefinition::default()
+        }
+    ; "retries"
+    )]
+    #[test_case(
+        r#"{ "retries": 3, "timeout": "30s" }"#,
+        RawTaskDefinition {
+            retries: Some(Spanned::new(3).with_range(13..14)),
+            timeout: Some(Spanned::new(turborepo_unescape::UnescapedString::from("30s")).with_range(27..32)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            retries: 3,
+            timeout: Some(std::time::Duration::from_secs(30)),
+            ..TaskDefinition::default()
+        }
+    ; "retries with timeout"
+    )]
+    #[test_case(
+        r#"{ "command": "echo hi" }"#,
+        RawTaskDefinition {
+            command: Some(Spanned::new(RawTaskCommand::String("echo hi".into())).with_range(13..22)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            command: Some(TaskCommand::Shell("echo hi".to_string())),
+            ..TaskDefinition::default()
+        }
+    ; "shell command"
+    )]
+    #[test_case(
+        r#"{ "command": ["tsc", "-b"] }"#,
+        RawTaskDefinition {
+            command: Some(Spanned::new(RawTaskCommand::Argv(vec!["tsc".into(), "-b".into()])).with_range(13..26)),
+            ..RawTaskDefinition::default()
+        },
+        TaskDefinition {
+            command: Some(TaskCommand::Argv(vec!["tsc".to_string(), "-b".to_string()])),
+            ..TaskDefinition::default()
+        }
+    ; "argv command"
+    )]
     fn test_deserialize_task_definition(
         task_definition_content: &str,
         expected_raw_task_definition: RawTaskDefinition,
@@ -273,4 +356,29 @@ mod tests {
 
         Ok(())
     }
+
+    #[test_case("-1" ; "negative")]
+    #[test_case("1.5" ; "fraction")]
+    #[test_case(r#""2""# ; "string")]
+    fn test_invalid_retries_are_rejected(retries: &str) {
+        let turbo_json = format!(r#"{{ "tasks": {{ "test": {{ "retries": {retries} }} }} }}"#);
+        assert!(RawRootTurboJson::parse(&turbo_json, "turbo.json").is_err());
+    }
+
+    #[test_case(r#"{ "command": " " }"# ; "blank string")]
+    #[test_case(r#"{ "command": [] }"# ; "empty argv")]
+    fn test_empty_command_is_rejected(task_definition_content: &str) {
+        let raw_task_definition: RawTaskDefinition = deserialize_from_json_str(
+            task_definition_content,
+            JsonParserOptions::default(),
+            "turbo.json",
+        )
+        .into_deserialized()
+        .unwrap();
+
+        let err =
+            TaskDefinition::from_raw(raw_task_definition, RelativeUnixPath::new("../..").unwrap())
+                .unwrap_err();
+        assert_eq!(err.to_string(), "`command` cannot be empty.");
+    }
 }
@@ -74,6 +74,7 @@ impl GroupingLayer {
             layer: Arc::clone(self),
             buffer,
             accumulated_bytes: Vec::new(),
+            output_observer: None,
         }
     }
 
@@ -93,10 +94,14 @@ impl GroupingLayer {
             layer: Arc::clone(self),
             buffer,
             accumulated_bytes: Vec::new(),
+            output_observer: None,
         }
     }
 }
 
+/// Callback invoked with each chunk of output written for a task.
+pub type OutputObserver = Arc<dyn Fn(&[u8]) + Send + Sync>;
+
 /// A buffered event from a task, replayed on [`TaskHandle::finish`].
 enum TaskEvent {
     Log(LogEvent),
@@ -123,6 +128,7 @@ pub struct TaskHandle {
     /// `None` in passthrough mode; `Some` in grouped mode.
     buffer: Option<Vec<TaskEvent>>,
     accumulated_bytes: Vec<u8>,
+    output_observer: Option<OutputObserver>,
 }
 
 impl TaskHandle {
@@ -139,9 +145,19 @@ impl TaskHandle {
         }
     }
 
+    /// Invoke `observer` with output as soon as it's written, including in
+    /// grouped mode where output only reaches the logger once the task
+    /// finishes.
+    pub fn observe_output(&mut self, observer: OutputObserver) {
+        self.output_observer = Some(observer);
+    }
+
     /// Write raw child process output bytes for this task.
     pub fn task_output(&mut self, channel: OutputChannel, bytes: &[u8]) {
         self.accumulated_bytes.extend_from_slice(bytes);
+        if let Some(observer) = &self.output_observer {
+            observer(bytes);
+        }
         match &mut self.buffer {
             None => {
                 self.layer.logger.task_output(&self.task_id, channel, bytes);
@@ -344,6 +360,26 @@ mod tests {
         assert_eq!(bytes, b"building...\nwarning: unused var\n");
     }
 
+    #[test]
+    fn grouped_observer_sees_output_before_finish() {
+        let (sink, layer) = setup(GroupingMode::Grouped);
+        let mut handle = layer.task("web#build");
+        let observed = Arc::new(Mutex::new(Vec::new()));
+        let observer_output = observed.clone();
+        handle.observe_output(Arc::new(move |bytes: &[u8]| {
+            observer_output.lock().unwrap().extend_from_slice(bytes);
+        }));
+
+        handle.task_output(OutputChannel::Stdout, b"building...\n");
+        handle.task_output(OutputChannel::Stderr, b"warning\n");
+
+        assert_eq!(*observed.lock().unwrap(), b"building...\nwarning\n");
+        assert!(sink.output_chunks.lock().unwrap().is_empty());
+
+        handle.finish(false);
+        assert_eq!(sink.output_chunks.lock().unwrap().len(), 2);
+    }
+
     #[test]
     fn grouped_error_task_passes_is_error() {
         let (sink, layer) = setup(GroupingMode::Grouped);
@@ -0,0 +1,286 @@
+//! Works out what is being completed in a turbo.json.
+//!
+//! The file is usually invalid JSON while it is being edited, so rather than
+//! parsing it, we scan the text before the cursor and keep track of the
+//! objects and arrays that are still open.
+
+use schemars::{schema::Schema, schema_for};
+use turbopath::AbsoluteSystemPath;
+use turborepo_turbo_json::RawTaskDefinition;
+
+/// Directories that are never useful as task inputs or outputs
+const IGNORED_DIRECTORIES: &[&str] = &["node_modules", ".git", ".turbo"];
+
+#[derive(Debug, PartialEq)]
+pub(crate) struct CompletionContext {
+    pub position: CursorPosition,
+    /// The offset of the text that the completion replaces
+    pub start: usize,
+    /// Whether the cursor is inside a string, in which case completions don't
+    /// need to be quoted
+    pub in_string: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum CursorPosition {
+    /// A key in `tasks`
+    TaskName,
+    /// A key in a task definition, which already has the `existing` keys
+    TaskField { existing: Vec<String> },
+    /// An entry in an array field of a task definition, such as `dependsOn`
+    TaskArray { field: String },
+}
+
+/// An object or array that is open at the cursor
+#[derive(Default)]
+struct Container {
+    is_object: bool,
+    /// The key of this container in its parent object
+    key: Option<String>,
+    /// The keys seen so far, if this is an object
+    keys: Vec<String>,
+    expecting_key: bool,
+}
+
+/// Finds what is being completed at `offset` in the text of a turbo.json
+pub(crate) fn completion_context(text: &str, offset: usize) -> Option<CompletionContext> {
+    let text = text.get(..offset)?;
+
+    let mut stack: Vec<Container> = vec![];
+    let mut string_start = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => {
+                let mut value = String::new();
+                let mut closed = false;
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => value.extend(chars.next().map(|(_, c)| c)),
+                        '"' => {
+                            closed = true;
+                            break;
+                        }
+                        c => value.push(c),
+                    }
+                }
+
+                if !closed {
+                    // the cursor is in this string
+                    string_start = Some(i + 1);
+                    break;
+                }
+
+                if let Some(container) = stack.last_mut()
+                    && container.is_object
+                    && container.expecting_key
+                {
+                    container.keys.push(value);
+                    container.expecting_key = false;
+                }
+            }
+            '/' if chars.peek().is_some_and(|(_, c)| *c == '/') => {
+                if !chars.any(|(_, c)| c == '\n') {
+                    // the cursor is in a comment
+                    return None;
+                }
+            }
+            '/' if chars.peek().is_some_and(|(_, c)| *c == '*') => {
+                chars.next();
+                let mut previous = None;
+                let closed = chars.any(|(_, c)| {
+                    let end = previous == Some('*') && c == '/';
+                    previous = Some(c);
+                    end
+                });
+                if !closed {
+                    return None;
+                }
+            }
+            '{' | '[' => {
+                let key = stack
+                    .last()
+                    .filter(|parent| parent.is_object)
+                    .and_then(|parent| parent.keys.last().cloned());
+                stack.push(Container {
+                    is_object: c == '{',
+                    key,
+                    expecting_key: c == '{',
+                    ..Default::default()
+                });
+            }
+            '}' | ']' => {
+                stack.pop();
+            }
+            ',' => {
+                if let Some(container) = stack.last_mut() {
+                    container.expecting_key = container.is_object;
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let [root, tasks, rest @ ..] = stack.as_slice() else {
+        return None;
+    };
+    if root.key.is_some() || tasks.key.as_deref() != Some("tasks") || !tasks.is_object {
+        return None;
+    }
+
+    let position = match rest {
+        [] if tasks.expecting_key => CursorPosition::TaskName,
+        [task] if task.is_object && task.expecting_key => CursorPosition::TaskField {
+            existing: task.keys.clone(),
+        },
+        [task, array] if task.is_object && !array.is_object => CursorPosition::TaskArray {
+            field: array.key.clone()?,
+        },
+        _ => return None,
+    };
+
+    // outside of a string, replace the word that is being typed
+    let start = string_start.unwrap_or_else(|| {
+        text.trim_end_matches(|c: char| !c.is_whitespace() && !"{[,:".contains(c))
+            .len()
+    });
+
+    Some(CompletionContext {
+        position,
+        start,
+        in_string: string_start.is_some(),
+    })
+}
+
+/// The fields of a task definition, along with their documentation from the
+/// JSON schema
+pub(crate) fn task_fields() -> Vec<(String, Option<String>)> {
+    let schema = schema_for!(RawTaskDefinition);
+
+    schema
+        .schema
+        .object
+        .map(|object| object.properties)
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(name, schema)| {
+            let description = match schema {
+                Schema::Object(object) => object.metadata.and_then(|metadata| metadata.description),
+                Schema::Bool(_) => None,
+            };
+            (name, description)
+        })
+        .collect()
+}
+
+/// The names of the directories in `dir` that could hold task inputs or
+/// outputs
+pub(crate) fn subdirectories(dir: &AbsoluteSystemPath) -> Vec<String> {
+    std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|file_type| file_type.is_dir()))
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|name| !IGNORED_DIRECTORIES.contains(&name.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use super::*;
+
+    /// Finds the completion context at the `|` in `text`
+    fn context(text: &str) -> Option<CompletionContext> {
+        let offset = text.find('|').expect("cursor");
+        completion_context(&text.replace('|', ""), offset)
+    }
+
+    #[test]
+    fn completes_task_names() {
+        assert_eq!(
+            context(r#"{ "tasks": { "build": {}, "|" } }"#),
+            Some(CompletionContext {
+                position: CursorPosition::TaskName,
+                start: 27,
+                in_string: true,
+            })
+        );
+    }
+
+    #[test]
+    fn completes_task_fields() {
+        assert_eq!(
+            context(
+                r#"{
+                  "tasks": {
+                    "build": {
+                      // "cache": false,
+                      "outputs": ["dist/**"],
+                      inp|
+                    }
+                  }
+                }"#
+            )
+            .map(|context| context.position),
+            Some(CursorPosition::TaskField {
+                existing: vec!["outputs".to_string()]
+            })
+        );
+    }
+
+    #[test]
+    fn completes_array_entries() {
+        let text =
+            r#"{ "tasks": { "build": { "cache": false, "dependsOn": ["^build", "^bu|"] } } }"#;
+        let context = context(text).expect("context");
+
+        assert_eq!(
+            context.position,
+            CursorPosition::TaskArray {
+                field: "dependsOn".to_string()
+            }
+        );
+        assert_eq!(&text[context.start..text.find('|').expect("cursor")], "^bu");
+        assert!(context.in_string);
+    }
+
+    #[test]
+    fn replaces_unquoted_word() {
+        let text = r#"{ "tasks": { "build": { "env": [NEXT|] } } }"#;
+        let context = context(text).expect("context");
+
+        assert_eq!(
+            &text[context.start..text.find('|').expect("cursor")],
+            "NEXT"
+        );
+        assert!(!context.in_string);
+    }
+
+    #[test]
+    fn ignores_values_and_other_keys() {
+        assert_eq!(
+            context(r#"{ "tasks": { "build": { "cache": "|" } } }"#),
+            None
+        );
+        assert_eq!(context(r#"{ "globalEnv": ["|"] }"#), None);
+        assert_eq!(context(r#"{ "tasks": { "build": {} } } // |"#), None);
+    }
+
+    #[test]
+    fn documents_task_fields() {
+        let fields = task_fields();
+        let (_, description) = fields
+            .iter()
+            .find(|(name, _)| name == "dependsOn")
+            .expect("dependsOn field");
+
+        assert!(
+            description
+                .as_deref()
+                .is_some_and(|description| description.contains("depends on"))
+        );
+        assert!(!fields.iter().any(|(name, _)| name == "extends"));
+    }
+}
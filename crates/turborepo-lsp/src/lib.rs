@@ -12,13 +12,17 @@
 #![deny(clippy::all)]
 #![warn(clippy::unwrap_used)]
 
+mod completion;
+mod turbo_json;
+
 use std::{
     borrow::Cow,
-    collections::{HashMap, HashSet},
+    collections::{BTreeSet, HashMap, HashSet},
     iter,
     sync::{Arc, Mutex, MutexGuard},
 };
 
+use completion::{CompletionContext, CursorPosition};
 use itertools::{Itertools, chain};
 use jsonc_parser::{
     CollectOptions,
@@ -31,7 +35,9 @@ use tower_lsp::{
     jsonrpc::{Error, Result as LspResult},
     lsp_types::*,
 };
-use turbopath::AbsoluteSystemPathBuf;
+use turbo_json::{ROOT_PACKAGE, TaskReference};
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+use turborepo_frameworks::infer_framework;
 use turborepo_lib::{
     DaemonClient, DaemonConnector, DaemonConnectorError, DaemonError, DaemonPackageDiscovery,
     DaemonPaths,
@@ -39,10 +45,14 @@ use turborepo_lib::{
 use turborepo_repository::{
     discovery::{self, DiscoveryResponse, PackageDiscovery, WorkspaceData},
     inference::RepoState,
+    package_graph::PackageInfo,
     package_json::PackageJson,
 };
+use turborepo_turbo_json::{CONFIG_FILE, CONFIG_FILE_JSONC};
 
 const TURBO_EXTENDS: &str = "$TURBO_EXTENDS$";
+const TURBO_DEFAULT: &str = "$TURBO_DEFAULT$";
+const TURBO_ROOT: &str = "$TURBO_ROOT$";
 
 pub struct Backend {
     client: Client,
@@ -229,7 +239,7 @@ impl LanguageServer for Backend {
                 )),
                 completion_provider: Some(CompletionOptions {
                     resolve_provider: Some(false),
-                    trigger_characters: Some(vec![".".to_string()]),
+                    trigger_characters: Some(vec![".".to_string(), "\"".to_string()]),
                     work_done_progress_options: Default::default(),
                     all_commit_characters: None,
                     ..Default::default()
@@ -268,6 +278,14 @@ impl LanguageServer for Backend {
                         work_done_progress: None,
                     },
                 })),
+                hover_provider: Some(HoverProviderCapability::Simple(true)),
+                definition_provider: Some(OneOf::Left(true)),
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: WorkDoneProgressOptions {
+                        work_done_progress: None,
+                    },
+                })),
                 ..ServerCapabilities::default()
             },
         })
@@ -541,6 +559,181 @@ impl LanguageServer for Backend {
         Ok(Some(code_actions))
     }
 
+    /// Show the resolved definition of a task, and which packages have a
+    /// script for it
+    async fn hover(&self, params: HoverParams) -> LspResult<Option<Hover>> {
+        let position = params.text_document_position_params;
+        let Some((rope, reference)) =
+            self.task_reference_at(&position.text_document.uri, position.position)
+        else {
+            return Ok(None);
+        };
+
+        let packages = self.packages().await?;
+        let current_package = current_package(&packages, &position.text_document.uri);
+
+        // `^task` runs in the dependencies, which we can't resolve from here, so
+        // we show the root definition instead
+        let package = match &reference.package {
+            Some(package) => package.as_str(),
+            None if reference.topological => ROOT_PACKAGE,
+            None => current_package,
+        };
+
+        let definition =
+            turbo_json::resolve_task_definition(package, &reference.task, &|name: &str| {
+                let turbo_json = packages
+                    .iter()
+                    .find(|p| p.name == name)?
+                    .turbo_json
+                    .as_ref()?;
+                self.read_file(turbo_json)
+            });
+
+        let task_id = if package == ROOT_PACKAGE && reference.package.is_none() {
+            reference.task.clone()
+        } else {
+            format!("{package}#{}", reference.task)
+        };
+        let mut contents = match definition {
+            Some(definition) => format!(
+                "`{task_id}`\n```json\n{}\n```",
+                serde_json::to_string_pretty(&definition).unwrap_or_default()
+            ),
+            None => format!("`{task_id}` is not configured in turbo.json"),
+        };
+
+        let defined_by = packages
+            .iter()
+            .filter(|p| p.scripts.contains(&reference.task))
+            .map(|p| format!("`{}`", p.name))
+            .join(", ");
+        if defined_by.is_empty() {
+            contents.push_str(&format!(
+                "\n\nNo package has a `{}` script.",
+                reference.task
+            ));
+        } else {
+            contents.push_str(&format!(
+                "\n\nPackages with a `{}` script: {defined_by}",
+                reference.task
+            ));
+        }
+
+        Ok(Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: contents,
+            }),
+            range: Some(convert_ranges(
+                &rope,
+                collapse_string_range(reference.range),
+            )),
+        }))
+    }
+
+    /// Jump from a task to the package.json scripts that it runs
+    async fn goto_definition(
+        &self,
+        params: GotoDefinitionParams,
+    ) -> LspResult<Option<GotoDefinitionResponse>> {
+        let position = params.text_document_position_params;
+        let Some((_, reference)) =
+            self.task_reference_at(&position.text_document.uri, position.position)
+        else {
+            return Ok(None);
+        };
+
+        let packages = self.packages().await?;
+        let current_package = current_package(&packages, &position.text_document.uri);
+
+        let locations = packages
+            .iter()
+            .filter(|p| p.scripts.contains(&reference.task))
+            .filter(|p| match &reference.package {
+                Some(package) => &p.name == package,
+                // a task in a package's turbo.json runs that package's script
+                None if !reference.topological && current_package != ROOT_PACKAGE => {
+                    p.name == current_package
+                }
+                None => true,
+            })
+            .filter_map(|p| {
+                let text = self.read_file(&p.package_json)?;
+                let range = turbo_json::script_range(&text, &reference.task)?;
+                let uri = Url::from_file_path(&p.package_json).ok()?;
+                Some(Location::new(
+                    uri,
+                    convert_ranges(&crop::Rope::from(text), range),
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        if locations.is_empty() {
+            return Ok(None);
+        }
+
+        Ok(Some(GotoDefinitionResponse::Array(locations)))
+    }
+
+    async fn prepare_rename(
+        &self,
+        params: TextDocumentPositionParams,
+    ) -> LspResult<Option<PrepareRenameResponse>> {
+        Ok(self
+            .task_reference_at(&params.text_document.uri, params.position)
+            .map(|(rope, reference)| {
+                PrepareRenameResponse::Range(convert_ranges(&rope, reference.task_range))
+            }))
+    }
+
+    /// Rename a task everywhere it is referenced in the root and package
+    /// turbo.json files
+    async fn rename(&self, params: RenameParams) -> LspResult<Option<WorkspaceEdit>> {
+        let position = params.text_document_position;
+        let Some((_, reference)) =
+            self.task_reference_at(&position.text_document.uri, position.position)
+        else {
+            return Ok(None);
+        };
+
+        if !turbo_json::is_valid_task_name(&params.new_name) {
+            return Err(Error::invalid_params(format!(
+                "`{}` is not a valid task name",
+                params.new_name
+            )));
+        }
+
+        let packages = self.packages().await?;
+
+        let mut changes = HashMap::new();
+        for turbo_json in packages.iter().filter_map(|p| p.turbo_json.as_ref()) {
+            let (Some(text), Ok(uri)) =
+                (self.read_file(turbo_json), Url::from_file_path(turbo_json))
+            else {
+                continue;
+            };
+            let rope = crop::Rope::from(text.as_str());
+
+            let edits = turbo_json::task_references(&text)
+                .into_iter()
+                .filter(|r| r.task == reference.task)
+                .map(|r| {
+                    TextEdit::new(convert_ranges(&rope, r.task_range), params.new_name.clone())
+                })
+                .collect::<Vec<_>>();
+
+            if !edits.is_empty() {
+                changes.insert(uri, edits);
+            }
+        }
+
+        Ok(Some(WorkspaceEdit {
+            changes: Some(changes),
+            ..Default::default()
+        }))
+    }
+
     async fn initialized(&self, _: InitializedParams) {
         self.client
             .log_message(MessageType::INFO, "initialized!")
@@ -620,10 +813,11 @@ impl LanguageServer for Backend {
             for change in document.content_changes {
                 match change.range {
                     Some(range) => {
-                        let start_offset = rope.byte_of_line(range.start.line as usize)
-                            + range.start.character as usize;
-                        let end_offset = rope.byte_of_line(range.end.line as usize)
-                            + range.end.character as usize;
+                        let (Some(start_offset), Some(end_offset)) =
+                            (byte_offset(rope, range.start), byte_offset(rope, range.end))
+                        else {
+                            continue;
+                        };
 
                         rope.replace(start_offset..end_offset, change.text);
                     }
@@ -660,45 +854,150 @@ impl LanguageServer for Backend {
             .await;
     }
 
-    /// Provide intellisense completions for package / task names
+    /// Provide intellisense completions based on where the cursor is in the
+    /// turbo.json
     ///
-    /// - get all packages
-    /// - get all package jsons
-    /// - produce all unique script names
-    /// - flatmap producing all package#script combos
-    /// - chain them
-    async fn completion(&self, _: CompletionParams) -> LspResult<Option<CompletionResponse>> {
-        let packages = self
-            .package_discovery()
-            .await
-            .map_err(|_e| Error::internal_error())?;
+    /// - task names: all scripts and package#script combos
+    /// - task definition keys: the fields of a task, from the JSON schema
+    /// - `dependsOn`: ^script, script, package#script and $TURBO_EXTENDS$
+    /// - `env`: the env vars of the frameworks that the packages use
+    /// - `inputs` / `outputs`: the special values and existing directories
+    async fn completion(&self, params: CompletionParams) -> LspResult<Option<CompletionResponse>> {
+        let position = params.text_document_position;
+        let uri = &position.text_document.uri;
+
+        let Some(rope) = lock_or_recover(&self.files).get(uri).cloned() else {
+            return Ok(None);
+        };
+        let Some((context, range)) = completion_at(&rope, position.position) else {
+            return Ok(None);
+        };
+        let item = |label: String, kind: CompletionItemKind| {
+            let new_text = if context.in_string {
+                label.clone()
+            } else {
+                format!("\"{label}\"")
+            };
+            CompletionItem {
+                label,
+                kind: Some(kind),
+                text_edit: Some(CompletionTextEdit::Edit(TextEdit::new(range, new_text))),
+                ..Default::default()
+            }
+        };
 
-        let package_jsons = packages
-            .workspaces
-            .into_iter()
-            .flat_map(|wd| PackageJson::load(&wd.package_json).ok()) // if we can't parse a package.json, then we can't infer its tasks
-            .collect::<Vec<_>>();
+        let items = match context.position {
+            CursorPosition::TaskName => {
+                let packages = self.packages().await?;
 
-        let tasks = package_jsons
-            .iter()
-            .flat_map(|p| p.scripts.keys())
-            .unique()
-            .map(|s| CompletionItem {
-                label: s.to_owned(),
-                kind: Some(CompletionItemKind::FIELD),
-                ..Default::default()
-            });
+                let tasks = packages
+                    .iter()
+                    .flat_map(|p| &p.scripts)
+                    .unique()
+                    .map(|s| item(s.to_owned(), CompletionItemKind::FIELD));
+                let keys = packages
+                    .iter()
+                    .flat_map(|p| p.scripts.iter().map(move |s| format!("{}#{s}", p.name)))
+                    .map(|key| item(key, CompletionItemKind::FIELD));
 
-        let keys = package_jsons
-            .iter()
-            .flat_map(|p| p.scripts.keys().map(move |k| (p.name.clone(), k)))
-            .map(|(package, s)| CompletionItem {
-                label: format!("{}#{}", package.unwrap_or_default().into_inner(), s),
-                kind: Some(CompletionItemKind::FIELD),
-                ..Default::default()
-            });
+                keys.chain(tasks).collect()
+            }
+            CursorPosition::TaskField { existing } => completion::task_fields()
+                .into_iter()
+                .filter(|(name, _)| !existing.contains(name))
+                .map(|(name, description)| CompletionItem {
+                    documentation: description.map(|description| {
+                        Documentation::MarkupContent(MarkupContent {
+                            kind: MarkupKind::Markdown,
+                            value: description,
+                        })
+                    }),
+                    ..item(name, CompletionItemKind::PROPERTY)
+                })
+                .collect(),
+            CursorPosition::TaskArray { field } => {
+                let packages = self.packages().await?;
+                let current_package = current_package(&packages, uri);
+
+                // in a package's turbo.json, only that package's frameworks and directories
+                // apply
+                let applicable = packages
+                    .iter()
+                    .filter(|p| current_package == ROOT_PACKAGE || p.name == current_package)
+                    .collect::<Vec<_>>();
+
+                match field.as_str() {
+                    "dependsOn" => {
+                        let tasks =
+                            packages
+                                .iter()
+                                .flat_map(|p| &p.scripts)
+                                .unique()
+                                .flat_map(|s| {
+                                    [
+                                        item(format!("^{s}"), CompletionItemKind::FIELD),
+                                        item(s.to_owned(), CompletionItemKind::FIELD),
+                                    ]
+                                });
+                        let keys = packages
+                            .iter()
+                            .flat_map(|p| p.scripts.iter().map(move |s| format!("{}#{s}", p.name)))
+                            .map(|key| item(key, CompletionItemKind::FIELD));
+                        let extends = (current_package != ROOT_PACKAGE).then(|| CompletionItem {
+                            detail: Some(
+                                "Include the dependencies from the turbo.json this extends"
+                                    .to_string(),
+                            ),
+                            ..item(TURBO_EXTENDS.to_string(), CompletionItemKind::CONSTANT)
+                        });
 
-        Ok(Some(CompletionResponse::Array(keys.chain(tasks).collect())))
+                        tasks.chain(keys).chain(extends).collect()
+                    }
+                    "env" => applicable
+                        .iter()
+                        .filter_map(|p| {
+                            let package_json = PackageJson::load(&p.package_json).ok()?;
+                            let info = PackageInfo {
+                                package_json,
+                                ..Default::default()
+                            };
+                            let framework = infer_framework(&info, false)?;
+                            Some((framework.slug(), framework.env(&HashMap::new())))
+                        })
+                        .flat_map(|(slug, env)| env.into_iter().map(move |var| (slug.clone(), var)))
+                        .unique_by(|(_, var)| var.clone())
+                        .map(|(slug, var)| CompletionItem {
+                            detail: Some(format!("Used by {slug}")),
+                            ..item(var, CompletionItemKind::VARIABLE)
+                        })
+                        .collect(),
+                    "inputs" | "outputs" => {
+                        let special = [
+                            (field == "inputs").then(|| CompletionItem {
+                                detail: Some("The files that turbo uses by default".to_string()),
+                                ..item(TURBO_DEFAULT.to_string(), CompletionItemKind::CONSTANT)
+                            }),
+                            Some(CompletionItem {
+                                detail: Some("The root of the repository".to_string()),
+                                ..item(format!("{TURBO_ROOT}/"), CompletionItemKind::CONSTANT)
+                            }),
+                        ];
+                        let directories = applicable
+                            .iter()
+                            .filter_map(|p| p.package_json.parent())
+                            .flat_map(completion::subdirectories)
+                            .collect::<BTreeSet<_>>()
+                            .into_iter()
+                            .map(|dir| item(format!("{dir}/**"), CompletionItemKind::FOLDER));
+
+                        special.into_iter().flatten().chain(directories).collect()
+                    }
+                    _ => return Ok(None),
+                }
+            }
+        };
+
+        Ok(Some(CompletionResponse::Array(items)))
     }
 }
 
@@ -738,6 +1037,90 @@ impl Backend {
             .await
     }
 
+    /// Lists every package in the repository, including the root, along with
+    /// its scripts
+    async fn packages(&self) -> LspResult<Vec<Package>> {
+        let repo_root = lock_or_recover(&self.repo_root).clone();
+
+        let Some(repo_root) = repo_root else {
+            self.client
+                .log_message(MessageType::INFO, "received request before initialization")
+                .await;
+            return Err(Error::internal_error());
+        };
+
+        let packages = match self.package_discovery().await {
+            Ok(packages) => packages,
+            Err(e) => {
+                self.client
+                    .log_message(MessageType::WARNING, e.to_string())
+                    .await;
+
+                let mut error = Error::internal_error();
+                error.message = "failed to get package list from the daemon".into();
+                return Err(error);
+            }
+        };
+
+        // package discovery does not yield the root, so we must add it
+        let root = WorkspaceData {
+            package_json: repo_root.join_component("package.json"),
+            turbo_json: None,
+        };
+
+        Ok(chain(packages.workspaces, iter::once(root))
+            .filter_map(|wd| {
+                let package_json = PackageJson::load(&wd.package_json).ok()?; // if we can't load a package.json, then we can't infer its tasks
+                let name = if wd.package_json.parent() == Some(&repo_root) {
+                    ROOT_PACKAGE.to_string()
+                } else {
+                    package_json.name?.into_inner()
+                };
+                // discovery only looks for turbo.json, but the config can also be
+                // in a turbo.jsonc
+                let turbo_json = wd.package_json.parent().and_then(|dir| {
+                    [CONFIG_FILE, CONFIG_FILE_JSONC]
+                        .into_iter()
+                        .map(|file| dir.join_component(file))
+                        .find(|path| path.exists())
+                });
+                Some(Package {
+                    name,
+                    scripts: package_json.scripts.into_keys().collect(),
+                    package_json: wd.package_json,
+                    turbo_json,
+                })
+            })
+            .collect())
+    }
+
+    /// Reads a file, preferring the editor's contents if it is open
+    fn read_file(&self, path: &AbsoluteSystemPath) -> Option<String> {
+        let open = Url::from_file_path(path).ok().and_then(|uri| {
+            lock_or_recover(&self.files)
+                .get(&uri)
+                .map(|rope| rope.chunks().join(""))
+        });
+
+        open.or_else(|| std::fs::read_to_string(path).ok())
+    }
+
+    /// Finds the task named at `position` in an open turbo.json
+    fn task_reference_at(
+        &self,
+        uri: &Url,
+        position: Position,
+    ) -> Option<(crop::Rope, TaskReference)> {
+        let rope = lock_or_recover(&self.files).get(uri)?.clone(); // cloning is cheap
+        let offset = byte_offset(&rope, position)?;
+
+        let reference = turbo_json::task_references(&rope.chunks().join(""))
+            .into_iter()
+            .find(|reference| reference.contains(offset))?;
+
+        Some((rope, reference))
+    }
+
     /// Handle a file update to a rope, emitting diagnostics if necessary.
     async fn handle_file_update(&self, uri: Url, rope: Option<crop::Rope>, version: Option<i32>) {
         let rope = match rope {
@@ -979,25 +1362,97 @@ impl Backend {
     }
 }
 
+/// A package in the repository, named `//` if it is the root
+struct Package {
+    name: String,
+    package_json: AbsoluteSystemPathBuf,
+    turbo_json: Option<AbsoluteSystemPathBuf>,
+    scripts: HashSet<String>,
+}
+
+/// The name of the package whose turbo.json is at `uri`, defaulting to the
+/// root
+fn current_package<'a>(packages: &'a [Package], uri: &Url) -> &'a str {
+    packages
+        .iter()
+        .find(|p| {
+            p.turbo_json
+                .as_ref()
+                .and_then(|turbo_json| Url::from_file_path(turbo_json).ok())
+                .as_ref()
+                == Some(uri)
+        })
+        .map_or(ROOT_PACKAGE, |p| p.name.as_str())
+}
+
 fn lock_or_recover<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
     mutex
         .lock()
         .unwrap_or_else(|poisoned| poisoned.into_inner())
 }
 
-fn convert_ranges(rope: &crop::Rope, range: jsonc_parser::common::Range) -> Range {
-    let start_line = rope.line_of_byte(range.start);
-    let end_line = rope.line_of_byte(range.end);
+/// Converts an LSP position, whose `character` counts UTF-16 code units, into
+/// a byte offset into the rope. Positions past the end of a line are clamped
+/// to the end of that line.
+fn byte_offset(rope: &crop::Rope, position: Position) -> Option<usize> {
+    let line = position.line as usize;
+    if line > rope.line_len() {
+        return None;
+    }
 
-    Range {
-        start: Position {
-            line: start_line as u32,
-            character: (range.start - rope.byte_of_line(start_line)) as u32,
-        },
-        end: Position {
-            line: end_line as u32,
-            character: (range.end - rope.byte_of_line(end_line)) as u32,
+    let line_start = rope.byte_of_line(line);
+    if line == rope.line_len() {
+        return Some(line_start);
+    }
+
+    let mut units = 0;
+    let mut bytes = 0;
+    for c in rope.line(line).chars() {
+        if units >= position.character as usize {
+            break;
+        }
+        units += c.len_utf16();
+        bytes += c.len_utf8();
+    }
+
+    Some(line_start + bytes)
+}
+
+/// Finds what is being completed at `position`, along with the range of the
+/// text that the completion replaces
+fn completion_at(rope: &crop::Rope, position: Position) -> Option<(CompletionContext, Range)> {
+    let offset = byte_offset(rope, position)?;
+    let context = completion::completion_context(&rope.chunks().join(""), offset)?;
+    let range = convert_ranges(
+        rope,
+        jsonc_parser::common::Range {
+            start: context.start,
+            end: offset,
         },
+    );
+
+    Some((context, range))
+}
+
+/// Converts a byte offset into the rope into an LSP position
+fn position_of_byte(rope: &crop::Rope, offset: usize) -> Position {
+    let line = rope.line_of_byte(offset);
+    let character: usize = rope
+        .byte_slice(rope.byte_of_line(line)..offset)
+        .chars()
+        .map(char::len_utf16)
+        .sum();
+
+    Position {
+        line: line as u32,
+        character: character as u32,
+    }
+}
+
+fn convert_ranges(rope: &crop::Rope, range: jsonc_parser::common::Range) -> Range {
+    Range {
+        start: position_of_byte(rope, range.start),
+        end: position_of_byte(rope, range.end),
     }
 }
 
@@ -1132,10 +1587,11 @@ mod tests {
     };
 
     use jsonc_parser::{ast::StringLit, common::Range};
-    use tower_lsp::lsp_types::{Diagnostic, NumberOrString};
+    use tower_lsp::lsp_types::{Diagnostic, NumberOrString, Position};
 
     use super::{
-        collect_transit_node_tasks, is_turbo_extends_sentinel, report_invalid_packages_and_tasks,
+        CursorPosition, byte_offset, collect_transit_node_tasks, completion_at, convert_ranges,
+        is_turbo_extends_sentinel, report_invalid_packages_and_tasks,
     };
 
     fn string_lit(value: &'static str) -> StringLit<'static> {
@@ -1224,6 +1680,76 @@ mod tests {
         assert_eq!(diagnostic_code(&diagnostics[0]), Some("turbo:no-such-task"));
     }
 
+    #[test]
+    fn converts_utf16_positions_on_non_ascii_lines() {
+        let text = "{\n  \"tasks\": { \"é🎉#build\": {} }\n}";
+        let rope = crop::Rope::from(text);
+        let start = text.find("build").expect("task name");
+
+        // `é` is one UTF-16 code unit and two bytes, `🎉` is two code units and
+        // four bytes
+        let position = Position {
+            line: 1,
+            character: 18,
+        };
+        assert_eq!(byte_offset(&rope, position), Some(start));
+
+        let range = convert_ranges(
+            &rope,
+            Range {
+                start,
+                end: start + "build".len(),
+            },
+        );
+        assert_eq!(range.start, position);
+        assert_eq!(range.end.character, 23);
+    }
+
+    #[test]
+    fn completes_after_multi_byte_characters() {
+        let rope = crop::Rope::from(
+            "{\n  \"tasks\": { \"héllo\": { \"dependsOn\": [\"🎉\", \"^bu\"] } }\n}",
+        );
+
+        // the cursor is after `^bu`, which is 48 UTF-16 code units but 51 bytes
+        // into the line
+        let cursor = Position {
+            line: 1,
+            character: 48,
+        };
+        let (context, range) = completion_at(&rope, cursor).expect("completion context");
+
+        assert_eq!(
+            context.position,
+            CursorPosition::TaskArray {
+                field: "dependsOn".to_string()
+            }
+        );
+        assert!(context.in_string);
+        assert_eq!(
+            range.start,
+            Position {
+                line: 1,
+                character: 45,
+            }
+        );
+        assert_eq!(range.end, cursor);
+    }
+
+    #[test]
+    fn clamps_positions_past_the_end_of_a_line() {
+        let rope = crop::Rope::from("{\n  \"é\": 1\n}");
+
+        let offset = byte_offset(
+            &rope,
+            Position {
+                line: 1,
+                character: 100,
+            },
+        );
+        assert_eq!(offset, Some("{\n  \"é\": 1".len()));
+    }
+
     fn diagnostic_code(diagnostic: &Diagnostic) -> Option<&str> {
         match diagnostic.code.as_ref()? {
             NumberOrString::String(code) => Some(code.as_str()),
@@ -0,0 +1,482 @@
+//! Helpers for finding and resolving the tasks named in turbo.json files.
+//!
+//! These work on the raw text of a file, so that they can be used for files
+//! that are open in the editor as well as ones read from disk.
+
+use std::collections::HashSet;
+
+use jsonc_parser::{
+    ast::{self, ObjectPropName, StringLit},
+    common::Range,
+};
+use serde_json::{Map, Value, json};
+use turbopath::RelativeUnixPath;
+use turborepo_errors::Spanned;
+use turborepo_task_id::{TaskId, TaskName};
+use turborepo_turbo_json::{
+    ProcessedGlob, ProcessedTaskDefinition, RawPackageTurboJson, RawRootTurboJson, RawTurboJson,
+    TurboJson,
+};
+use turborepo_unescape::UnescapedString;
+
+use crate::{TURBO_DEFAULT, TURBO_ROOT};
+
+/// The name used to refer to the root package in a task id
+pub(crate) const ROOT_PACKAGE: &str = "//";
+
+/// A task named in a turbo.json, either as a key in `tasks` or as an entry in
+/// `dependsOn` or `with`
+#[derive(Debug, Clone)]
+pub(crate) struct TaskReference {
+    pub package: Option<String>,
+    pub task: String,
+    pub topological: bool,
+    /// The range of the whole string, including quotes
+    pub range: Range,
+    /// The range of the task name, without any `^` or `package#` prefix
+    pub task_range: Range,
+}
+
+impl TaskReference {
+    fn new(string: &StringLit) -> Option<Self> {
+        let value = string.value.as_ref();
+        let (topological, package_task) = match value.strip_prefix('^') {
+            Some(package_task) => (true, package_task),
+            None => (false, value),
+        };
+
+        // `$TURBO_EXTENDS$` and the deprecated env var dependencies don't name
+        // a task
+        if package_task.starts_with('$') {
+            return None;
+        }
+
+        let (package, task) = package_task
+            .split_once('#') // turbo packages may not have # in them
+            .map(|(p, t)| (Some(p), t))
+            .unwrap_or((None, package_task));
+        if task.is_empty() {
+            return None;
+        }
+
+        // the task is always at the end of the string, just before the quote
+        let task_end = string.range.end - 1;
+        Some(Self {
+            package: package.map(str::to_owned),
+            task: task.to_owned(),
+            topological,
+            range: string.range,
+            task_range: Range {
+                start: task_end - task.len(),
+                end: task_end,
+            },
+        })
+    }
+
+    /// Whether `offset` is within the quotes of this reference
+    pub fn contains(&self, offset: usize) -> bool {
+        self.range.start < offset && offset < self.range.end
+    }
+}
+
+/// Finds every task named in the `tasks` of a turbo.json
+pub(crate) fn task_references(text: &str) -> Vec<TaskReference> {
+    with_object(text, |object| {
+        let tasks = object.get_object("tasks")?;
+
+        let mut references = vec![];
+        for task in &tasks.properties {
+            if let ObjectPropName::String(name) = &task.name {
+                references.extend(TaskReference::new(name));
+            }
+
+            let Some(definition) = task.value.as_object() else {
+                continue;
+            };
+            for key in ["dependsOn", "with"] {
+                references.extend(
+                    definition
+                        .get_array(key)
+                        .into_iter()
+                        .flat_map(|array| &array.elements)
+                        .filter_map(|element| element.as_string_lit())
+                        .filter_map(TaskReference::new),
+                );
+            }
+        }
+
+        Some(references)
+    })
+    .unwrap_or_default()
+}
+
+/// Finds the range of the key of `script` in a package.json
+pub(crate) fn script_range(text: &str, script: &str) -> Option<Range> {
+    with_object(text, |object| {
+        let property = object
+            .get_object("scripts")?
+            .properties
+            .iter()
+            .find(|property| property.name.as_str() == script)?;
+
+        Some(match &property.name {
+            ObjectPropName::String(string) => string.range,
+            ObjectPropName::Word(word) => word.range,
+        })
+    })
+}
+
+/// Whether a task can be renamed to `name` without changing the meaning of
+/// the strings that reference it
+pub(crate) fn is_valid_task_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with(['^', '$'])
+        && !name.contains(['#', '"', '\\'])
+        && !name.chars().any(char::is_control)
+}
+
+/// Resolves the definition of `task` for `package`, merging the definitions
+/// from the turbo.json files that it extends the same way that `turbo run`
+/// does.
+///
+/// `read_turbo_json` returns the contents of the turbo.json for a package,
+/// using [`ROOT_PACKAGE`] for the root.
+pub(crate) fn resolve_task_definition(
+    package: &str,
+    task: &str,
+    read_turbo_json: &dyn Fn(&str) -> Option<String>,
+) -> Option<Value> {
+    let root = parse_turbo_json(&read_turbo_json(ROOT_PACKAGE)?, true)?;
+
+    let mut chain = vec![];
+    if package != ROOT_PACKAGE {
+        let mut visited = HashSet::from([ROOT_PACKAGE.to_owned()]);
+        turbo_json_chain(package, read_turbo_json, &mut visited, &mut chain);
+    }
+    // only the root turbo.json can configure future flags
+    for turbo_json in &mut chain {
+        turbo_json.future_flags = root.future_flags;
+    }
+    chain.insert(0, root);
+
+    let task_id = TaskId::new(package, task);
+    let task_name = TaskName::from(task);
+    let definitions = task_definitions(&chain, &task_id, &task_name);
+    if definitions.is_empty() {
+        return None;
+    }
+
+    definition_to_json(ProcessedTaskDefinition::from_iter(definitions))
+}
+
+/// Collects the turbo.json files that `package` extends, followed by its own,
+/// in the order that their task definitions are merged
+fn turbo_json_chain(
+    package: &str,
+    read_turbo_json: &dyn Fn(&str) -> Option<String>,
+    visited: &mut HashSet<String>,
+    chain: &mut Vec<TurboJson>,
+) {
+    if !visited.insert(package.to_owned()) {
+        return;
+    }
+    // packages without a turbo.json only use the root configuration
+    let Some(turbo_json) = read_turbo_json(package).and_then(|text| parse_turbo_json(&text, false))
+    else {
+        return;
+    };
+
+    for extended in turbo_json.extends.as_inner() {
+        turbo_json_chain(extended, read_turbo_json, visited, chain);
+    }
+    chain.push(turbo_json);
+}
+
+/// The definitions of a task along the chain of turbo.json files, stopping
+/// inheritance at the last definition with `"extends": false`
+fn task_definitions(
+    chain: &[TurboJson],
+    task_id: &TaskId,
+    task_name: &TaskName,
+) -> Vec<ProcessedTaskDefinition> {
+    let opted_out = chain.iter().rposition(|turbo_json| {
+        turbo_json
+            .tasks
+            .get(task_name)
+            .and_then(|definition| definition.extends.as_ref())
+            .is_some_and(|extends| !*extends.as_inner())
+    });
+
+    chain
+        .iter()
+        .enumerate()
+        .skip(opted_out.unwrap_or_default())
+        .filter_map(|(index, turbo_json)| {
+            let definition = turbo_json.task(task_id, task_name).ok()??;
+            // a definition that only opts out of inheritance contributes nothing
+            (Some(index) != opted_out || definition.has_config_beyond_extends())
+                .then_some(definition)
+        })
+        .collect()
+}
+
+fn parse_turbo_json(text: &str, is_root: bool) -> Option<TurboJson> {
+    let raw = if is_root {
+        RawRootTurboJson::parse(text, "turbo.json")
+            .ok()?
+            .try_into()
+            .ok()?
+    } else {
+        RawTurboJson::from(RawPackageTurboJson::parse(text, "turbo.json").ok()?)
+    };
+    TurboJson::try_from(raw).ok()
+}
+
+/// Renders a resolved task definition with the keys used in turbo.json
+fn definition_to_json(definition: ProcessedTaskDefinition) -> Option<Value> {
+    // globs are shown the way they are written, rather than relative to the
+    // package
+    let turbo_root = RelativeUnixPath::new(TURBO_ROOT).ok()?;
+    let globs = |globs: &[ProcessedGlob]| -> Vec<String> {
+        globs.iter().map(|glob| glob.resolve(turbo_root)).collect()
+    };
+    let strings = |values: &[Spanned<UnescapedString>]| -> Vec<String> {
+        values
+            .iter()
+            .map(|value| value.as_str().to_owned())
+            .collect()
+    };
+
+    let mut object = Map::new();
+    let mut insert = |key: &str, value: Value| {
+        object.insert(key.to_owned(), value);
+    };
+
+    if let Some(description) = definition.description {
+        insert("description", json!(description.as_str()));
+    }
+    if let Some(command) = definition.command {
+        insert("command", json!(command.as_inner()));
+    }
+    if let Some(cache) = definition.cache {
+        insert("cache", json!(cache.as_inner()));
+    }
+    if let Some(depends_on) = definition.depends_on {
+        insert("dependsOn", json!(strings(&depends_on.deps)));
+    }
+    if let Some(env) = definition.env {
+        insert("env", json!(env.vars));
+    }
+    if let Some(pass_through_env) = definition.pass_through_env {
+        insert("passThroughEnv", json!(pass_through_env.vars));
+    }
+    if let Some(inputs) = definition.inputs {
+        let mut entries = vec![];
+        if inputs.structured_startup {
+            entries.push(json!({
+                "mode": "startup",
+                "globs": globs(&inputs.globs),
+                "withDefaults": inputs.default,
+            }));
+        } else {
+            if inputs.default {
+                entries.push(json!(TURBO_DEFAULT));
+            }
+            entries.extend(globs(&inputs.globs).into_iter().map(Value::String));
+        }
+        if inputs.structured_jit {
+            entries.push(json!({
+                "mode": "jit",
+                "globs": globs(&inputs.jit_globs),
+                "withDefaults": inputs.jit_default,
+            }));
+        }
+        if let Some(dependency_outputs) = inputs.dependency_outputs {
+            let mut entry = json!({
+                "mode": "dependencyOutputs",
+                "globs": globs(&dependency_outputs.globs),
+            });
+            if let Some(from) = dependency_outputs.from {
+                entry["from"] = json!(strings(&from));
+            }
+            entries.push(entry);
+        }
+        insert("inputs", Value::Array(entries));
+    }
+    if let Some(outputs) = definition.outputs {
+        insert("outputs", json!(globs(&outputs.globs)));
+    }
+    if let Some(output_logs) = definition.output_logs {
+        insert("outputLogs", json!(output_logs.as_inner()));
+    }
+    if let Some(env_mode) = definition.env_mode {
+        insert("envMode", json!(env_mode.as_inner()));
+    }
+    if let Some(persistent) = definition.persistent {
+        insert("persistent", json!(persistent.as_inner()));
+    }
+    if let Some(interruptible) = definition.interruptible {
+        insert("interruptible", json!(interruptible.as_inner()));
+    }
+    if let Some(interactive) = definition.interactive {
+        insert("interactive", json!(interactive.as_inner()));
+    }
+    if let Some(timeout) = definition.timeout {
+        insert("timeout", json!(timeout.as_str()));
+    }
+    if let Some(retries) = definition.retries {
+        insert("retries", json!(retries.as_inner()));
+    }
+    if let Some(with) = definition.with {
+        let tasks = with.tasks.iter().map(|task| task.as_inner().to_string());
+        insert("with", json!(tasks.collect::<Vec<_>>()));
+    }
+    if let Some(incremental) = definition.incremental {
+        let partitions = incremental
+            .into_iter()
+            .map(|partition| {
+                let mut entry = json!({ "outputs": globs(&partition.outputs.globs) });
+                if let Some(inputs) = partition.inputs {
+                    entry["inputs"] = json!(globs(&inputs.globs));
+                }
+                entry
+            })
+            .collect();
+        insert("incremental", Value::Array(partitions));
+    }
+
+    Some(Value::Object(object))
+}
+
+/// Parses `text` and calls `f` with the top level object, if there is one
+fn with_object<T>(text: &str, f: impl FnOnce(&ast::Object) -> Option<T>) -> Option<T> {
+    let parse = jsonc_parser::parse_to_ast(text, &Default::default(), &Default::default()).ok()?;
+    f(parse.value.as_ref()?.as_object()?)
+}
+
+#[cfg(test)]
+#[allow(clippy::expect_used)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn finds_task_references() {
+        let text = r#"{
+          "tasks": {
+            "build": { "dependsOn": ["^build", "web#codegen", "$TURBO_EXTENDS$"] },
+            "dev": { "with": ["api#dev"] }
+          }
+        }"#;
+
+        let references = task_references(text)
+            .into_iter()
+            .map(|reference| {
+                (
+                    reference.package,
+                    &text[reference.task_range.start..reference.task_range.end],
+                    reference.topological,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            references,
+            vec![
+                (None, "build", false),
+                (None, "build", true),
+                (Some("web".to_string()), "codegen", false),
+                (None, "dev", false),
+                (Some("api".to_string()), "dev", false),
+            ]
+        );
+    }
+
+    #[test]
+    fn finds_script_range() {
+        let text = r#"{ "name": "web", "scripts": { "build": "next build" } }"#;
+
+        let range = script_range(text, "build").expect("build script");
+
+        assert_eq!(&text[range.start..range.end], r#""build""#);
+        assert!(script_range(text, "dev").is_none());
+    }
+
+    #[test]
+    fn validates_task_names() {
+        assert!(is_valid_task_name("test:unit"));
+        assert!(!is_valid_task_name(""));
+        assert!(!is_valid_task_name("^build"));
+        assert!(!is_valid_task_name("web#build"));
+        assert!(!is_valid_task_name("bu\"ild"));
+    }
+
+    #[test]
+    fn resolves_task_definition_through_extends() {
+        let turbo_jsons = HashMap::from([
+            (
+                ROOT_PACKAGE,
+                r#"{
+                  "tasks": {
+                    "build": { "dependsOn": ["^build"], "outputs": ["dist/**"] },
+                    "web#build": { "dependsOn": ["^build"], "outputs": [".next/**"] }
+                  }
+                }"#,
+            ),
+            (
+                "web",
+                r#"{
+                  "extends": ["//"],
+                  "tasks": {
+                    // comments are allowed
+                    "build": { "outputs": ["$TURBO_EXTENDS$", "public/**"], "cache": false }
+                  }
+                }"#,
+            ),
+        ]);
+        let read_turbo_json = |package: &str| turbo_jsons.get(package).map(|text| text.to_string());
+
+        assert_eq!(
+            resolve_task_definition("web", "build", &read_turbo_json),
+            Some(json!({
+                "dependsOn": ["^build"],
+                "outputs": [".next/**", "public/**"],
+                "cache": false
+            }))
+        );
+        assert_eq!(
+            resolve_task_definition("docs", "build", &read_turbo_json),
+            Some(json!({ "dependsOn": ["^build"], "outputs": ["dist/**"] }))
+        );
+        assert_eq!(
+            resolve_task_definition("web", "lint", &read_turbo_json),
+            None
+        );
+    }
+
+    #[test]
+    fn stops_inheriting_at_extends_false() {
+        let turbo_jsons = HashMap::from([
+            (
+                ROOT_PACKAGE,
+                r#"{ "tasks": { "build": { "dependsOn": ["^build"], "env": ["API_URL"] } } }"#,
+            ),
+            (
+                "web",
+                r#"{
+                  "extends": ["//"],
+                  "tasks": { "build": { "extends": false, "outputs": ["$TURBO_ROOT$/dist/**"] } }
+                }"#,
+            ),
+        ]);
+        let read_turbo_json = |package: &str| turbo_jsons.get(package).map(|text| text.to_string());
+
+        assert_eq!(
+            resolve_task_definition("web", "build", &read_turbo_json),
+            Some(json!({ "outputs": ["$TURBO_ROOT$/dist/**"] }))
+        );
+    }
+}
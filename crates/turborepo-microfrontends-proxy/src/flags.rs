@@ -0,0 +1,205 @@
+//! Local evaluation of the feature flags that gate microfrontend path groups.
+//!
+//! Flags are resolved per request, with the first source that sets a flag
+//! winning:
+//! 1. The `x-turbo-mfe-flags` request header
+//! 2. The `turbo-mfe-flags` cookie
+//! 3. The JSON overrides file pointed to by `TURBO_MFE_FLAG_OVERRIDES`
+//!
+//! The header and cookie hold a comma separated list of flags, either bare
+//! (`my-flag`, which enables it) or with a value (`my-flag=false`). The
+//! overrides file is an object mapping flag names to booleans. Flags that
+//! aren't set anywhere are off.
+
+use std::{collections::HashMap, path::Path};
+
+use hyper::{HeaderMap, header::COOKIE};
+
+use crate::ProxyError;
+
+/// Environment variable pointing at a JSON file of flag overrides
+pub const FLAG_OVERRIDES_ENV_VAR: &str = "TURBO_MFE_FLAG_OVERRIDES";
+/// Request header used to set flags for a single request
+pub const FLAGS_HEADER: &str = "x-turbo-mfe-flags";
+/// Cookie used to set flags for a browser session
+pub const FLAGS_COOKIE: &str = "turbo-mfe-flags";
+
+/// Flag values loaded from the overrides file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FlagOverrides {
+    flags: HashMap<String, bool>,
+}
+
+impl FlagOverrides {
+    pub fn new(flags: HashMap<String, bool>) -> Self {
+        Self { flags }
+    }
+
+    /// Loads overrides from the file named by [`FLAG_OVERRIDES_ENV_VAR`].
+    /// Returns no overrides if the variable isn't set.
+    pub fn from_env() -> Result<Self, ProxyError> {
+        match std::env::var_os(FLAG_OVERRIDES_ENV_VAR) {
+            Some(path) if !path.is_empty() => Self::load(Path::new(&path)),
+            _ => Ok(Self::default()),
+        }
+    }
+
+    pub fn load(path: &Path) -> Result<Self, ProxyError> {
+        let contents = std::fs::read_to_string(path).map_err(|e| {
+            ProxyError::Config(format!(
+                "Failed to read flag overrides from {}: {e}",
+                path.display()
+            ))
+        })?;
+        let flags = serde_json::from_str(&contents).map_err(|e| {
+            ProxyError::Config(format!(
+                "Flag overrides in {} must be an object of flag names to booleans: {e}",
+                path.display()
+            ))
+        })?;
+        Ok(Self { flags })
+    }
+
+    /// Resolves the flags for a request with the given headers
+    pub(crate) fn resolve(&self, headers: &HeaderMap) -> RequestFlags<'_> {
+        let mut request = HashMap::new();
+        for cookie in headers
+            .get_all(COOKIE)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(';'))
+        {
+            if let Some((name, value)) = cookie.trim().split_once('=')
+                && name == FLAGS_COOKIE
+            {
+                parse_flag_list(value, &mut request);
+            }
+        }
+        // Parsed after the cookie so the header takes precedence
+        for value in headers
+            .get_all(FLAGS_HEADER)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+        {
+            parse_flag_list(value, &mut request);
+        }
+
+        RequestFlags {
+            request,
+            overrides: self,
+        }
+    }
+}
+
+/// The flags that apply to a single request.
+pub(crate) struct RequestFlags<'a> {
+    request: HashMap<String, bool>,
+    overrides: &'a FlagOverrides,
+}
+
+impl RequestFlags<'_> {
+    pub(crate) fn is_enabled(&self, flag: &str) -> bool {
+        self.request
+            .get(flag)
+            .or_else(|| self.overrides.flags.get(flag))
+            .copied()
+            .unwrap_or(false)
+    }
+}
+
+fn parse_flag_list(list: &str, flags: &mut HashMap<String, bool>) {
+    for entry in list.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let (name, enabled) = match entry.split_once('=') {
+            Some((name, value)) => match value.trim() {
+                "1" | "true" | "on" => (name.trim(), true),
+                "0" | "false" | "off" => (name.trim(), false),
+                _ => continue,
+            },
+            None => (entry, true),
+        };
+        if !name.is_empty() {
+            flags.insert(name.to_string(), enabled);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use hyper::header::HeaderValue;
+
+    use super::*;
+
+    fn headers(entries: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in entries {
+            headers.append(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    #[test]
+    fn test_unset_flags_are_off() {
+        let overrides = FlagOverrides::default();
+        let flags = overrides.resolve(&HeaderMap::new());
+        assert!(!flags.is_enabled("new-docs"));
+    }
+
+    #[test]
+    fn test_header_flags() {
+        let overrides = FlagOverrides::default();
+        let headers = headers(&[(FLAGS_HEADER, "new-docs, beta=false,other=1,bad=maybe")]);
+        let flags = overrides.resolve(&headers);
+        assert!(flags.is_enabled("new-docs"));
+        assert!(!flags.is_enabled("beta"));
+        assert!(flags.is_enabled("other"));
+        assert!(!flags.is_enabled("bad"));
+    }
+
+    #[test]
+    fn test_cookie_flags() {
+        let overrides = FlagOverrides::default();
+        let headers = headers(&[("cookie", "session=abc; turbo-mfe-flags=new-docs,beta")]);
+        let flags = overrides.resolve(&headers);
+        assert!(flags.is_enabled("new-docs"));
+        assert!(flags.is_enabled("beta"));
+        assert!(!flags.is_enabled("session"));
+    }
+
+    #[test]
+    fn test_precedence() {
+        let overrides = FlagOverrides::new(HashMap::from([
+            ("from-file".to_string(), true),
+            ("cookie-wins".to_string(), true),
+            ("header-wins".to_string(), false),
+        ]));
+        let headers = headers(&[
+            (
+                "cookie",
+                "turbo-mfe-flags=cookie-wins=false,header-wins=false",
+            ),
+            (FLAGS_HEADER, "header-wins"),
+        ]);
+        let flags = overrides.resolve(&headers);
+        assert!(flags.is_enabled("from-file"));
+        assert!(!flags.is_enabled("cookie-wins"));
+        assert!(flags.is_enabled("header-wins"));
+    }
+
+    #[test]
+    fn test_load_overrides_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flags.json");
+        std::fs::write(&path, r#"{ "new-docs": true, "beta": false }"#).unwrap();
+
+        let overrides = FlagOverrides::load(&path).unwrap();
+        let flags = overrides.resolve(&HeaderMap::new());
+        assert!(flags.is_enabled("new-docs"));
+        assert!(!flags.is_enabled("beta"));
+
+        std::fs::write(&path, r#"{ "new-docs": "yes" }"#).unwrap();
+        assert!(matches!(
+            FlagOverrides::load(&path),
+            Err(ProxyError::Config(_))
+        ));
+    }
+}
@@ -15,6 +15,7 @@ pub struct RouteMatch {
 pub struct Router {
     trie: TrieNode,
     apps: Vec<AppInfo>,
+    targets: Vec<RouteTarget>,
     default_app_idx: usize,
 }
 
@@ -25,6 +26,14 @@ struct AppInfo {
     fallback: Option<Arc<str>>,
 }
 
+/// The app a path group routes to, along with the flag gating the group
+#[derive(Debug, Clone)]
+struct RouteTarget {
+    app_idx: usize,
+    flag: Option<Arc<str>>,
+}
+
+// Matches store an index into `Router::targets`
 #[derive(Clone, Default)]
 struct TrieNode {
     exact_children: HashMap<Arc<str>, TrieNode>,
@@ -38,7 +47,13 @@ struct TrieNode {
 struct Route {
     app_name: String,
     port: u16,
+    groups: Vec<RouteGroup>,
+}
+
+#[derive(Debug, Clone)]
+struct RouteGroup {
     patterns: Vec<PathPattern>,
+    flag: Option<Arc<str>>,
 }
 
 #[derive(Debug, Clone)]
@@ -76,7 +91,7 @@ impl Router {
             app_ports.insert(app_name.to_string(), port);
 
             if let Some(routing) = config.routing(app_name) {
-                let mut patterns = Vec::new();
+                let mut groups = Vec::with_capacity(routing.len());
                 for path_group in routing {
                     let mut group_patterns = Vec::with_capacity(path_group.paths.len());
                     for path in &path_group.paths {
@@ -87,13 +102,16 @@ impl Router {
                             )
                         })?);
                     }
-                    patterns.extend(group_patterns);
+                    groups.push(RouteGroup {
+                        patterns: group_patterns,
+                        flag: path_group.flag.as_deref().map(Arc::from),
+                    });
                 }
 
                 routes.push(Route {
                     app_name: app_name.to_string(),
                     port,
-                    patterns,
+                    groups,
                 });
             } else if default_app.is_none() {
                 default_app = Some((app_name.to_string(), port));
@@ -107,6 +125,7 @@ impl Router {
         })?;
 
         let mut apps = Vec::new();
+        let mut targets = Vec::new();
         let mut trie = TrieNode::default();
 
         for route in routes {
@@ -120,8 +139,15 @@ impl Router {
                 fallback,
             });
 
-            for pattern in route.patterns {
-                trie.insert(&pattern.segments, app_idx);
+            for group in route.groups {
+                let target_idx = targets.len();
+                targets.push(RouteTarget {
+                    app_idx,
+                    flag: group.flag,
+                });
+                for pattern in group.patterns {
+                    trie.insert(&pattern.segments, target_idx);
+                }
             }
         }
 
@@ -138,11 +164,25 @@ impl Router {
         Ok(Self {
             trie,
             apps,
+            targets,
             default_app_idx,
         })
     }
 
+    /// Matches `path` with every flag off, so flagged path groups route to
+    /// the default application.
     pub fn match_route(&self, path: &str) -> RouteMatch {
+        self.match_route_with_flags(path, |_| false)
+    }
+
+    /// Matches `path`, routing flagged path groups to their application only
+    /// if `is_enabled` returns true for the group's flag. Otherwise the
+    /// request goes to the default application.
+    pub fn match_route_with_flags(
+        &self,
+        path: &str,
+        is_enabled: impl Fn(&str) -> bool,
+    ) -> RouteMatch {
         // Normalize path: strip leading and trailing slashes
         let mut path = path;
         while let Some(stripped) = path.strip_prefix('/') {
@@ -152,7 +192,7 @@ impl Router {
             path = stripped;
         }
 
-        let app_idx = if path.is_empty() {
+        let target = if path.is_empty() {
             self.trie.lookup(&[])
         } else {
             let mut segments = Vec::new();
@@ -163,7 +203,19 @@ impl Router {
             }
             self.trie.lookup(&segments)
         }
-        .unwrap_or(self.default_app_idx);
+        .map(|target_idx| &self.targets[target_idx]);
+
+        let app_idx = match target {
+            Some(RouteTarget {
+                app_idx,
+                flag: None,
+            }) => *app_idx,
+            Some(RouteTarget {
+                app_idx,
+                flag: Some(flag),
+            }) if is_enabled(flag) => *app_idx,
+            _ => self.default_app_idx,
+        };
 
         let app = &self.apps[app_idx];
         RouteMatch {
@@ -175,28 +227,28 @@ impl Router {
 }
 
 impl TrieNode {
-    fn insert(&mut self, segments: &[Segment], app_idx: usize) {
+    fn insert(&mut self, segments: &[Segment], target_idx: usize) {
         if segments.is_empty() {
-            self.terminal_match = Some(app_idx);
+            self.terminal_match = Some(target_idx);
             return;
         }
 
         match &segments[0] {
             Segment::Exact(name) => {
                 let child = self.exact_children.entry(Arc::clone(name)).or_default();
-                child.insert(&segments[1..], app_idx);
+                child.insert(&segments[1..], target_idx);
             }
             Segment::Param => {
                 let child = self
                     .param_child
                     .get_or_insert_with(|| Box::new(TrieNode::default()));
-                child.insert(&segments[1..], app_idx);
+                child.insert(&segments[1..], target_idx);
             }
             Segment::Wildcard => {
-                self.wildcard_match = Some(app_idx);
+                self.wildcard_match = Some(target_idx);
             }
             Segment::WildcardPlus => {
-                self.wildcard_plus_match = Some(app_idx);
+                self.wildcard_plus_match = Some(target_idx);
             }
         }
     }
@@ -208,20 +260,20 @@ impl TrieNode {
         }
 
         if let Some(child) = self.exact_children.get(segments[0])
-            && let Some(app_idx) = child.lookup(&segments[1..])
+            && let Some(target_idx) = child.lookup(&segments[1..])
         {
-            return Some(app_idx);
+            return Some(target_idx);
         }
 
         if let Some(child) = &self.param_child
-            && let Some(app_idx) = child.lookup(&segments[1..])
+            && let Some(target_idx) = child.lookup(&segments[1..])
         {
-            return Some(app_idx);
+            return Some(target_idx);
         }
 
         // Both * and + match one or more segments
-        if let Some(app_idx) = self.wildcard_match.or(self.wildcard_plus_match) {
-            return Some(app_idx);
+        if let Some(target_idx) = self.wildcard_match.or(self.wildcard_plus_match) {
+            return Some(target_idx);
         }
 
         None
@@ -1,6 +1,7 @@
 #![deny(clippy::all)]
 
 mod error;
+mod flags;
 mod headers;
 mod http;
 mod http_router;
@@ -10,5 +11,6 @@ mod server;
 mod websocket;
 
 pub use error::{ErrorPage, ProxyError};
+pub use flags::{FLAG_OVERRIDES_ENV_VAR, FLAGS_COOKIE, FLAGS_HEADER, FlagOverrides};
 pub use http_router::{RouteMatch, Router};
 pub use server::ProxyServer;
@@ -10,6 +10,7 @@ use tracing::{debug, error};
 
 use crate::{
     ProxyError,
+    flags::FlagOverrides,
     headers::{is_websocket_upgrade, validate_request_headers},
     http::{BoxedBody, HttpClient, handle_http_request},
     http_router::Router,
@@ -19,6 +20,7 @@ use crate::{
 pub(crate) async fn handle_request(
     mut req: Request<Incoming>,
     router: Arc<Router>,
+    flag_overrides: Arc<FlagOverrides>,
     remote_addr: SocketAddr,
     ws_ctx: WebSocketContext,
     http_client: HttpClient,
@@ -33,7 +35,8 @@ pub(crate) async fn handle_request(
 
     debug!("Request: {} {} from {}", method, path, remote_addr.ip());
 
-    let route_match = router.match_route(&path);
+    let flags = flag_overrides.resolve(req.headers());
+    let route_match = router.match_route_with_flags(&path, |flag| flags.is_enabled(flag));
     debug!(
         "Matched route: app={}, port={}",
         route_match.app_name, route_match.port
@@ -17,6 +17,7 @@ use turborepo_microfrontends::Config;
 
 use crate::{
     ProxyError,
+    flags::FlagOverrides,
     http::HttpClient,
     http_router::Router,
     websocket::{WebSocketContext, WebSocketHandle},
@@ -49,6 +50,7 @@ fn is_connection_closed_error(err: &hyper::Error) -> bool {
 pub struct ProxyServer {
     config: Arc<Config>,
     router: Arc<Router>,
+    flag_overrides: Arc<FlagOverrides>,
     port: u16,
     shutdown_tx: broadcast::Sender<()>,
     ws_handles: Arc<DashMap<usize, WebSocketHandle>>,
@@ -63,6 +65,7 @@ impl ProxyServer {
     pub fn new(config: Config) -> Result<Self, ProxyError> {
         let router = Router::new(&config)
             .map_err(|e| ProxyError::Config(format!("Failed to build router: {e}")))?;
+        let flag_overrides = FlagOverrides::from_env()?;
 
         let port = config.local_proxy_port().unwrap_or(DEFAULT_PROXY_PORT);
         let (shutdown_tx, _) = broadcast::channel(1);
@@ -76,6 +79,7 @@ impl ProxyServer {
         Ok(Self {
             config: Arc::new(config),
             router: Arc::new(router),
+            flag_overrides: Arc::new(flag_overrides),
             port,
             shutdown_tx,
             ws_handles: Arc::new(DashMap::new()),
@@ -153,6 +157,7 @@ impl ProxyServer {
                     let io = TokioIo::new(stream);
 
                     let router = self.router.clone();
+                    let flag_overrides = self.flag_overrides.clone();
                     let ws_handles_clone = ws_handles.clone();
                     let ws_id_counter_clone = self.ws_id_counter.clone();
                     let ws_connection_count_clone = self.ws_connection_count.clone();
@@ -166,6 +171,7 @@ impl ProxyServer {
 
                         let service = hyper::service::service_fn(move |req| {
                             let router = router.clone();
+                            let flag_overrides = flag_overrides.clone();
                             let ws_ctx = WebSocketContext {
                                 handles: ws_handles_clone.clone(),
                                 id_counter: ws_id_counter_clone.clone(),
@@ -173,7 +179,15 @@ impl ProxyServer {
                             };
                             let http_client = http_client.clone();
                             async move {
-                                crate::proxy::handle_request(req, router, remote_addr, ws_ctx, http_client).await
+                                crate::proxy::handle_request(
+                                    req,
+                                    router,
+                                    flag_overrides,
+                                    remote_addr,
+                                    ws_ctx,
+                                    http_client,
+                                )
+                                .await
                             }
                         });
 
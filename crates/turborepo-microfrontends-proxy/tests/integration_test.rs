@@ -114,6 +114,47 @@ async fn test_router_with_config() {
     assert_eq!(route.port, 3000);
 }
 
+#[tokio::test]
+async fn test_router_with_flagged_path_group() {
+    let config_json = r#"{
+        "applications": {
+            "web": {
+                "development": {
+                    "local": { "port": 3000 }
+                }
+            },
+            "docs": {
+                "development": {
+                    "local": { "port": 3001 }
+                },
+                "routing": [
+                    { "paths": ["/docs", "/docs/:path*"] },
+                    { "paths": ["/guides/:path*"], "flag": "new-guides" }
+                ]
+            }
+        }
+    }"#;
+
+    let config = Config::from_str(config_json, "test.json").unwrap();
+    let router = Router::new(&config).unwrap();
+
+    // Unflagged groups route regardless of flags
+    let route = router.match_route("/docs/intro");
+    assert_eq!(route.app_name.as_ref(), "docs");
+
+    // Flagged groups go to the default app unless the flag is on
+    let route = router.match_route("/guides/setup");
+    assert_eq!(route.app_name.as_ref(), "web");
+    assert_eq!(route.port, 3000);
+
+    let route = router.match_route_with_flags("/guides/setup", |flag| flag == "other");
+    assert_eq!(route.app_name.as_ref(), "web");
+
+    let route = router.match_route_with_flags("/guides/setup", |flag| flag == "new-guides");
+    assert_eq!(route.app_name.as_ref(), "docs");
+    assert_eq!(route.port, 3001);
+}
+
 #[tokio::test]
 async fn test_multiple_child_apps() {
     let config_json = r#"{
@@ -1,9 +1,9 @@
-//! OpenTelemetry metrics exporter for Turborepo.
+//! OpenTelemetry metrics and trace exporter for Turborepo.
 //!
-//! This crate provides OTLP (OpenTelemetry Protocol) metrics export
-//! functionality for Turborepo run summaries. It enables sending run and task
-//! metrics to any OTLP-compatible observability backend (e.g., Grafana,
-//! Datadog, Honeycomb).
+//! This crate provides OTLP (OpenTelemetry Protocol) export functionality for
+//! Turborepo run summaries. It enables sending run and task metrics, and
+//! optionally traces, to any OTLP-compatible observability backend (e.g.,
+//! Grafana, Datadog, Honeycomb, Jaeger, Tempo).
 //!
 //! # Architecture
 //!
@@ -12,8 +12,8 @@
 //! - [`Config`]: Resolved configuration for the exporter (endpoint, protocol,
 //!   headers, etc.)
 //! - [`Protocol`]: The transport protocol to use (gRPC or HTTP/Protobuf)
-//! - [`Handle`]: The main entry point for recording metrics; manages the OTLP
-//!   exporter lifecycle
+//! - [`Handle`]: The main entry point for recording metrics and traces; manages
+//!   the OTLP exporter lifecycle
 //! - [`RunMetricsPayload`] / [`TaskMetricsPayload`]: Structured data
 //!   representing run and task metrics
 //! - [`MetricsConfig`]: Toggle which metric categories to emit (run summaries,
@@ -34,6 +34,7 @@
 //!     interval: Duration::from_secs(15),
 //!     resource_attributes: BTreeMap::new(),
 //!     metrics: MetricsConfig::default(),
+//!     traces: false,
 //! };
 //!
 //! let handle = Handle::try_new(config)?;
@@ -62,6 +63,13 @@
 //! When `task_details` is enabled:
 //! - `turbo.task.duration_ms` - Histogram of individual task durations
 //! - `turbo.task.cache.events` - Counter of cache events with hit/miss status
+//! - `turbo.task.cpu_time_ms` - Histogram of CPU time used by each task's
+//!   processes, split by `turbo.task.cpu.mode` (`user` or `system`)
+//! - `turbo.task.memory.peak_rss` - Histogram of the peak resident memory of
+//!   each task's processes, in bytes
+//!
+//! CPU and memory are only measured on Linux and are not recorded for cache
+//! hits.
 //!
 //! # Metric Attributes and Cardinality
 //!
@@ -88,22 +96,51 @@
 //!
 //! **Gated by `task_attributes`** (unbounded, opt-in):
 //! - `turbo.task.id`, `turbo.task.hash`, `turbo.task.external_inputs_hash`
+//!
+//! # Traces Emitted
+//!
+//! When `traces` is enabled, each run is exported as a trace built from the
+//! completed run summary:
+//! - A root `turbo run` span covering the whole run
+//! - A child span per task, named after its task ID, with `turbo.task.id`,
+//!   `turbo.task.name`, `turbo.task.package`, `turbo.task.hash`,
+//!   `turbo.task.cache_status` and (when available) `turbo.task.cache_source`
+//!   and `turbo.task.exit_code` attributes, plus `turbo.task.cpu.user_ms`,
+//!   `turbo.task.cpu.system_ms` and `turbo.task.memory.peak_rss_bytes` when
+//!   resource usage was measured
+//! - A span link from each task span to the spans of the tasks it depends on,
+//!   so the critical path of a run can be followed in the trace viewer
+//!
+//! Span attributes don't create new series in the backend, so unlike metric
+//! attributes they aren't gated behind cardinality opt-ins.
+//!
+//! With `http/protobuf`, an endpoint ending in `/v1/metrics` has that suffix
+//! replaced by `/v1/traces` for the trace exporter. Any other endpoint is
+//! used as-is for both signals.
 
 use std::{
     collections::{BTreeMap, HashMap},
     sync::Arc,
-    time::Duration,
+    time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 use opentelemetry::{
-    KeyValue,
+    Context, KeyValue,
     metrics::{Counter, Histogram, Meter, MeterProvider as _},
+    trace::{
+        Link, Span as _, SpanBuilder, SpanContext, SpanId, SpanKind, Status, TraceContextExt as _,
+        TraceFlags, TraceState, Tracer as _, TracerProvider as _,
+    },
 };
 use opentelemetry_otlp::{WithExportConfig, WithHttpConfig, WithTonicConfig};
 use opentelemetry_sdk::{
     Resource,
     metrics::{SdkMeterProvider, Temporality, periodic_reader_with_async_runtime},
     runtime::Tokio,
+    trace::{
+        IdGenerator, RandomIdGenerator, SdkTracer, SdkTracerProvider,
+        span_processor_with_async_runtime,
+    },
 };
 use opentelemetry_semantic_conventions::resource::SERVICE_NAME;
 use thiserror::Error;
@@ -128,6 +165,19 @@ const DURATION_HISTOGRAM_BOUNDARIES_MS: &[f64] = &[
     3_600_000.0,
 ];
 
+const MEMORY_HISTOGRAM_BOUNDARIES_BYTES: &[f64] = &[
+    16_777_216.0,     // 16 MiB
+    67_108_864.0,     // 64 MiB
+    134_217_728.0,    // 128 MiB
+    268_435_456.0,    // 256 MiB
+    536_870_912.0,    // 512 MiB
+    1_073_741_824.0,  // 1 GiB
+    2_147_483_648.0,  // 2 GiB
+    4_294_967_296.0,  // 4 GiB
+    8_589_934_592.0,  // 8 GiB
+    17_179_869_184.0, // 16 GiB
+];
+
 /// Protocol supported by the OTLP exporter.
 ///
 /// Both gRPC and HTTP transports use `https://` endpoints - see
@@ -225,6 +275,8 @@ pub struct Config {
     pub interval: Duration,
     pub resource_attributes: BTreeMap<String, String>,
     pub metrics: MetricsConfig,
+    /// Export a trace for each run, with a span per task.
+    pub traces: bool,
 }
 
 /// Summary of a Turborepo run encoded for metrics export.
@@ -232,6 +284,10 @@ pub struct Config {
 pub struct RunMetricsPayload {
     pub run_id: String,
     pub turbo_version: String,
+    /// Milliseconds since the Unix epoch
+    pub start_time_ms: i64,
+    /// Milliseconds since the Unix epoch
+    pub end_time_ms: i64,
     pub duration_ms: f64,
     pub attempted_tasks: u64,
     pub failed_tasks: u64,
@@ -251,10 +307,26 @@ pub struct TaskMetricsPayload {
     pub hash: String,
     pub external_inputs_hash: String,
     pub command: String,
+    /// Milliseconds since the Unix epoch, if the task executed
+    pub start_time_ms: Option<i64>,
+    /// Milliseconds since the Unix epoch, if the task executed
+    pub end_time_ms: Option<i64>,
     pub duration_ms: Option<f64>,
     pub cache_status: TaskCacheStatus,
     pub cache_source: Option<String>,
     pub exit_code: Option<i32>,
+    /// Task IDs of the tasks this task depends on
+    pub dependencies: Vec<String>,
+    /// CPU time and memory used by the task's processes, if measured
+    pub resource_usage: Option<TaskResourceUsage>,
+}
+
+/// Resources used by a task's process tree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskResourceUsage {
+    pub user_cpu_ms: u64,
+    pub system_cpu_ms: u64,
+    pub peak_rss_bytes: u64,
 }
 
 /// Cache status for a task.
@@ -295,12 +367,15 @@ struct Instruments {
     run_cached: Counter<u64>,
     task_duration: Histogram<f64>,
     task_cache: Counter<u64>,
+    task_cpu_time: Histogram<f64>,
+    task_peak_rss: Histogram<u64>,
 }
 
 struct HandleInner {
     provider: SdkMeterProvider,
     instruments: Arc<Instruments>,
     metrics: MetricsConfig,
+    tracer_provider: Option<SdkTracerProvider>,
 }
 
 /// Handle to the configured exporter.
@@ -322,14 +397,20 @@ impl Handle {
         let provider = build_provider(&config)?;
         let meter = provider.meter("turborepo");
         let instruments = Arc::new(create_instruments(&meter));
+        let tracer_provider = config
+            .traces
+            .then(|| build_tracer_provider(&config))
+            .transpose()?;
 
         tracing::debug!(
             target: "turborepo_otel",
-            "initialized otel exporter: endpoint={} protocol={:?} run_summary={} task_details={}",
+            "initialized otel exporter: endpoint={} protocol={:?} run_summary={} task_details={} \
+             traces={}",
             config.endpoint,
             config.protocol,
             config.metrics.run_summary,
-            config.metrics.task_details
+            config.metrics.task_details,
+            config.traces
         );
 
         Ok(Self {
@@ -337,6 +418,7 @@ impl Handle {
                 provider,
                 instruments,
                 metrics: config.metrics,
+                tracer_provider,
             }),
         })
     }
@@ -360,6 +442,9 @@ impl Handle {
                 .instruments
                 .record_task_details(payload, self.inner.metrics);
         }
+        if let Some(tracer_provider) = &self.inner.tracer_provider {
+            record_trace(&tracer_provider.tracer("turborepo"), payload);
+        }
     }
 
     /// Shutdown the exporter, flushing any pending metrics.
@@ -383,6 +468,14 @@ impl Handle {
                  {err}"
             );
         }
+        if let Some(tracer_provider) = &self.inner.tracer_provider
+            && let Err(err) = tracer_provider.shutdown()
+        {
+            debug!(
+                "otel exporter shutdown could not flush traces (collector may be unreachable): \
+                 {err}"
+            );
+        }
     }
 }
 
@@ -439,8 +532,135 @@ impl Instruments {
                 self.task_duration.record(duration, &attrs);
             }
             self.task_cache.add(1, &attrs);
+            if let Some(usage) = task.resource_usage {
+                for (mode, cpu_ms) in [("user", usage.user_cpu_ms), ("system", usage.system_cpu_ms)]
+                {
+                    let mut attrs = attrs.clone();
+                    attrs.push(KeyValue::new("turbo.task.cpu.mode", mode));
+                    self.task_cpu_time.record(cpu_ms as f64, &attrs);
+                }
+                self.task_peak_rss.record(usage.peak_rss_bytes, &attrs);
+            }
+        }
+    }
+}
+
+/// Export a completed run as a trace.
+///
+/// Spans are created after the fact from the run summary, so start and end
+/// times are set explicitly. Task span IDs are assigned up front so that each
+/// task span can link to the spans of its dependencies regardless of the
+/// order tasks appear in the summary.
+fn record_trace(tracer: &SdkTracer, payload: &RunMetricsPayload) {
+    tracing::debug!(
+        target: "turborepo_otel",
+        "record_trace run_id={} task_count={}",
+        payload.run_id,
+        payload.tasks.len()
+    );
+    let run_start = system_time(payload.start_time_ms);
+    let run_end = system_time(payload.end_time_ms);
+
+    let mut run_attrs = vec![
+        KeyValue::new("turbo.run.id", payload.run_id.clone()),
+        KeyValue::new("turbo.run.exit_code", i64::from(payload.exit_code)),
+        KeyValue::new("turbo.version", payload.turbo_version.clone()),
+        KeyValue::new("turbo.run.tasks.attempted", payload.attempted_tasks as i64),
+        KeyValue::new("turbo.run.tasks.failed", payload.failed_tasks as i64),
+        KeyValue::new("turbo.run.tasks.cached", payload.cached_tasks as i64),
+    ];
+    if let Some(branch) = &payload.scm_branch {
+        run_attrs.push(KeyValue::new("turbo.scm.branch", branch.clone()));
+    }
+    if let Some(revision) = &payload.scm_revision {
+        run_attrs.push(KeyValue::new("turbo.scm.revision", revision.clone()));
+    }
+    let mut run_span = tracer.build(
+        SpanBuilder::from_name("turbo run")
+            .with_kind(SpanKind::Internal)
+            .with_start_time(run_start)
+            .with_attributes(run_attrs),
+    );
+    if payload.exit_code != 0 {
+        run_span.set_status(Status::error(format!(
+            "run exited with code {}",
+            payload.exit_code
+        )));
+    }
+    let trace_id = run_span.span_context().trace_id();
+    let parent = Context::new().with_remote_span_context(run_span.span_context().clone());
+
+    let id_generator = RandomIdGenerator::default();
+    let span_ids: HashMap<&str, SpanId> = payload
+        .tasks
+        .iter()
+        .map(|task| (task.task_id.as_str(), id_generator.new_span_id()))
+        .collect();
+
+    for task in &payload.tasks {
+        let links = task
+            .dependencies
+            .iter()
+            .filter_map(|dependency| span_ids.get(dependency.as_str()))
+            .map(|span_id| {
+                Link::with_context(SpanContext::new(
+                    trace_id,
+                    *span_id,
+                    TraceFlags::SAMPLED,
+                    false,
+                    TraceState::default(),
+                ))
+            })
+            .collect();
+
+        let mut attrs = vec![
+            KeyValue::new("turbo.task.id", task.task_id.clone()),
+            KeyValue::new("turbo.task.name", task.task.clone()),
+            KeyValue::new("turbo.task.package", task.package.clone()),
+            KeyValue::new("turbo.task.hash", task.hash.clone()),
+            KeyValue::new("turbo.task.cache_status", task.cache_status.as_str()),
+        ];
+        if let Some(source) = &task.cache_source {
+            attrs.push(KeyValue::new("turbo.task.cache_source", source.clone()));
+        }
+        if let Some(exit_code) = task.exit_code {
+            attrs.push(KeyValue::new("turbo.task.exit_code", i64::from(exit_code)));
+        }
+        if let Some(usage) = task.resource_usage {
+            attrs.extend([
+                KeyValue::new("turbo.task.cpu.user_ms", usage.user_cpu_ms as i64),
+                KeyValue::new("turbo.task.cpu.system_ms", usage.system_cpu_ms as i64),
+                KeyValue::new(
+                    "turbo.task.memory.peak_rss_bytes",
+                    usage.peak_rss_bytes as i64,
+                ),
+            ]);
+        }
+
+        // Tasks that never executed are shown as instantaneous at the start of
+        // the run rather than being dropped from the trace.
+        let start = task.start_time_ms.map_or(run_start, system_time);
+        let end = task.end_time_ms.map_or(start, system_time);
+        let mut builder = SpanBuilder::from_name(task.task_id.clone())
+            .with_kind(SpanKind::Internal)
+            .with_start_time(start)
+            .with_attributes(attrs)
+            .with_links(links);
+        if let Some(span_id) = span_ids.get(task.task_id.as_str()) {
+            builder = builder.with_span_id(*span_id);
         }
+        let mut span = tracer.build_with_context(builder, &parent);
+        if let Some(exit_code) = task.exit_code.filter(|code| *code != 0) {
+            span.set_status(Status::error(format!("task exited with code {exit_code}")));
+        }
+        span.end_with_timestamp(end);
     }
+
+    run_span.end_with_timestamp(run_end);
+}
+
+fn system_time(millis: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(u64::try_from(millis).unwrap_or_default())
 }
 
 /// Validates that the endpoint is non-empty and uses a secure OTLP transport
@@ -518,6 +738,58 @@ fn build_provider(config: &Config) -> Result<SdkMeterProvider, Error> {
         .build())
 }
 
+fn build_tracer_provider(config: &Config) -> Result<SdkTracerProvider, Error> {
+    let exporter = match config.protocol {
+        Protocol::Grpc => {
+            let export_config = opentelemetry_otlp::ExportConfig {
+                endpoint: Some(config.endpoint.clone()),
+                protocol: opentelemetry_otlp::Protocol::Grpc,
+                timeout: Some(config.timeout),
+            };
+            let mut builder = opentelemetry_otlp::SpanExporter::builder()
+                .with_tonic()
+                .with_export_config(export_config);
+            if !config.headers.is_empty() {
+                builder = builder.with_metadata(build_metadata(&config.headers)?);
+            }
+            builder.build().map_err(Error::Exporter)?
+        }
+        Protocol::HttpProtobuf => {
+            let export_config = opentelemetry_otlp::ExportConfig {
+                endpoint: Some(http_traces_endpoint(&config.endpoint)),
+                protocol: opentelemetry_otlp::Protocol::HttpBinary,
+                timeout: Some(config.timeout),
+            };
+            let mut builder = opentelemetry_otlp::SpanExporter::builder()
+                .with_http()
+                .with_export_config(export_config);
+            if !config.headers.is_empty() {
+                let headers: HashMap<_, _> = config.headers.clone().into_iter().collect();
+                builder = builder.with_headers(headers);
+            }
+            builder.build().map_err(Error::Exporter)?
+        }
+    };
+
+    let processor =
+        span_processor_with_async_runtime::BatchSpanProcessor::builder(exporter, Tokio).build();
+
+    Ok(SdkTracerProvider::builder()
+        .with_resource(build_resource(config))
+        .with_span_processor(processor)
+        .build())
+}
+
+/// HTTP exporters post to the configured endpoint as-is, so an endpoint that
+/// points at the metrics path needs to be redirected to the traces path.
+fn http_traces_endpoint(endpoint: &str) -> String {
+    let trimmed = endpoint.trim_end_matches('/');
+    match trimmed.strip_suffix("/v1/metrics") {
+        Some(base) => format!("{base}/v1/traces"),
+        None => endpoint.to_string(),
+    }
+}
+
 fn build_metadata(headers: &BTreeMap<String, String>) -> Result<MetadataMap, Error> {
     let mut map = MetadataMap::new();
     for (key, value) in headers {
@@ -580,6 +852,18 @@ fn create_instruments(meter: &Meter) -> Instruments {
         .u64_counter("turbo.task.cache.events")
         .with_description("Cache hit/miss events")
         .build();
+    let task_cpu_time = meter
+        .f64_histogram("turbo.task.cpu_time_ms")
+        .with_description("CPU time used by a task's processes in milliseconds")
+        .with_unit("ms")
+        .with_boundaries(DURATION_HISTOGRAM_BOUNDARIES_MS.to_vec())
+        .build();
+    let task_peak_rss = meter
+        .u64_histogram("turbo.task.memory.peak_rss")
+        .with_description("Peak resident memory of a task's processes in bytes")
+        .with_unit("By")
+        .with_boundaries(MEMORY_HISTOGRAM_BOUNDARIES_BYTES.to_vec())
+        .build();
 
     Instruments {
         run_duration,
@@ -588,6 +872,8 @@ fn create_instruments(meter: &Meter) -> Instruments {
         run_cached,
         task_duration,
         task_cache,
+        task_cpu_time,
+        task_peak_rss,
     }
 }
 
@@ -634,6 +920,7 @@ mod tests {
             interval: Duration::from_secs(15),
             resource_attributes: BTreeMap::new(),
             metrics: MetricsConfig::default(),
+            traces: false,
         };
         let result = Handle::try_new(config);
         assert!(result.is_err());
@@ -653,6 +940,7 @@ mod tests {
             interval: Duration::from_secs(15),
             resource_attributes: BTreeMap::new(),
             metrics: MetricsConfig::default(),
+            traces: false,
         };
         let result = Handle::try_new(config);
         assert!(result.is_err());
@@ -787,6 +1075,110 @@ mod tests {
         assert_eq!(DURATION_HISTOGRAM_BOUNDARIES_MS.last(), Some(&3_600_000.0));
     }
 
+    #[test]
+    fn test_http_traces_endpoint() {
+        assert_eq!(
+            http_traces_endpoint("https://collector.example.com:4318/v1/metrics"),
+            "https://collector.example.com:4318/v1/traces"
+        );
+        assert_eq!(
+            http_traces_endpoint("https://collector.example.com:4318/v1/metrics/"),
+            "https://collector.example.com:4318/v1/traces"
+        );
+        assert_eq!(
+            http_traces_endpoint("https://api.datadoghq.com/api/v2/otlp"),
+            "https://api.datadoghq.com/api/v2/otlp"
+        );
+    }
+
+    fn task_payload(task_id: &str, dependencies: &[&str]) -> TaskMetricsPayload {
+        let (package, task) = task_id.split_once('#').unwrap();
+        TaskMetricsPayload {
+            task_id: task_id.to_string(),
+            task: task.to_string(),
+            package: package.to_string(),
+            hash: "abc123".to_string(),
+            external_inputs_hash: "def456".to_string(),
+            command: "echo".to_string(),
+            start_time_ms: Some(1_000),
+            end_time_ms: Some(2_000),
+            duration_ms: Some(1_000.0),
+            cache_status: TaskCacheStatus::Miss,
+            cache_source: None,
+            exit_code: Some(0),
+            dependencies: dependencies.iter().map(|d| d.to_string()).collect(),
+            resource_usage: None,
+        }
+    }
+
+    #[test]
+    fn test_record_trace_spans() {
+        use opentelemetry_sdk::trace::InMemorySpanExporter;
+
+        let exporter = InMemorySpanExporter::default();
+        let provider = SdkTracerProvider::builder()
+            .with_simple_exporter(exporter.clone())
+            .build();
+        let mut failed = task_payload("web#build", &["ui#build"]);
+        failed.exit_code = Some(1);
+        failed.resource_usage = Some(TaskResourceUsage {
+            user_cpu_ms: 800,
+            system_cpu_ms: 200,
+            peak_rss_bytes: 1_048_576,
+        });
+        let payload = RunMetricsPayload {
+            run_id: "run-1".to_string(),
+            turbo_version: "2.0.0".to_string(),
+            start_time_ms: 500,
+            end_time_ms: 2_500,
+            duration_ms: 2_000.0,
+            attempted_tasks: 2,
+            failed_tasks: 1,
+            cached_tasks: 0,
+            exit_code: 1,
+            scm_branch: None,
+            scm_revision: None,
+            tasks: vec![failed, task_payload("ui#build", &[])],
+        };
+
+        record_trace(&provider.tracer("test"), &payload);
+
+        let spans = exporter.get_finished_spans().unwrap();
+        assert_eq!(spans.len(), 3);
+        let root = spans.iter().find(|span| span.name == "turbo run").unwrap();
+        let web = spans.iter().find(|span| span.name == "web#build").unwrap();
+        let ui = spans.iter().find(|span| span.name == "ui#build").unwrap();
+
+        assert_eq!(root.start_time, system_time(500));
+        assert_eq!(root.end_time, system_time(2_500));
+        for task in [web, ui] {
+            assert_eq!(task.parent_span_id, root.span_context.span_id());
+            assert_eq!(task.span_context.trace_id(), root.span_context.trace_id());
+        }
+        assert_eq!(web.links.links.len(), 1);
+        assert_eq!(
+            web.links.links[0].span_context.span_id(),
+            ui.span_context.span_id()
+        );
+        assert!(ui.links.links.is_empty());
+        assert!(matches!(web.status, Status::Error { .. }));
+        assert_eq!(ui.status, Status::Unset);
+        assert!(
+            web.attributes
+                .iter()
+                .any(|kv| kv.key.as_str() == "turbo.task.package" && kv.value.as_str() == "web")
+        );
+        assert!(web.attributes.iter().any(|kv| {
+            kv.key.as_str() == "turbo.task.memory.peak_rss_bytes"
+                && kv.value == opentelemetry::Value::I64(1_048_576)
+        }));
+        assert!(
+            !ui.attributes
+                .iter()
+                .any(|kv| kv.key.as_str() == "turbo.task.cpu.user_ms")
+        );
+    }
+
     #[test]
     fn test_build_resource_default_service_name() {
         let config = Config {
@@ -797,6 +1189,7 @@ mod tests {
             interval: Duration::from_secs(15),
             resource_attributes: BTreeMap::new(),
             metrics: MetricsConfig::default(),
+            traces: false,
         };
         let resource = build_resource(&config);
         let attrs: Vec<_> = resource
@@ -822,6 +1215,7 @@ mod tests {
             interval: Duration::from_secs(15),
             resource_attributes: resource_attrs,
             metrics: MetricsConfig::default(),
+            traces: false,
         };
         let resource = build_resource(&config);
         let attrs: Vec<_> = resource
@@ -848,6 +1242,7 @@ mod tests {
             interval: Duration::from_secs(15),
             resource_attributes: resource_attrs,
             metrics: MetricsConfig::default(),
+            traces: false,
         };
         let resource = build_resource(&config);
         let attrs: Vec<_> = resource
@@ -877,6 +1272,7 @@ mod tests {
             interval: Duration::from_secs(15),
             resource_attributes: resource_attrs,
             metrics: MetricsConfig::default(),
+            traces: false,
         };
         let resource = build_resource(&config);
         let attrs: Vec<_> = resource
@@ -47,6 +47,7 @@ use self::{
     state::{ChildCommand, ChildCommandChannel, ChildStateManager},
 };
 use super::{Command, PtySize};
+use crate::resource_usage::{self, ResourceUsage, SharedResourceUsage};
 
 /// A child process that can be interacted with asynchronously.
 ///
@@ -66,6 +67,7 @@ pub struct Child {
     /// Flag indicating this child is being stopped as part of a shutdown of the
     /// ProcessManager, rather than individually stopped.
     closing: Arc<AtomicBool>,
+    resource_usage: SharedResourceUsage,
     #[cfg(test)]
     _pty_test_guard: Option<Arc<PtyTestGuard>>,
 }
@@ -137,6 +139,8 @@ impl Child {
         // - the child process is killed (and doesn't have an exit code)
         // - the child process fails somehow (some syscall fails)
         let (exit_tx, exit_rx) = watch::channel(None);
+        let resource_usage = resource_usage::track(pid, exit_rx.clone());
+        let exit_usage = resource_usage.clone();
 
         let _task = tokio::spawn(async move {
             // On Windows it is important that this gets dropped once the child process
@@ -154,7 +158,11 @@ impl Child {
                     manager.shutdown_initiated = true;
                     manager.handle_child_command(command, &mut command_rx, &mut child, controller).await;
                 }
-                status = child.wait() => {
+                status = async {
+                    // the CPU time has to be read before the child is reaped
+                    resource_usage::record_exit(pid, &exit_usage).await;
+                    child.wait().await
+                } => {
                     drop(controller);
                     manager.handle_child_exit(status).await;
                 }
@@ -174,6 +182,7 @@ impl Child {
             label,
             shutdown_style,
             closing: Arc::new(AtomicBool::new(false)),
+            resource_usage,
             #[cfg(test)]
             _pty_test_guard: pty_test_guard,
         })
@@ -261,6 +270,16 @@ impl Child {
         &self.label
     }
 
+    /// The CPU time and peak memory used by the process and its descendants.
+    /// The CPU time is only filled in once the process has exited. Only
+    /// available on Linux.
+    pub fn resource_usage(&self) -> Option<ResourceUsage> {
+        *self
+            .resource_usage
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
     /// Mark this child as being stopped as part of a ProcessManager shutdown
     pub fn set_closing(&self) {
         self.closing.store(true, Ordering::Release);
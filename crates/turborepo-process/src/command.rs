@@ -106,6 +106,26 @@ impl Command {
     pub fn program(&self) -> &OsStr {
         &self.program
     }
+
+    /// Run the command via `program`, which receives `args` followed by the
+    /// original program and its arguments
+    pub(crate) fn wrap<I, S>(mut self, program: impl AsRef<OsStr>, args: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        let mut wrapper_args = args
+            .into_iter()
+            .map(|arg| arg.as_ref().to_os_string())
+            .collect::<Vec<_>>();
+        wrapper_args.push(std::mem::replace(
+            &mut self.program,
+            program.as_ref().to_os_string(),
+        ));
+        wrapper_args.append(&mut self.args);
+        self.args = wrapper_args;
+        self
+    }
 }
 
 impl From<Command> for std::process::Command {
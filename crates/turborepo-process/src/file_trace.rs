@@ -0,0 +1,201 @@
+//! Recording the files a command reads by running it under `strace`.
+//!
+//! `strace` follows every process the command spawns and logs each
+//! successful `open` with the path of the returned file descriptor. The log
+//! is parsed once the command exits to find which files were read.
+
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    fs::File,
+    io::{self, BufRead, BufReader},
+    path::Path,
+};
+
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
+
+use crate::Command;
+
+const OPEN_SYSCALLS: &[&str] = &["open", "openat", "openat2", "creat"];
+
+/// Open flags that mean a file is being written rather than read.
+const WRITE_FLAGS: &[&str] = &["O_WRONLY", "O_CREAT", "O_TRUNC"];
+
+/// Wraps `cmd` so that the files it and all of its descendants open are
+/// recorded to `trace_file`.
+///
+/// `strace` exits with the exit code of the traced command, so the wrapped
+/// command can be treated exactly like the original.
+pub fn trace_file_reads(cmd: Command, strace: &Path, trace_file: &AbsoluteSystemPath) -> Command {
+    let syscalls = format!("trace={}", OPEN_SYSCALLS.join(","));
+    cmd.wrap(
+        strace,
+        [
+            OsStr::new("-f"),
+            OsStr::new("-qq"),
+            // Print the path of file descriptors, including those returned by `open`
+            OsStr::new("-y"),
+            OsStr::new("-e"),
+            OsStr::new(&syscalls),
+            OsStr::new("-o"),
+            trace_file.as_std_path().as_os_str(),
+            OsStr::new("--"),
+        ],
+    )
+}
+
+/// Reads the trace written by a command wrapped with [`trace_file_reads`],
+/// returning the files that were opened for reading in the order they were
+/// first read.
+///
+/// Files the command created or wrote to before reading are not included as
+/// their contents came from the command itself.
+pub fn read_traced_files(
+    trace_file: &AbsoluteSystemPath,
+) -> io::Result<Vec<AbsoluteSystemPathBuf>> {
+    let trace = File::open(trace_file.as_std_path())?;
+    parse_trace(BufReader::new(trace))
+}
+
+fn parse_trace(trace: impl BufRead) -> io::Result<Vec<AbsoluteSystemPathBuf>> {
+    // Calls interrupted by another process are logged as `<unfinished ...>`
+    // and completed by a later `<... openat resumed>` line for the same pid
+    let mut unfinished: HashMap<String, String> = HashMap::new();
+    let mut written = HashSet::new();
+    let mut seen = HashSet::new();
+    let mut reads = Vec::new();
+
+    for line in trace.lines() {
+        let line = line?;
+        let (pid, call) = split_pid(&line);
+        let call = if let Some(start) = call.strip_suffix(" <unfinished ...>") {
+            unfinished.insert(pid.to_owned(), start.to_owned());
+            continue;
+        } else if let Some(resumed) = call.strip_prefix("<... ") {
+            let Some((_, rest)) = resumed.split_once(" resumed>") else {
+                continue;
+            };
+            let Some(start) = unfinished.remove(pid) else {
+                continue;
+            };
+            format!("{start}{rest}")
+        } else {
+            call.to_owned()
+        };
+
+        let Some(open) = OpenCall::parse(&call) else {
+            continue;
+        };
+        let Ok(path) = AbsoluteSystemPathBuf::new(open.path) else {
+            continue;
+        };
+        if open.writes {
+            written.insert(path);
+        } else if !written.contains(&path) && seen.insert(path.clone()) {
+            reads.push(path);
+        }
+    }
+
+    Ok(reads)
+}
+
+/// Splits the pid that prefixes each line when following forks.
+fn split_pid(line: &str) -> (&str, &str) {
+    if let Some(rest) = line.strip_prefix("[pid ")
+        && let Some((pid, call)) = rest.split_once(']')
+    {
+        return (pid.trim(), call.trim_start());
+    }
+    match line.split_once(' ') {
+        Some((pid, call)) if pid.bytes().all(|b| b.is_ascii_digit()) => (pid, call.trim_start()),
+        _ => ("", line),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct OpenCall<'a> {
+    path: &'a str,
+    writes: bool,
+}
+
+impl<'a> OpenCall<'a> {
+    /// Parses a successful open call, e.g.
+    /// `openat(AT_FDCWD, "index.js", O_RDONLY|O_CLOEXEC) = 3</repo/index.js>`
+    fn parse(call: &'a str) -> Option<Self> {
+        let (name, rest) = call.split_once('(')?;
+        if !OPEN_SYSCALLS.contains(&name) {
+            return None;
+        }
+        let (args, result) = rest.rsplit_once(") = ")?;
+        // Failed calls return -1 along with the error
+        let (fd, path) = result.split_once('<')?;
+        fd.parse::<u32>().ok()?;
+        let path = path.strip_suffix('>')?;
+        if args.contains("O_DIRECTORY") {
+            return None;
+        }
+
+        Some(Self {
+            path,
+            writes: name == "creat" || WRITE_FLAGS.iter().any(|flag| args.contains(flag)),
+        })
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::{OpenCall, parse_trace};
+
+    #[test_case(
+        r#"openat(AT_FDCWD, "src/index.ts", O_RDONLY|O_CLOEXEC) = 3</repo/src/index.ts>"#,
+        Some(OpenCall { path: "/repo/src/index.ts", writes: false })
+        ; "read"
+    )]
+    #[test_case(
+        r#"openat(AT_FDCWD, "dist/index.js", O_WRONLY|O_CREAT|O_TRUNC|O_CLOEXEC, 0666) = 4</repo/dist/index.js>"#,
+        Some(OpenCall { path: "/repo/dist/index.js", writes: true })
+        ; "write"
+    )]
+    #[test_case(
+        r#"openat2(AT_FDCWD, "a.txt", {flags=O_RDONLY, resolve=0}, 24) = 3</repo/a.txt>"#,
+        Some(OpenCall { path: "/repo/a.txt", writes: false })
+        ; "openat2"
+    )]
+    #[test_case(
+        r#"openat(AT_FDCWD, "missing.json", O_RDONLY) = -1 ENOENT (No such file or directory)"#,
+        None
+        ; "failed"
+    )]
+    #[test_case(
+        r#"openat(AT_FDCWD, "src", O_RDONLY|O_NONBLOCK|O_CLOEXEC|O_DIRECTORY) = 3</repo/src>"#,
+        None
+        ; "directory"
+    )]
+    #[test_case(
+        r#"read(3</repo/a.txt>, "", 4096) = 0"#,
+        None
+        ; "other syscall"
+    )]
+    fn test_parse_open_call(call: &str, expected: Option<OpenCall>) {
+        assert_eq!(OpenCall::parse(call), expected);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_parse_trace() {
+        let trace = r#"100 openat(AT_FDCWD, "/repo/a.txt", O_RDONLY) = 3</repo/a.txt>
+100 openat(AT_FDCWD, "/repo/out.txt", O_WRONLY|O_CREAT|O_TRUNC, 0666) = 3</repo/out.txt>
+101   openat(AT_FDCWD, "/repo/b.txt", O_RDONLY <unfinished ...>
+100 openat(AT_FDCWD, "/repo/out.txt", O_RDONLY) = 4</repo/out.txt>
+101   <... openat resumed>) = 5</repo/b.txt>
+101 --- SIGCHLD {si_signo=SIGCHLD, si_code=CLD_EXITED} ---
+101 openat(AT_FDCWD, "/repo/a.txt", O_RDONLY) = 3</repo/a.txt>
+"#;
+
+        let files = parse_trace(trace.as_bytes()).unwrap();
+        let files = files.iter().map(|file| file.as_str()).collect::<Vec<_>>();
+        assert_eq!(files, vec!["/repo/a.txt", "/repo/b.txt"]);
+    }
+}
@@ -14,8 +14,10 @@
 
 mod child;
 mod command;
+pub mod file_trace;
 #[cfg(windows)]
 mod job_object;
+mod resource_usage;
 
 use std::{
     collections::HashMap,
@@ -29,7 +31,10 @@ use tokio::task::JoinSet;
 use tracing::{debug, trace};
 use turborepo_task_id::TaskId;
 
-pub use self::child::{Child, ChildExit, ChildStdin};
+pub use self::{
+    child::{Child, ChildExit, ChildStdin},
+    resource_usage::ResourceUsage,
+};
 
 /// A process manager that is responsible for spawning and managing child
 /// processes. When the manager is Open, new child processes can be spawned
@@ -247,6 +252,28 @@ impl ProcessManager {
         self.close(CloseMode::Wait).await
     }
 
+    /// Wait for the children that are currently running to exit without
+    /// closing the process manager.
+    pub async fn wait_for_running(&self) {
+        let mut set = JoinSet::new();
+        {
+            let lock = self.lock_state();
+            for child in lock
+                .children
+                .values()
+                .flatten()
+                .filter(|child| !child.has_exited())
+            {
+                let mut child = child.clone();
+                set.spawn(async move { child.wait().await });
+            }
+        }
+
+        while let Some(out) = set.join_next().await {
+            trace!("process exited: {:?}", out);
+        }
+    }
+
     /// Close the process manager, running the given callback on each child
     ///
     /// note: this is designed to be called multiple times, ie calling close
@@ -623,6 +650,31 @@ mod test {
         assert!(child2.is_closing());
     }
 
+    #[tokio::test]
+    async fn test_wait_for_running_leaves_manager_open() {
+        let manager = ProcessManager::new(false);
+        let mut child = manager
+            .spawn(
+                get_script_command("hello_world.js"),
+                Duration::from_secs(2),
+                test_task_id(),
+            )
+            .unwrap()
+            .unwrap();
+
+        manager.wait_for_running().await;
+
+        assert_eq!(child.wait().await, Some(ChildExit::Finished(Some(0))));
+        assert!(manager.running_task_ids().is_empty());
+        assert!(!manager.is_closing());
+        assert!(
+            manager
+                .spawn(get_command(), Duration::from_secs(2), test_task_id())
+                .is_some()
+        );
+        manager.stop().await;
+    }
+
     #[tokio::test]
     async fn test_stop_tasks_during_close() {
         let manager = ProcessManager::new(false);
@@ -0,0 +1,337 @@
+//! `resource_usage`
+//!
+//! Measures the CPU time and memory used by a child process and all of its
+//! descendants. Other platforms than Linux don't report any usage.
+//!
+//! The CPU time is read once the child has exited but before it is reaped,
+//! from the child's own time and the time of the descendants that it waited
+//! for. Descendants that are orphaned instead of waited for aren't counted.
+//! The exit is observed through a pidfd registered with the tokio reactor, so
+//! no thread is held while the child runs. Kernels without pidfd support
+//! (before 5.3) don't report CPU time.
+//!
+//! Peak memory is sampled from `/proc` while the child runs, so a spike
+//! between two samples can be missed.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use tokio::sync::watch;
+
+use crate::ChildExit;
+
+/// Resources used by a process tree over its lifetime
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ResourceUsage {
+    pub user_time: Duration,
+    pub system_time: Duration,
+    /// The largest combined resident set size of the tree seen at once
+    pub peak_rss_bytes: u64,
+}
+
+pub(crate) type SharedResourceUsage = Arc<Mutex<Option<ResourceUsage>>>;
+
+fn update(usage: &SharedResourceUsage, f: impl FnOnce(&mut ResourceUsage)) {
+    let mut usage = usage
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    f(usage.get_or_insert_with(ResourceUsage::default));
+}
+
+/// Starts sampling the memory of the process tree rooted at `pid` until
+/// `exit` reports that the process has exited. Returns the usage collected so
+/// far, which stays `None` if the platform can't be sampled.
+pub(crate) fn track(
+    pid: Option<u32>,
+    exit: watch::Receiver<Option<ChildExit>>,
+) -> SharedResourceUsage {
+    let usage = SharedResourceUsage::default();
+    #[cfg(target_os = "linux")]
+    if let Some(pid) = pid {
+        tokio::spawn(linux::sample_until_exit(pid, exit, usage.clone()));
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = (pid, exit);
+    usage
+}
+
+/// Waits for `pid` to exit and records the CPU time used by it and its
+/// descendants.
+///
+/// This doesn't reap the process, and must finish before the process is
+/// reaped, since the totals are gone afterwards.
+pub(crate) async fn record_exit(pid: Option<u32>, usage: &SharedResourceUsage) {
+    #[cfg(target_os = "linux")]
+    if let Some(pid) = pid {
+        if let Some((user_time, system_time)) = linux::exit_totals(pid).await {
+            update(usage, |usage| {
+                usage.user_time = user_time;
+                usage.system_time = system_time;
+            });
+        }
+    }
+    #[cfg(not(target_os = "linux"))]
+    let _ = (pid, usage);
+}
+
+#[cfg(target_os = "linux")]
+mod linux {
+    use std::{
+        os::fd::{FromRawFd, OwnedFd, RawFd},
+        time::Duration,
+    };
+
+    use tokio::{
+        io::{Interest, unix::AsyncFd},
+        sync::watch,
+    };
+    use tracing::{debug, trace};
+
+    use super::{SharedResourceUsage, update};
+    use crate::ChildExit;
+
+    const SAMPLE_INTERVAL: Duration = Duration::from_millis(200);
+
+    pub(super) async fn sample_until_exit(
+        pid: u32,
+        mut exit: watch::Receiver<Option<ChildExit>>,
+        usage: SharedResourceUsage,
+    ) {
+        let mut sampler = Sampler::new();
+        let mut interval = tokio::time::interval(SAMPLE_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                biased;
+                // Also resolves if the sender is dropped
+                _ = exit.changed() => break,
+                _ = interval.tick() => {
+                    if exit.borrow().is_some() {
+                        break;
+                    }
+                    if sampler.sample(pid) {
+                        let peak_rss_bytes = sampler.peak_rss_bytes();
+                        update(&usage, |usage| usage.peak_rss_bytes = peak_rss_bytes);
+                    }
+                }
+            }
+        }
+        trace!("stopped sampling resource usage of {pid}");
+    }
+
+    /// Waits until `pid` exits, without reaping it, and returns the user and
+    /// system time used by it and the descendants it waited for
+    pub(super) async fn exit_totals(pid: u32) -> Option<(Duration, Duration)> {
+        let pidfd = match open_pidfd(pid) {
+            Ok(pidfd) => pidfd,
+            Err(error) => {
+                // The process was already reaped, or the kernel lacks pidfd support
+                debug!("unable to wait for {pid} to exit: {error}");
+                return None;
+            }
+        };
+        // A pidfd becomes readable once the process exits, and the process
+        // stays a zombie until it is reaped
+        let pidfd = AsyncFd::with_interest(pidfd, Interest::READABLE).ok()?;
+        if let Err(error) = pidfd.readable().await {
+            debug!("unable to wait for {pid} to exit: {error}");
+            return None;
+        }
+
+        // The stat of a zombie process is kept until it is reaped
+        let stat = read_stat(pid)?;
+        Some((
+            ticks_to_duration(stat.utime + stat.cutime),
+            ticks_to_duration(stat.stime + stat.cstime),
+        ))
+    }
+
+    fn open_pidfd(pid: u32) -> std::io::Result<OwnedFd> {
+        let fd = unsafe { libc::syscall(libc::SYS_pidfd_open, pid as libc::pid_t, 0) };
+        if fd < 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+        // SAFETY: the syscall returned a new file descriptor that nothing else owns
+        Ok(unsafe { OwnedFd::from_raw_fd(fd as RawFd) })
+    }
+
+    /// The fields of `/proc/<pid>/stat` that we use
+    #[derive(Debug, PartialEq, Eq)]
+    pub(super) struct ProcStat {
+        pub utime: u64,
+        pub stime: u64,
+        /// The time of the children that this process waited for
+        pub cutime: u64,
+        pub cstime: u64,
+        /// In pages
+        pub rss: u64,
+    }
+
+    /// Parses the contents of `/proc/<pid>/stat`. See `man 5 proc` for the
+    /// format.
+    pub(super) fn parse_stat(contents: &str) -> Option<ProcStat> {
+        // The command name is in parentheses and may itself contain spaces or
+        // parentheses, so start after the last closing parenthesis. Fields are
+        // numbered from 1 in the man page and the state (field 3) is first.
+        let (_, fields) = contents.rsplit_once(')')?;
+        let fields: Vec<&str> = fields.split_whitespace().collect();
+        let field = |number: usize| fields.get(number - 3)?.parse::<u64>().ok();
+        Some(ProcStat {
+            utime: field(14)?,
+            stime: field(15)?,
+            cutime: field(16)?,
+            cstime: field(17)?,
+            rss: field(24)?,
+        })
+    }
+
+    fn read_stat(pid: u32) -> Option<ProcStat> {
+        parse_stat(&std::fs::read_to_string(format!("/proc/{pid}/stat")).ok()?)
+    }
+
+    pub(super) fn ticks_to_duration(ticks: u64) -> Duration {
+        let ticks_per_second = u64::try_from(unsafe { libc::sysconf(libc::_SC_CLK_TCK) })
+            .ok()
+            .filter(|ticks| *ticks > 0)
+            .unwrap_or(100);
+        Duration::from_millis(ticks * 1000 / ticks_per_second)
+    }
+
+    /// The direct children of `pid`, across all of its threads
+    fn children(pid: u32) -> Vec<u32> {
+        let Ok(tasks) = std::fs::read_dir(format!("/proc/{pid}/task")) else {
+            return Vec::new();
+        };
+        tasks
+            .flatten()
+            .filter_map(|task| std::fs::read_to_string(task.path().join("children")).ok())
+            .flat_map(|children| {
+                children
+                    .split_whitespace()
+                    .filter_map(|child| child.parse().ok())
+                    .collect::<Vec<_>>()
+            })
+            .collect()
+    }
+
+    /// Reads the stats of `root` and all of its descendants
+    pub(super) fn process_tree(root: u32) -> Vec<ProcStat> {
+        let mut stats = Vec::new();
+        let mut pending = vec![root];
+        while let Some(pid) = pending.pop() {
+            // The process may have exited since we found it
+            let Some(stat) = read_stat(pid) else {
+                continue;
+            };
+            stats.push(stat);
+            pending.extend(children(pid));
+        }
+        stats
+    }
+
+    pub(super) struct Sampler {
+        peak_rss_pages: u64,
+        page_size: u64,
+    }
+
+    impl Sampler {
+        pub fn new() -> Self {
+            let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+            Self {
+                peak_rss_pages: 0,
+                page_size: u64::try_from(page_size)
+                    .ok()
+                    .filter(|size| *size > 0)
+                    .unwrap_or(4096),
+            }
+        }
+
+        /// Records a sample of the tree rooted at `root`, returning false if
+        /// the root process no longer exists
+        pub fn sample(&mut self, root: u32) -> bool {
+            let tree = process_tree(root);
+            if tree.is_empty() {
+                return false;
+            }
+            self.record(tree);
+            true
+        }
+
+        pub fn record(&mut self, tree: Vec<ProcStat>) {
+            let rss_pages = tree.iter().map(|stat| stat.rss).sum();
+            self.peak_rss_pages = self.peak_rss_pages.max(rss_pages);
+        }
+
+        pub fn peak_rss_bytes(&self) -> u64 {
+            self.peak_rss_pages * self.page_size
+        }
+    }
+}
+
+#[cfg(all(test, target_os = "linux"))]
+mod test {
+    use super::linux::*;
+
+    #[test]
+    fn test_parse_stat() {
+        let stat = "4242 (node (worker) 1) S 1 4242 4242 0 -1 4194560 2190 0 0 0 37 12 5 3 20 0 \
+                    11 0 123456 1180000000 25000 18446744073709551615 1 1 0 0 0 0 0 4096 16898 0 \
+                    0 0 17 3 0 0 0 0 0";
+
+        assert_eq!(
+            parse_stat(stat),
+            Some(ProcStat {
+                utime: 37,
+                stime: 12,
+                cutime: 5,
+                cstime: 3,
+                rss: 25000,
+            })
+        );
+        assert_eq!(parse_stat("4242 (node) S 1"), None);
+    }
+
+    #[test]
+    fn test_sampler_keeps_peak_rss() {
+        let stat = |rss| ProcStat {
+            utime: 0,
+            stime: 0,
+            cutime: 0,
+            cstime: 0,
+            rss,
+        };
+        let mut sampler = Sampler::new();
+
+        sampler.record(vec![stat(100), stat(300)]);
+        sampler.record(vec![stat(150), stat(50)]);
+
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as u64;
+        assert_eq!(sampler.peak_rss_bytes(), 400 * page_size);
+    }
+
+    #[test]
+    fn test_samples_current_process() {
+        let mut sampler = Sampler::new();
+
+        assert!(sampler.sample(std::process::id()));
+        assert!(sampler.peak_rss_bytes() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_exit_totals_include_exited_descendants() {
+        // All of the work happens in a subshell that exits before its parent
+        let mut child = std::process::Command::new("sh")
+            .arg("-c")
+            .arg("(i=0; while [ $i -lt 300000 ]; do i=$((i+1)); done); exit 0")
+            .spawn()
+            .expect("spawn sh");
+
+        let (user_time, system_time) = exit_totals(child.id()).await.expect("exit totals");
+        // the process is still waiting to be reaped
+        assert!(child.try_wait().expect("try_wait").is_some());
+
+        assert!(user_time + system_time >= ticks_to_duration(1));
+    }
+}
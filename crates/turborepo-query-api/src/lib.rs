@@ -57,10 +57,14 @@ pub trait QueryRun: Send + Sync + 'static {
     fn scm(&self) -> &turborepo_scm::SCM;
     fn root_turbo_json(&self) -> &turborepo_turbo_json::TurboJson;
 
+    /// Returns the packages changed between `base` and `head`, along with
+    /// their dependents. `depth` limits how many levels of dependents are
+    /// included.
     fn calculate_affected_packages(
         &self,
         base: Option<String>,
         head: Option<String>,
+        depth: Option<usize>,
     ) -> Result<HashMap<PackageName, PackageInclusionReason>, AffectedPackagesError>;
 
     /// Returns the set of files that changed between two git refs.
@@ -119,6 +123,45 @@ pub struct QueryResult {
 /// The standard GraphQL introspection query used by `turbo query --schema`.
 pub const SCHEMA_QUERY: &str = include_str!("schema_query.graphql");
 
+/// An event from a run in progress, published to GraphQL subscribers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunEvent {
+    /// The tasks in the run changed, e.g. when `turbo watch` starts a new run
+    TasksUpdated {
+        tasks: Vec<String>,
+    },
+    TaskStarted {
+        task: String,
+    },
+    /// The cache status of a task became known
+    TaskCacheStatus {
+        task: String,
+        status: String,
+        cache_hit: bool,
+    },
+    /// A complete line of task output
+    TaskLog {
+        task: String,
+        line: String,
+    },
+    TaskFinished {
+        task: String,
+        outcome: TaskOutcome,
+    },
+}
+
+/// How a task in a run finished.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskOutcome {
+    Success,
+    Failure,
+    CacheHit,
+    Stopped,
+}
+
+/// Publishes [`RunEvent`]s to every subscriber.
+pub type RunEventSender = tokio::sync::broadcast::Sender<RunEvent>;
+
 /// Abstraction over the query execution layer.
 ///
 /// `turborepo-lib` uses this trait to dispatch query operations without
@@ -144,6 +187,19 @@ pub trait QueryServer: Send + Sync {
         run: Arc<dyn QueryRun>,
         signal: turborepo_signals::SignalHandler,
     ) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send + '_>>;
+
+    /// Start a GraphQL server for a run in progress on `listener`.
+    ///
+    /// Subscriptions receive the events published on `events`. Unlike
+    /// [`QueryServer::run_query_server`] the browser isn't opened. Blocks
+    /// until the signal handler fires.
+    fn serve_run(
+        &self,
+        run: Arc<dyn QueryRun>,
+        signal: turborepo_signals::SignalHandler,
+        events: RunEventSender,
+        listener: std::net::TcpListener,
+    ) -> Pin<Box<dyn Future<Output = Result<(), Error>> + Send + '_>>;
 }
 
 // Compile-time assertions that both traits remain object-safe.
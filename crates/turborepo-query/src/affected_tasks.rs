@@ -62,7 +62,7 @@ pub fn calculate_affected_tasks(
     base: Option<String>,
     head: Option<String>,
 ) -> Result<Vec<AffectedTask>, Error> {
-    let affected_packages = run.calculate_affected_packages(base.clone(), head.clone())?;
+    let affected_packages = run.calculate_affected_packages(base.clone(), head.clone(), None)?;
 
     // Check if this is an "all packages changed" scenario
     let all_packages_reason = affected_packages.values().find_map(|reason| match reason {
@@ -328,6 +328,7 @@ mod tests {
             &self,
             _base: Option<String>,
             _head: Option<String>,
+            _depth: Option<usize>,
         ) -> Result<HashMap<PackageName, PackageInclusionReason>, AffectedPackagesError> {
             Ok(self.affected_packages.clone())
         }
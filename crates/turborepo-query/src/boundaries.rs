@@ -24,6 +24,7 @@ impl From<BoundariesDiagnostic> for Diagnostic {
                 span,
                 text: _,
                 path,
+                ..
             } => Diagnostic {
                 message,
                 path: Some(path.to_string()),
@@ -108,6 +109,16 @@ impl From<BoundariesDiagnostic> for Diagnostic {
                 import: None,
                 reason: None,
             },
+            BoundariesDiagnostic::UnusedDependency {
+                name, span, text, ..
+            } => Diagnostic {
+                message,
+                path: Some(text.name().to_string()),
+                start: span.map(|span| span.offset()),
+                end: span.map(|span| span.offset() + span.len()),
+                import: Some(name),
+                reason: None,
+            },
             BoundariesDiagnostic::CircularDependency { .. } => Diagnostic {
                 message,
                 path: None,
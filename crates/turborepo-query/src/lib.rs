@@ -7,6 +7,7 @@ mod file;
 mod package;
 mod package_graph;
 mod server;
+mod subscription;
 mod task;
 
 use std::{
@@ -22,12 +23,12 @@ use itertools::Itertools;
 use package::Package;
 use package_graph::{Edge, PackageGraph};
 pub use server::run_server;
-use tokio::select;
+use tokio::{net::TcpListener, select};
 use turbo_trace::TraceError;
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf};
 pub use turborepo_query_api::{
     AffectedPackagesError, BoundariesFuture, QueryErrorLocation, QueryResult, QueryRun,
-    SCHEMA_QUERY,
+    RunEventSender, SCHEMA_QUERY,
 };
 use turborepo_repository::{
     change_mapper::{AllPackageChangeReason, PackageInclusionReason},
@@ -624,10 +625,12 @@ impl RepositoryQuery {
         base: Option<String>,
         head: Option<String>,
         filter: Option<PackagePredicate>,
+        #[graphql(desc = "Limit how many levels of dependents of changed packages are included")]
+        depth: Option<usize>,
     ) -> Result<Array<ChangedPackage>, Error> {
         let mut packages = self
             .run
-            .calculate_affected_packages(base, head)?
+            .calculate_affected_packages(base, head, depth)?
             .into_iter()
             .map(|(package, reason)| {
                 Ok(ChangedPackage {
@@ -818,12 +821,14 @@ pub async fn graphiql() -> impl IntoResponse {
         GraphiQLSource::build()
             .version("5.0.0-rc.1")
             .endpoint("/")
+            .subscription_endpoint("/ws")
             .finish(),
     )
 }
 
 pub async fn run_query_server(run: Arc<dyn QueryRun>, signal: SignalHandler) -> Result<(), Error> {
     let subscriber = signal.subscribe().ok_or(Error::NoSignalHandler)?;
+    let listener = TcpListener::bind("127.0.0.1:8000").await?;
     println!("GraphiQL IDE: http://localhost:8000");
     webbrowser::open("http://localhost:8000")?;
     select! {
@@ -832,7 +837,29 @@ pub async fn run_query_server(run: Arc<dyn QueryRun>, signal: SignalHandler) ->
             println!("Shutting down GraphQL server");
             return Ok(());
         }
-        result = server::run_server(run) => {
+        result = server::run_server(run, None, listener) => {
+            result?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Serves queries and subscriptions to `events` for a run in progress on
+/// `listener` until the signal handler fires.
+pub async fn serve_run(
+    run: Arc<dyn QueryRun>,
+    signal: SignalHandler,
+    events: RunEventSender,
+    listener: std::net::TcpListener,
+) -> Result<(), Error> {
+    let subscriber = signal.subscribe().ok_or(Error::NoSignalHandler)?;
+    listener.set_nonblocking(true)?;
+    let listener = TcpListener::from_std(listener)?;
+    select! {
+        biased;
+        _ = subscriber.listen() => {}
+        result = server::run_server(run, Some(events), listener) => {
             result?;
         }
     }
@@ -855,6 +882,8 @@ pub async fn execute_query(
     query: &str,
     variables_json: Option<&str>,
 ) -> Result<QueryResult, Error> {
+    // Subscriptions can't be run as a one-off query, so they're only part of
+    // the schema served over HTTP
     let schema = Schema::new(RepositoryQuery::new(run), EmptyMutation, EmptySubscription);
 
     let variables: Variables = variables_json
@@ -1,18 +1,22 @@
 use std::sync::Arc;
 
-use async_graphql::{EmptyMutation, EmptySubscription, Schema};
-use async_graphql_axum::GraphQL;
+use async_graphql::{EmptyMutation, Schema};
+use async_graphql_axum::{GraphQL, GraphQLSubscription};
 use axum::{
     extract::Request,
-    http::{header::HOST, StatusCode},
+    http::{
+        header::{HOST, ORIGIN},
+        StatusCode,
+    },
     middleware::{self, Next},
     response::Response,
     routing::get,
     Router,
 };
 use tokio::net::TcpListener;
+use turborepo_query_api::RunEventSender;
 
-use crate::{graphiql, QueryRun, RepositoryQuery};
+use crate::{graphiql, subscription::RunSubscription, QueryRun, RepositoryQuery};
 
 fn is_allowed_host(host: &str) -> bool {
     let host = match host.rsplit_once(':') {
@@ -26,12 +30,25 @@ fn is_allowed_host(host: &str) -> bool {
     matches!(host, "127.0.0.1" | "localhost")
 }
 
-async fn require_localhost_host(request: Request, next: Next) -> Result<Response, StatusCode> {
-    let allowed = request
-        .headers()
+fn is_allowed_origin(origin: &str) -> bool {
+    origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+        .is_some_and(is_allowed_host)
+}
+
+async fn require_localhost(request: Request, next: Next) -> Result<Response, StatusCode> {
+    let headers = request.headers();
+    let allowed = headers
         .get(HOST)
         .and_then(|host| host.to_str().ok())
-        .is_some_and(is_allowed_host);
+        .is_some_and(is_allowed_host)
+        // Browsers send an origin with every WebSocket upgrade, so this keeps
+        // other sites from subscribing to run events. Non-browser clients
+        // don't send one.
+        && headers.get(ORIGIN).is_none_or(|origin| {
+            origin.to_str().ok().is_some_and(is_allowed_origin)
+        });
 
     if !allowed {
         return Err(StatusCode::FORBIDDEN);
@@ -40,22 +57,31 @@ async fn require_localhost_host(request: Request, next: Next) -> Result<Response
     Ok(next.run(request).await)
 }
 
-pub async fn run_server(run: Arc<dyn QueryRun>) -> std::io::Result<()> {
+/// Serves the schema on `listener`. Subscriptions receive `events` if given.
+pub async fn run_server(
+    run: Arc<dyn QueryRun>,
+    events: Option<RunEventSender>,
+    listener: TcpListener,
+) -> std::io::Result<()> {
     let turbo_query = RepositoryQuery::new(run);
 
-    let schema = Schema::new(turbo_query, EmptyMutation, EmptySubscription);
+    let schema = Schema::new(turbo_query, EmptyMutation, RunSubscription::new(events));
     let app = Router::new()
-        .route("/", get(graphiql).post_service(GraphQL::new(schema)))
-        .layer(middleware::from_fn(require_localhost_host));
+        .route(
+            "/",
+            get(graphiql).post_service(GraphQL::new(schema.clone())),
+        )
+        .route_service("/ws", GraphQLSubscription::new(schema))
+        .layer(middleware::from_fn(require_localhost));
 
-    axum::serve(TcpListener::bind("127.0.0.1:8000").await?, app).await?;
+    axum::serve(listener, app).await?;
 
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::is_allowed_host;
+    use super::{is_allowed_host, is_allowed_origin};
 
     #[test]
     fn allows_localhost_hosts() {
@@ -70,4 +96,18 @@ mod tests {
         assert!(!is_allowed_host("127.0.0.1:8000:extra"));
         assert!(!is_allowed_host("127.0.0.1:not-a-port"));
     }
+
+    #[test]
+    fn allows_localhost_origins() {
+        assert!(is_allowed_origin("http://localhost:8000"));
+        assert!(is_allowed_origin("https://127.0.0.1:8000"));
+    }
+
+    #[test]
+    fn rejects_non_localhost_origins() {
+        assert!(!is_allowed_origin("https://example.com"));
+        assert!(!is_allowed_origin("http://localhost.example.com:8000"));
+        assert!(!is_allowed_origin("localhost:8000"));
+        assert!(!is_allowed_origin("null"));
+    }
 }
@@ -0,0 +1,246 @@
+//! Subscriptions to the events of a run in progress.
+//!
+//! Events are only available when the server is started by `turbo run` or
+//! `turbo watch` with `--experimental-query-server`. The standalone `turbo
+//! query` server has no run to follow, so subscribing returns an error.
+
+use async_graphql::{Enum, Error, Result, SimpleObject, Subscription, Union};
+use futures::{stream, Stream, StreamExt};
+use tokio::sync::broadcast::error::RecvError;
+use turborepo_query_api::{RunEvent as Event, RunEventSender};
+
+pub struct RunSubscription {
+    events: Option<RunEventSender>,
+}
+
+impl RunSubscription {
+    pub fn new(events: Option<RunEventSender>) -> Self {
+        Self { events }
+    }
+
+    fn subscribe(&self) -> Result<impl Stream<Item = Event>> {
+        let events = self.events.as_ref().ok_or_else(|| {
+            Error::new(
+                "No run in progress. Start `turbo run` or `turbo watch` with \
+                 `--experimental-query-server` to subscribe to its events.",
+            )
+        })?;
+
+        Ok(stream::unfold(
+            events.subscribe(),
+            |mut receiver| async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok(event) => return Some((event, receiver)),
+                        // Subscribers that fall behind skip the events they missed
+                        // instead of holding up the run
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            },
+        ))
+    }
+}
+
+#[Subscription]
+impl RunSubscription {
+    /// Events from the run as tasks start, finish, and produce output.
+    /// Pass `tasks` to only receive events for those task IDs.
+    async fn run_events(&self, tasks: Option<Vec<String>>) -> Result<impl Stream<Item = RunEvent>> {
+        Ok(self.subscribe()?.filter_map(move |event| {
+            let event = is_for_tasks(&event, tasks.as_deref()).then(|| RunEvent::from(event));
+            async move { event }
+        }))
+    }
+
+    /// Lines of output from a single task
+    async fn task_logs(&self, task_id: String) -> Result<impl Stream<Item = String>> {
+        Ok(self.subscribe()?.filter_map(move |event| {
+            let line = match event {
+                Event::TaskLog { task, line } if task == task_id => Some(line),
+                _ => None,
+            };
+            async move { line }
+        }))
+    }
+}
+
+fn is_for_tasks(event: &Event, tasks: Option<&[String]>) -> bool {
+    let Some(tasks) = tasks else {
+        return true;
+    };
+    match event {
+        Event::TasksUpdated { .. } => true,
+        Event::TaskStarted { task }
+        | Event::TaskCacheStatus { task, .. }
+        | Event::TaskLog { task, .. }
+        | Event::TaskFinished { task, .. } => tasks.contains(task),
+    }
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq, Debug)]
+#[graphql(remote = "turborepo_query_api::TaskOutcome")]
+enum TaskOutcome {
+    Success,
+    Failure,
+    CacheHit,
+    Stopped,
+}
+
+#[derive(SimpleObject)]
+struct TasksUpdated {
+    task_ids: Vec<String>,
+}
+
+#[derive(SimpleObject)]
+struct TaskStarted {
+    task_id: String,
+}
+
+#[derive(SimpleObject)]
+struct TaskCacheStatus {
+    task_id: String,
+    status: String,
+    cache_hit: bool,
+}
+
+#[derive(SimpleObject)]
+struct TaskLog {
+    task_id: String,
+    line: String,
+}
+
+#[derive(SimpleObject)]
+struct TaskFinished {
+    task_id: String,
+    outcome: TaskOutcome,
+}
+
+#[derive(Union)]
+enum RunEvent {
+    TasksUpdated(TasksUpdated),
+    TaskStarted(TaskStarted),
+    TaskCacheStatus(TaskCacheStatus),
+    TaskLog(TaskLog),
+    TaskFinished(TaskFinished),
+}
+
+impl From<Event> for RunEvent {
+    fn from(event: Event) -> Self {
+        match event {
+            Event::TasksUpdated { tasks } => {
+                RunEvent::TasksUpdated(TasksUpdated { task_ids: tasks })
+            }
+            Event::TaskStarted { task } => RunEvent::TaskStarted(TaskStarted { task_id: task }),
+            Event::TaskCacheStatus {
+                task,
+                status,
+                cache_hit,
+            } => RunEvent::TaskCacheStatus(TaskCacheStatus {
+                task_id: task,
+                status,
+                cache_hit,
+            }),
+            Event::TaskLog { task, line } => RunEvent::TaskLog(TaskLog {
+                task_id: task,
+                line,
+            }),
+            Event::TaskFinished { task, outcome } => RunEvent::TaskFinished(TaskFinished {
+                task_id: task,
+                outcome: outcome.into(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use async_graphql::{EmptyMutation, Object, Request, Schema};
+    use tokio::sync::broadcast;
+    use turborepo_query_api::TaskOutcome;
+
+    use super::*;
+
+    struct Query;
+
+    #[Object]
+    impl Query {
+        async fn ok(&self) -> bool {
+            true
+        }
+    }
+
+    fn schema(events: Option<RunEventSender>) -> Schema<Query, EmptyMutation, RunSubscription> {
+        Schema::new(Query, EmptyMutation, RunSubscription::new(events))
+    }
+
+    #[tokio::test]
+    async fn test_run_events() {
+        let (events, _) = broadcast::channel(16);
+        let schema = schema(Some(events.clone()));
+        let mut stream = schema.execute_stream(Request::new(
+            r#"subscription {
+                runEvents(tasks: ["web#build"]) {
+                    __typename
+                    ... on TaskStarted { taskId }
+                    ... on TaskFinished { taskId outcome }
+                }
+            }"#,
+        ));
+
+        // The subscription is only registered once the stream is polled, so
+        // send the events from another task once polling has started
+        let sender = tokio::spawn(async move {
+            while events.receiver_count() == 0 {
+                tokio::task::yield_now().await;
+            }
+            for event in [
+                Event::TaskStarted {
+                    task: "docs#build".into(),
+                },
+                Event::TaskStarted {
+                    task: "web#build".into(),
+                },
+                Event::TaskFinished {
+                    task: "web#build".into(),
+                    outcome: TaskOutcome::CacheHit,
+                },
+            ] {
+                events.send(event).unwrap();
+            }
+        });
+
+        let started = stream.next().await.unwrap();
+        assert!(started.errors.is_empty(), "{:?}", started.errors);
+        assert_eq!(
+            started.data.into_json().unwrap(),
+            serde_json::json!({
+                "runEvents": { "__typename": "TaskStarted", "taskId": "web#build" }
+            })
+        );
+        let finished = stream.next().await.unwrap();
+        assert_eq!(
+            finished.data.into_json().unwrap(),
+            serde_json::json!({
+                "runEvents": {
+                    "__typename": "TaskFinished",
+                    "taskId": "web#build",
+                    "outcome": "CACHE_HIT"
+                }
+            })
+        );
+        sender.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_without_run() {
+        let schema = schema(None);
+        let mut stream = schema.execute_stream(Request::new(
+            r#"subscription { taskLogs(taskId: "web#build") }"#,
+        ));
+        let response = stream.next().await.unwrap();
+        assert_eq!(response.errors.len(), 1);
+        assert!(response.errors[0].message.starts_with("No run in progress"));
+    }
+}
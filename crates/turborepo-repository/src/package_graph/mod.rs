@@ -515,6 +515,61 @@ impl PackageGraph {
         dependents
     }
 
+    /// Like [`PackageGraph::dependencies`], but only includes packages at most
+    /// `depth` edges away from `node`.
+    ///
+    /// Example:
+    ///
+    /// a -> b -> c
+    ///
+    /// dependencies_within_depth(a, 1) = {b}
+    pub fn dependencies_within_depth<'a>(
+        &'a self,
+        node: &PackageNode,
+        depth: usize,
+    ) -> HashSet<&'a PackageNode> {
+        let mut dependencies = turborepo_graph_utils::transitive_closure_within_depth(
+            &self.graph,
+            self.node_lookup.get(node).cloned(),
+            petgraph::Direction::Outgoing,
+            depth,
+        );
+        // Root dependencies are implied direct dependencies of every package
+        if depth > 0 {
+            dependencies.extend(self.root_internal_dependencies());
+        }
+        dependencies.remove(node);
+        dependencies
+    }
+
+    /// Like [`PackageGraph::ancestors`], but only includes packages at most
+    /// `depth` edges away from `node`.
+    ///
+    /// Example:
+    ///
+    /// a -> b -> c
+    ///
+    /// ancestors_within_depth(c, 1) = {b}
+    pub fn ancestors_within_depth(
+        &self,
+        node: &PackageNode,
+        depth: usize,
+    ) -> HashSet<&PackageNode> {
+        // If node is a root dep, then *every* package directly depends on it
+        let mut dependents = if depth > 0 && self.root_internal_dependencies().contains(node) {
+            self.graph.node_weights().collect()
+        } else {
+            turborepo_graph_utils::transitive_closure_within_depth(
+                &self.graph,
+                self.node_lookup.get(node).cloned(),
+                petgraph::Direction::Incoming,
+                depth,
+            )
+        };
+        dependents.remove(node);
+        dependents
+    }
+
     pub fn root_internal_package_dependencies(&self) -> HashSet<WorkspacePackage> {
         let dependencies = self.root_internal_dependencies();
         dependencies
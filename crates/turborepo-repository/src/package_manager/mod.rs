@@ -1271,6 +1271,37 @@ impl PackageManager {
         }
     }
 
+    /// Returns the version specifier used to declare a dependency on another
+    /// package in the workspace. npm and yarn 1 don't support the
+    /// `workspace:` protocol, but resolve `*` to the workspace package.
+    pub fn workspace_dependency_specifier(&self) -> &'static str {
+        match self.lockfile_manager() {
+            PackageManager::Npm | PackageManager::Yarn => "*",
+            _ => "workspace:*",
+        }
+    }
+
+    /// Returns whether running a script with the package manager also runs
+    /// its `pre` and `post` scripts. pnpm 7 and later, and the managers with a
+    /// pnpm-compatible CLI, only do so when `enable-pre-post-scripts` is set
+    /// in `.npmrc`.
+    pub fn runs_pre_post_scripts(&self, repo_root: &AbsoluteSystemPath) -> bool {
+        match self {
+            PackageManager::Npm
+            | PackageManager::Yarn
+            | PackageManager::Pnpm6
+            | PackageManager::Bun => true,
+            PackageManager::Berry => false,
+            PackageManager::Pnpm
+            | PackageManager::Pnpm9
+            | PackageManager::Nub { .. }
+            | PackageManager::Aube { .. } => npmrc::NpmRc::from_file(repo_root)
+                .ok()
+                .and_then(|npmrc| npmrc.enable_pre_post_scripts)
+                .unwrap_or(false),
+        }
+    }
+
     /// Read catalog definitions from the package manager's configuration.
     /// Currently only pnpm supports catalogs in pnpm-workspace.yaml.
     pub fn read_catalogs(&self, repo_root: &AbsoluteSystemPath) -> Option<pnpm::PnpmCatalogs> {
@@ -2192,4 +2223,40 @@ mod tests {
 
         assert!(package_manager.link_workspace_packages(repo_root));
     }
+
+    #[test_case(PackageManager::Npm, "*")]
+    #[test_case(PackageManager::Yarn, "*")]
+    #[test_case(PackageManager::Berry, "workspace:*")]
+    #[test_case(PackageManager::Pnpm9, "workspace:*")]
+    #[test_case(PackageManager::Bun, "workspace:*")]
+    #[test_case(PackageManager::Nub { lockfile: Box::new(PackageManager::Npm) }, "*")]
+    #[test_case(PackageManager::Aube { lockfile: Box::new(PackageManager::Pnpm9) }, "workspace:*")]
+    fn test_workspace_dependency_specifier(pm: PackageManager, expected: &str) {
+        assert_eq!(pm.workspace_dependency_specifier(), expected);
+    }
+
+    #[test_case(PackageManager::Npm, true)]
+    #[test_case(PackageManager::Yarn, true)]
+    #[test_case(PackageManager::Pnpm6, true)]
+    #[test_case(PackageManager::Bun, true)]
+    #[test_case(PackageManager::Berry, false)]
+    #[test_case(PackageManager::Pnpm9, false)]
+    #[test_case(PackageManager::Nub { lockfile: Box::new(PackageManager::Npm) }, false)]
+    fn test_runs_pre_post_scripts(pm: PackageManager, expected: bool) {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::from_std_path(tmpdir.path()).unwrap();
+        assert_eq!(pm.runs_pre_post_scripts(repo_root), expected);
+    }
+
+    #[test]
+    fn test_pnpm_runs_pre_post_scripts_when_enabled() {
+        let tmpdir = tempfile::tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::from_std_path(tmpdir.path()).unwrap();
+        repo_root
+            .join_component(npmrc::NPMRC_FILENAME)
+            .create_with_contents("enable-pre-post-scripts=true\n")
+            .unwrap();
+
+        assert!(PackageManager::Pnpm9.runs_pre_post_scripts(repo_root));
+    }
 }
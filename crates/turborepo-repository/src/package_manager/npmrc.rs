@@ -15,7 +15,7 @@ pub enum Error {
 
 /// Representation of .npmrc used by both npm and pnpm to configure behavior
 /// The representation is intentionally incomplete and is only intended to
-/// contain settings that affect the package graph.
+/// contain settings that affect the package graph or how scripts are run.
 #[derive(Debug, PartialEq, Eq, Clone, Default)]
 pub struct NpmRc {
     /// Used by pnpm to determine whether dependencies
@@ -26,6 +26,9 @@ pub struct NpmRc {
     /// or each workspace gets its own lockfile. Defaults to true.
     /// When false, pnpm creates a pnpm-lock.yaml in each workspace directory.
     pub shared_workspace_lockfile: Option<bool>,
+    /// Used by pnpm to determine whether `pre` and `post` scripts are run
+    /// alongside a script. Defaults to false as of pnpm 7.
+    pub enable_pre_post_scripts: Option<bool>,
 }
 
 impl NpmRc {
@@ -54,9 +57,14 @@ impl NpmRc {
             .get_from::<&str>(None, "shared-workspace-lockfile")
             .and_then(parse_bool);
 
+        let enable_pre_post_scripts = ini
+            .get_from::<&str>(None, "enable-pre-post-scripts")
+            .and_then(parse_bool);
+
         Self {
             link_workspace_packages,
             shared_workspace_lockfile,
+            enable_pre_post_scripts,
         }
     }
 }
@@ -91,6 +99,7 @@ mod test {
             NpmRc {
                 link_workspace_packages: None,
                 shared_workspace_lockfile: None,
+                enable_pre_post_scripts: None,
             }
         );
     }
@@ -108,6 +117,7 @@ mod test {
             NpmRc {
                 link_workspace_packages: Some(false),
                 shared_workspace_lockfile: None,
+                enable_pre_post_scripts: Some(true),
             }
         );
     }
@@ -121,6 +131,7 @@ mod test {
             NpmRc {
                 link_workspace_packages: None,
                 shared_workspace_lockfile: Some(false),
+                enable_pre_post_scripts: None,
             }
         );
     }
@@ -134,6 +145,7 @@ mod test {
             NpmRc {
                 link_workspace_packages: None,
                 shared_workspace_lockfile: Some(true),
+                enable_pre_post_scripts: None,
             }
         );
     }
@@ -132,6 +132,28 @@ impl IncrementalTaskCache {
             .await
     }
 
+    /// Returns a function that finds the repo-relative paths of the files
+    /// currently matching any partition's output globs. It owns everything
+    /// it needs so the glob can run on a blocking thread.
+    pub(crate) fn output_files(
+        &self,
+    ) -> impl FnOnce() -> Result<Vec<AnchoredSystemPathBuf>, crate::Error> + Send + 'static {
+        let repo_root = self.repo_root.clone();
+        let package_dir = self.package_dir.clone();
+        let outputs = self
+            .partitions
+            .iter()
+            .map(|partition| partition.outputs.clone())
+            .collect::<Vec<_>>();
+        move || {
+            let mut files = Vec::new();
+            for outputs in &outputs {
+                files.extend(collect_partition_files(&repo_root, &package_dir, outputs)?);
+            }
+            Ok(files)
+        }
+    }
+
     /// Fetch incremental artifacts for all partitions sequentially.
     /// Sequential ordering ensures deterministic overlap resolution:
     /// later partitions overwrite earlier ones (last-write-wins by array
@@ -275,7 +297,7 @@ impl IncrementalTaskCache {
         }
 
         self.cache
-            .put(self.repo_root.clone(), key.to_owned(), files, 0)
+            .put(self.repo_root.clone(), key.to_owned(), files, 0, None)
             .await
             .map_err(crate::Error::Cache)?;
 
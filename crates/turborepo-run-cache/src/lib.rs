@@ -7,14 +7,16 @@
 //! - Integration with output watchers for output tracking
 //! - Task definition-aware output glob handling
 //! - Incremental cache management for tool-specific artifacts
+//! - Detection of files written outside of a task's declared outputs
 
 pub mod incremental;
+pub mod undeclared_outputs;
 
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     io::Write,
     str::FromStr,
-    sync::{Arc, Mutex},
+    sync::{Arc, Mutex, OnceLock},
     time::Duration,
 };
 
@@ -25,9 +27,13 @@ use turbopath::{
     AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPath, AnchoredSystemPathBuf,
 };
 use turborepo_cache::{
-    AsyncCache, CacheError, CacheHitMetadata, CacheOpts, CacheSource, http::UploadMap,
+    ArtifactDetails, AsyncCache, CacheError, CacheHitMetadata, CacheOpts, CacheSource,
+    http::UploadMap, inspect,
+};
+use turborepo_hash::{
+    FileHashes, TurboHash,
+    explain::{GlobalHashInputs, TaskHashInputs},
 };
-use turborepo_hash::{FileHashes, TurboHash};
 use turborepo_repository::package_graph::PackageInfo;
 use turborepo_scm::SCM;
 use turborepo_task_id::TaskId;
@@ -36,6 +42,7 @@ use turborepo_types::{
     OutputLogsMode, RunCacheOpts, TaskDefinition, TaskDefinitionExt, TaskOutputs, TaskOutputsExt,
 };
 use turborepo_ui::{ColorConfig, GREY, LogWriter, color, tui::event::CacheResult};
+use undeclared_outputs::UndeclaredFiles;
 
 /// Errors that can occur during cache operations.
 #[derive(Debug, thiserror::Error)]
@@ -58,6 +65,8 @@ pub enum Error {
     Scm(#[from] turborepo_scm::Error),
     #[error(transparent)]
     Path(#[from] turbopath::PathError),
+    #[error("Failed to inspect package directory: {0}")]
+    PackageSnapshot(#[source] std::io::Error),
 }
 
 /// Abstraction over output change tracking.
@@ -120,6 +129,19 @@ pub struct RunCache {
     /// True when `--remote-only` is active, skips on-disk file checks for
     /// incremental.
     remote_only: bool,
+    /// When a task misses the cache, report which of its hash inputs changed
+    /// since the most recent local cache entry for the task.
+    explain_misses: bool,
+    /// Whether artifacts, and so hash inputs, are written to the local cache
+    local_writes: bool,
+    /// The local cache directory, where hash inputs are recorded
+    cache_dir: AbsoluteSystemPathBuf,
+    /// The hash inputs recorded before this run, read on the first miss that
+    /// is explained
+    recorded_hash_inputs: OnceLock<Option<RecordedHashInputs>>,
+    /// The tasks running in each package while checking for undeclared
+    /// outputs
+    package_activity: Arc<undeclared_outputs::PackageActivity>,
 }
 
 /// Trait used to output cache information to user
@@ -151,6 +173,25 @@ impl RunCache {
             ui,
             errors_only_show_hash: run_cache_opts.errors_only_show_hash,
             remote_only,
+            explain_misses: run_cache_opts.explain_misses,
+            local_writes: cache_opts.cache.local.write,
+            cache_dir: AbsoluteSystemPathBuf::from_unknown(repo_root, cache_opts.cache_dir.clone()),
+            recorded_hash_inputs: OnceLock::new(),
+            package_activity: Arc::default(),
+        }
+    }
+
+    /// Records the global hash inputs of this run in the local cache. The
+    /// hash inputs recorded with each task only refer to them by hash.
+    pub fn record_global_hash_inputs(&self, inputs: &GlobalHashInputs) {
+        if !self.explain_misses || !self.local_writes {
+            return;
+        }
+        let Ok(json) = serde_json::to_string(inputs) else {
+            return;
+        };
+        if let Err(err) = inspect::write_global_hash_inputs(&self.cache_dir, &inputs.hash, &json) {
+            debug!("failed to record global hash inputs: {err}");
         }
     }
 
@@ -161,6 +202,7 @@ impl RunCache {
         workspace_info: &PackageInfo,
         task_id: TaskId<'static>,
         hash: &str,
+        hash_inputs: Option<Arc<TaskHashInputs>>,
     ) -> TaskCache {
         let log_file_path = self
             .repo_root
@@ -192,6 +234,7 @@ impl RunCache {
         TaskCache {
             expanded_outputs: Vec::new(),
             run_cache: self.clone(),
+            package_dir: self.repo_root.resolve(workspace_info.package_path()),
             repo_relative_globs,
             hash: hash.to_owned(),
             task_id,
@@ -203,6 +246,7 @@ impl RunCache {
             warnings: self.warnings.clone(),
             errors_only_show_hash: self.errors_only_show_hash,
             incremental_cache,
+            hash_inputs,
         }
     }
 
@@ -219,6 +263,45 @@ impl RunCache {
     }
 }
 
+/// Expands output globs into the files they match, following symlinked output
+/// roots that stay within the repository.
+fn glob_outputs(
+    repo_root: &AbsoluteSystemPath,
+    inclusions: &[globwalk::ValidatedGlob],
+    exclusions: &[globwalk::ValidatedGlob],
+) -> Result<HashSet<AbsoluteSystemPathBuf>, Error> {
+    let files = globwalk::globwalk(repo_root, inclusions, exclusions, globwalk::WalkType::All)?
+        .into_iter()
+        .chain(expand_symlinked_output_roots(
+            repo_root, inclusions, exclusions,
+        )?)
+        .collect::<HashSet<_>>();
+
+    for path in &files {
+        if !repo_root.contains(path) {
+            return Err(Error::OutputOutsideRepo(path.to_string()));
+        }
+    }
+
+    Ok(files)
+}
+
+/// Returns the repo-relative paths of the files currently matched by
+/// `outputs`.
+pub fn expand_outputs(
+    repo_root: &AbsoluteSystemPath,
+    outputs: &TaskOutputs,
+) -> Result<HashSet<AnchoredSystemPathBuf>, Error> {
+    Ok(glob_outputs(
+        repo_root,
+        &outputs.validated_inclusions()?,
+        &outputs.validated_exclusions()?,
+    )?
+    .into_iter()
+    .map(|path| AnchoredSystemPathBuf::relative_path_between(repo_root, &path))
+    .collect())
+}
+
 fn expand_symlinked_output_roots(
     repo_root: &AbsoluteSystemPath,
     inclusions: &[globwalk::ValidatedGlob],
@@ -341,6 +424,7 @@ fn exclusions_for_symlinked_prefix(
 pub struct TaskCache {
     expanded_outputs: Vec<AnchoredSystemPathBuf>,
     run_cache: Arc<RunCache>,
+    package_dir: AbsoluteSystemPathBuf,
     repo_relative_globs: TaskOutputs,
     hash: String,
     task_output_logs: OutputLogsMode,
@@ -357,6 +441,8 @@ pub struct TaskCache {
     /// Incremental cache for tool-specific artifacts, present only when the
     /// task has `incremental` partitions configured.
     incremental_cache: Option<incremental::IncrementalTaskCache>,
+    /// The inputs to the task's hash, recorded alongside its cache entry
+    hash_inputs: Option<Arc<TaskHashInputs>>,
 }
 
 impl TaskCache {
@@ -404,6 +490,59 @@ impl TaskCache {
         Ok(())
     }
 
+    /// Reports which hash inputs changed since the most recent local cache
+    /// entry for this task.
+    fn write_miss_explanation(&self, task_handle: &mut turborepo_log::grouping::TaskHandle) {
+        let Some(hash_inputs) = &self.hash_inputs else {
+            return;
+        };
+        let task_id = self.task_id.to_string();
+        let recorded = self.run_cache.recorded_hash_inputs.get_or_init(|| {
+            RecordedHashInputs::read(&self.run_cache.cache_dir)
+                .inspect_err(|err| debug!("failed to read local cache entries: {err}"))
+                .ok()
+        });
+        let previous = recorded.as_ref().and_then(|recorded| {
+            recorded
+                .for_task(&task_id)
+                .find(|previous| previous.hash != self.hash)
+        });
+
+        let mut lines = Vec::new();
+        match previous {
+            None => lines.push(
+                color!(
+                    self.ui,
+                    GREY,
+                    "no earlier cache entry for {} to compare against",
+                    task_id
+                )
+                .to_string(),
+            ),
+            Some(previous) => {
+                let changes = hash_inputs.changes_since(&previous);
+                if changes.is_empty() {
+                    lines.push(format!(
+                        "no recorded inputs changed since {}, the hash may differ because of a \
+                         different turbo version",
+                        color!(self.ui, GREY, "{}", previous.hash)
+                    ));
+                } else {
+                    lines.push(format!(
+                        "inputs changed since {}:",
+                        color!(self.ui, GREY, "{}", previous.hash)
+                    ));
+                    lines.extend(changes.iter().map(|change| format!("  - {change}")));
+                }
+            }
+        }
+
+        for line in lines {
+            let line = format!("{line}\n");
+            task_handle.task_output(turborepo_log::OutputChannel::Stdout, line.as_bytes());
+        }
+    }
+
     /// Write a cache status message to the task output stream.
     ///
     /// This renders as plain text with the task's prefix — matching
@@ -560,6 +699,9 @@ impl TaskCache {
                     )
                 };
                 self.write_status(task_handle, tui_sender, &message, CacheResult::Miss);
+                if self.run_cache.explain_misses {
+                    self.write_miss_explanation(task_handle);
+                }
 
                 return Ok(None);
             };
@@ -667,26 +809,11 @@ impl TaskCache {
 
         let validated_inclusions = self.repo_relative_globs.validated_inclusions()?;
         let validated_exclusions = self.repo_relative_globs.validated_exclusions()?;
-        let files_to_be_cached = globwalk::globwalk(
+        let files_to_be_cached = glob_outputs(
             &self.run_cache.repo_root,
             &validated_inclusions,
             &validated_exclusions,
-            globwalk::WalkType::All,
         )?;
-        let files_to_be_cached = files_to_be_cached
-            .into_iter()
-            .chain(expand_symlinked_output_roots(
-                &self.run_cache.repo_root,
-                &validated_inclusions,
-                &validated_exclusions,
-            )?)
-            .collect::<HashSet<_>>();
-
-        for path in &files_to_be_cached {
-            if !self.run_cache.repo_root.contains(path) {
-                return Err(Error::OutputOutsideRepo(path.to_string()));
-            }
-        }
 
         // If we're only caching the log output, *and* output globs are not empty,
         // we should warn the user
@@ -714,6 +841,13 @@ impl TaskCache {
                 self.hash.clone(),
                 relative_paths.clone(),
                 duration.as_millis() as u64,
+                Some(ArtifactDetails {
+                    task_id: self.task_id.to_string(),
+                    hash_inputs: self
+                        .hash_inputs
+                        .as_ref()
+                        .and_then(|inputs| serde_json::to_string(inputs).ok()),
+                }),
             )
             .await?;
 
@@ -750,6 +884,102 @@ impl TaskCache {
         &self.expanded_outputs
     }
 
+    /// Snapshot the package directory so files written by the task can be
+    /// found afterwards. Returns `None` when the task's outputs won't be
+    /// saved, as nothing would be missing from the cache.
+    ///
+    /// The package is walked on a blocking thread, as for root tasks it can
+    /// cover most of the repository.
+    pub async fn snapshot_package(
+        &self,
+    ) -> Result<Option<undeclared_outputs::PackageSnapshot>, Error> {
+        if self.caching_disabled || self.run_cache.writes_disabled {
+            return Ok(None);
+        }
+        let repo_root = self.run_cache.repo_root.clone();
+        let package_dir = self.package_dir.clone();
+        let activity = self.run_cache.package_activity.clone();
+        tokio::task::spawn_blocking(move || {
+            undeclared_outputs::PackageSnapshot::capture(&repo_root, &package_dir, &activity)
+        })
+        .await
+        .map_err(|e| Error::SpawnBlocking(e.to_string()))?
+        .map(Some)
+        .map_err(Error::PackageSnapshot)
+    }
+
+    /// Returns the files created or modified since `snapshot` was captured
+    /// that aren't matched by the task's `outputs` (or its incremental
+    /// partitions' outputs) and so won't be saved to the cache. Files matched
+    /// by an exclusion in `outputs` were deliberately left out of the cache,
+    /// so they count as declared.
+    pub async fn undeclared_outputs(
+        &self,
+        snapshot: undeclared_outputs::PackageSnapshot,
+    ) -> Result<Vec<AnchoredSystemPathBuf>, Error> {
+        let repo_root = self.run_cache.repo_root.clone();
+        let outputs = self.repo_relative_globs.clone();
+        let incremental_output_files = self
+            .incremental_cache
+            .as_ref()
+            .map(|incremental| incremental.output_files());
+        tokio::task::spawn_blocking(move || {
+            let changed_files = snapshot.changed_files().map_err(Error::PackageSnapshot)?;
+            if changed_files.is_empty() {
+                return Ok(changed_files);
+            }
+
+            let excluded_outputs = TaskOutputs {
+                inclusions: outputs.exclusions.clone(),
+                exclusions: Vec::new(),
+            };
+            let mut declared_outputs = expand_outputs(&repo_root, &outputs)?;
+            declared_outputs.extend(expand_outputs(&repo_root, &excluded_outputs)?);
+            if let Some(incremental_output_files) = incremental_output_files {
+                declared_outputs.extend(incremental_output_files()?);
+            }
+
+            Ok(changed_files
+                .into_iter()
+                .filter(|path| !declared_outputs.contains(path))
+                .collect())
+        })
+        .await
+        .map_err(|e| Error::SpawnBlocking(e.to_string()))?
+    }
+
+    /// Record a warning, printed at the end of the run, listing files the task
+    /// used without declaring them.
+    pub fn warn_undeclared_files(&self, kind: UndeclaredFiles, files: &[AnchoredSystemPathBuf]) {
+        let consequence = match kind {
+            UndeclaredFiles::Inputs => {
+                " or its dependencies, changes to these won't cause a cache miss"
+            }
+            UndeclaredFiles::Outputs { .. } => {
+                " key in `turbo.json`, these won't be restored from the cache"
+            }
+        };
+        let mut warning = format!(
+            "task {} {}{consequence}: {}",
+            self.task_id,
+            kind.description(),
+            undeclared_outputs::summarize(files)
+        );
+        if let UndeclaredFiles::Outputs {
+            ran_concurrently: true,
+        } = kind
+        {
+            warning.push_str(
+                " (other tasks ran in the same package at the same time and may have written some \
+                 of these)",
+            );
+        }
+        let _ = self
+            .warnings
+            .lock()
+            .map(|mut warnings| warnings.push(warning));
+    }
+
     /// Returns true if this task has incremental cache partitions configured
     /// AND caching is not fully disabled. Read/write flag checks are handled
     /// independently by `fetch_incremental` and `upload_incremental`.
@@ -859,6 +1089,7 @@ impl ConfigCache {
                         self.hash.clone(),
                         vec![self.anchored_path.clone()],
                         0,
+                        None,
                     )
                     .await
             }
@@ -898,6 +1129,50 @@ impl ConfigCache {
     }
 }
 
+/// The hash inputs recorded in the local cache, indexed by the task that
+/// recorded them
+pub struct RecordedHashInputs {
+    cache_dir: AbsoluteSystemPathBuf,
+    /// The hashes of each task's artifacts, most recent first
+    hashes: HashMap<String, Vec<String>>,
+}
+
+impl RecordedHashInputs {
+    pub fn read(cache_dir: &AbsoluteSystemPath) -> Result<Self, CacheError> {
+        let mut hashes: HashMap<String, Vec<String>> = HashMap::new();
+        for entry in inspect::list(cache_dir)? {
+            if let Some(task_id) = entry.task_id {
+                hashes.entry(task_id).or_default().push(entry.hash);
+            }
+        }
+        Ok(Self {
+            cache_dir: cache_dir.to_owned(),
+            hashes,
+        })
+    }
+
+    /// The hash inputs recorded for `task_id`, most recent first. Entries
+    /// written without hash inputs are skipped.
+    pub fn for_task(&self, task_id: &str) -> impl Iterator<Item = TaskHashInputs> + '_ {
+        self.hashes
+            .get(task_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|hash| {
+                let inputs = inspect::hash_inputs(&self.cache_dir, hash).ok()??;
+                let inputs: TaskHashInputs = serde_json::from_str(&inputs).ok()?;
+                let global = inspect::global_hash_inputs(&self.cache_dir, &inputs.global_hash)
+                    .ok()
+                    .flatten()
+                    .and_then(|global| serde_json::from_str(&global).ok());
+                Some(match global {
+                    Some(global) => inputs.with_global(Arc::new(global)),
+                    None => inputs,
+                })
+            })
+    }
+}
+
 /// Build a "cache hit produced by sha: <sha>" or "cache hit produced by sha:
 /// <sha> (dirty)" message for verbose logging. Returns `None` when no SHA is
 /// available.
@@ -946,6 +1221,7 @@ mod test {
             remote_cache_opts: None,
             cache_max_age: None,
             cache_max_size: None,
+            cache_deduplication: false,
         }
     }
 
@@ -985,11 +1261,17 @@ mod test {
             ui,
             errors_only_show_hash,
             remote_only: false,
+            explain_misses: false,
+            local_writes: true,
+            cache_dir: repo_root.join_components(&[".turbo", "cache"]),
+            recorded_hash_inputs: OnceLock::new(),
+            package_activity: Arc::default(),
         });
 
         TaskCache {
             expanded_outputs: Vec::new(),
             run_cache,
+            package_dir: repo_root.join_component("pkg"),
             repo_relative_globs: TaskOutputs {
                 inclusions: outputs,
                 exclusions: Vec::new(),
@@ -1004,6 +1286,7 @@ mod test {
             warnings,
             errors_only_show_hash,
             incremental_cache: None,
+            hash_inputs: None,
         }
     }
 
@@ -1684,4 +1967,72 @@ mod test {
 
         assert!(task_cache.expanded_outputs().contains(&expected));
     }
+
+    #[tokio::test]
+    async fn undeclared_outputs_excludes_declared_outputs() {
+        let temp = tempdir().unwrap();
+        let repo_dir = temp.path().join("repo");
+        std::fs::create_dir_all(repo_dir.join("pkg/src")).unwrap();
+        std::fs::write(repo_dir.join("pkg/src/index.ts"), b"source").unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(repo_dir.as_path()).unwrap();
+        let task_cache = task_cache_for_outputs(&repo_root, vec!["pkg/dist/**".to_string()]);
+
+        let snapshot = task_cache.snapshot_package().await.unwrap().unwrap();
+        std::fs::create_dir_all(repo_dir.join("pkg/dist")).unwrap();
+        std::fs::write(repo_dir.join("pkg/dist/index.js"), b"output").unwrap();
+        std::fs::write(repo_dir.join("pkg/index.d.ts"), b"types").unwrap();
+
+        let undeclared = task_cache.undeclared_outputs(snapshot).await.unwrap();
+
+        assert_eq!(
+            undeclared,
+            vec![
+                AnchoredSystemPathBuf::from_raw(format!(
+                    "pkg{}index.d.ts",
+                    std::path::MAIN_SEPARATOR_STR
+                ))
+                .unwrap()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn undeclared_outputs_excludes_negated_outputs() {
+        let temp = tempdir().unwrap();
+        let repo_dir = temp.path().join("repo");
+        std::fs::create_dir_all(repo_dir.join("pkg/src")).unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(repo_dir.as_path()).unwrap();
+        let mut task_cache = task_cache_for_outputs(&repo_root, vec!["pkg/.next/**".to_string()]);
+        task_cache.repo_relative_globs.exclusions = vec!["pkg/.next/cache/**".to_string()];
+
+        let snapshot = task_cache.snapshot_package().await.unwrap().unwrap();
+        std::fs::create_dir_all(repo_dir.join("pkg/.next/cache")).unwrap();
+        std::fs::write(repo_dir.join("pkg/.next/build-manifest.json"), b"{}").unwrap();
+        std::fs::write(repo_dir.join("pkg/.next/cache/webpack.pack"), b"cache").unwrap();
+        std::fs::write(repo_dir.join("pkg/src/generated.ts"), b"generated").unwrap();
+
+        let undeclared = task_cache.undeclared_outputs(snapshot).await.unwrap();
+
+        assert_eq!(
+            undeclared,
+            vec![
+                AnchoredSystemPathBuf::from_raw(
+                    ["pkg", "src", "generated.ts"].join(std::path::MAIN_SEPARATOR_STR)
+                )
+                .unwrap()
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn snapshot_package_skipped_when_caching_disabled() {
+        let temp = tempdir().unwrap();
+        let repo_dir = temp.path().join("repo");
+        std::fs::create_dir_all(repo_dir.join("pkg")).unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(repo_dir.as_path()).unwrap();
+        let mut task_cache = task_cache_for_outputs(&repo_root, vec!["pkg/dist/**".to_string()]);
+        task_cache.caching_disabled = true;
+
+        assert!(task_cache.snapshot_package().await.unwrap().is_none());
+    }
 }
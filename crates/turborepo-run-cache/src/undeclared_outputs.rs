@@ -0,0 +1,354 @@
+//! Detection of files a task writes outside of its declared `outputs`.
+//!
+//! Only files matching a task's `outputs` globs are saved to the cache, so
+//! anything else the task writes is missing when the task is later restored
+//! from a cache hit. To catch this, the package directory is snapshotted
+//! before the task runs and compared against its state after the task exits.
+//!
+//! Files can't be attributed to a task when another task runs in the same
+//! package at the same time, since either of them could have written them.
+//! [`PackageActivity`] tracks the tasks running in each package so that these
+//! files can be reported without failing the task.
+
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    sync::{Arc, Mutex},
+    time::SystemTime,
+};
+
+use tracing::debug;
+use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
+
+/// Directories that hold tooling state or installed dependencies rather than
+/// task inputs or outputs. Shared with the check for undeclared inputs.
+pub const IGNORED_DIRS: &[&str] = &["node_modules", ".git", ".turbo", ".yarn"];
+
+/// The number of files listed before the rest are summarized as a count.
+const MAX_LISTED_FILES: usize = 10;
+
+/// The files of a task being reported as undeclared.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UndeclaredFiles {
+    /// Files the task read that aren't part of its hash
+    Inputs,
+    /// Files the task wrote outside of its `outputs`. `ran_concurrently` is
+    /// set when other tasks ran in the package at the same time and may have
+    /// written some of them.
+    Outputs { ran_concurrently: bool },
+}
+
+impl UndeclaredFiles {
+    /// Describes what the task did with the files, e.g. "read files not
+    /// covered by its `inputs`".
+    pub fn description(&self) -> &'static str {
+        match self {
+            UndeclaredFiles::Inputs => "read files not covered by its `inputs`",
+            UndeclaredFiles::Outputs { .. } => "wrote files not covered by its `outputs`",
+        }
+    }
+}
+
+impl fmt::Display for UndeclaredFiles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UndeclaredFiles::Inputs => "undeclared inputs",
+            UndeclaredFiles::Outputs { .. } => "undeclared outputs",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+/// The tasks with a snapshot of each package directory, shared by every task
+/// in a run.
+#[derive(Debug, Default)]
+pub struct PackageActivity {
+    packages: Mutex<HashMap<AbsoluteSystemPathBuf, PackageTasks>>,
+}
+
+#[derive(Debug, Default)]
+struct PackageTasks {
+    running: usize,
+    /// The number of snapshots ever captured of the package
+    started: u64,
+}
+
+impl PackageActivity {
+    /// Records a task starting in `package_dir`. Returns whether other tasks
+    /// are already running there, along with the number of tasks started so
+    /// far.
+    fn start(&self, package_dir: &AbsoluteSystemPath) -> (bool, u64) {
+        let Ok(mut packages) = self.packages.lock() else {
+            return (true, 0);
+        };
+        let tasks = packages.entry(package_dir.to_owned()).or_default();
+        tasks.running += 1;
+        tasks.started += 1;
+        (tasks.running > 1, tasks.started)
+    }
+
+    fn started(&self, package_dir: &AbsoluteSystemPath) -> u64 {
+        self.packages
+            .lock()
+            .ok()
+            .and_then(|packages| packages.get(package_dir).map(|tasks| tasks.started))
+            .unwrap_or(u64::MAX)
+    }
+
+    fn finish(&self, package_dir: &AbsoluteSystemPath) {
+        if let Ok(mut packages) = self.packages.lock()
+            && let Some(tasks) = packages.get_mut(package_dir)
+        {
+            tasks.running = tasks.running.saturating_sub(1);
+        }
+    }
+}
+
+/// The modification time and size of every file in a package directory.
+///
+/// Directories belonging to other workspace packages (those with their own
+/// `package.json`) are skipped so a root task doesn't snapshot the whole
+/// repository.
+#[derive(Debug)]
+pub struct PackageSnapshot {
+    repo_root: AbsoluteSystemPathBuf,
+    package_dir: AbsoluteSystemPathBuf,
+    files: HashMap<AnchoredSystemPathBuf, FileStamp>,
+    activity: Arc<PackageActivity>,
+    /// Whether another task was running in the package when this snapshot
+    /// was captured
+    started_alongside_others: bool,
+    /// The number of tasks started in the package up to this one
+    started: u64,
+}
+
+impl PackageSnapshot {
+    /// Snapshots `package_dir`, recording the task as running there until the
+    /// snapshot is dropped.
+    pub fn capture(
+        repo_root: &AbsoluteSystemPath,
+        package_dir: &AbsoluteSystemPath,
+        activity: &Arc<PackageActivity>,
+    ) -> io::Result<Self> {
+        let (started_alongside_others, started) = activity.start(package_dir);
+        let mut snapshot = Self {
+            repo_root: repo_root.to_owned(),
+            package_dir: package_dir.to_owned(),
+            files: HashMap::new(),
+            activity: activity.clone(),
+            started_alongside_others,
+            started,
+        };
+        walk_dir(repo_root, package_dir, &mut snapshot.files)?;
+        Ok(snapshot)
+    }
+
+    /// Returns the repo-relative paths of files that were created or modified
+    /// since the snapshot was captured, in sorted order.
+    pub fn changed_files(&self) -> io::Result<Vec<AnchoredSystemPathBuf>> {
+        let mut current = HashMap::new();
+        walk_dir(&self.repo_root, &self.package_dir, &mut current)?;
+        let mut changed = current
+            .into_iter()
+            .filter(|(path, stamp)| self.files.get(path) != Some(stamp))
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+        changed.sort();
+        Ok(changed)
+    }
+
+    /// Whether another task ran in the same package while this snapshot was
+    /// held, in which case the changed files may have been written by it.
+    pub fn ran_concurrently(&self) -> bool {
+        self.started_alongside_others || self.activity.started(&self.package_dir) > self.started
+    }
+}
+
+impl Drop for PackageSnapshot {
+    fn drop(&mut self) {
+        self.activity.finish(&self.package_dir);
+    }
+}
+
+/// Formats a list of files for display, truncating long lists.
+pub fn summarize(files: &[AnchoredSystemPathBuf]) -> String {
+    let listed = files
+        .iter()
+        .take(MAX_LISTED_FILES)
+        .map(|file| file.as_str())
+        .collect::<Vec<_>>()
+        .join(", ");
+    match files.len().checked_sub(MAX_LISTED_FILES) {
+        Some(remaining) if remaining > 0 => format!("{listed} and {remaining} more"),
+        _ => listed,
+    }
+}
+
+fn walk_dir(
+    repo_root: &AbsoluteSystemPath,
+    dir: &AbsoluteSystemPath,
+    files: &mut HashMap<AnchoredSystemPathBuf, FileStamp>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(dir.as_std_path())? {
+        let entry = entry?;
+        let Some(name) = entry.file_name().to_str().map(str::to_owned) else {
+            debug!("skipping non-UTF-8 path in {dir}: {:?}", entry.file_name());
+            continue;
+        };
+        let path = dir.join_component(&name);
+        // Files can be removed while we walk, those are simply not recorded
+        let metadata = match entry.metadata() {
+            Ok(metadata) => metadata,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => return Err(err),
+        };
+
+        if metadata.is_dir() {
+            let is_nested_package = path.join_component("package.json").exists();
+            if is_nested_package || IGNORED_DIRS.contains(&name.as_str()) {
+                continue;
+            }
+            match walk_dir(repo_root, &path, files) {
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                result => result?,
+            }
+        } else {
+            files.insert(
+                AnchoredSystemPathBuf::relative_path_between(repo_root, &path),
+                FileStamp {
+                    modified: metadata.modified().ok(),
+                    len: metadata.len(),
+                },
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+
+    use tempfile::tempdir;
+    use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPathBuf};
+
+    use super::{PackageActivity, PackageSnapshot, summarize};
+
+    fn anchored(path: &str) -> AnchoredSystemPathBuf {
+        AnchoredSystemPathBuf::from_raw(path.replace('/', std::path::MAIN_SEPARATOR_STR)).unwrap()
+    }
+
+    #[test]
+    fn changed_files_reports_created_and_modified_files() {
+        let temp = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(temp.path()).unwrap();
+        let package_dir = repo_root.join_components(&["packages", "app"]);
+        package_dir.join_component("src").create_dir_all().unwrap();
+        package_dir
+            .join_components(&["src", "index.ts"])
+            .create_with_contents("source")
+            .unwrap();
+        package_dir
+            .join_component("tsconfig.tsbuildinfo")
+            .create_with_contents("{}")
+            .unwrap();
+
+        let snapshot = PackageSnapshot::capture(&repo_root, &package_dir, &Arc::default()).unwrap();
+        assert!(snapshot.changed_files().unwrap().is_empty());
+
+        package_dir.join_component("dist").create_dir_all().unwrap();
+        package_dir
+            .join_components(&["dist", "index.js"])
+            .create_with_contents("output")
+            .unwrap();
+        package_dir
+            .join_component("tsconfig.tsbuildinfo")
+            .create_with_contents("{\"version\":\"5\"}")
+            .unwrap();
+
+        assert_eq!(
+            snapshot.changed_files().unwrap(),
+            vec![
+                anchored("packages/app/dist/index.js"),
+                anchored("packages/app/tsconfig.tsbuildinfo"),
+            ]
+        );
+    }
+
+    #[test]
+    fn capture_skips_tooling_dirs_and_nested_packages() {
+        let temp = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(temp.path()).unwrap();
+        let snapshot = PackageSnapshot::capture(&repo_root, &repo_root, &Arc::default()).unwrap();
+
+        for dir in [
+            &["node_modules", "dep"][..],
+            &[".git"],
+            &[".turbo"],
+            &["packages", "lib"],
+        ] {
+            let dir = repo_root.join_components(dir);
+            dir.create_dir_all().unwrap();
+            dir.join_component("package.json")
+                .create_with_contents("{}")
+                .unwrap();
+        }
+
+        assert!(snapshot.changed_files().unwrap().is_empty());
+    }
+
+    #[test]
+    fn overlapping_tasks_in_a_package_ran_concurrently() {
+        let temp = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(temp.path()).unwrap();
+        let package_dir = repo_root.join_components(&["packages", "app"]);
+        let other_package_dir = repo_root.join_components(&["packages", "lib"]);
+        package_dir.create_dir_all().unwrap();
+        other_package_dir.create_dir_all().unwrap();
+        let activity = Arc::new(PackageActivity::default());
+
+        // `build` starts first and `lint` starts while it's running
+        let build = PackageSnapshot::capture(&repo_root, &package_dir, &activity).unwrap();
+        let lint = PackageSnapshot::capture(&repo_root, &package_dir, &activity).unwrap();
+        let other = PackageSnapshot::capture(&repo_root, &other_package_dir, &activity).unwrap();
+        package_dir
+            .join_component(".eslintcache")
+            .create_with_contents("{}")
+            .unwrap();
+
+        // Both see the file, but neither can tell which of them wrote it
+        assert_eq!(
+            build.changed_files().unwrap(),
+            lint.changed_files().unwrap()
+        );
+        assert!(build.ran_concurrently());
+        assert!(lint.ran_concurrently());
+        assert!(!other.ran_concurrently());
+        drop(build);
+        drop(lint);
+
+        // Tasks that run one after another don't overlap
+        let build = PackageSnapshot::capture(&repo_root, &package_dir, &activity).unwrap();
+        assert!(!build.ran_concurrently());
+    }
+
+    #[test]
+    fn summarize_truncates_long_lists() {
+        let files = (0..12)
+            .map(|i| anchored(&format!("file{i}.txt")))
+            .collect::<Vec<_>>();
+
+        assert_eq!(summarize(&files[..2]), "file0.txt, file1.txt");
+        assert_eq!(
+            summarize(&files),
+            "file0.txt, file1.txt, file2.txt, file3.txt, file4.txt, file5.txt, file6.txt, \
+             file7.txt, file8.txt, file9.txt and 2 more"
+        );
+    }
+}
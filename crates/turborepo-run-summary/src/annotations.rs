@@ -0,0 +1,103 @@
+//! GitHub Actions workflow commands that annotate failed tasks.
+//!
+//! Annotations point at the `package.json` of each failed task's package so
+//! failures are listed on the pull request alongside the workflow run.
+
+use turbopath::AnchoredSystemPath;
+
+use crate::task::TaskSummary;
+
+/// Returns an `::error` workflow command for each failed task, in task order.
+pub(crate) fn github_annotations(tasks: &[TaskSummary]) -> Vec<String> {
+    let mut failed_tasks = tasks
+        .iter()
+        .filter_map(|task| {
+            let execution = task.shared.execution.as_ref()?;
+            execution.is_failure().then_some((task, execution))
+        })
+        .collect::<Vec<_>>();
+    failed_tasks.sort_by(|(a, _), (b, _)| a.task_id.cmp(&b.task_id));
+
+    failed_tasks
+        .into_iter()
+        .map(|(task, execution)| {
+            let mut properties = Vec::new();
+            if let Some(package_json) = task
+                .shared
+                .directory
+                .as_deref()
+                .and_then(|directory| AnchoredSystemPath::new(directory).ok())
+                .map(|directory| directory.join_component("package.json").to_unix())
+            {
+                properties.push(format!("file={}", escape_property(package_json.as_str())));
+            }
+            properties.push(format!(
+                "title={}",
+                escape_property(&format!("{} failed", task.task_id))
+            ));
+            format!(
+                "::error {}::{}",
+                properties.join(","),
+                escape_data(&execution.failure_message())
+            )
+        })
+        .collect()
+}
+
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod test {
+    use test_case::test_case;
+
+    use super::{escape_data, escape_property, github_annotations};
+    use crate::test_cases::{execution, task_summary};
+
+    #[test]
+    fn test_github_annotations() {
+        let tasks = [
+            task_summary(
+                "web",
+                "build",
+                "apps/web",
+                true,
+                Some(execution(0, 1500, 0)),
+            ),
+            task_summary(
+                "web",
+                "test",
+                "apps/web",
+                false,
+                Some(execution(0, 2000, 1)),
+            ),
+            task_summary("docs", "lint", "apps/docs", false, None),
+        ];
+
+        assert_eq!(
+            github_annotations(&tasks),
+            vec!["::error file=apps/web/package.json,title=web#test failed::command exited (1)"]
+        );
+    }
+
+    #[test_case("exited (1)", "exited (1)" ; "plain")]
+    #[test_case("100% done\nnext", "100%25 done%0Anext" ; "newline and percent")]
+    fn test_escape_data(data: &str, expected: &str) {
+        assert_eq!(escape_data(data), expected);
+    }
+
+    #[test_case("web#build failed", "web#build failed" ; "plain")]
+    #[test_case("a:b,c", "a%3Ab%2Cc" ; "separators")]
+    fn test_escape_property(property: &str, expected: &str) {
+        assert_eq!(escape_property(property), expected);
+    }
+}
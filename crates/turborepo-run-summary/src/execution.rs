@@ -7,7 +7,10 @@ use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPath};
 use turborepo_task_id::TaskId;
 use turborepo_ui::{BOLD, BOLD_GREEN, BOLD_RED, ColorConfig, MAGENTA, color};
 
-use crate::{TurboDuration, task::TaskExecutionSummary};
+use crate::{
+    TurboDuration,
+    task::{TaskAttemptSummary, TaskExecutionSummary, TaskResourceUsage},
+};
 
 // Just used to make changing the type that gets passed to the state management
 // thread easy
@@ -43,6 +46,10 @@ pub struct ExecutionSummary<'a> {
     #[serde(skip)]
     duration: TurboDuration,
     pub exit_code: i32,
+    // total CPU time of all executed tasks, with the peak memory of the
+    // hungriest task
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resource_usage: Option<TaskResourceUsage>,
 }
 
 impl<'a> ExecutionSummary<'a> {
@@ -55,6 +62,11 @@ impl<'a> ExecutionSummary<'a> {
         end_time: DateTime<Local>,
     ) -> Self {
         let duration = TurboDuration::new(&start_time, &end_time);
+        let resource_usage = state
+            .tasks
+            .iter()
+            .filter_map(|task| task.execution.as_ref()?.resource_usage)
+            .reduce(TaskResourceUsage::merge);
         Self {
             command,
             success: state.success,
@@ -67,9 +79,15 @@ impl<'a> ExecutionSummary<'a> {
             end_time: end_time.timestamp_millis(),
             duration,
             exit_code,
+            resource_usage,
         }
     }
 
+    // used to name the JUnit report
+    pub(crate) fn command(&self) -> &str {
+        &self.command
+    }
+
     /// We implement this on `ExecutionSummary` and not `RunSummary` because
     /// the `execution` field is nullable (due to normalize).
     pub fn print<T: TaskSummaryInfo>(
@@ -238,6 +256,10 @@ pub struct TaskTracker<T> {
     sender: mpsc::Sender<Message>,
     started_at: T,
     task_id: TaskId<'static>,
+    // Attempts that failed and were retried
+    attempts: Vec<TaskAttemptSummary>,
+    // Usage of every attempt so far
+    resource_usage: Option<TaskResourceUsage>,
 }
 
 #[derive(Debug, Clone)]
@@ -295,6 +317,8 @@ impl ExecutionTracker {
             sender: self.sender.clone(),
             task_id,
             started_at: (),
+            attempts: Vec::new(),
+            resource_usage: None,
         }
     }
 
@@ -326,13 +350,19 @@ impl TaskTracker<()> {
             sender,
             task_id,
             started_at: (),
+            attempts: Vec::new(),
+            resource_usage: None,
         }
     }
 
     // Start the tracker
     pub async fn start(self) -> TaskTracker<DateTime<Local>> {
         let TaskTracker {
-            sender, task_id, ..
+            sender,
+            task_id,
+            attempts,
+            resource_usage,
+            ..
         } = self;
         let started_at = Local::now();
         sender
@@ -346,6 +376,8 @@ impl TaskTracker<()> {
             sender,
             started_at,
             task_id,
+            attempts,
+            resource_usage,
         }
     }
 
@@ -373,11 +405,30 @@ impl TaskTracker<chrono::DateTime<Local>> {
     // internal turbo error
     pub fn cancel(self) {}
 
+    // Track a failed attempt of a task that is about to be retried
+    pub fn attempt_failed(&mut self, exit_code: Option<i32>) {
+        record_attempt(
+            &mut self.attempts,
+            &self.started_at,
+            Local::now(),
+            exit_code,
+        );
+    }
+
+    // Track the resources used by an attempt of the task
+    pub fn record_resource_usage(&mut self, usage: TaskResourceUsage) {
+        self.resource_usage = Some(match self.resource_usage {
+            Some(previous) => previous.merge(usage),
+            None => usage,
+        });
+    }
+
     pub async fn cached(self) -> TaskExecutionSummary {
         let Self {
             sender,
             started_at,
             task_id,
+            ..
         } = self;
 
         let ended_at = Local::now();
@@ -387,6 +438,9 @@ impl TaskTracker<chrono::DateTime<Local>> {
             // Go synthesizes a zero exit code on cache hits
             exit_code: Some(0),
             error: None,
+            timed_out: false,
+            attempts: Vec::new(),
+            resource_usage: None,
         };
 
         let state = TaskState {
@@ -408,14 +462,22 @@ impl TaskTracker<chrono::DateTime<Local>> {
             sender,
             started_at,
             task_id,
+            mut attempts,
+            resource_usage,
         } = self;
 
         let ended_at = Local::now();
+        if !attempts.is_empty() {
+            record_attempt(&mut attempts, &started_at, ended_at, Some(exit_code));
+        }
         let execution = TaskExecutionSummary {
             start_time: started_at.timestamp_millis(),
             end_time: ended_at.timestamp_millis(),
             exit_code: Some(exit_code),
             error: None,
+            timed_out: false,
+            attempts,
+            resource_usage,
         };
 
         let state = TaskState {
@@ -436,19 +498,41 @@ impl TaskTracker<chrono::DateTime<Local>> {
         self,
         exit_code: Option<i32>,
         error: impl fmt::Display,
+    ) -> TaskExecutionSummary {
+        self.failed(exit_code, error.to_string(), false).await
+    }
+
+    // Track that the task was stopped after exceeding its configured timeout
+    pub async fn build_timed_out(self, error: impl fmt::Display) -> TaskExecutionSummary {
+        self.failed(None, error.to_string(), true).await
+    }
+
+    async fn failed(
+        self,
+        exit_code: Option<i32>,
+        error: String,
+        timed_out: bool,
     ) -> TaskExecutionSummary {
         let Self {
             sender,
             started_at,
             task_id,
+            mut attempts,
+            resource_usage,
         } = self;
 
         let ended_at = Local::now();
+        if !attempts.is_empty() {
+            record_attempt(&mut attempts, &started_at, ended_at, exit_code);
+        }
         let execution = TaskExecutionSummary {
             start_time: started_at.timestamp_millis(),
             end_time: ended_at.timestamp_millis(),
             exit_code,
-            error: Some(error.to_string()),
+            error: Some(error),
+            timed_out,
+            attempts,
+            resource_usage,
         };
 
         let state = TaskState {
@@ -466,6 +550,24 @@ impl TaskTracker<chrono::DateTime<Local>> {
     }
 }
 
+// Each attempt starts when the previous one ended, or when the task started
+// for the first attempt.
+fn record_attempt(
+    attempts: &mut Vec<TaskAttemptSummary>,
+    started_at: &DateTime<Local>,
+    ended_at: DateTime<Local>,
+    exit_code: Option<i32>,
+) {
+    let start_time = attempts
+        .last()
+        .map_or_else(|| started_at.timestamp_millis(), |attempt| attempt.end_time);
+    attempts.push(TaskAttemptSummary {
+        start_time,
+        end_time: ended_at.timestamp_millis(),
+        exit_code,
+    });
+}
+
 #[cfg(test)]
 mod test {
     use chrono::Duration;
@@ -588,12 +690,47 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_resource_usage_across_retries() -> Result<(), Box<dyn std::error::Error>> {
+        let summary = ExecutionTracker::new();
+        let mut tracker = summary
+            .task_tracker(TaskId::new("foo", "build"))
+            .start()
+            .await;
+        tracker.record_resource_usage(TaskResourceUsage {
+            user_cpu_ms: 100,
+            system_cpu_ms: 10,
+            peak_rss_bytes: 2048,
+        });
+        tracker.attempt_failed(Some(1));
+        tracker.record_resource_usage(TaskResourceUsage {
+            user_cpu_ms: 50,
+            system_cpu_ms: 5,
+            peak_rss_bytes: 1024,
+        });
+        let execution = tracker.build_succeeded(0).await;
+
+        assert_eq!(
+            execution.resource_usage,
+            Some(TaskResourceUsage {
+                user_cpu_ms: 150,
+                system_cpu_ms: 15,
+                peak_rss_bytes: 2048,
+            })
+        );
+        summary.finish().await?;
+        Ok(())
+    }
+
     #[test_case(
         TaskExecutionSummary {
             start_time: 123,
             end_time: 234,
             exit_code: Some(0),
-            error: None
+            error: None,
+            timed_out: false,
+            attempts: vec![],
+            resource_usage: None,
         },
         json!({ "startTime": 123, "endTime": 234, "exitCode": 0 })
         ; "success"
@@ -604,10 +741,78 @@ mod test {
             end_time: 234,
             exit_code: Some(1),
             error: Some("cannot find anything".into()),
+            timed_out: false,
+            attempts: vec![],
+            resource_usage: None,
         },
         json!({ "startTime": 123, "endTime": 234, "exitCode": 1, "error": "cannot find anything" })
         ; "failure"
     )]
+    #[test_case(
+        TaskExecutionSummary {
+            start_time: 123,
+            end_time: 234,
+            exit_code: None,
+            error: Some("command (pkg) timed out after 900s".into()),
+            timed_out: true,
+            attempts: vec![],
+            resource_usage: None,
+        },
+        json!({
+            "startTime": 123,
+            "endTime": 234,
+            "exitCode": null,
+            "error": "command (pkg) timed out after 900s",
+            "timedOut": true
+        })
+        ; "timed out"
+    )]
+    #[test_case(
+        TaskExecutionSummary {
+            start_time: 123,
+            end_time: 345,
+            exit_code: Some(0),
+            error: None,
+            timed_out: false,
+            attempts: vec![
+                TaskAttemptSummary { start_time: 123, end_time: 234, exit_code: Some(1) },
+                TaskAttemptSummary { start_time: 234, end_time: 345, exit_code: Some(0) },
+            ],
+            resource_usage: None,
+        },
+        json!({
+            "startTime": 123,
+            "endTime": 345,
+            "exitCode": 0,
+            "attempts": [
+                { "startTime": 123, "endTime": 234, "exitCode": 1 },
+                { "startTime": 234, "endTime": 345, "exitCode": 0 },
+            ]
+        })
+        ; "retried"
+    )]
+    #[test_case(
+        TaskExecutionSummary {
+            start_time: 123,
+            end_time: 234,
+            exit_code: Some(0),
+            error: None,
+            timed_out: false,
+            attempts: vec![],
+            resource_usage: Some(TaskResourceUsage {
+                user_cpu_ms: 1200,
+                system_cpu_ms: 300,
+                peak_rss_bytes: 104_857_600,
+            }),
+        },
+        json!({
+            "startTime": 123,
+            "endTime": 234,
+            "exitCode": 0,
+            "resourceUsage": { "userCpuMs": 1200, "systemCpuMs": 300, "peakRssBytes": 104_857_600 }
+        })
+        ; "resource usage"
+    )]
     fn test_serialization(
         value: impl serde::Serialize,
         expected: serde_json::Value,
@@ -669,6 +874,88 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_timed_out_task_is_failure() -> Result<(), Box<dyn std::error::Error>> {
+        let summary = ExecutionTracker::new();
+        let task_id = TaskId::new("e2e", "test");
+        let tracker = summary.task_tracker(task_id.clone());
+        tracker
+            .start()
+            .await
+            .build_timed_out("command (e2e) timed out after 1s")
+            .await;
+
+        let state = summary.finish().await?;
+        assert_eq!(state.attempted, 1);
+        assert_eq!(state.failed, 1);
+        let Some(execution) = state.tasks.first().and_then(|t| t.execution.as_ref()) else {
+            panic!("timed out task should have execution data");
+        };
+        assert!(execution.timed_out);
+        assert!(execution.is_failure());
+        assert_eq!(execution.exit_code, None);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_retried_task_records_attempts() -> Result<(), Box<dyn std::error::Error>> {
+        let summary = ExecutionTracker::new();
+        let flaky = TaskId::new("e2e", "test");
+        let stable = TaskId::new("app", "build");
+        {
+            let mut tracker = summary.task_tracker(flaky.clone()).start().await;
+            tracker.attempt_failed(Some(1));
+            tracker.attempt_failed(None);
+            tracker.build_succeeded(0).await;
+        }
+        summary
+            .task_tracker(stable.clone())
+            .start()
+            .await
+            .build_succeeded(0)
+            .await;
+
+        let state = summary.finish().await?;
+        assert_eq!(state.attempted, 2);
+        assert_eq!(state.success, 2);
+        assert_eq!(state.failed, 0);
+        let Some(execution) = state
+            .tasks
+            .iter()
+            .find(|task| task.task_id == flaky)
+            .and_then(|task| task.execution.as_ref())
+        else {
+            panic!("flaky task should have execution data");
+        };
+        let exit_codes: Vec<_> = execution
+            .attempts
+            .iter()
+            .map(|attempt| attempt.exit_code)
+            .collect();
+        assert_eq!(exit_codes, vec![Some(1), None, Some(0)]);
+        assert_eq!(execution.attempts[0].start_time, execution.start_time);
+        assert_eq!(
+            execution.attempts[1].start_time,
+            execution.attempts[0].end_time
+        );
+        assert_eq!(execution.attempts[2].end_time, execution.end_time);
+        assert!(!execution.is_failure());
+
+        let Some(execution) = state
+            .tasks
+            .iter()
+            .find(|task| task.task_id == stable)
+            .and_then(|task| task.execution.as_ref())
+        else {
+            panic!("stable task should have execution data");
+        };
+        assert!(
+            execution.attempts.is_empty(),
+            "tasks that weren't retried don't record attempts"
+        );
+        Ok(())
+    }
+
     // Verifies ExecutionSummary computes successful() correctly from SummaryState
     #[test]
     fn test_execution_summary_stats_from_state() {
@@ -718,6 +1005,7 @@ mod test {
             sender,
             started_at: (),
             task_id: TaskId::new("pkg", "build"),
+            attempts: Vec::new(),
         }
     }
 
@@ -743,6 +1031,7 @@ mod test {
             sender,
             started_at: Local::now(),
             task_id: TaskId::new("pkg", "build"),
+            attempts: Vec::new(),
         };
         // cached() sends Event::Cached — must not panic
         tracker.cached().await;
@@ -756,6 +1045,7 @@ mod test {
             sender,
             started_at: Local::now(),
             task_id: TaskId::new("pkg", "build"),
+            attempts: Vec::new(),
         };
         // build_succeeded() sends Event::Built — must not panic
         tracker.build_succeeded(0).await;
@@ -769,6 +1059,7 @@ mod test {
             sender,
             started_at: Local::now(),
             task_id: TaskId::new("pkg", "build"),
+            attempts: Vec::new(),
         };
         // build_failed() sends Event::BuildFailed — must not panic
         tracker.build_failed(Some(1), "error").await;
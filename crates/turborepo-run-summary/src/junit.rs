@@ -0,0 +1,251 @@
+//! JUnit XML rendering of a run summary.
+//!
+//! Each task is a test case, grouped into a test suite per package, so CI
+//! dashboards that ingest test results can show task timings, cache status,
+//! and failures. Failed tasks include the end of their log.
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use turbopath::{AbsoluteSystemPath, AnchoredSystemPath};
+
+use crate::task::{CacheStatus, TaskSummary};
+
+/// The number of log lines included with a failed task.
+const LOG_TAIL_LINES: usize = 50;
+
+/// Renders `tasks` as a JUnit XML report. Log files are read relative to
+/// `repo_root`.
+pub(crate) fn render(
+    repo_root: &AbsoluteSystemPath,
+    command: &str,
+    duration_ms: i64,
+    tasks: &[TaskSummary],
+) -> String {
+    let mut suites: BTreeMap<&str, Vec<&TaskSummary>> = BTreeMap::new();
+    for task in tasks {
+        suites.entry(&task.package).or_default().push(task);
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        r#"<testsuites name="{}" tests="{}" failures="{}" skipped="{}" time="{}">"#,
+        escape(command),
+        tasks.len(),
+        tasks.iter().filter(|task| is_failure(task)).count(),
+        tasks.iter().filter(|task| is_skipped(task)).count(),
+        seconds(duration_ms),
+    );
+    for (package, mut tasks) in suites {
+        tasks.sort_by(|a, b| a.task.cmp(&b.task));
+        let _ = writeln!(
+            xml,
+            r#"  <testsuite name="{}" tests="{}" failures="{}" skipped="{}" time="{}">"#,
+            escape(package),
+            tasks.len(),
+            tasks.iter().filter(|task| is_failure(task)).count(),
+            tasks.iter().filter(|task| is_skipped(task)).count(),
+            seconds(tasks.iter().map(|task| task_duration_ms(task)).sum()),
+        );
+        for task in tasks {
+            render_test_case(&mut xml, repo_root, task);
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+    xml.push_str("</testsuites>\n");
+
+    xml
+}
+
+fn render_test_case(xml: &mut String, repo_root: &AbsoluteSystemPath, task: &TaskSummary) {
+    let cache_status = match task.shared.cache.status() {
+        CacheStatus::Hit => "HIT",
+        CacheStatus::Miss => "MISS",
+    };
+    let _ = writeln!(
+        xml,
+        r#"    <testcase name="{}" classname="{}" time="{}">"#,
+        escape(&task.task),
+        escape(&task.package),
+        seconds(task_duration_ms(task)),
+    );
+    let _ = writeln!(
+        xml,
+        "      <properties>\n        <property name=\"cache\" value=\"{cache_status}\"/>\n      \
+         </properties>"
+    );
+    match &task.shared.execution {
+        Some(execution) if execution.is_failure() => {
+            let log_tail = task
+                .shared
+                .log_file
+                .as_deref()
+                .and_then(|log_file| read_log_tail(repo_root, log_file))
+                .unwrap_or_default();
+            let _ = writeln!(
+                xml,
+                r#"      <failure message="{}">{}</failure>"#,
+                escape(&execution.failure_message()),
+                escape(&log_tail),
+            );
+        }
+        Some(_) => (),
+        None => xml.push_str("      <skipped/>\n"),
+    }
+    xml.push_str("    </testcase>\n");
+}
+
+fn is_failure(task: &TaskSummary) -> bool {
+    task.shared
+        .execution
+        .as_ref()
+        .is_some_and(|execution| execution.is_failure())
+}
+
+fn is_skipped(task: &TaskSummary) -> bool {
+    task.shared.execution.is_none()
+}
+
+fn task_duration_ms(task: &TaskSummary) -> i64 {
+    task.shared
+        .execution
+        .as_ref()
+        .map_or(0, |execution| execution.end_time - execution.start_time)
+}
+
+fn seconds(ms: i64) -> String {
+    format!("{:.3}", ms as f64 / 1000.0)
+}
+
+fn read_log_tail(repo_root: &AbsoluteSystemPath, log_file: &str) -> Option<String> {
+    let log_file = repo_root.resolve(AnchoredSystemPath::new(log_file).ok()?);
+    let contents = log_file.read_to_string().ok()?;
+    let lines = contents.lines().collect::<Vec<_>>();
+    Some(lines[lines.len().saturating_sub(LOG_TAIL_LINES)..].join("\n"))
+}
+
+/// Escapes text for use in XML, dropping ANSI escape sequences and control
+/// characters that XML can't represent.
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                // Skip CSI sequences such as colors, which end with a byte in `@`..=`~`
+                if chars.next() == Some('[') {
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+            }
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(c),
+            c if c.is_control() => (),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod test {
+    use tempfile::tempdir;
+    use test_case::test_case;
+    use turbopath::AbsoluteSystemPath;
+
+    use super::{escape, render};
+    use crate::test_cases::{execution, task_summary};
+
+    #[test]
+    fn test_render() {
+        let repo_root = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPath::from_std_path(repo_root.path()).unwrap();
+        let log = (0..60)
+            .map(|i| format!("\x1b[31mline {i}\x1b[0m"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let log_file = repo_root.join_components(&["apps", "web", ".turbo", "turbo-test.log"]);
+        log_file.ensure_dir().unwrap();
+        log_file.create_with_contents(log).unwrap();
+
+        let tasks = [
+            task_summary(
+                "web",
+                "build",
+                "apps/web",
+                true,
+                Some(execution(0, 1500, 0)),
+            ),
+            task_summary(
+                "web",
+                "test",
+                "apps/web",
+                false,
+                Some(execution(0, 2000, 1)),
+            ),
+            task_summary("docs", "lint", "apps/docs", false, None),
+        ];
+        let xml = render(repo_root, "turbo run build test lint", 4000, &tasks);
+
+        assert!(xml.contains(
+            r#"<testsuites name="turbo run build test lint" tests="3" failures="1" skipped="1" time="4.000">"#
+        ));
+        assert!(
+            xml.contains(
+                r#"<testsuite name="web" tests="2" failures="1" skipped="0" time="3.500">"#
+            )
+        );
+        assert!(xml.contains(
+            r#"<testsuite name="docs" tests="1" failures="0" skipped="1" time="0.000">"#
+        ));
+
+        let build = r#"    <testcase name="build" classname="web" time="1.500">
+      <properties>
+        <property name="cache" value="HIT"/>
+      </properties>
+    </testcase>
+"#;
+        assert!(xml.contains(build));
+
+        // Only the end of the log is included, without colors
+        let log_tail = (10..60)
+            .map(|i| format!("line {i}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let test = format!(
+            r#"    <testcase name="test" classname="web" time="2.000">
+      <properties>
+        <property name="cache" value="MISS"/>
+      </properties>
+      <failure message="command exited (1)">{log_tail}</failure>
+    </testcase>
+"#
+        );
+        assert!(xml.contains(&test));
+        assert!(!xml.contains("line 9\n"));
+
+        let lint = r#"    <testcase name="lint" classname="docs" time="0.000">
+      <properties>
+        <property name="cache" value="MISS"/>
+      </properties>
+      <skipped/>
+    </testcase>
+"#;
+        assert!(xml.contains(lint));
+    }
+
+    #[test_case("plain text", "plain text" ; "plain")]
+    #[test_case(r#"<a href="x">&'</a>"#, "&lt;a href=&quot;x&quot;&gt;&amp;&apos;&lt;/a&gt;" ; "markup")]
+    #[test_case("\x1b[31merror\x1b[0m: failed", "error: failed" ; "ansi colors")]
+    #[test_case("line one\nline\u{0}two", "line one\nlinetwo" ; "control characters")]
+    fn test_escape(text: &str, expected: &str) {
+        assert_eq!(escape(text), expected);
+    }
+}
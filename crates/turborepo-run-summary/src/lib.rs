@@ -3,13 +3,17 @@
 //! This crate provides types and traits for tracking task execution
 //! and generating run summaries.
 
+mod annotations;
 mod duration;
 mod execution;
 mod global_hash;
+mod junit;
 pub mod observability;
 mod scm;
 mod task;
 mod task_factory;
+#[cfg(test)]
+mod test_cases;
 mod tracker;
 
 pub use duration::TurboDuration;
@@ -20,8 +24,9 @@ pub use global_hash::{GlobalEnvConfiguration, GlobalEnvVarSummary, GlobalHashSum
 pub use observability::Handle as ObservabilityHandle;
 pub use scm::SCMState;
 pub use task::{
-    SharedTaskSummary, SinglePackageTaskSummary, TaskCacheSummary, TaskEnvConfiguration,
-    TaskEnvVarSummary, TaskExecutionSummary, TaskSummary, TaskSummaryTaskDefinition,
+    SharedTaskSummary, SinglePackageTaskSummary, TaskAttemptSummary, TaskCacheSummary,
+    TaskEnvConfiguration, TaskEnvVarSummary, TaskExecutionSummary, TaskResourceUsage, TaskSummary,
+    TaskSummaryTaskDefinition,
 };
 pub use task_factory::{Error as TaskFactoryError, TaskSummaryFactory, get_external_deps_hash};
 pub use tracker::{Error, RunSummary, RunTracker, SinglePackageRunSummary};
@@ -5,7 +5,7 @@ use std::{
 };
 
 use turborepo_config::{ExperimentalOtelMetricsOptions, ExperimentalOtelOptions};
-use turborepo_otel::{RunMetricsPayload, TaskCacheStatus, TaskMetricsPayload};
+use turborepo_otel::{RunMetricsPayload, TaskCacheStatus, TaskMetricsPayload, TaskResourceUsage};
 use url::{Host, Url};
 
 use super::{Handle, RunObserver};
@@ -79,6 +79,7 @@ fn config_from_options(
     let headers = options.headers.clone().unwrap_or_default();
     let resource_attributes = options.resource.clone().unwrap_or_default();
     let metrics = metrics_config(options.metrics.as_ref());
+    let traces = options.traces.unwrap_or(false);
     let timeout = Duration::from_millis(options.timeout_ms.unwrap_or(10_000));
     let interval = Duration::from_millis(options.interval_ms.unwrap_or(15_000));
 
@@ -90,6 +91,7 @@ fn config_from_options(
         interval,
         resource_attributes,
         metrics,
+        traces,
     };
 
     apply_auth_token(&mut config, token);
@@ -226,6 +228,8 @@ fn build_payload(summary: &RunSummary<'_>) -> Option<RunMetricsPayload> {
     Some(RunMetricsPayload {
         run_id: summary.id().to_string(),
         turbo_version: summary.turbo_version().to_string(),
+        start_time_ms: execution.start_time,
+        end_time_ms: execution.end_time,
         duration_ms,
         attempted_tasks,
         failed_tasks,
@@ -238,6 +242,8 @@ fn build_payload(summary: &RunSummary<'_>) -> Option<RunMetricsPayload> {
 }
 
 fn build_task_payload(task: &TaskSummary) -> TaskMetricsPayload {
+    let start_time_ms = task.shared.execution.as_ref().map(|exec| exec.start_time);
+    let end_time_ms = task.shared.execution.as_ref().map(|exec| exec.end_time);
     let duration_ms = task
         .shared
         .execution
@@ -257,6 +263,16 @@ fn build_task_payload(task: &TaskSummary) -> TaskMetricsPayload {
         .cache
         .cache_source_label()
         .map(|label| label.to_string());
+    let resource_usage = task
+        .shared
+        .execution
+        .as_ref()
+        .and_then(|exec| exec.resource_usage)
+        .map(|usage| TaskResourceUsage {
+            user_cpu_ms: usage.user_cpu_ms,
+            system_cpu_ms: usage.system_cpu_ms,
+            peak_rss_bytes: usage.peak_rss_bytes,
+        });
 
     TaskMetricsPayload {
         task_id: task.task_id.to_string(),
@@ -271,10 +287,19 @@ fn build_task_payload(task: &TaskSummary) -> TaskMetricsPayload {
         // TODO: Keep passthrough CLI args out of OTEL payloads until we define
         // a sensitive-data redaction policy for `cli_arguments`.
         command: task.shared.command.clone(),
+        start_time_ms,
+        end_time_ms,
         duration_ms,
         cache_status,
         cache_source,
         exit_code,
+        dependencies: task
+            .shared
+            .dependencies
+            .iter()
+            .map(ToString::to_string)
+            .collect(),
+        resource_usage,
     }
 }
 
@@ -300,6 +325,7 @@ mod tests {
                 run_attributes: turborepo_otel::RunAttributesConfig::default(),
                 task_attributes: turborepo_otel::TaskAttributesConfig::default(),
             },
+            traces: false,
         }
     }
 
@@ -478,6 +504,7 @@ mod tests {
         assert!(!config.metrics.run_attributes.scm_revision);
         assert!(!config.metrics.task_attributes.id);
         assert!(!config.metrics.task_attributes.hashes);
+        assert!(!config.traces);
     }
 
     #[test]
@@ -500,6 +527,7 @@ mod tests {
                 run_attributes: None,
                 task_attributes: None,
             }),
+            traces: Some(true),
             ..Default::default()
         };
 
@@ -516,6 +544,7 @@ mod tests {
         );
         assert!(!config.metrics.run_summary);
         assert!(config.metrics.task_details);
+        assert!(config.traces);
     }
 
     #[test]
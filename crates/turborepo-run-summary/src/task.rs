@@ -6,8 +6,8 @@ use turborepo_cache::CacheHitMetadata;
 use turborepo_env::{DetailedMap, EnvironmentVariableMap};
 use turborepo_task_id::TaskId;
 use turborepo_types::{
-    EnvMode, HashTrackerCacheHitMetadata, HashTrackerDetailedMap, OutputLogsMode, TaskDefinition,
-    TaskOutputs,
+    EnvMode, HashTrackerCacheHitMetadata, HashTrackerDetailedMap, OutputLogsMode, TaskCommand,
+    TaskDefinition, TaskOutputs,
 };
 
 use crate::execution::TaskSummaryInfo;
@@ -20,6 +20,47 @@ pub struct TaskExecutionSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error: Option<String>,
     pub exit_code: Option<i32>,
+    /// Whether the task was stopped because it exceeded its `timeout`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub timed_out: bool,
+    /// Every run of the task's command, in order. Only populated when the task
+    /// was retried, in which case the final entry is the attempt that
+    /// determined the task's outcome.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub attempts: Vec<TaskAttemptSummary>,
+    /// CPU time and memory used by the task's processes. Only collected on
+    /// Linux, and never for cache hits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resource_usage: Option<TaskResourceUsage>,
+}
+
+#[derive(Debug, Serialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskAttemptSummary {
+    pub start_time: i64,
+    pub end_time: i64,
+    pub exit_code: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TaskResourceUsage {
+    pub user_cpu_ms: u64,
+    pub system_cpu_ms: u64,
+    /// The most resident memory used by the process tree at once
+    pub peak_rss_bytes: u64,
+}
+
+impl TaskResourceUsage {
+    /// Combines usage that was measured separately, such as across retries.
+    /// CPU time is added up while the peak is the larger of the two.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            user_cpu_ms: self.user_cpu_ms + other.user_cpu_ms,
+            system_cpu_ms: self.system_cpu_ms + other.system_cpu_ms,
+            peak_rss_bytes: self.peak_rss_bytes.max(other.peak_rss_bytes),
+        }
+    }
 }
 
 impl TaskExecutionSummary {
@@ -28,6 +69,15 @@ impl TaskExecutionSummary {
         // or was killed in a manner where we didn't collect an exit code.
         !matches!(self.exit_code, Some(0))
     }
+
+    // Used in reports that describe why a task failed
+    pub(crate) fn failure_message(&self) -> String {
+        match (&self.error, self.exit_code) {
+            (Some(error), _) => error.clone(),
+            (None, Some(exit_code)) => format!("command exited ({exit_code})"),
+            (None, None) => "command failed".to_string(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Clone)]
@@ -142,6 +192,12 @@ pub struct TaskSummaryTaskDefinition {
     pass_through_env: Option<Vec<String>>,
     interactive: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
+    timeout: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    retries: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    command: Option<TaskCommand>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     env_mode: Option<EnvMode>,
     #[serde(skip_serializing_if = "Option::is_none")]
     incremental: Option<Vec<IncrementalPartitionSummary>>,
@@ -407,6 +463,9 @@ impl From<TaskDefinition> for TaskSummaryTaskDefinition {
             persistent,
             interruptible,
             interactive,
+            timeout,
+            retries,
+            command,
             env_mode,
             with: _,
             incremental,
@@ -460,6 +519,9 @@ impl From<TaskDefinition> for TaskSummaryTaskDefinition {
             persistent,
             interruptible,
             interactive,
+            timeout: timeout.map(|timeout| format!("{}s", timeout.as_secs())),
+            retries: (retries > 0).then_some(retries),
+            command,
             env,
             pass_through_env,
             env_mode,
@@ -687,6 +749,9 @@ mod test {
                     end_time: 2,
                     error: None,
                     exit_code: Some(0),
+                    timed_out: false,
+                    attempts: Vec::new(),
+                    resource_usage: None,
                 }),
             },
         };
@@ -112,17 +112,20 @@ where
         workspace_info: &PackageInfo,
         display_task: impl Fn(&TaskId<'static>) -> Option<T> + Copy,
     ) -> Result<SharedTaskSummary<T>, Error> {
-        // TODO: command should be optional
-        let command = workspace_info
-            .package_json
-            .scripts
-            .get(task_id.task())
-            .map(|script| script.as_inner())
-            .cloned()
-            .unwrap_or_else(|| "<NONEXISTENT>".to_string());
-
         let task_definition = self.task_definition(task_id)?;
 
+        // TODO: command should be optional
+        let command = match &task_definition.command {
+            Some(command) => command.to_string(),
+            None => workspace_info
+                .package_json
+                .scripts
+                .get(task_id.task())
+                .map(|script| script.as_inner())
+                .cloned()
+                .unwrap_or_else(|| "<NONEXISTENT>".to_string()),
+        };
+
         let expanded_outputs = self
             .hash_tracker
             .expanded_outputs(task_id)
@@ -0,0 +1,76 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use turborepo_cache::{CacheHitMetadata, CacheSource};
+use turborepo_task_id::TaskId;
+use turborepo_types::EnvMode;
+
+use crate::task::{
+    SharedTaskSummary, TaskCacheSummary, TaskEnvConfiguration, TaskEnvVarSummary,
+    TaskExecutionSummary, TaskSummary, TaskSummaryTaskDefinition,
+};
+
+/// A task summary for `package#task` in `directory`. A task without an
+/// `execution` was skipped.
+pub(crate) fn task_summary(
+    package: &str,
+    task: &str,
+    directory: &str,
+    cache_hit: bool,
+    execution: Option<TaskExecutionSummary>,
+) -> TaskSummary {
+    let cache = cache_hit.then_some(CacheHitMetadata {
+        source: CacheSource::Local,
+        time_saved: 0,
+        sha: None,
+        dirty_hash: None,
+    });
+    TaskSummary {
+        task_id: TaskId::new(package, task).into_owned(),
+        task: task.to_string(),
+        package: package.to_string(),
+        shared: SharedTaskSummary {
+            hash: Some(Arc::from("hash")),
+            hash_reason: None,
+            inputs: BTreeMap::new(),
+            hash_of_external_dependencies: String::new(),
+            cache: TaskCacheSummary::from(cache),
+            command: format!("echo {task}"),
+            cli_arguments: vec![],
+            outputs: None,
+            excluded_outputs: None,
+            log_file: Some(format!("{directory}/.turbo/turbo-{task}.log")),
+            directory: Some(directory.to_string()),
+            dependencies: vec![],
+            dependents: vec![],
+            with: vec![],
+            resolved_task_definition: TaskSummaryTaskDefinition::default(),
+            expanded_outputs: vec![],
+            framework: String::new(),
+            env_mode: EnvMode::Strict,
+            environment_variables: TaskEnvVarSummary {
+                specified: TaskEnvConfiguration {
+                    env: vec![],
+                    pass_through_env: None,
+                },
+                configured: vec![],
+                inferred: vec![],
+                pass_through: None,
+            },
+            execution,
+        },
+    }
+}
+
+/// The execution of a task that ran from `start_time` to `end_time` and
+/// exited with `exit_code`.
+pub(crate) fn execution(start_time: i64, end_time: i64, exit_code: i32) -> TaskExecutionSummary {
+    TaskExecutionSummary {
+        start_time,
+        end_time,
+        error: None,
+        exit_code: Some(exit_code),
+        timed_out: false,
+        attempts: Vec::new(),
+        resource_usage: None,
+    }
+}
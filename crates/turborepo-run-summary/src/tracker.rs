@@ -17,12 +17,15 @@ use turborepo_env::EnvironmentVariableMap;
 use turborepo_repository::package_graph::{PackageGraph, PackageName};
 use turborepo_scm::SCM;
 use turborepo_task_id::TaskId;
-use turborepo_types::{DryRunMode, EngineInfo, EnvMode, HashTrackerInfo, RunOptsInfo};
+use turborepo_types::{
+    DryRunMode, EngineInfo, EnvMode, HashTrackerInfo, RunOptsInfo, SummaryFormat,
+};
 use turborepo_ui::{BOLD, BOLD_CYAN, ColorConfig, GREY, color, cprintln, cwriteln};
 
 use crate::{
-    GlobalHashSummary, SCMState, TaskTracker,
+    GlobalHashSummary, SCMState, TaskTracker, annotations,
     execution::{ExecutionSummary, ExecutionTracker, TaskState},
+    junit,
     observability::Handle as ObservabilityHandle,
     task::{SinglePackageTaskSummary, TaskSummary},
     task_factory::TaskSummaryFactory,
@@ -80,6 +83,8 @@ pub struct RunSummary<'a> {
     #[serde(skip)]
     should_save: bool,
     #[serde(skip)]
+    summary_formats: Vec<SummaryFormat>,
+    #[serde(skip)]
     run_type: RunType,
     #[serde(skip)]
     observability_handle: Option<ObservabilityHandle>,
@@ -197,6 +202,7 @@ impl RunTracker {
             monorepo: !single_package,
             repo_root,
             should_save,
+            summary_formats: run_opts.summary_formats().to_vec(),
             run_type,
             observability_handle: self.observability_handle.clone(),
         })
@@ -409,18 +415,29 @@ impl<'a> RunSummary<'a> {
             return self.close_dry_run(pkg_dep_graph, ui);
         }
 
-        if self.should_save
-            && let Err(err) = self.save().await
-        {
-            turborepo_log::warn(
-                turborepo_log::Source::turbo(turborepo_log::Subsystem::Summary),
-                format!("Error writing run summary: {err}"),
-            )
-            .emit()
+        if self.should_save {
+            if let Err(err) = self.save().await {
+                turborepo_log::warn(
+                    turborepo_log::Source::turbo(turborepo_log::Subsystem::Summary),
+                    format!("Error writing run summary: {err}"),
+                )
+                .emit()
+            }
+
+            // Annotate failures on the pull request
+            if turborepo_ci::Vendor::get_constant() == Some("GITHUB_ACTIONS") {
+                for annotation in annotations::github_annotations(&self.tasks) {
+                    println!("{annotation}");
+                }
+            }
         }
 
         if !is_watch && let Some(execution) = &self.execution {
-            let path = self.get_path();
+            let path = if self.summary_formats.contains(&SummaryFormat::Json) {
+                self.get_path()
+            } else {
+                self.get_junit_path()
+            };
             let failed_tasks = self.get_failed_tasks();
             execution.print(ui, path, failed_tasks);
         }
@@ -798,6 +815,13 @@ impl<'a> RunSummary<'a> {
             .join_components(&[".turbo", "runs", &filename])
     }
 
+    fn get_junit_path(&self) -> AbsoluteSystemPathBuf {
+        let filename = format!("{}.xml", self.id);
+
+        self.repo_root
+            .join_components(&[".turbo", "runs", &filename])
+    }
+
     fn get_failed_tasks(&self) -> Vec<&TaskSummary> {
         self.tasks
             .iter()
@@ -810,18 +834,54 @@ impl<'a> RunSummary<'a> {
             .collect()
     }
 
-    /// Saves the run summary to disk asynchronously.
+    /// Saves the run summary to disk asynchronously, in each of the
+    /// configured formats.
     ///
     /// Uses `tokio::task::spawn_blocking` to perform the file I/O on a
     /// blocking thread pool, avoiding blocking the async runtime.
     async fn save(&mut self) -> Result<(), Error> {
-        let json = self.format_json()?;
-        let summary_path = self.get_path();
+        let json = if self.summary_formats.contains(&SummaryFormat::Json) {
+            Some((self.get_path(), self.format_json()?))
+        } else {
+            None
+        };
+        // The JUnit report includes task logs, so it's rendered on the blocking
+        // thread along with the writes
+        let junit = self
+            .summary_formats
+            .contains(&SummaryFormat::Junit)
+            .then(|| {
+                self.normalize();
+                let (command, duration_ms) = self
+                    .execution
+                    .as_ref()
+                    .map(|execution| {
+                        (
+                            execution.command().to_owned(),
+                            execution.end_time - execution.start_time,
+                        )
+                    })
+                    .unwrap_or_default();
+                (
+                    self.get_junit_path(),
+                    self.repo_root.to_owned(),
+                    command,
+                    duration_ms,
+                    self.tasks.clone(),
+                )
+            });
 
         // Perform file I/O in a blocking task to avoid blocking the async runtime
         tokio::task::spawn_blocking(move || -> Result<(), Error> {
-            summary_path.ensure_dir()?;
-            summary_path.create_with_contents(json)?;
+            if let Some((summary_path, json)) = json {
+                summary_path.ensure_dir()?;
+                summary_path.create_with_contents(json)?;
+            }
+            if let Some((junit_path, repo_root, command, duration_ms, tasks)) = junit {
+                let report = junit::render(&repo_root, &command, duration_ms, &tasks);
+                junit_path.ensure_dir()?;
+                junit_path.create_with_contents(report)?;
+            }
             Ok(())
         })
         .await
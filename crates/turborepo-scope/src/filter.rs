@@ -110,8 +110,8 @@ impl PackageInference {
     }
 
     pub fn apply(&self, selector: &mut TargetSelector) -> Result<(), ResolutionError> {
-        // if the name pattern is provided, do not attempt inference
-        if !selector.name_pattern.is_empty() {
+        // if the name pattern or tag is provided, do not attempt inference
+        if !selector.name_pattern.is_empty() || selector.tag.is_some() {
             return Ok(());
         };
 
@@ -165,6 +165,8 @@ pub struct FilterResolver<'a, T: GitChangeDetector> {
     turbo_root: &'a AbsoluteSystemPath,
     inference: Option<PackageInference>,
     change_detector: T,
+    package_tags: HashMap<PackageName, Vec<String>>,
+    depth: Option<usize>,
 }
 
 impl<'a> FilterResolver<'a, ScopeChangeDetector<'a>> {
@@ -206,9 +208,25 @@ impl<'a, T: GitChangeDetector> FilterResolver<'a, T> {
             turbo_root,
             inference,
             change_detector,
+            package_tags: HashMap::new(),
+            depth: None,
         }
     }
 
+    /// Sets the tags each package declares in its `turbo.json`, which are
+    /// used to resolve `tag:` selectors.
+    pub fn with_package_tags(mut self, package_tags: HashMap<PackageName, Vec<String>>) -> Self {
+        self.package_tags = package_tags;
+        self
+    }
+
+    /// Limits how many levels of dependencies or dependents `...` includes
+    /// for every selector, including the one implied by `--affected`.
+    pub fn with_depth(mut self, depth: Option<usize>) -> Self {
+        self.depth = depth;
+        self
+    }
+
     /// Resolve the set of packages matching the given filter patterns.
     ///
     /// Returns the matched packages alongside a [`FilterMode`] that
@@ -246,7 +264,12 @@ impl<'a, T: GitChangeDetector> FilterResolver<'a, T> {
         // Parse selectors once — reused for both mode classification and resolution.
         let selectors: Vec<TargetSelector> = patterns
             .iter()
-            .map(|pattern| TargetSelector::from_str(pattern))
+            .map(|pattern| {
+                TargetSelector::from_str(pattern).map(|selector| TargetSelector {
+                    depth: self.depth,
+                    ..selector
+                })
+            })
             .collect::<Result<Vec<_>, _>>()?;
 
         let mode = self.classify_filter_mode(&selectors, affected);
@@ -260,6 +283,7 @@ impl<'a, T: GitChangeDetector> FilterResolver<'a, T> {
                 merge_base: true,
             }),
             include_dependents: true,
+            depth: self.depth,
             ..Default::default()
         });
 
@@ -467,7 +491,7 @@ impl<'a, T: GitChangeDetector> FilterResolver<'a, T> {
                 let node = package_graph::PackageNode::Workspace(package.clone());
 
                 if selector.include_dependencies {
-                    let dependencies = self.pkg_graph.dependencies(&node);
+                    let dependencies = self.dependencies(&node, selector.depth);
                     let dependencies = dependencies
                         .iter()
                         .filter(|node| !matches!(node, package_graph::PackageNode::Root))
@@ -489,7 +513,7 @@ impl<'a, T: GitChangeDetector> FilterResolver<'a, T> {
                 }
 
                 if selector.include_dependents {
-                    let dependents = self.pkg_graph.ancestors(&node);
+                    let dependents = self.ancestors(&node, selector.depth);
                     for dependent in dependents.iter().map(|i| i.as_package_name()) {
                         walked_dependents.insert(
                             dependent.clone(),
@@ -507,7 +531,7 @@ impl<'a, T: GitChangeDetector> FilterResolver<'a, T> {
                                 package_graph::PackageNode::Workspace(dependent.to_owned());
 
                             let dependent_dependencies =
-                                self.pkg_graph.dependencies(&dependent_node);
+                                self.dependencies(&dependent_node, selector.depth);
 
                             let dependent_dependencies = dependent_dependencies
                                 .iter()
@@ -621,6 +645,11 @@ impl<'a, T: GitChangeDetector> FilterResolver<'a, T> {
         } else {
             entry_packages
         };
+        let filtered_entry_packages = if let Some(tag) = &selector.tag {
+            match_package_tags(tag, &self.package_tags, filtered_entry_packages)?
+        } else {
+            filtered_entry_packages
+        };
 
         let mut roots = HashMap::new();
         let mut matched = HashSet::new();
@@ -637,7 +666,7 @@ impl<'a, T: GitChangeDetector> FilterResolver<'a, T> {
             }
 
             let workspace_node = package_graph::PackageNode::Workspace(package.clone());
-            let dependencies = self.pkg_graph.dependencies(&workspace_node);
+            let dependencies = self.dependencies(&workspace_node, selector.depth);
 
             for changed_package in changed_packages.keys() {
                 if !selector.exclude_self && package.eq(changed_package) {
@@ -780,7 +809,26 @@ impl<'a, T: GitChangeDetector> FilterResolver<'a, T> {
                 match_package_names(&selector.name_pattern, &all_packages, entry_packages)?;
         }
 
-        // if neither a name pattern, parent dir, or from ref is provided, then
+        if let Some(tag) = &selector.tag {
+            if !selector_valid {
+                entry_packages = self
+                    .all_packages()
+                    .into_iter()
+                    .map(|name| {
+                        (
+                            name,
+                            PackageInclusionReason::IncludedByFilter {
+                                filters: vec![selector.raw.to_string()],
+                            },
+                        )
+                    })
+                    .collect();
+                selector_valid = true;
+            }
+            entry_packages = match_package_tags(tag, &self.package_tags, entry_packages)?;
+        }
+
+        // if neither a name pattern, tag, parent dir, or from ref is provided, then
         // the selector is invalid
         if !selector_valid {
             Err(ResolutionError::InvalidSelector(
@@ -804,6 +852,28 @@ impl<'a, T: GitChangeDetector> FilterResolver<'a, T> {
         )
     }
 
+    fn dependencies(
+        &self,
+        node: &package_graph::PackageNode,
+        depth: Option<usize>,
+    ) -> HashSet<&'a package_graph::PackageNode> {
+        match depth {
+            Some(depth) => self.pkg_graph.dependencies_within_depth(node, depth),
+            None => self.pkg_graph.dependencies(node),
+        }
+    }
+
+    fn ancestors(
+        &self,
+        node: &package_graph::PackageNode,
+        depth: Option<usize>,
+    ) -> HashSet<&'a package_graph::PackageNode> {
+        match depth {
+            Some(depth) => self.pkg_graph.ancestors_within_depth(node, depth),
+            None => self.pkg_graph.ancestors(node),
+        }
+    }
+
     fn all_packages(&self) -> HashSet<PackageName> {
         let mut packages = self
             .pkg_graph
@@ -843,6 +913,42 @@ fn match_package_names(
     Ok(packages)
 }
 
+/// match the provided tag pattern against the tags each package declares
+/// and return the packages that have a matching tag
+///
+/// the pattern supports the same wildcards as name patterns
+fn match_package_tags(
+    tag_pattern: &str,
+    package_tags: &HashMap<PackageName, Vec<String>>,
+    mut packages: HashMap<PackageName, PackageInclusionReason>,
+) -> Result<HashMap<PackageName, PackageInclusionReason>, ResolutionError> {
+    let matched_packages = packages_with_tag(tag_pattern, package_tags)?;
+
+    // If the pattern was an exact tag and no package declares it, then error
+    if matched_packages.is_empty() && SimpleGlob::new(tag_pattern)?.is_exact() {
+        return Err(ResolutionError::NoPackagesMatchedWithTag(
+            tag_pattern.to_owned(),
+        ));
+    }
+
+    packages.retain(|pkg, _| matched_packages.contains(pkg));
+
+    Ok(packages)
+}
+
+/// Returns the packages that declare a tag matching `tag_pattern`.
+pub fn packages_with_tag(
+    tag_pattern: &str,
+    package_tags: &HashMap<PackageName, Vec<String>>,
+) -> Result<HashSet<PackageName>, ResolutionError> {
+    let matcher = SimpleGlob::new(tag_pattern)?;
+    Ok(package_tags
+        .iter()
+        .filter(|(_, tags)| tags.iter().any(|tag| matcher.is_match(tag)))
+        .map(|(name, _)| name.clone())
+        .collect())
+}
+
 /// Errors that can occur during scope resolution.
 #[derive(Debug, thiserror::Error, Diagnostic)]
 pub enum ResolutionError {
@@ -856,6 +962,8 @@ pub enum ResolutionError {
     PackageNotInWorkspace,
     #[error("No package found with name '{0}' in workspace")]
     NoPackagesMatchedWithName(String),
+    #[error("No package found with tag '{0}' in workspace")]
+    NoPackagesMatchedWithTag(String),
     #[error("selector not used: {0}")]
     InvalidSelector(#[from] InvalidSelectorError),
     #[error("Invalid regex pattern")]
@@ -1255,6 +1363,46 @@ mod test {
         &["project-0"] ;
         "infer single package from subdirectory"
     )]
+    #[test_case(
+        vec![
+            TargetSelector {
+                name_pattern: "project-0".to_string(),
+                include_dependencies: true,
+                depth: Some(1),
+                ..Default::default()
+            }
+        ],
+        None,
+        &["project-0", "project-1", "project-5"] ;
+        "select direct dependencies"
+    )]
+    #[test_case(
+        vec![
+            TargetSelector {
+                name_pattern: "project-2".to_string(),
+                include_dependents: true,
+                depth: Some(1),
+                ..Default::default()
+            }
+        ],
+        None,
+        &["project-1", "project-2"] ;
+        "select direct dependents"
+    )]
+    #[test_case(
+        vec![
+            TargetSelector {
+                name_pattern: "project-2".to_string(),
+                include_dependents: true,
+                exclude_self: true,
+                depth: Some(2),
+                ..Default::default()
+            }
+        ],
+        None,
+        &["project-0", "project-1"] ;
+        "select dependents two levels up excluding self"
+    )]
     fn filter(
         selectors: Vec<TargetSelector>,
         package_inference: Option<PackageInference>,
@@ -1400,6 +1548,88 @@ mod test {
         );
     }
 
+    fn tag(tag: &str) -> TargetSelector {
+        TargetSelector {
+            tag: Some(tag.to_string()),
+            raw: format!("tag:{tag}"),
+            ..Default::default()
+        }
+    }
+
+    #[test_case(vec![tag("service")], &["api", "worker"] ; "select by tag")]
+    #[test_case(vec![tag("serv*")], &["api", "worker"] ; "select by tag pattern")]
+    #[test_case(
+        vec![tag("service"), TargetSelector { exclude: true, ..tag("queue") }],
+        &["api"] ;
+        "exclude by tag"
+    )]
+    #[test_case(
+        vec![TargetSelector { include_dependencies: true, ..tag("frontend") }],
+        &["web", "ui"] ;
+        "tag with dependencies"
+    )]
+    #[test_case(
+        vec![TargetSelector { parent_dir: Some(AnchoredSystemPathBuf::try_from("packages/w*").unwrap()), ..tag("service") }],
+        &["worker"] ;
+        "tag within directory"
+    )]
+    #[test_case(
+        vec![TargetSelector { git_range: Some(GitRange { from_ref: Some("HEAD~1".to_string()), ..Default::default() }), ..tag("service") }],
+        &["api"] ;
+        "changed packages with tag"
+    )]
+    #[test_case(
+        vec![TargetSelector { git_range: Some(GitRange { from_ref: Some("HEAD~1".to_string()), ..Default::default() }), match_dependencies: true, ..tag("frontend") }],
+        &["web"] ;
+        "tagged packages with changed dependencies"
+    )]
+    fn filter_by_tag(selectors: Vec<TargetSelector>, expected: &[&str]) {
+        let (_tempdir, resolver) = make_project(
+            &[
+                ("packages/web", "packages/ui"),
+                ("packages/api", "packages/ui"),
+            ],
+            &["packages/worker"],
+            None,
+            TestChangeDetector::new(&[("HEAD~1", None, &["api", "ui"])]),
+        );
+        let resolver = resolver.with_package_tags(HashMap::from([
+            (PackageName::from("web"), vec!["frontend".to_string()]),
+            (PackageName::from("api"), vec!["service".to_string()]),
+            (
+                PackageName::from("worker"),
+                vec!["service".to_string(), "queue".to_string()],
+            ),
+        ]));
+
+        let packages = resolver.get_filtered_packages(selectors).unwrap();
+
+        assert_eq!(
+            packages.into_keys().collect::<HashSet<_>>(),
+            expected.iter().map(|s| PackageName::from(*s)).collect()
+        );
+    }
+
+    #[test]
+    fn test_no_matching_tag() {
+        let (_tempdir, resolver) =
+            make_project(&[], &["packages/api"], None, TestChangeDetector::new(&[]));
+        let resolver = resolver.with_package_tags(HashMap::from([(
+            PackageName::from("api"),
+            vec!["service".to_string()],
+        )]));
+
+        let packages = resolver.get_filtered_packages(vec![tag("servcie")]);
+        assert!(packages.is_err(), "non existing tag should error");
+
+        let packages = resolver.get_filtered_packages(vec![tag("web*")]).unwrap();
+        assert!(
+            packages.is_empty(),
+            "expected no matches, got {:?}",
+            packages
+        );
+    }
+
     #[test]
     fn test_no_directory() {
         let (_tempdir, resolver) = make_project(
@@ -2213,4 +2443,39 @@ mod test {
             ])
         );
     }
+
+    #[test]
+    fn depth_limits_affected_and_filter_traversal() {
+        let (_tempdir, resolver) = make_project(
+            &[
+                ("packages/project-0", "packages/project-1"),
+                ("packages/project-1", "packages/project-2"),
+            ],
+            &[],
+            None,
+            TestChangeDetector::new(&[("main", None, &["project-2"])]),
+        );
+        let resolver = resolver.with_depth(Some(1));
+
+        let affected = Some((Some("main".to_string()), None));
+        let (pkgs, _) = resolver.resolve(&affected, &[]).unwrap();
+        assert_eq!(
+            pkgs.into_keys().collect::<HashSet<_>>(),
+            HashSet::from([
+                PackageName::from("project-2"),
+                PackageName::from("project-1"),
+            ])
+        );
+
+        let (pkgs, _) = resolver
+            .resolve(&None, &["project-0...".to_string()])
+            .unwrap();
+        assert_eq!(
+            pkgs.into_keys().collect::<HashSet<_>>(),
+            HashSet::from([
+                PackageName::from("project-0"),
+                PackageName::from("project-1"),
+            ])
+        );
+    }
 }
@@ -39,18 +39,24 @@ use turborepo_types::{FilterMode, ScopeOpts};
 /// describes how the filter was classified (all packages, exclude-only,
 /// or explicit selection). The caller uses `FilterMode` to decide
 /// whether root tasks should be injected.
-#[tracing::instrument(skip(opts, pkg_graph, scm))]
+///
+/// `package_tags` holds the tags each package declares in its `turbo.json`
+/// and is used to resolve `tag:` selectors.
+#[tracing::instrument(skip(opts, pkg_graph, scm, package_tags))]
 pub fn resolve_packages(
     opts: &ScopeOpts,
     turbo_root: &AbsoluteSystemPath,
     pkg_graph: &PackageGraph,
     scm: &SCM,
     global_deps: &[String],
+    package_tags: HashMap<PackageName, Vec<String>>,
 ) -> Result<(HashMap<PackageName, PackageInclusionReason>, FilterMode), ResolutionError> {
     let pkg_inference = opts.pkg_inference_root.as_ref().map(|pkg_inference_path| {
         PackageInference::calculate(turbo_root, pkg_inference_path, pkg_graph)
     });
 
     FilterResolver::new(opts, pkg_graph, turbo_root, pkg_inference, scm, global_deps)?
+        .with_package_tags(package_tags)
+        .with_depth(opts.filter_depth)
         .resolve(&opts.affected_range, opts.get_filters())
 }
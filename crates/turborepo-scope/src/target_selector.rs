@@ -14,6 +14,7 @@ use turbopath::AnchoredSystemPathBuf;
 ///
 /// Examples:
 /// - `"foo"` → name_pattern="foo"
+/// - `"tag:frontend"` → tag="frontend"
 /// - `"{packages/*}"` → directory="packages/*"
 /// - `"[HEAD~1]"` → git_range from HEAD~1
 /// - `"foo{src}[main]"` → all three components
@@ -55,10 +56,27 @@ pub struct TargetSelector {
     pub follow_prod_deps_only: bool,
     pub parent_dir: Option<AnchoredSystemPathBuf>,
     pub name_pattern: String,
+    /// Matches packages that declare this tag in their `turbo.json`
+    pub tag: Option<String>,
     pub git_range: Option<GitRange>,
+    /// Limits how many levels of dependencies or dependents `...` includes.
+    /// `None` includes the full transitive closure.
+    pub depth: Option<usize>,
     pub raw: String,
 }
 
+/// Prefix that turns the name part of a selector into a tag pattern
+const TAG_PREFIX: &str = "tag:";
+
+/// Splits a `tag:` pattern out of the name part of a selector
+fn parse_name(name: &str) -> Result<(String, Option<String>), InvalidSelectorError> {
+    match name.strip_prefix(TAG_PREFIX) {
+        Some("") => Err(InvalidSelectorError::EmptyTag),
+        Some(tag) => Ok((String::new(), Some(tag.to_string()))),
+        None => Ok((name.to_string(), None)),
+    }
+}
+
 impl FromStr for TargetSelector {
     type Err = InvalidSelectorError;
 
@@ -121,12 +139,14 @@ impl FromStr for TargetSelector {
                         ..Default::default()
                     })
                 } else {
+                    let (name_pattern, tag) = parse_name(selector)?;
                     Ok(TargetSelector {
                         exclude,
                         exclude_self,
                         include_dependencies,
                         include_dependents,
-                        name_pattern: selector.to_string(),
+                        name_pattern,
+                        tag,
                         raw: raw_selector.to_string(),
                         ..Default::default()
                     })
@@ -136,9 +156,7 @@ impl FromStr for TargetSelector {
 
         let mut pre_add_dependencies = false;
 
-        let name_pattern = captures
-            .name("name")
-            .map_or(String::new(), |m| m.as_str().to_string());
+        let (name_pattern, tag) = parse_name(captures.name("name").map_or("", |m| m.as_str()))?;
 
         let mut parent_dir = None;
 
@@ -160,7 +178,7 @@ impl FromStr for TargetSelector {
 
         let git_range = if let Some(commits) = captures.name("commits") {
             let commits_str = if let Some(commits) = commits.as_str().strip_prefix("...") {
-                if parent_dir.is_none() && name_pattern.is_empty() {
+                if parent_dir.is_none() && name_pattern.is_empty() && tag.is_none() {
                     return Err(InvalidSelectorError::CantMatchDependencies);
                 }
                 pre_add_dependencies = true;
@@ -230,6 +248,7 @@ impl FromStr for TargetSelector {
             include_dependents,
             match_dependencies: pre_add_dependencies,
             name_pattern,
+            tag,
             parent_dir,
             raw: raw_selector.to_string(),
             ..Default::default()
@@ -240,19 +259,21 @@ impl FromStr for TargetSelector {
 /// Errors when parsing target selectors.
 #[derive(Debug, Error, PartialEq)]
 pub enum InvalidSelectorError {
-    #[error("cannot use match dependencies without specifying either a directory or package")]
+    #[error("cannot use match dependencies without specifying either a directory, package, or tag")]
     CantMatchDependencies,
     #[error("invalid anchored path: {0}")]
     InvalidAnchoredPath(String),
     #[error("empty path specification")]
     EmptyPathSpecification,
+    #[error("empty tag specification")]
+    EmptyTag,
     #[error("invalid git range selector: {0}")]
     InvalidGitRange(String),
 
     #[error("invalid selector regex: {0}")]
     InvalidSelectorRegex(String),
 
-    #[error("selector \"{0}\" must have a reference, directory, or name pattern")]
+    #[error("selector \"{0}\" must have a reference, directory, tag, or name pattern")]
     InvalidSelector(String),
 }
 
@@ -317,6 +338,12 @@ mod test {
     #[test_case("foo...[master]...", TargetSelector { raw: "foo...[master]...".to_string(), git_range: Some(GitRange { from_ref: Some("master".to_string()), to_ref: None, include_uncommitted: true, ..Default::default() }), name_pattern: "foo".to_string(), match_dependencies: true, include_dependencies: true, ..Default::default() }; "foo...[master] dot dot dot")]
     #[test_case("{foo}...[master]", TargetSelector { raw: "{foo}...[master]".to_string(), git_range: Some(GitRange { from_ref: Some("master".to_string()), to_ref: None, include_uncommitted: true, ..Default::default() }), parent_dir: Some(AnchoredSystemPathBuf::try_from("foo").unwrap()), match_dependencies: true, ..Default::default() }; " curly brackets foo...[master]")]
     #[test_case("...@repo/pkg[master]", TargetSelector { raw: "...@repo/pkg[master]".to_string(), git_range: Some(GitRange { from_ref: Some("master".to_string()), to_ref: None, include_uncommitted: true, ..Default::default() }), name_pattern: "@repo/pkg".to_string(), include_dependents: true, ..Default::default() }; "gh 9096")]
+    #[test_case("tag:frontend", TargetSelector { raw: "tag:frontend".to_string(), tag: Some("frontend".to_string()), ..Default::default() }; "tag")]
+    #[test_case("!tag:frontend", TargetSelector { raw: "!tag:frontend".to_string(), tag: Some("frontend".to_string()), exclude: true, ..Default::default() }; "excluded tag")]
+    #[test_case("...tag:service-*...", TargetSelector { raw: "...tag:service-*...".to_string(), tag: Some("service-*".to_string()), include_dependents: true, include_dependencies: true, ..Default::default() }; "tag glob with dependents and dependencies")]
+    #[test_case("tag:service{apps/*}", TargetSelector { raw: "tag:service{apps/*}".to_string(), tag: Some("service".to_string()), parent_dir: Some(AnchoredSystemPathBuf::try_from(if cfg!(windows) { "apps\\*" } else { "apps/*" }).unwrap()), ..Default::default() }; "tag with directory")]
+    #[test_case("tag:service[main]", TargetSelector { raw: "tag:service[main]".to_string(), git_range: Some(GitRange { from_ref: Some("main".to_string()), to_ref: None, include_uncommitted: true, ..Default::default() }), tag: Some("service".to_string()), ..Default::default() }; "tag with git range")]
+    #[test_case("tag:service...[main]", TargetSelector { raw: "tag:service...[main]".to_string(), git_range: Some(GitRange { from_ref: Some("main".to_string()), to_ref: None, include_uncommitted: true, ..Default::default() }), tag: Some("service".to_string()), match_dependencies: true, ..Default::default() }; "tag match dependencies")]
     fn parse_target_selector(raw_selector: &str, want: TargetSelector) {
         let result = TargetSelector::from_str(raw_selector);
 
@@ -343,6 +370,8 @@ mod test {
     #[test_case("[..]" ; "missing entire two-dot git range")]
     #[test_case("[some-ref...]" ; "missing git range end")]
     #[test_case("[...]" ; "missing entire git range")]
+    #[test_case("tag:" ; "empty tag")]
+    #[test_case("tag:[main]" ; "empty tag with git range")]
     fn parse_target_selector_invalid(raw_selector: &str) {
         let result = TargetSelector::from_str(raw_selector);
 
@@ -4,7 +4,7 @@
 //! tasks.
 
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     ffi::OsString,
     path::{Path, PathBuf},
 };
@@ -15,10 +15,11 @@ use turborepo_env::EnvironmentVariableMap;
 use turborepo_process::Command;
 use turborepo_repository::{
     package_graph::{PackageGraph, PackageInfo, PackageName},
+    package_json::PackageJson,
     package_manager::PackageManager,
 };
 use turborepo_task_id::TaskId;
-use turborepo_types::TaskArgs;
+use turborepo_types::{TaskArgs, TaskCommand};
 
 use crate::MfeConfigProvider;
 
@@ -83,6 +84,8 @@ fn package_manager_command(
 /// - `PackageGraphCommandProvider` in turborepo-lib (executes package.json
 ///   scripts)
 /// - `MicroFrontendProxyProvider` in turborepo-lib (starts MFE proxy)
+/// - `InlineCommandProvider` in turborepo-lib (executes `command` from
+///   turbo.json)
 pub trait CommandProvider<E> {
     /// Create a command for the given task.
     ///
@@ -188,10 +191,32 @@ impl PackageInfoProvider for PackageGraph {
     }
 }
 
+/// How package.json scripts are run
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ScriptRunner {
+    /// `<package manager> run <script>`
+    PackageManager,
+    /// The script is run through the shell by turbo itself
+    Direct(DirectScriptConfig),
+}
+
+/// What turbo needs to run scripts the way the package manager would
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct DirectScriptConfig {
+    run_pre_post_scripts: bool,
+    /// `npm_config_user_agent`, which tools read to detect the package manager
+    user_agent: String,
+    /// `npm_execpath`, the package manager's executable
+    execpath: Option<PathBuf>,
+    /// `npm_node_execpath`, the Node.js executable
+    node_execpath: Option<PathBuf>,
+}
+
 /// Command provider that creates commands from package.json scripts.
 ///
 /// This provider looks up the task's script in the package's package.json
-/// and creates a command to execute it via the package manager.
+/// and creates a command to execute it via the package manager, or directly
+/// through the shell when direct script execution is enabled.
 #[derive(Debug)]
 pub struct PackageGraphCommandProvider<'a, M = crate::NoMfeConfig> {
     repo_root: &'a AbsoluteSystemPath,
@@ -199,6 +224,7 @@ pub struct PackageGraphCommandProvider<'a, M = crate::NoMfeConfig> {
     package_manager_binary: Result<PathBuf, which::Error>,
     task_args: TaskArgs<'a>,
     mfe_configs: Option<&'a M>,
+    script_runner: ScriptRunner,
 }
 
 impl<'a, M: MfeConfigProvider> PackageGraphCommandProvider<'a, M> {
@@ -215,9 +241,23 @@ impl<'a, M: MfeConfigProvider> PackageGraphCommandProvider<'a, M> {
             package_manager_binary,
             task_args,
             mfe_configs,
+            script_runner: ScriptRunner::PackageManager,
         }
     }
 
+    /// Runs scripts through the shell instead of the package manager, setting
+    /// up the environment and `pre`/`post` scripts the package manager would.
+    pub fn with_direct_script_execution(mut self) -> Self {
+        let package_manager = self.package_graph.package_manager();
+        self.script_runner = ScriptRunner::Direct(DirectScriptConfig {
+            run_pre_post_scripts: package_manager.runs_pre_post_scripts(self.repo_root),
+            user_agent: user_agent(package_manager, self.package_graph.root_package_json()),
+            execpath: self.package_manager_binary.as_ref().ok().cloned(),
+            node_execpath: which::which("node").ok(),
+        });
+        self
+    }
+
     fn package_info(&self, task_id: &TaskId) -> Result<&PackageInfo, CommandProviderError> {
         self.package_graph
             .package_info(&PackageName::from(task_id.package()))
@@ -247,29 +287,44 @@ impl<'a, M: MfeConfigProvider, E: From<CommandProviderError>> CommandProvider<E>
         {
             return Ok(None);
         }
-        let package_manager_binary = self
-            .package_manager_binary
-            .as_deref()
-            .map_err(|e| CommandProviderError::from(*e))?;
-        let (program, mut args) =
-            package_manager_command(self.package_graph.package_manager(), package_manager_binary);
-        args.extend([OsString::from("run"), OsString::from(task_id.task())]);
-        if let Some(pass_through_args) = self.task_args.args_for_task(task_id) {
-            args.extend(
-                self.package_graph
-                    .package_manager()
-                    .arg_separator(pass_through_args)
-                    .map(OsString::from),
-            );
-            args.extend(pass_through_args.iter().map(OsString::from));
-        }
-        let mut cmd = Command::new(program);
-        cmd.args(args);
-
-        let package_dir = self.repo_root.resolve(workspace_info.package_path());
-        cmd.current_dir(package_dir);
-
-        apply_environment(&mut cmd, environment);
+        let mut cmd = match &self.script_runner {
+            ScriptRunner::Direct(config) => direct_script_command(
+                self.repo_root,
+                workspace_info,
+                task_id.task(),
+                self.task_args.args_for_task(task_id).unwrap_or_default(),
+                config,
+                environment,
+            ),
+            ScriptRunner::PackageManager => {
+                let package_manager_binary = self
+                    .package_manager_binary
+                    .as_deref()
+                    .map_err(|e| CommandProviderError::from(*e))?;
+                let (program, mut args) = package_manager_command(
+                    self.package_graph.package_manager(),
+                    package_manager_binary,
+                );
+                args.extend([OsString::from("run"), OsString::from(task_id.task())]);
+                if let Some(pass_through_args) = self.task_args.args_for_task(task_id) {
+                    args.extend(
+                        self.package_graph
+                            .package_manager()
+                            .arg_separator(pass_through_args)
+                            .map(OsString::from),
+                    );
+                    args.extend(pass_through_args.iter().map(OsString::from));
+                }
+                let mut cmd = Command::new(program);
+                cmd.args(args);
+
+                let package_dir = self.repo_root.resolve(workspace_info.package_path());
+                cmd.current_dir(package_dir);
+
+                apply_environment(&mut cmd, environment);
+                cmd
+            }
+        };
 
         // If the task has an associated proxy, then we indicate this to the underlying
         // task via an env var
@@ -304,6 +359,370 @@ impl<'a, M: MfeConfigProvider, E: From<CommandProviderError>> CommandProvider<E>
     }
 }
 
+/// Creates a command that runs a package.json script through the shell the
+/// way `npm run` would, without starting the package manager.
+fn direct_script_command(
+    repo_root: &AbsoluteSystemPath,
+    package_info: &PackageInfo,
+    script_name: &str,
+    pass_through_args: &[String],
+    config: &DirectScriptConfig,
+    environment: &EnvironmentVariableMap,
+) -> Command {
+    let script = |name: &str| {
+        package_info
+            .package_json
+            .scripts
+            .get(name)
+            .map(|script| script.as_inner().as_str())
+            .filter(|script| !script.is_empty())
+    };
+    let main_script = script(script_name).unwrap_or_default();
+    let mut command_line = main_script.to_owned();
+    for arg in pass_through_args {
+        command_line.push(' ');
+        command_line.push_str(&shell_quote(arg));
+    }
+
+    let (pre_event, post_event) = (format!("pre{script_name}"), format!("post{script_name}"));
+    let (pre_script, post_script) = if config.run_pre_post_scripts {
+        (script(&pre_event), script(&post_event))
+    } else {
+        (None, None)
+    };
+    let mut cmd = if pre_script.is_none() && post_script.is_none() {
+        let mut cmd = shell_command(&command_line);
+        apply_environment(&mut cmd, environment);
+        cmd
+    } else {
+        // Each stage sees its own lifecycle event, and a failing stage stops
+        // the ones after it
+        let stages = [
+            pre_script.map(|script| LifecycleStage {
+                event: &pre_event,
+                script,
+                command_line: script,
+            }),
+            Some(LifecycleStage {
+                event: script_name,
+                script: main_script,
+                command_line: &command_line,
+            }),
+            post_script.map(|script| LifecycleStage {
+                event: &post_event,
+                script,
+                command_line: script,
+            }),
+        ];
+        let stages: Vec<_> = stages.into_iter().flatten().collect();
+        lifecycle_command(&stages, environment)
+    };
+
+    let package_dir = repo_root.resolve(package_info.package_path());
+    cmd.current_dir(package_dir.clone());
+
+    cmd.env("npm_command", "run-script");
+    cmd.env("npm_lifecycle_event", script_name);
+    cmd.env("npm_lifecycle_script", main_script);
+    cmd.env(
+        "npm_package_json",
+        repo_root.resolve(package_info.package_json_path()).as_str(),
+    );
+    if let Some(name) = &package_info.package_json.name {
+        cmd.env("npm_package_name", name.as_inner());
+    }
+    if let Some(version) = &package_info.package_json.version {
+        cmd.env("npm_package_version", version);
+    }
+    cmd.env("INIT_CWD", package_dir.as_str());
+    cmd.env("npm_config_user_agent", &config.user_agent);
+    if let Some(execpath) = &config.execpath {
+        cmd.env("npm_execpath", execpath);
+    }
+    if let Some(node_execpath) = &config.node_execpath {
+        cmd.env("npm_node_execpath", node_execpath);
+    }
+    if let Some((name, path)) = node_modules_bin_path(repo_root, &package_dir, environment) {
+        cmd.env(name, path);
+    }
+
+    cmd
+}
+
+/// `<package manager>/<version> <platform> <arch>`, in the form of the
+/// `npm_config_user_agent` that package managers set. The version is read
+/// from the `packageManager` field of the root package.json.
+fn user_agent(package_manager: &PackageManager, root_package_json: &PackageJson) -> String {
+    let name = package_manager.command();
+    let version = root_package_json
+        .package_manager
+        .as_ref()
+        .and_then(|field| field.as_inner().split_once('@'))
+        .filter(|(field_name, _)| *field_name == name)
+        .map(|(_, version)| version.split('+').next().unwrap_or(version));
+    let platform = match std::env::consts::OS {
+        "macos" => "darwin",
+        "windows" => "win32",
+        os => os,
+    };
+    let arch = match std::env::consts::ARCH {
+        "x86_64" => "x64",
+        "x86" => "ia32",
+        "aarch64" => "arm64",
+        arch => arch,
+    };
+    match version {
+        Some(version) => format!("{name}/{version} {platform} {arch}"),
+        None => format!("{name} {platform} {arch}"),
+    }
+}
+
+/// One of the `pre`, main and `post` scripts run for a package.json script
+struct LifecycleStage<'a> {
+    event: &'a str,
+    script: &'a str,
+    /// The script along with any arguments passed to it
+    command_line: &'a str,
+}
+
+#[cfg(windows)]
+fn lifecycle_command(stages: &[LifecycleStage], environment: &EnvironmentVariableMap) -> Command {
+    // cmd expands `%variables%` when it reads a line, before any `set` on the
+    // line runs, so each stage runs in its own cmd that reads the script
+    // after the stage's variables are set. The outer cmd takes the scripts
+    // from the environment with delayed expansion so that it never parses
+    // them itself.
+    let mut command_line = Vec::new();
+    let mut variables = Vec::new();
+    for (i, stage) in stages.iter().enumerate() {
+        let script_variable = format!("TURBO_LIFECYCLE_SCRIPT_{i}");
+        let command_variable = format!("TURBO_LIFECYCLE_COMMAND_{i}");
+        command_line.push(format!(
+            "(set \"npm_lifecycle_event={}\" & set \"npm_lifecycle_script=!{script_variable}!\" & \
+             cmd /d /s /c \"!{command_variable}!\")",
+            stage.event
+        ));
+        variables.push((script_variable, stage.script));
+        variables.push((command_variable, stage.command_line));
+    }
+
+    let mut cmd = Command::new("cmd.exe");
+    cmd.args(["/v:on", "/d", "/s", "/c", &command_line.join(" && ")]);
+    apply_environment(&mut cmd, environment);
+    for (name, value) in variables {
+        cmd.env(name, value);
+    }
+    cmd
+}
+
+#[cfg(not(windows))]
+fn lifecycle_command(stages: &[LifecycleStage], environment: &EnvironmentVariableMap) -> Command {
+    // The command line is on its own line so a trailing comment in the script
+    // can't swallow the closing parenthesis
+    let stages: Vec<_> = stages
+        .iter()
+        .map(|stage| {
+            format!(
+                "(\nexport npm_lifecycle_event={} npm_lifecycle_script={}\n{}\n)",
+                shell_quote(stage.event),
+                shell_quote(stage.script),
+                stage.command_line
+            )
+        })
+        .collect();
+    let mut cmd = shell_command(&stages.join(" && "));
+    apply_environment(&mut cmd, environment);
+    cmd
+}
+
+/// Command provider for tasks that define a `command` in turbo.json.
+///
+/// Inline commands take precedence over a package.json script with the same
+/// name, so this provider must be added before `PackageGraphCommandProvider`.
+#[derive(Debug)]
+pub struct InlineCommandProvider<'a, T> {
+    repo_root: &'a AbsoluteSystemPath,
+    package_graph: &'a T,
+    commands: HashMap<TaskId<'static>, &'a TaskCommand>,
+    task_args: TaskArgs<'a>,
+}
+
+impl<'a, T: PackageInfoProvider> InlineCommandProvider<'a, T> {
+    /// Creates a new `InlineCommandProvider`.
+    ///
+    /// # Arguments
+    /// * `repo_root` - The root of the repository
+    /// * `package_graph` - The package graph provider
+    /// * `commands` - The inline command of each task that defines one
+    /// * `task_args` - Arguments to pass through to the requested tasks
+    pub fn new(
+        repo_root: &'a AbsoluteSystemPath,
+        package_graph: &'a T,
+        commands: impl Iterator<Item = (&'a TaskId<'static>, &'a TaskCommand)>,
+        task_args: TaskArgs<'a>,
+    ) -> Self {
+        Self {
+            repo_root,
+            package_graph,
+            commands: commands
+                .map(|(task_id, command)| (task_id.clone(), command))
+                .collect(),
+            task_args,
+        }
+    }
+}
+
+impl<'a, T: PackageInfoProvider + Send + Sync, E: From<CommandProviderError>> CommandProvider<E>
+    for InlineCommandProvider<'a, T>
+{
+    fn command(
+        &self,
+        task_id: &TaskId,
+        environment: &EnvironmentVariableMap,
+    ) -> Result<Option<Command>, E> {
+        let Some(command) = self.commands.get(task_id) else {
+            return Ok(None);
+        };
+        let package_info = self
+            .package_graph
+            .package_info(&PackageName::from(task_id.package()))
+            .ok_or_else(|| CommandProviderError::MissingPackage {
+                package_name: task_id.package().into(),
+                task_id: task_id.clone().into_owned(),
+            })?;
+        let package_dir = self.repo_root.resolve(package_info.package_path());
+
+        let variables = [
+            ("TURBO_ROOT", self.repo_root.as_str()),
+            ("TURBO_PACKAGE_DIR", package_dir.as_str()),
+            ("TURBO_PACKAGE_NAME", task_id.package()),
+        ];
+        let pass_through_args = self.task_args.args_for_task(task_id).unwrap_or_default();
+
+        let mut cmd = match command {
+            TaskCommand::Shell(command) => {
+                let mut command_line = substitute_variables(command, &variables);
+                for arg in pass_through_args {
+                    command_line.push(' ');
+                    command_line.push_str(&shell_quote(arg));
+                }
+                shell_command(&command_line)
+            }
+            TaskCommand::Argv(argv) => {
+                let mut argv = argv.iter().map(|arg| substitute_variables(arg, &variables));
+                let program = argv.next().unwrap_or_default();
+                let mut cmd = Command::new(program);
+                cmd.args(argv.chain(pass_through_args.iter().cloned()));
+                cmd
+            }
+        };
+        cmd.current_dir(package_dir.clone());
+
+        apply_environment(&mut cmd, environment);
+        cmd.envs(variables);
+        // Match package manager scripts, which can run binaries installed by
+        // the package or the workspace root
+        if let Some((name, path)) = node_modules_bin_path(self.repo_root, &package_dir, environment)
+        {
+            cmd.env(name, path);
+        }
+
+        cmd.open_stdin();
+
+        Ok(Some(cmd))
+    }
+}
+
+/// Replaces `$NAME` and `${NAME}` for each of the given variables. Any other
+/// `$` is left for the shell or program to interpret.
+fn substitute_variables(input: &str, variables: &[(&str, &str)]) -> String {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+    while let Some(index) = rest.find('$') {
+        output.push_str(&rest[..index]);
+        rest = &rest[index + 1..];
+        let (name, remainder) = match rest.strip_prefix('{').and_then(|r| r.split_once('}')) {
+            Some(braced) => braced,
+            None => {
+                let end = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                rest.split_at(end)
+            }
+        };
+        match variables.iter().find(|(variable, _)| *variable == name) {
+            Some((_, value)) => {
+                output.push_str(value);
+                rest = remainder;
+            }
+            None => output.push('$'),
+        }
+    }
+    output.push_str(rest);
+    output
+}
+
+#[cfg(windows)]
+fn shell_command(command_line: &str) -> Command {
+    let mut cmd = Command::new("cmd.exe");
+    cmd.args(["/d", "/s", "/c", command_line]);
+    cmd
+}
+
+#[cfg(not(windows))]
+fn shell_command(command_line: &str) -> Command {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", command_line]);
+    cmd
+}
+
+#[cfg(windows)]
+fn shell_quote(arg: &str) -> String {
+    format!("\"{}\"", arg.replace('"', "\"\""))
+}
+
+#[cfg(not(windows))]
+fn shell_quote(arg: &str) -> String {
+    format!("'{}'", arg.replace('\'', r"'\''"))
+}
+
+/// Prepends the `node_modules/.bin` directories of the package, the
+/// directories between it and the repository root, and the root itself to the
+/// task's `PATH`. Returns the name of the variable along with its new value.
+fn node_modules_bin_path<'e>(
+    repo_root: &AbsoluteSystemPath,
+    package_dir: &AbsoluteSystemPath,
+    environment: &'e EnvironmentVariableMap,
+) -> Option<(&'e str, OsString)> {
+    // Windows environment variables are case-insensitive, usually `Path`
+    let (name, path) = environment
+        .iter()
+        .find(|(name, _)| {
+            if cfg!(windows) {
+                name.eq_ignore_ascii_case("PATH")
+            } else {
+                name.as_str() == "PATH"
+            }
+        })
+        .map(|(name, path)| (name.as_str(), path.as_str()))
+        .unwrap_or(("PATH", ""));
+
+    let dirs = package_dir
+        .ancestors()
+        .take_while(|dir| repo_root.contains(dir))
+        .map(|dir| {
+            dir.join_components(&["node_modules", ".bin"])
+                .as_std_path()
+                .to_owned()
+        });
+    let path = if path.is_empty() {
+        std::env::join_paths(dirs)
+    } else {
+        std::env::join_paths(dirs.chain(std::env::split_paths(path)))
+    };
+    path.ok().map(|path| (name, path))
+}
+
 /// Command provider for microfrontends proxy tasks.
 ///
 /// This provider handles the `proxy` task for microfrontends configurations,
@@ -755,6 +1174,316 @@ mod tests {
         assert!(cmd.is_none(), "expected no cmd, got {cmd:?}");
     }
 
+    fn inline_command(
+        repo_root: &AbsoluteSystemPathBuf,
+        command: &TaskCommand,
+        pass_through_args: &[String],
+    ) -> Command {
+        let package_info_provider = MockPackageInfoProvider {
+            package_info: package_info(PackageJson::default()),
+            package_manager: PackageManager::Npm,
+        };
+        let task_id = TaskId::new("web", "codegen");
+        let tasks = ["codegen".to_string()];
+        let provider = InlineCommandProvider::new(
+            repo_root,
+            &package_info_provider,
+            std::iter::once((&task_id, command)),
+            TaskArgs::new(pass_through_args, &tasks),
+        );
+
+        CommandProvider::<CommandProviderError>::command(
+            &provider,
+            &task_id,
+            &filtered_environment(),
+        )
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_substitute_variables() {
+        let variables = [("TURBO_PACKAGE_DIR", "/repo/web"), ("TURBO_ROOT", "/repo")];
+        assert_eq!(
+            substitute_variables("$TURBO_PACKAGE_DIR/src ${TURBO_ROOT}/dist", &variables),
+            "/repo/web/src /repo/dist"
+        );
+        assert_eq!(
+            substitute_variables("$TURBO_ROOTS $HOME ${OTHER} $", &variables),
+            "$TURBO_ROOTS $HOME ${OTHER} $"
+        );
+    }
+
+    #[test]
+    fn test_inline_command_not_provided_for_other_tasks() {
+        let (_tempdir, repo_root, _package_dir) = create_test_repo();
+        let package_info_provider = MockPackageInfoProvider {
+            package_info: package_info(PackageJson::default()),
+            package_manager: PackageManager::Npm,
+        };
+        let task_id = TaskId::new("web", "codegen");
+        let command = TaskCommand::Shell("echo codegen".to_string());
+        let provider = InlineCommandProvider::new(
+            &repo_root,
+            &package_info_provider,
+            std::iter::once((&task_id, &command)),
+            TaskArgs::new(&[], &[]),
+        );
+
+        let cmd = CommandProvider::<CommandProviderError>::command(
+            &provider,
+            &TaskId::new("web", "build"),
+            &EnvironmentVariableMap::default(),
+        )
+        .unwrap();
+        assert!(cmd.is_none(), "expected no cmd, got {cmd:?}");
+    }
+
+    #[test]
+    fn test_inline_argv_command_is_not_run_in_shell() {
+        let (_tempdir, repo_root, package_dir) = create_test_repo();
+        let command = TaskCommand::Argv(vec![
+            "docker".to_string(),
+            "build".to_string(),
+            "$TURBO_PACKAGE_DIR".to_string(),
+        ]);
+        let cmd = inline_command(&repo_root, &command, &["--no-cache".to_string()]);
+
+        assert_eq!(cmd.program(), OsStr::new("docker"));
+        assert!(
+            cmd.label()
+                .ends_with(&format!("build {} --no-cache", package_dir.display())),
+            "unexpected command: {}",
+            cmd.label()
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_inline_shell_command_runs_in_package_dir() {
+        let (_tempdir, repo_root, package_dir) = create_test_repo();
+        let command = TaskCommand::Shell(
+            "echo \"$TURBO_PACKAGE_NAME $PWD ${TURBO_ROOT}\" \"$ALLOWED_VAR\"".to_string(),
+        );
+        let cmd = inline_command(&repo_root, &command, &["it's".to_string()]);
+        let stdout = command_stdout(cmd).await;
+
+        // The shell sets `$PWD` from the resolved working directory
+        let package_dir = fs::canonicalize(package_dir).unwrap();
+        assert_eq!(
+            stdout.trim_end(),
+            format!("web {} {repo_root} allowed it's", package_dir.display())
+        );
+    }
+
+    fn script_package_info(scripts: &[(&str, &str)]) -> PackageInfo {
+        package_info(PackageJson {
+            name: Some(Spanned::new("web".to_string())),
+            version: Some("1.2.3".to_string()),
+            scripts: scripts
+                .iter()
+                .map(|(name, script)| (name.to_string(), Spanned::new(script.to_string())))
+                .collect(),
+            ..Default::default()
+        })
+    }
+
+    fn direct_script_config(run_pre_post_scripts: bool) -> DirectScriptConfig {
+        DirectScriptConfig {
+            run_pre_post_scripts,
+            user_agent: "pnpm/9.1.0 linux x64".to_string(),
+            execpath: Some(PathBuf::from("/usr/local/bin/pnpm")),
+            node_execpath: Some(PathBuf::from("/usr/local/bin/node")),
+        }
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_direct_script_sets_lifecycle_environment() {
+        let (_tempdir, repo_root, _package_dir) = create_test_repo();
+        let package_info = script_package_info(&[(
+            "build",
+            "echo \"$npm_lifecycle_event $npm_package_name@$npm_package_version $ALLOWED_VAR\"; \
+             echo \"$npm_config_user_agent\"; echo \"$npm_execpath $npm_node_execpath\"",
+        )]);
+        let cmd = direct_script_command(
+            &repo_root,
+            &package_info,
+            "build",
+            &["it's".to_string()],
+            &direct_script_config(true),
+            &filtered_environment(),
+        );
+        let stdout = command_stdout(cmd).await;
+        assert_eq!(
+            stdout.lines().collect::<Vec<_>>(),
+            [
+                "build web@1.2.3 allowed",
+                "pnpm/9.1.0 linux x64",
+                "/usr/local/bin/pnpm /usr/local/bin/node it's",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_user_agent_reads_package_manager_version() {
+        let platform = user_agent(&PackageManager::Pnpm9, &PackageJson::default());
+        assert!(platform.starts_with("pnpm "), "{platform}");
+        let platform = platform.trim_start_matches("pnpm ");
+
+        let root_package_json = PackageJson {
+            package_manager: Some(Spanned::new("pnpm@9.1.0+sha512.abc".to_string())),
+            ..Default::default()
+        };
+        assert_eq!(
+            user_agent(&PackageManager::Pnpm9, &root_package_json),
+            format!("pnpm/9.1.0 {platform}")
+        );
+        // A `packageManager` field naming another package manager is ignored
+        assert_eq!(
+            user_agent(&PackageManager::Npm, &root_package_json),
+            format!("npm {platform}")
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_direct_script_prepends_node_modules_bin_to_path() {
+        let (_tempdir, repo_root, package_dir) = create_test_repo();
+        let package_info = script_package_info(&[("build", "echo \"$PATH\"")]);
+        let cmd = direct_script_command(
+            &repo_root,
+            &package_info,
+            "build",
+            &[],
+            &direct_script_config(true),
+            &filtered_environment(),
+        );
+        let stdout = command_stdout(cmd).await;
+
+        let package_bin = package_dir.join("node_modules").join(".bin");
+        let root_bin = repo_root.join_components(&["node_modules", ".bin"]);
+        assert!(
+            stdout.starts_with(&format!("{}:{root_bin}:", package_bin.display())),
+            "unexpected PATH: {stdout}"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_direct_script_includes_intermediate_node_modules_bins() {
+        let (_tempdir, repo_root, _package_dir) = create_test_repo();
+        let package_dir = repo_root.join_components(&["apps", "web"]);
+        package_dir.create_dir_all().unwrap();
+        let package_info = PackageInfo {
+            package_json_path: AnchoredSystemPathBuf::from_raw("apps/web/package.json").unwrap(),
+            ..script_package_info(&[("build", "echo \"$PATH\"")])
+        };
+        let cmd = direct_script_command(
+            &repo_root,
+            &package_info,
+            "build",
+            &[],
+            &direct_script_config(true),
+            &filtered_environment(),
+        );
+        let stdout = command_stdout(cmd).await;
+
+        let bins = [
+            repo_root.join_components(&["apps", "web", "node_modules", ".bin"]),
+            repo_root.join_components(&["apps", "node_modules", ".bin"]),
+            repo_root.join_components(&["node_modules", ".bin"]),
+        ];
+        assert!(
+            stdout.starts_with(&format!("{}:{}:{}:", bins[0], bins[1], bins[2])),
+            "unexpected PATH: {stdout}"
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_direct_script_runs_pre_and_post_scripts() {
+        let (_tempdir, repo_root, _package_dir) = create_test_repo();
+        let package_info = script_package_info(&[
+            ("prebuild", "echo $npm_lifecycle_event # comment"),
+            ("build", "echo $npm_lifecycle_event"),
+            ("postbuild", "echo $npm_lifecycle_event"),
+        ]);
+
+        let cmd = direct_script_command(
+            &repo_root,
+            &package_info,
+            "build",
+            &["--watch".to_string()],
+            &direct_script_config(true),
+            &filtered_environment(),
+        );
+        let stdout = command_stdout(cmd).await;
+        assert_eq!(stdout, "prebuild\nbuild --watch\npostbuild\n");
+
+        let cmd = direct_script_command(
+            &repo_root,
+            &package_info,
+            "build",
+            &[],
+            &direct_script_config(false),
+            &filtered_environment(),
+        );
+        let stdout = command_stdout(cmd).await;
+        assert_eq!(stdout, "build\n");
+    }
+
+    #[cfg(windows)]
+    #[tokio::test]
+    async fn test_direct_script_runs_pre_and_post_scripts() {
+        let (_tempdir, repo_root, _package_dir) = create_test_repo();
+        let package_info = script_package_info(&[
+            (
+                "prebuild",
+                "echo %npm_lifecycle_event% %npm_lifecycle_script%",
+            ),
+            ("build", "echo %npm_lifecycle_event%"),
+            ("postbuild", "echo %npm_lifecycle_event%"),
+        ]);
+
+        let cmd = direct_script_command(
+            &repo_root,
+            &package_info,
+            "build",
+            &[],
+            &direct_script_config(true),
+            &filtered_environment(),
+        );
+        let stdout = command_stdout(cmd).await;
+        assert_eq!(
+            stdout.lines().map(str::trim_end).collect::<Vec<_>>(),
+            [
+                "prebuild echo %npm_lifecycle_event% %npm_lifecycle_script%",
+                "build",
+                "postbuild",
+            ]
+        );
+    }
+
+    #[cfg(unix)]
+    #[tokio::test]
+    async fn test_direct_script_failing_pre_script_stops_run() {
+        let (_tempdir, repo_root, _package_dir) = create_test_repo();
+        let package_info = script_package_info(&[("prebuild", "exit 3"), ("build", "echo build")]);
+        let cmd = direct_script_command(
+            &repo_root,
+            &package_info,
+            "build",
+            &[],
+            &direct_script_config(true),
+            &filtered_environment(),
+        );
+
+        let output = tokio::process::Command::from(cmd).output().await.unwrap();
+        assert_eq!(output.status.code(), Some(3));
+        assert!(output.stdout.is_empty());
+    }
+
     #[cfg(windows)]
     #[test]
     fn npm_cmd_unwraps_to_node_and_npm_cli() {
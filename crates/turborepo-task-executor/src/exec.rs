@@ -9,6 +9,10 @@
 
 use std::{
     io::Write,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
     time::{Duration, Instant},
 };
 
@@ -18,15 +22,18 @@ use tracing::{Instrument, error};
 use turbopath::AnchoredSystemPathBuf;
 use turborepo_cache::CacheHitMetadata;
 use turborepo_env::{EnvironmentVariableMap, platform::PlatformEnv};
-use turborepo_process::{ChildExit, ChildStdin, Command, ProcessManager};
-use turborepo_run_cache::TaskCache;
-use turborepo_run_summary::TaskTracker;
+use turborepo_process::{ChildExit, ChildStdin, Command, ProcessManager, ResourceUsage};
+use turborepo_run_cache::{
+    TaskCache,
+    undeclared_outputs::{self, PackageSnapshot, UndeclaredFiles},
+};
+use turborepo_run_summary::{TaskResourceUsage, TaskTracker};
 use turborepo_task_id::TaskId;
 use turborepo_telemetry::events::{TrackedErrors, task::PackageTaskEventBuilder};
-use turborepo_types::{ContinueMode, StopExecution, UIMode};
-use turborepo_ui::ColorConfig;
+use turborepo_types::{ContinueMode, StopExecution, UIMode, UndeclaredFilesMode};
+use turborepo_ui::{ColorConfig, tui::event::CacheResult};
 
-use crate::{TaskAccessProvider, TaskOutput};
+use crate::{TaskAccessProvider, TaskOutput, input_trace::InputTrace};
 
 /// Windows NT status codes that indicate out-of-memory conditions.
 /// These are the signed i32 representations of the unsigned NT status codes.
@@ -95,6 +102,8 @@ pub enum ExecOutcome {
         exit_code: Option<i32>,
         message: String,
     },
+    /// Task was stopped after running longer than its configured timeout
+    TimedOut { message: String },
     /// Task didn't execute normally due to a shutdown being initiated by
     /// another task
     Shutdown,
@@ -152,6 +161,15 @@ pub trait TaskErrorCollector: Clone + Send {
 
     /// Push an error from task execution.
     fn push_execution_error(&self, task_id: String, command: String, exit_code: i32);
+
+    /// Push an error for a task that exceeded its timeout.
+    fn push_timeout_error(&self, task_id: String, command: String, timeout: Duration);
+
+    /// Push an error for a task that wrote files outside of its `outputs`.
+    fn push_undeclared_outputs_error(&self, task_id: String, files: Vec<String>);
+
+    /// Push an error for a task that read files outside of its `inputs`.
+    fn push_undeclared_inputs_error(&self, task_id: String, files: Vec<String>);
 }
 
 /// Provider trait for collecting task warnings.
@@ -184,6 +202,8 @@ pub struct TaskExecutor<H, E, W, A> {
     pub execution_env: EnvironmentVariableMap,
     pub manager: ProcessManager,
     pub takes_input: bool,
+    pub timeout: Option<Duration>,
+    pub retries: u32,
 
     // Configuration
     pub continue_on_error: ContinueMode,
@@ -191,6 +211,8 @@ pub struct TaskExecutor<H, E, W, A> {
     pub color_config: ColorConfig,
     pub is_github_actions: bool,
     pub pretty_prefix: StyledObject<String>,
+    pub undeclared_outputs: UndeclaredFilesMode,
+    pub input_trace: Option<InputTrace>,
 
     // Cache
     pub task_cache: TaskCache,
@@ -238,18 +260,21 @@ where
         callback: oneshot::Sender<Result<(), StopExecution>>,
         telemetry: &PackageTaskEventBuilder,
     ) -> Result<(), InternalError> {
-        let tracker: TaskTracker<chrono::DateTime<chrono::Local>> = tracker.start().await;
+        let mut tracker: TaskTracker<chrono::DateTime<chrono::Local>> = tracker.start().await;
         let task_start = Instant::now();
         let span = tracing::debug_span!("execute_task", task = %self.task_id.task());
         span.follows_from(parent_span_id);
 
         let result = self
-            .execute_inner(&task_output, &mut task_handle, telemetry)
+            .execute_inner(&mut tracker, &task_output, &mut task_handle, telemetry)
             .instrument(span)
             .await;
         let task_duration = task_start.elapsed();
 
-        let is_error = matches!(result, Ok(ExecOutcome::Task { .. }));
+        let is_error = matches!(
+            result,
+            Ok(ExecOutcome::Task { .. } | ExecOutcome::TimedOut { .. })
+        );
         let is_cache_hit = matches!(result, Ok(ExecOutcome::Success(SuccessOutcome::CacheHit)));
         // Flush any events buffered by the grouping layer.
         task_handle.finish(is_error);
@@ -263,7 +288,7 @@ where
             Ok(ExecOutcome::Success(_)) => {
                 callback.send(Ok(())).ok();
             }
-            Ok(ExecOutcome::Task { .. }) => {
+            Ok(ExecOutcome::Task { .. } | ExecOutcome::TimedOut { .. }) => {
                 callback
                     .send(stop_execution_for_task_error(self.continue_on_error))
                     .ok();
@@ -326,6 +351,13 @@ where
                     ContinueMode::Never => self.manager.stop().await,
                 }
             }
+            Ok(ExecOutcome::TimedOut { message }) => {
+                tracker.build_timed_out(message).await;
+                match self.continue_on_error {
+                    ContinueMode::Always | ContinueMode::DependenciesSuccessful => (),
+                    ContinueMode::Never => self.manager.stop().await,
+                }
+            }
             Ok(ExecOutcome::Shutdown) => {
                 tracker.cancel();
                 self.manager.stop().await;
@@ -345,6 +377,7 @@ where
 
     async fn execute_inner(
         &mut self,
+        tracker: &mut TaskTracker<chrono::DateTime<chrono::Local>>,
         task_output: &TaskOutput,
         task_handle: &mut turborepo_log::grouping::TaskHandle,
         telemetry: &PackageTaskEventBuilder,
@@ -367,6 +400,7 @@ where
             .await;
         match cache_result {
             Ok(Some(status)) => {
+                task_output.cache_status(CacheResult::Hit);
                 self.hash_tracker.insert_expanded_outputs(
                     self.task_id.clone(),
                     self.task_cache.expanded_outputs().to_vec(),
@@ -375,7 +409,7 @@ where
                     .insert_cache_status(self.task_id.clone(), status);
                 return Ok(ExecOutcome::Success(SuccessOutcome::CacheHit));
             }
-            Ok(None) => (),
+            Ok(None) => task_output.cache_status(CacheResult::Miss),
             Err(e) => {
                 telemetry.track_error(TrackedErrors::ErrorFetchingFromCache);
                 task_handle.emit(turborepo_log::LogEvent::new(
@@ -407,6 +441,188 @@ where
             }
         }
 
+        // Snapshot the package before the task runs so we can find files it
+        // writes outside of its `outputs`.
+        let package_snapshot = match self.undeclared_outputs {
+            UndeclaredFilesMode::Off => None,
+            UndeclaredFilesMode::Warn | UndeclaredFilesMode::Error => {
+                match self.task_cache.snapshot_package().await {
+                    Ok(snapshot) => snapshot,
+                    Err(e) => {
+                        error!(
+                            "unable to check {} for undeclared outputs: {e}",
+                            self.task_id
+                        );
+                        None
+                    }
+                }
+            }
+        };
+
+        // Trace the files the task reads so we can find those outside of its
+        // `inputs`.
+        let input_trace = self.input_trace.take().and_then(|input_trace| {
+            match input_trace.trace(self.cmd.clone()) {
+                Ok(cmd) => {
+                    self.cmd = cmd;
+                    Some(input_trace)
+                }
+                Err(e) => {
+                    error!(
+                        "unable to check {} for undeclared inputs: {e}",
+                        self.task_id
+                    );
+                    None
+                }
+            }
+        });
+
+        // Re-run failed attempts while retries remain. Earlier failures are
+        // recorded on the tracker but only the final attempt is reported as a
+        // task error, so a task that eventually passes doesn't fail the run.
+        let mut attempts = Attempts::new(self.retries);
+        let mut outcome = loop {
+            let outcome = self
+                .run_attempt(
+                    tracker,
+                    task_output,
+                    task_handle,
+                    telemetry,
+                    attempts.is_final(),
+                )
+                .await?;
+            let exit_code = match attempts.retry(outcome) {
+                Ok(exit_code) => exit_code,
+                Err(outcome) => break outcome,
+            };
+            tracker.attempt_failed(exit_code);
+            task_handle.emit(turborepo_log::LogEvent::new(
+                turborepo_log::Level::Warn,
+                turborepo_log::Source::task(&self.task_id_for_display),
+                format!(
+                    "command finished with error, retrying (attempt {} of {})...",
+                    attempts.next_attempt(),
+                    attempts.total()
+                ),
+            ));
+        };
+
+        if let Some(input_trace) = &input_trace {
+            if matches!(outcome, ExecOutcome::Success(SuccessOutcome::Run)) {
+                outcome = self.check_undeclared_inputs(input_trace, task_handle);
+            }
+            input_trace.clean_up();
+        }
+
+        match (outcome, package_snapshot) {
+            (ExecOutcome::Success(SuccessOutcome::Run), Some(snapshot)) => {
+                Ok(self.check_undeclared_outputs(snapshot, task_handle).await)
+            }
+            (outcome, _) => Ok(outcome),
+        }
+    }
+
+    /// Report files the task read that aren't part of its hash, failing the
+    /// task if configured to.
+    fn check_undeclared_inputs(
+        &self,
+        input_trace: &InputTrace,
+        task_handle: &mut turborepo_log::grouping::TaskHandle,
+    ) -> ExecOutcome {
+        let kind = UndeclaredFiles::Inputs;
+        match input_trace.undeclared_inputs() {
+            Ok(files) => self.report_undeclared_files(kind, input_trace.mode, files, task_handle),
+            Err(e) => {
+                error!("unable to check {} for {kind}: {e}", self.task_id);
+                ExecOutcome::Success(SuccessOutcome::Run)
+            }
+        }
+    }
+
+    /// Report files the task created or modified that aren't covered by its
+    /// `outputs`, failing the task if configured to.
+    async fn check_undeclared_outputs(
+        &self,
+        snapshot: PackageSnapshot,
+        task_handle: &mut turborepo_log::grouping::TaskHandle,
+    ) -> ExecOutcome {
+        let kind = UndeclaredFiles::Outputs {
+            ran_concurrently: snapshot.ran_concurrently(),
+        };
+        match self.task_cache.undeclared_outputs(snapshot).await {
+            Ok(files) => {
+                self.report_undeclared_files(kind, self.undeclared_outputs, files, task_handle)
+            }
+            Err(e) => {
+                error!("unable to check {} for {kind}: {e}", self.task_id);
+                ExecOutcome::Success(SuccessOutcome::Run)
+            }
+        }
+    }
+
+    /// Warn about files the task used without declaring them, or fail the
+    /// task in `Error` mode.
+    fn report_undeclared_files(
+        &self,
+        kind: UndeclaredFiles,
+        mode: UndeclaredFilesMode,
+        files: Vec<AnchoredSystemPathBuf>,
+        task_handle: &mut turborepo_log::grouping::TaskHandle,
+    ) -> ExecOutcome {
+        if files.is_empty() {
+            return ExecOutcome::Success(SuccessOutcome::Run);
+        }
+
+        // Files written while another task ran in the same package can't be
+        // attributed to this task, so they never fail it
+        let fail = match kind {
+            UndeclaredFiles::Inputs => mode == UndeclaredFilesMode::Error,
+            UndeclaredFiles::Outputs { ran_concurrently } => {
+                mode == UndeclaredFilesMode::Error && !ran_concurrently
+            }
+        };
+        if !fail {
+            self.task_cache.warn_undeclared_files(kind, &files);
+            return ExecOutcome::Success(SuccessOutcome::Run);
+        }
+
+        let message = format!(
+            "task {}: {}",
+            kind.description(),
+            undeclared_outputs::summarize(&files)
+        );
+        task_handle.emit(turborepo_log::LogEvent::new(
+            turborepo_log::Level::Error,
+            turborepo_log::Source::task(&self.task_id_for_display),
+            message.clone(),
+        ));
+        let task_id = self.task_id_for_display.clone();
+        let files = files.iter().map(|file| file.to_string()).collect();
+        match kind {
+            UndeclaredFiles::Inputs => self.errors.push_undeclared_inputs_error(task_id, files),
+            UndeclaredFiles::Outputs { .. } => {
+                self.errors.push_undeclared_outputs_error(task_id, files)
+            }
+        }
+        ExecOutcome::Task {
+            exit_code: None,
+            message,
+        }
+    }
+
+    /// Spawn the task's command once and wait for it to exit.
+    ///
+    /// Failures are only pushed to the error collector on the final attempt.
+    async fn run_attempt(
+        &mut self,
+        tracker: &mut TaskTracker<chrono::DateTime<chrono::Local>>,
+        task_output: &TaskOutput,
+        task_handle: &mut turborepo_log::grouping::TaskHandle,
+        telemetry: &PackageTaskEventBuilder,
+        final_attempt: bool,
+    ) -> Result<ExecOutcome, InternalError> {
+        let tui_sender = task_output.sender();
+
         // Spawn the process
         let cmd = self.cmd.clone();
         let mut process =
@@ -422,8 +638,10 @@ where
                         format!("command finished with error: {e}"),
                     ));
                     let error_string = e.to_string();
-                    self.errors
-                        .push_spawn_error(self.task_id_for_display.clone(), e);
+                    if final_attempt {
+                        self.errors
+                            .push_spawn_error(self.task_id_for_display.clone(), e);
+                    }
                     return Ok(ExecOutcome::Task {
                         exit_code: None,
                         message: error_string,
@@ -475,6 +693,20 @@ where
             None
         };
 
+        // Stop the task through its configured shutdown style once the timeout
+        // elapses. The output piping below keeps draining until the child
+        // actually exits, so nothing written during shutdown is lost.
+        let timed_out = Arc::new(AtomicBool::new(false));
+        let timeout_watchdog = self.timeout.map(|timeout| {
+            let mut child = process.clone();
+            let timed_out = timed_out.clone();
+            tokio::spawn(async move {
+                tokio::time::sleep(timeout).await;
+                timed_out.store(true, Ordering::Release);
+                child.stop().await;
+            })
+        });
+
         // Create output writer and pipe outputs.
         // The stdout_writer is scoped so that TaskHandleWriter drops before
         // the error handling section, which needs &mut task_handle for emit().
@@ -484,7 +716,11 @@ where
                 telemetry.track_error(TrackedErrors::FailedToCaptureOutputs);
             })?;
 
-            let status = match process.wait_with_piped_outputs(&mut stdout_writer).await {
+            let status = process.wait_with_piped_outputs(&mut stdout_writer).await;
+            if let Some(watchdog) = timeout_watchdog {
+                watchdog.abort();
+            }
+            let status = match status {
                 Ok(Some(exit_status)) => exit_status,
                 Err(e) => {
                     telemetry.track_error(TrackedErrors::FailedToPipeOutputs);
@@ -502,6 +738,39 @@ where
             }
             status
         };
+
+        if let Some(usage) = process.resource_usage() {
+            tracker.record_resource_usage(task_resource_usage(usage));
+        }
+
+        if let Some(timeout) = self.timeout
+            && timed_out.load(Ordering::Acquire)
+            && !process.is_closing()
+        {
+            if let Err(e) = self.task_cache.on_error(task_handle, tui_sender) {
+                error!("error reading logs: {e}");
+            }
+            let message = format!(
+                "command {} timed out after {}s",
+                process.label(),
+                timeout.as_secs()
+            );
+            if final_attempt {
+                Self::emit_task_error(
+                    task_handle,
+                    &self.task_id_for_display,
+                    self.continue_on_error,
+                    &message,
+                );
+                self.errors.push_timeout_error(
+                    self.task_id_for_display.clone(),
+                    process.label().to_string(),
+                    timeout,
+                );
+            }
+            return Ok(ExecOutcome::TimedOut { message });
+        }
+
         match exit_status {
             ChildExit::Finished(Some(0)) => Ok(ExecOutcome::Success(SuccessOutcome::Run)),
             ChildExit::Finished(Some(code)) => {
@@ -519,17 +788,19 @@ where
                 } else {
                     format!("command {} exited ({})", process.label(), code)
                 };
-                Self::emit_task_error(
-                    task_handle,
-                    &self.task_id_for_display,
-                    self.continue_on_error,
-                    &message,
-                );
-                self.errors.push_execution_error(
-                    self.task_id_for_display.clone(),
-                    process.label().to_string(),
-                    code,
-                );
+                if final_attempt {
+                    Self::emit_task_error(
+                        task_handle,
+                        &self.task_id_for_display,
+                        self.continue_on_error,
+                        &message,
+                    );
+                    self.errors.push_execution_error(
+                        self.task_id_for_display.clone(),
+                        process.label().to_string(),
+                        code,
+                    );
+                }
                 Ok(ExecOutcome::Task {
                     exit_code: Some(code),
                     message,
@@ -540,17 +811,19 @@ where
                     error!("error reading logs: {e}");
                 }
                 let message = format!("command {} exited unexpectedly", process.label());
-                Self::emit_task_error(
-                    task_handle,
-                    &self.task_id_for_display,
-                    self.continue_on_error,
-                    &message,
-                );
-                self.errors.push_execution_error(
-                    self.task_id_for_display.clone(),
-                    process.label().to_string(),
-                    1,
-                );
+                if final_attempt {
+                    Self::emit_task_error(
+                        task_handle,
+                        &self.task_id_for_display,
+                        self.continue_on_error,
+                        &message,
+                    );
+                    self.errors.push_execution_error(
+                        self.task_id_for_display.clone(),
+                        process.label().to_string(),
+                        1,
+                    );
+                }
                 Ok(ExecOutcome::Task {
                     exit_code: Some(1),
                     message,
@@ -566,17 +839,19 @@ where
                     process.label(),
                     SIGKILL_EXIT_CODE
                 );
-                Self::emit_task_error(
-                    task_handle,
-                    &self.task_id_for_display,
-                    self.continue_on_error,
-                    &message,
-                );
-                self.errors.push_execution_error(
-                    self.task_id_for_display.clone(),
-                    process.label().to_string(),
-                    SIGKILL_EXIT_CODE,
-                );
+                if final_attempt {
+                    Self::emit_task_error(
+                        task_handle,
+                        &self.task_id_for_display,
+                        self.continue_on_error,
+                        &message,
+                    );
+                    self.errors.push_execution_error(
+                        self.task_id_for_display.clone(),
+                        process.label().to_string(),
+                        SIGKILL_EXIT_CODE,
+                    );
+                }
                 Ok(ExecOutcome::Task {
                     exit_code: Some(SIGKILL_EXIT_CODE),
                     message,
@@ -621,6 +896,58 @@ fn stop_execution_for_task_error(continue_on_error: ContinueMode) -> Result<(),
     }
 }
 
+fn task_resource_usage(usage: ResourceUsage) -> TaskResourceUsage {
+    TaskResourceUsage {
+        user_cpu_ms: usage.user_time.as_millis() as u64,
+        system_cpu_ms: usage.system_time.as_millis() as u64,
+        peak_rss_bytes: usage.peak_rss_bytes,
+    }
+}
+
+/// Tracks the attempts made at running a task with `retries` configured.
+#[derive(Debug)]
+struct Attempts {
+    retries: u32,
+    retries_left: u32,
+}
+
+impl Attempts {
+    fn new(retries: u32) -> Self {
+        Self {
+            retries,
+            retries_left: retries,
+        }
+    }
+
+    /// Whether the next attempt is the last one, so its failure is reported
+    /// as a task error.
+    fn is_final(&self) -> bool {
+        self.retries_left == 0
+    }
+
+    /// Uses up a retry if `outcome` is a failure that should be retried,
+    /// returning the exit code of the failed attempt. Otherwise `outcome` is
+    /// the outcome of the task and is handed back.
+    fn retry(&mut self, outcome: ExecOutcome) -> Result<Option<i32>, ExecOutcome> {
+        let exit_code = match outcome {
+            ExecOutcome::Task { exit_code, .. } if self.retries_left > 0 => exit_code,
+            ExecOutcome::TimedOut { .. } if self.retries_left > 0 => None,
+            outcome => return Err(outcome),
+        };
+        self.retries_left -= 1;
+        Ok(exit_code)
+    }
+
+    /// The 1-based number of the next attempt.
+    fn next_attempt(&self) -> u32 {
+        self.retries - self.retries_left + 1
+    }
+
+    fn total(&self) -> u32 {
+        self.retries + 1
+    }
+}
+
 fn should_save_outputs_after_execute(result: &Result<ExecOutcome, InternalError>) -> bool {
     matches!(result, Ok(ExecOutcome::Success(SuccessOutcome::Run)))
 }
@@ -663,6 +990,86 @@ mod tests {
         );
     }
 
+    fn failed(exit_code: i32) -> ExecOutcome {
+        ExecOutcome::Task {
+            exit_code: Some(exit_code),
+            message: format!("exited ({exit_code})"),
+        }
+    }
+
+    fn timed_out() -> ExecOutcome {
+        ExecOutcome::TimedOut {
+            message: "timed out".to_string(),
+        }
+    }
+
+    #[test]
+    fn retry_then_succeed() {
+        let mut attempts = Attempts::new(2);
+        assert!(!attempts.is_final());
+        assert_eq!(attempts.retry(failed(1)).unwrap(), Some(1));
+        assert_eq!(attempts.next_attempt(), 2);
+        assert_eq!(attempts.total(), 3);
+        assert!(!attempts.is_final());
+
+        let outcome = attempts
+            .retry(ExecOutcome::Success(SuccessOutcome::Run))
+            .unwrap_err();
+        assert!(matches!(outcome, ExecOutcome::Success(SuccessOutcome::Run)));
+    }
+
+    #[test]
+    fn retries_exhausted() {
+        let mut attempts = Attempts::new(2);
+        assert_eq!(attempts.retry(failed(1)).unwrap(), Some(1));
+        assert_eq!(attempts.retry(failed(2)).unwrap(), Some(2));
+        assert_eq!(attempts.next_attempt(), 3);
+        // The third attempt is the last, its failure is the task's failure
+        assert!(attempts.is_final());
+
+        let outcome = attempts.retry(failed(3)).unwrap_err();
+        assert!(matches!(
+            outcome,
+            ExecOutcome::Task {
+                exit_code: Some(3),
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn timeout_is_retried() {
+        let mut attempts = Attempts::new(1);
+        assert_eq!(attempts.retry(timed_out()).unwrap(), None);
+        assert!(attempts.is_final());
+
+        let outcome = attempts.retry(timed_out()).unwrap_err();
+        assert!(matches!(outcome, ExecOutcome::TimedOut { .. }));
+    }
+
+    #[test]
+    fn without_retries_first_attempt_is_final() {
+        let mut attempts = Attempts::new(0);
+        assert!(attempts.is_final());
+
+        let outcome = attempts.retry(failed(1)).unwrap_err();
+        assert!(matches!(outcome, ExecOutcome::Task { .. }));
+    }
+
+    #[test]
+    fn restarts_and_shutdowns_are_not_retried() {
+        let mut attempts = Attempts::new(3);
+        assert!(matches!(
+            attempts.retry(ExecOutcome::Restarted).unwrap_err(),
+            ExecOutcome::Restarted
+        ));
+        assert!(matches!(
+            attempts.retry(ExecOutcome::Shutdown).unwrap_err(),
+            ExecOutcome::Shutdown
+        ));
+        assert_eq!(attempts.next_attempt(), 1);
+    }
+
     #[test]
     fn only_successful_task_runs_save_outputs() {
         assert!(should_save_outputs_after_execute(&Ok(
@@ -676,6 +1083,9 @@ mod tests {
                 exit_code: Some(1),
                 message: "failed".to_string(),
             }),
+            Ok(ExecOutcome::TimedOut {
+                message: "timed out".to_string(),
+            }),
             Ok(ExecOutcome::Shutdown),
             Ok(ExecOutcome::Restarted),
             Err(InternalError::UnknownChildExit),
@@ -0,0 +1,159 @@
+//! Detection of files a task reads that aren't part of its hash.
+//!
+//! A task is only re-run when the files in its hash change, so any other file
+//! it reads can change without causing a cache miss. To catch this, the task
+//! is run under `strace` and every file it opens for reading is compared
+//! against the files hashed for it and its dependencies.
+
+use std::{
+    collections::{BTreeSet, HashSet},
+    path::PathBuf,
+};
+
+use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPathBuf, PathError};
+use turborepo_process::{Command, file_trace};
+use turborepo_run_cache::undeclared_outputs::IGNORED_DIRS;
+use turborepo_types::{TaskOutputs, UndeclaredFilesMode};
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    #[error("unable to read file trace: {0}")]
+    Trace(#[from] std::io::Error),
+    #[error(transparent)]
+    Path(#[from] PathError),
+    #[error(transparent)]
+    Cache(#[from] turborepo_run_cache::Error),
+}
+
+/// Traces the files a task reads so they can be checked against its inputs.
+pub struct InputTrace {
+    pub mode: UndeclaredFilesMode,
+    /// Path to the `strace` binary
+    pub strace: PathBuf,
+    pub repo_root: AbsoluteSystemPathBuf,
+    pub trace_file: AbsoluteSystemPathBuf,
+    /// Repo-relative files hashed for the task, its dependencies, and the
+    /// global hash
+    pub declared_files: HashSet<AnchoredSystemPathBuf>,
+    /// Repo-relative outputs of the task and its dependencies
+    pub declared_outputs: Vec<TaskOutputs>,
+}
+
+impl InputTrace {
+    /// Wraps `cmd` so the files it reads are written to the trace file.
+    pub fn trace(&self, cmd: Command) -> Result<Command, Error> {
+        self.trace_file.ensure_dir()?;
+        Ok(file_trace::trace_file_reads(
+            cmd,
+            &self.strace,
+            &self.trace_file,
+        ))
+    }
+
+    /// Returns the repo-relative paths of files the task read that aren't
+    /// part of its hash, in sorted order.
+    pub fn undeclared_inputs(&self) -> Result<Vec<AnchoredSystemPathBuf>, Error> {
+        let reads = file_trace::read_traced_files(&self.trace_file)?;
+        // `strace` reports paths with symlinks resolved
+        let real_repo_root = self.repo_root.to_realpath()?;
+        let mut undeclared = reads
+            .into_iter()
+            // Files that were since removed were temporary, directories have
+            // nothing to hash
+            .filter(|path| real_repo_root.contains(path) && path.as_std_path().is_file())
+            .map(|path| AnchoredSystemPathBuf::relative_path_between(&real_repo_root, &path))
+            .filter(|path| {
+                !self.declared_files.contains(path)
+                    && !path
+                        .components()
+                        .any(|component| IGNORED_DIRS.contains(&component.as_str()))
+            })
+            .collect::<BTreeSet<_>>();
+
+        // Reading a dependency's outputs is expected, and reading the task's
+        // own outputs is only possible once it has written them
+        for outputs in &self.declared_outputs {
+            if undeclared.is_empty() {
+                break;
+            }
+            for file in turborepo_run_cache::expand_outputs(&self.repo_root, outputs)? {
+                undeclared.remove(&file);
+            }
+        }
+
+        Ok(undeclared.into_iter().collect())
+    }
+
+    /// Removes the trace file, it's only needed until the task is checked.
+    pub fn clean_up(&self) {
+        let _ = self.trace_file.remove_file();
+    }
+}
+
+#[cfg(all(test, unix))]
+mod test {
+    use std::collections::HashSet;
+
+    use tempfile::tempdir;
+    use turbopath::{AbsoluteSystemPathBuf, AnchoredSystemPathBuf, RelativeUnixPath};
+    use turborepo_types::{TaskOutputs, UndeclaredFilesMode};
+
+    use super::InputTrace;
+
+    #[test]
+    fn undeclared_inputs_excludes_declared_files_and_outputs() {
+        let temp = tempdir().unwrap();
+        let repo_root = AbsoluteSystemPathBuf::try_from(temp.path())
+            .unwrap()
+            .to_realpath()
+            .unwrap();
+        for file in [
+            "packages/app/src/index.ts",
+            "packages/lib/dist/index.js",
+            "tsconfig.base.json",
+            "node_modules/react/index.js",
+        ] {
+            let path = repo_root.join_unix_path(RelativeUnixPath::new(file).unwrap());
+            path.ensure_dir().unwrap();
+            path.create_with_contents("").unwrap();
+        }
+
+        let trace_file = repo_root.join_components(&[".turbo", "trace.log"]);
+        trace_file.ensure_dir().unwrap();
+        let trace = [
+            "packages/app/src/index.ts",
+            "packages/lib/dist/index.js",
+            "tsconfig.base.json",
+            "node_modules/react/index.js",
+            "packages/app/src/removed.ts",
+        ]
+        .iter()
+        .map(|file| {
+            let path = repo_root.join_unix_path(RelativeUnixPath::new(file).unwrap());
+            format!(r#"100 openat(AT_FDCWD, "{path}", O_RDONLY) = 3<{path}>"#)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+        trace_file.create_with_contents(trace).unwrap();
+
+        let input_trace = InputTrace {
+            mode: UndeclaredFilesMode::Error,
+            strace: "strace".into(),
+            repo_root: repo_root.clone(),
+            trace_file,
+            declared_files: HashSet::from([AnchoredSystemPathBuf::from_raw(
+                "packages/app/src/index.ts",
+            )
+            .unwrap()]),
+            declared_outputs: vec![TaskOutputs {
+                inclusions: vec!["packages/lib/dist/**".to_string()],
+                exclusions: vec![],
+            }],
+        };
+
+        assert_eq!(
+            input_trace.undeclared_inputs().unwrap(),
+            vec![AnchoredSystemPathBuf::from_raw("tsconfig.base.json").unwrap()]
+        );
+    }
+}
@@ -21,17 +21,19 @@
 
 mod command;
 mod exec;
+mod input_trace;
 mod output;
 mod visitor;
 
 pub use command::{
-    CommandFactory, CommandProvider, CommandProviderError, MicroFrontendProxyProvider,
-    PackageGraphCommandProvider, PackageInfoProvider,
+    CommandFactory, CommandProvider, CommandProviderError, InlineCommandProvider,
+    MicroFrontendProxyProvider, PackageGraphCommandProvider, PackageInfoProvider,
 };
 pub use exec::{
     DryRunExecutor, ExecOutcome, HashTrackerProvider, InternalError, SuccessOutcome,
     TaskErrorCollector, TaskExecutor, TaskWarningCollector,
 };
+pub use input_trace::{Error as InputTraceError, InputTrace};
 pub use output::{StdWriter, TaskOutput};
 use serde::Serialize;
 use turbopath::AbsoluteSystemPathBuf;
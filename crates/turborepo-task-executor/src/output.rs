@@ -3,33 +3,68 @@
 //! This module provides types for handling task output to both the terminal
 //! and the TUI.
 
-use turborepo_ui::sender::TaskSender;
+use turborepo_ui::{
+    sender::TaskSender,
+    tui::event::{CacheResult, OutputLogs, TaskEvent, TaskEventObserver, TaskResult},
+};
 
 /// Wrapper for TUI lifecycle signaling.
 ///
-/// In stream mode (no TUI), this is `None` — lifecycle signals are not
+/// In stream mode (no TUI), the sender is `None` — lifecycle signals are not
 /// needed since `TerminalSink` handles all rendering.
 ///
 /// In TUI mode, this holds a `TaskSender` for start/succeeded/failed
 /// lifecycle events that the TUI needs to manage task panes.
-pub struct TaskOutput(Option<TaskSender>);
+///
+/// Independently of the UI, an observer can be attached to receive the same
+/// lifecycle events, e.g. to publish them to subscribers of the run.
+pub struct TaskOutput {
+    sender: Option<TaskSender>,
+    observer: Option<(String, TaskEventObserver)>,
+}
 
 impl TaskOutput {
     pub fn stream() -> Self {
-        Self(None)
+        Self {
+            sender: None,
+            observer: None,
+        }
     }
 
     pub fn tui(sender: TaskSender) -> Self {
-        Self(Some(sender))
+        Self {
+            sender: Some(sender),
+            observer: None,
+        }
+    }
+
+    /// Also send the lifecycle events of `task` to `observer`
+    pub fn with_observer(mut self, task: String, observer: TaskEventObserver) -> Self {
+        self.observer = Some((task, observer));
+        self
     }
 
     pub fn sender(&self) -> Option<&TaskSender> {
-        self.0.as_ref()
+        self.sender.as_ref()
+    }
+
+    fn observe(&self, event: impl FnOnce(String) -> TaskEvent) {
+        if let Some((task, observer)) = &self.observer {
+            observer(event(task.clone()));
+        }
     }
 
     /// Signal task completion to the TUI (if active).
     pub fn finish(self, is_error: bool, is_cache_hit: bool) {
-        if let Some(sender) = self.0 {
+        self.observe(|task| TaskEvent::End {
+            task,
+            result: match (is_error, is_cache_hit) {
+                (true, _) => TaskResult::Failure,
+                (false, true) => TaskResult::CacheHit,
+                (false, false) => TaskResult::Success,
+            },
+        });
+        if let Some(sender) = self.sender {
             if is_error {
                 sender.failed();
             } else {
@@ -39,15 +74,29 @@ impl TaskOutput {
     }
 
     /// Signal task start to the TUI (if active).
-    pub fn start(&self, output_logs: turborepo_ui::tui::event::OutputLogs) {
-        if let Some(sender) = &self.0 {
+    pub fn start(&self, output_logs: OutputLogs) {
+        self.observe(|task| TaskEvent::Start { task });
+        if let Some(sender) = &self.sender {
             sender.start(output_logs);
         }
     }
 
+    /// Report whether the task's outputs were restored from the cache. The
+    /// TUI is told by the task cache itself, so this only reaches observers.
+    pub fn cache_status(&self, result: CacheResult) {
+        self.observe(|task| TaskEvent::Status {
+            task,
+            status: match result {
+                CacheResult::Hit => "cache hit".to_string(),
+                CacheResult::Miss => "cache miss".to_string(),
+            },
+            result,
+        });
+    }
+
     /// Set stdin for interactive tasks (TUI only).
     pub fn set_stdin(&self, stdin: Box<dyn std::io::Write + Send>) {
-        if let Some(sender) = &self.0 {
+        if let Some(sender) = &self.sender {
             sender.set_stdin(stdin);
         }
     }
@@ -102,7 +151,7 @@ impl std::io::Write for StdWriter {
 #[cfg(test)]
 mod tests {
     use std::sync::{
-        Arc,
+        Arc, Mutex,
         atomic::{AtomicBool, Ordering},
     };
 
@@ -141,6 +190,38 @@ mod tests {
         }
     }
 
+    #[test]
+    fn stream_mode_publishes_lifecycle_events() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let observed = events.clone();
+        let output = TaskOutput::stream().with_observer(
+            "web#build".into(),
+            Arc::new(move |event| observed.lock().unwrap().push(event)),
+        );
+
+        output.start(OutputLogs::Full);
+        output.cache_status(CacheResult::Miss);
+        output.finish(true, false);
+
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                TaskEvent::Start {
+                    task: "web#build".into()
+                },
+                TaskEvent::Status {
+                    task: "web#build".into(),
+                    status: "cache miss".into(),
+                    result: CacheResult::Miss,
+                },
+                TaskEvent::End {
+                    task: "web#build".into(),
+                    result: TaskResult::Failure,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn stream_mode_has_no_sender() {
         let output = TaskOutput::stream();
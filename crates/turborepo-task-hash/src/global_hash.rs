@@ -10,7 +10,7 @@ use thiserror::Error;
 use tracing::debug;
 use turbopath::{AbsoluteSystemPath, AbsoluteSystemPathBuf, RelativeUnixPathBuf};
 use turborepo_env::{DetailedMap, EnvironmentVariableMap, get_global_hashable_env_vars};
-use turborepo_hash::{GlobalHashable, TurboHash};
+use turborepo_hash::{GlobalHashable, TurboHash, explain::GlobalHashInputs};
 use turborepo_lockfiles::Lockfile;
 use turborepo_repository::{
     package_graph::PackageInfo,
@@ -250,7 +250,22 @@ fn collect_global_deps(
 impl<'a> GlobalHashableInputs<'a> {
     #[tracing::instrument(skip_all)]
     pub fn calculate_global_hash(&self) -> String {
-        let global_hashable = GlobalHashable {
+        self.hashable().hash()
+    }
+
+    /// The inputs to the global hash, for explaining cache misses. Environment
+    /// variable values are hashed.
+    pub fn hash_inputs(&self, global_hash: &str) -> GlobalHashInputs {
+        let secret_env_vars = self
+            .resolved_env_vars
+            .as_ref()
+            .map(|evm| evm.all.to_secret_hashable())
+            .unwrap_or_default();
+        GlobalHashInputs::new(&self.hashable(), global_hash, &secret_env_vars)
+    }
+
+    fn hashable(&self) -> GlobalHashable<'_> {
+        GlobalHashable {
             global_cache_key: self.global_cache_key,
             global_file_hash_map: &self.global_file_hash_map,
             root_external_dependencies_hash: self.root_external_dependencies_hash,
@@ -266,9 +281,7 @@ impl<'a> GlobalHashableInputs<'a> {
             env_mode: self.env_mode,
             framework_inference: self.framework_inference,
             global_configuration: self.global_configuration,
-        };
-
-        global_hashable.hash()
+        }
     }
 }
 
@@ -27,7 +27,10 @@ use turborepo_env::{
     BUILTIN_PASS_THROUGH_ENV, BySource, CompiledWildcards, DetailedMap, EnvironmentVariableMap,
 };
 use turborepo_frameworks::{Framework, Slug as FrameworkSlug, infer_framework};
-use turborepo_hash::{FileHashes, LockFilePackagesRef, TaskHashable, TurboHash};
+use turborepo_hash::{
+    FileHashes, LockFilePackagesRef, TaskHashable, TurboHash,
+    explain::{GlobalHashInputs, TaskHashInputs},
+};
 use turborepo_repository::package_graph::{PackageInfo, PackageName};
 use turborepo_scm::{RepoGitIndex, SCM};
 use turborepo_task_id::TaskId;
@@ -249,6 +252,8 @@ pub struct TaskHashTrackerState {
     package_task_cache: HashMap<TaskId<'static>, CacheHitMetadata>,
     #[serde(skip)]
     package_task_inputs_expanded_hashes: HashMap<TaskId<'static>, Arc<FileHashes>>,
+    #[serde(skip)]
+    package_task_hash_inputs: HashMap<TaskId<'static>, Arc<TaskHashInputs>>,
 }
 
 /// Caches package-inputs hashes, and package-task hashes.
@@ -262,6 +267,9 @@ pub struct TaskHasher<'a, R> {
     task_hash_tracker: TaskHashTracker,
     compiled_builtins: Option<CompiledWildcards>,
     external_deps_hash_cache: HashMap<String, String>,
+    /// When set, the inputs of each task hash are recorded so that cache
+    /// misses can be explained later
+    global_hash_inputs: Option<Arc<GlobalHashInputs>>,
 }
 
 impl<'a, R: RunOptsHashInfo> TaskHasher<'a, R> {
@@ -290,9 +298,16 @@ impl<'a, R: RunOptsHashInfo> TaskHasher<'a, R> {
             task_hash_tracker: TaskHashTracker::new(expanded_hashes),
             compiled_builtins,
             external_deps_hash_cache: HashMap::new(),
+            global_hash_inputs: None,
         }
     }
 
+    /// Records the inputs of every task hash calculated from now on, which
+    /// can be retrieved with `TaskHashTracker::hash_inputs`.
+    pub fn record_hash_inputs(&mut self, global_hash_inputs: Arc<GlobalHashInputs>) {
+        self.global_hash_inputs = Some(global_hash_inputs);
+    }
+
     /// Pre-compute and cache external dependency hashes for all packages.
     /// Many tasks share the same package, so this avoids re-sorting
     /// transitive dependencies for every task.
@@ -492,14 +507,38 @@ impl<'a, R: RunOptsHashInfo> TaskHasher<'a, R> {
             outputs,
 
             pass_through_args: self.run_opts.pass_through_args(),
+            command: task_definition.command(),
             env: task_definition.env(),
             resolved_env_vars: hashable_env_pairs,
             pass_through_env: task_definition.pass_through_env().unwrap_or_default(),
             env_mode: task_env_mode,
         };
 
+        // Hashing consumes the hashable, so keep a copy to record its inputs
+        let recorded_hashable = self
+            .global_hash_inputs
+            .is_some()
+            .then(|| task_hashable.clone());
+
         let task_hash = task_hashable.calculate_task_hash()?;
 
+        if let Some(global_hash_inputs) = &self.global_hash_inputs
+            && let Some(hashable) = recorded_hashable
+            && let Some(files) = self.task_hash_tracker.get_expanded_inputs(task_id)
+        {
+            self.task_hash_tracker.insert_hash_inputs(
+                task_id.clone(),
+                TaskHashInputs::new(
+                    &hashable,
+                    &task_hash,
+                    global_hash_inputs.clone(),
+                    files,
+                    self.dependency_hash_map(dependency_set, excluded_dependency_hashes),
+                    &env_vars.all.to_secret_hashable(),
+                ),
+            );
+        }
+
         let task_hash_arc: Arc<str> = Arc::from(task_hash.as_str());
         self.task_hash_tracker.insert_hash(
             task_id.clone(),
@@ -609,6 +648,31 @@ impl<'a, R: RunOptsHashInfo> TaskHasher<'a, R> {
         Ok(dependency_hash_list)
     }
 
+    /// Like `calculate_dependency_hashes`, but keyed by task ID. Dependencies
+    /// without a hash are skipped.
+    fn dependency_hash_map(
+        &self,
+        dependency_set: &[&TaskNode],
+        excluded_dependency_hashes: Option<&HashSet<TaskId<'static>>>,
+    ) -> BTreeMap<String, String> {
+        self.task_hash_tracker.with_state(|state| {
+            dependency_set
+                .iter()
+                .filter_map(|dependency_task| match dependency_task {
+                    TaskNode::Task(task_id) => Some(task_id),
+                    TaskNode::Root => None,
+                })
+                .filter(|task_id| {
+                    !excluded_dependency_hashes.is_some_and(|excluded| excluded.contains(*task_id))
+                })
+                .filter_map(|task_id| {
+                    let hash = state.package_task_hashes.get(task_id)?;
+                    Some((task_id.to_string(), hash.to_string()))
+                })
+                .collect()
+        })
+    }
+
     pub fn into_task_hash_tracker_state(self) -> TaskHashTrackerState {
         self.task_hash_tracker.into_state()
     }
@@ -855,6 +919,19 @@ impl TaskHashTracker {
         });
     }
 
+    /// The inputs of the task's hash, if they were recorded
+    pub fn hash_inputs(&self, task_id: &TaskId) -> Option<Arc<TaskHashInputs>> {
+        self.with_state(|state| state.package_task_hash_inputs.get(task_id).cloned())
+    }
+
+    fn insert_hash_inputs(&self, task_id: TaskId<'static>, hash_inputs: TaskHashInputs) {
+        self.with_state_mut(|state| {
+            state
+                .package_task_hash_inputs
+                .insert(task_id, Arc::new(hash_inputs));
+        });
+    }
+
     pub fn cache_status(&self, task_id: &TaskId) -> Option<CacheHitMetadata> {
         self.with_state(|state| state.package_task_cache.get(task_id).cloned())
     }
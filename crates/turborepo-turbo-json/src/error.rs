@@ -141,6 +141,23 @@ pub enum Error {
         text: NamedSource<String>,
     },
 
+    #[error("Invalid `timeout` for task: {reason}")]
+    InvalidTaskTimeout {
+        reason: String,
+        #[label("`timeout` set here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
+
+    #[error("`command` cannot be empty.")]
+    EmptyTaskCommand {
+        #[label("`command` set here")]
+        span: Option<SourceSpan>,
+        #[source_code]
+        text: NamedSource<String>,
+    },
+
     #[error(transparent)]
     #[diagnostic(transparent)]
     InvalidEnvPrefix(Box<InvalidEnvPrefixError>),
@@ -161,6 +161,9 @@ impl ProcessedTaskDefinition {
         set_field!(self, other, persistent);
         set_field!(self, other, interruptible);
         set_field!(self, other, interactive);
+        set_field!(self, other, timeout);
+        set_field!(self, other, retries);
+        set_field!(self, other, command);
         set_field!(self, other, env_mode);
         set_field!(self, other, incremental);
     }
@@ -211,6 +214,9 @@ mod test {
             output_logs: None,
             interruptible: None,
             interactive: None,
+            timeout: None,
+            retries: None,
+            command: None,
             env_mode: None,
             with: None,
             incremental: None,
@@ -249,6 +255,9 @@ mod test {
             depends_on: None,
             pass_through_env: None,
             interactive: None,
+            timeout: None,
+            retries: None,
+            command: None,
             env_mode: None,
             with: None,
             incremental: None,
@@ -269,6 +278,9 @@ mod test {
             pass_through_env: None,
             interruptible: None,
             interactive: None,
+            timeout: None,
+            retries: None,
+            command: None,
             env_mode: None,
             with: None,
             incremental: None,
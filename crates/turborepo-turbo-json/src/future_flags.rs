@@ -82,6 +82,12 @@ pub struct FutureFlags {
     /// `globalPassThroughEnv` becomes `global.passThroughEnv`.
     #[serde(default)]
     pub global_configuration: bool,
+    /// Run package.json scripts through the shell instead of the package
+    /// manager. `node_modules/.bin` directories are added to `PATH`, npm
+    /// lifecycle environment variables are set, and `pre` and `post` scripts
+    /// run when the package manager would run them.
+    #[serde(default)]
+    pub direct_script_execution: bool,
     /// Enable incremental task caching. When enabled, Turborepo persists
     /// tool-managed incremental build artifacts (e.g. `.tsbuildinfo`) across
     /// runs via the remote cache, restoring them before execution on cache
@@ -106,7 +112,7 @@ impl TS for FutureFlags {
         "{ errorsOnlyShowHash?: boolean, experimentalObservability?: boolean, longerSignatureKey?: \
          boolean, affectedUsingTaskInputs?: boolean, watchUsingTaskInputs?: boolean, \
          pruneIncludesGlobalFiles?: boolean, filterUsingTasks?: boolean, globalConfiguration?: \
-         boolean }"
+         boolean, directScriptExecution?: boolean }"
             .to_string()
     }
 
@@ -114,7 +120,7 @@ impl TS for FutureFlags {
         "{ errorsOnlyShowHash?: boolean, experimentalObservability?: boolean, longerSignatureKey?: \
          boolean, affectedUsingTaskInputs?: boolean, watchUsingTaskInputs?: boolean, \
          pruneIncludesGlobalFiles?: boolean, filterUsingTasks?: boolean, globalConfiguration?: \
-         boolean }"
+         boolean, directScriptExecution?: boolean }"
             .to_string()
     }
 
@@ -122,7 +128,7 @@ impl TS for FutureFlags {
         "type FutureFlags = { errorsOnlyShowHash?: boolean, experimentalObservability?: boolean, \
          longerSignatureKey?: boolean, affectedUsingTaskInputs?: boolean, watchUsingTaskInputs?: \
          boolean, pruneIncludesGlobalFiles?: boolean, filterUsingTasks?: boolean, \
-         globalConfiguration?: boolean };"
+         globalConfiguration?: boolean, directScriptExecution?: boolean };"
             .to_string()
     }
 
@@ -130,7 +136,7 @@ impl TS for FutureFlags {
         "type FutureFlags = { errorsOnlyShowHash?: boolean, experimentalObservability?: boolean, \
          longerSignatureKey?: boolean, affectedUsingTaskInputs?: boolean, watchUsingTaskInputs?: \
          boolean, pruneIncludesGlobalFiles?: boolean, filterUsingTasks?: boolean, \
-         globalConfiguration?: boolean };"
+         globalConfiguration?: boolean, directScriptExecution?: boolean };"
             .to_string()
     }
 
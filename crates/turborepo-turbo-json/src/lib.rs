@@ -48,7 +48,7 @@ pub use processed::{
 pub use raw::{
     HasConfigBeyondExtends, Pipeline, RawExperimentalObservability, RawIncrementalPartition,
     RawObservabilityOtel, RawObservabilityOtelMetrics, RawPackageTurboJson, RawRemoteCacheOptions,
-    RawRootTurboJson, RawTaskDefinition, RawTurboJson,
+    RawRootTurboJson, RawTaskCommand, RawTaskDefinition, RawTurboJson,
 };
 pub use validator::{TOPOLOGICAL_PIPELINE_DELIMITER, Validator};
 
@@ -882,6 +882,13 @@ mod tests {
         }"#,
         "implicit dependencies and tags"
     )]
+    #[test_case(
+        r#"{
+            "implicitDependencies": ["react-dom"],
+            "unusedDependencies": true
+        }"#,
+        "unused dependencies"
+    )]
     #[test_case(
         r#"{
           "dependencies": {
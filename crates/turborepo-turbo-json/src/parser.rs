@@ -25,7 +25,8 @@ use crate::raw::{
     Pipeline, RawExperimentalCIConfig, RawExperimentalObservability, RawGlobalConfig,
     RawObservabilityOtel, RawObservabilityOtelMetrics, RawObservabilityOtelRunAttributes,
     RawObservabilityOtelTaskAttributes, RawPackageTurboJson, RawRemoteCacheOptions,
-    RawRootTurboJson, RawStructuredInput, RawTaskDefinition, RawTaskInput, RawTurboJson,
+    RawRootTurboJson, RawStructuredInput, RawTaskCommand, RawTaskDefinition, RawTaskInput,
+    RawTurboJson,
 };
 
 /// Error type for turbo.json parsing failures using biome parser
@@ -161,6 +162,54 @@ impl DeserializationVisitor for RawTaskInputVisitor {
     }
 }
 
+impl Deserializable for RawTaskCommand {
+    fn deserialize(
+        value: &impl DeserializableValue,
+        name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self> {
+        value.deserialize(RawTaskCommandVisitor, name, diagnostics)
+    }
+}
+
+struct RawTaskCommandVisitor;
+
+impl DeserializationVisitor for RawTaskCommandVisitor {
+    type Output = RawTaskCommand;
+
+    const EXPECTED_TYPE: VisitableType = VisitableType::STR.union(VisitableType::ARRAY);
+
+    fn visit_str(
+        self,
+        value: biome_deserialize::Text,
+        _range: TextRange,
+        _name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self::Output> {
+        match UnescapedString::from_escaped(value.text().to_string()) {
+            Ok(value) => Some(RawTaskCommand::String(value)),
+            Err(error) => {
+                diagnostics.push(DeserializationDiagnostic::new(format!("{error}")));
+                None
+            }
+        }
+    }
+
+    fn visit_array(
+        self,
+        items: impl Iterator<Item = Option<impl DeserializableValue>>,
+        _range: TextRange,
+        name: &str,
+        diagnostics: &mut Vec<DeserializationDiagnostic>,
+    ) -> Option<Self::Output> {
+        let argv = items
+            .flatten()
+            .map(|item| UnescapedString::deserialize(&item, name, diagnostics))
+            .collect::<Option<Vec<_>>>()?;
+        Some(RawTaskCommand::Argv(argv))
+    }
+}
+
 struct PipelineVisitor;
 
 impl DeserializationVisitor for PipelineVisitor {
@@ -273,6 +322,9 @@ impl WithMetadata for RawTaskDefinition {
         self.outputs.add_text(text.clone());
         self.output_logs.add_text(text.clone());
         self.interactive.add_text(text.clone());
+        self.timeout.add_text(text.clone());
+        self.retries.add_text(text.clone());
+        self.command.add_text(text.clone());
         self.with.add_text(text);
     }
 
@@ -289,6 +341,9 @@ impl WithMetadata for RawTaskDefinition {
         self.outputs.add_path(path.clone());
         self.output_logs.add_path(path.clone());
         self.interactive.add_path(path.clone());
+        self.timeout.add_path(path.clone());
+        self.retries.add_path(path.clone());
+        self.command.add_path(path.clone());
         self.with.add_path(path);
     }
 }
@@ -300,6 +355,7 @@ impl WithMetadata for RawRemoteCacheOptions {
         self.team_slug.add_text(text.clone());
         self.team_id.add_text(text.clone());
         self.signature.add_text(text.clone());
+        self.public_key.add_text(text.clone());
         self.preflight.add_text(text.clone());
         self.timeout.add_text(text.clone());
         self.enabled.add_text(text.clone());
@@ -312,6 +368,7 @@ impl WithMetadata for RawRemoteCacheOptions {
         self.team_slug.add_path(path.clone());
         self.team_id.add_path(path.clone());
         self.signature.add_path(path.clone());
+        self.public_key.add_path(path.clone());
         self.preflight.add_path(path.clone());
         self.timeout.add_path(path.clone());
         self.enabled.add_path(path.clone());
@@ -379,6 +436,7 @@ impl WithMetadata for RawObservabilityOtel {
         self.timeout_ms.add_text(text.clone());
         self.interval_ms.add_text(text.clone());
         self.use_remote_cache_token.add_text(text.clone());
+        self.traces.add_text(text.clone());
         self.metrics.add_text(text.clone());
         if let Some(metrics) = &mut self.metrics {
             metrics.add_text(text);
@@ -392,6 +450,7 @@ impl WithMetadata for RawObservabilityOtel {
         self.timeout_ms.add_path(path.clone());
         self.interval_ms.add_path(path.clone());
         self.use_remote_cache_token.add_path(path.clone());
+        self.traces.add_path(path.clone());
         self.metrics.add_path(path.clone());
         if let Some(metrics) = &mut self.metrics {
             metrics.add_path(path);
@@ -427,6 +486,7 @@ impl WithMetadata for RawGlobalConfig {
         self.cache_dir.add_text(text.clone());
         self.cache_max_age.add_text(text.clone());
         self.cache_max_size.add_text(text.clone());
+        self.cache_deduplication.add_text(text.clone());
         self.no_update_notifier.add_text(text.clone());
         self.concurrency.add_text(text.clone());
         self.remote_cache.add_text(text.clone());
@@ -444,6 +504,7 @@ impl WithMetadata for RawGlobalConfig {
         self.cache_dir.add_path(path.clone());
         self.cache_max_age.add_path(path.clone());
         self.cache_max_size.add_path(path.clone());
+        self.cache_deduplication.add_path(path.clone());
         self.no_update_notifier.add_path(path.clone());
         self.concurrency.add_path(path.clone());
         self.remote_cache.add_path(path.clone());
@@ -470,6 +531,7 @@ impl WithMetadata for RawRootTurboJson {
         self.cache_dir.add_text(text.clone());
         self.cache_max_age.add_text(text.clone());
         self.cache_max_size.add_text(text.clone());
+        self.cache_deduplication.add_text(text.clone());
         self.pipeline.add_text(text.clone());
         self.remote_cache.add_text(text.clone());
         self.ui.add_text(text.clone());
@@ -502,6 +564,7 @@ impl WithMetadata for RawRootTurboJson {
         self.cache_dir.add_path(path.clone());
         self.cache_max_age.add_path(path.clone());
         self.cache_max_size.add_path(path.clone());
+        self.cache_deduplication.add_path(path.clone());
         self.pipeline.add_path(path.clone());
         self.remote_cache.add_path(path.clone());
         self.ui.add_path(path.clone());
@@ -14,7 +14,7 @@ use turborepo_unescape::UnescapedString;
 use crate::{
     error::Error,
     future_flags::FutureFlags,
-    raw::{RawStructuredInput, RawTaskDefinition, RawTaskInput},
+    raw::{RawStructuredInput, RawTaskCommand, RawTaskDefinition, RawTaskInput},
 };
 
 const TURBO_DEFAULT: &str = "$TURBO_DEFAULT$";
@@ -575,6 +575,9 @@ pub struct ProcessedTaskDefinition {
     pub outputs: Option<ProcessedOutputs>,
     pub output_logs: Option<Spanned<OutputLogsMode>>,
     pub interactive: Option<Spanned<bool>>,
+    pub timeout: Option<Spanned<UnescapedString>>,
+    pub retries: Option<Spanned<u32>>,
+    pub command: Option<Spanned<RawTaskCommand>>,
     pub env_mode: Option<Spanned<EnvMode>>,
     pub with: Option<ProcessedWith>,
     pub incremental: Option<Vec<ProcessedIncrementalPartition>>,
@@ -664,6 +667,9 @@ impl ProcessedTaskDefinition {
                 .transpose()?,
             output_logs: raw_task.output_logs,
             interactive: raw_task.interactive,
+            timeout: raw_task.timeout,
+            retries: raw_task.retries,
+            command: raw_task.command,
             env_mode: raw_task.env_mode,
             with: raw_task
                 .with
@@ -686,6 +692,9 @@ impl ProcessedTaskDefinition {
             || self.outputs.is_some()
             || self.output_logs.is_some()
             || self.interactive.is_some()
+            || self.timeout.is_some()
+            || self.retries.is_some()
+            || self.command.is_some()
             || self.with.is_some()
             || self.incremental.is_some()
     }
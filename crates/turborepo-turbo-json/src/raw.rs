@@ -163,6 +163,19 @@ pub struct RawRemoteCacheOptions {
     #[ts(optional)]
     pub signature: Option<Spanned<bool>>,
 
+    /// An Ed25519 public key used to verify artifacts when `signature` is
+    /// enabled.
+    ///
+    /// When set, artifacts are signed with the private key in the
+    /// environment variable `TURBO_REMOTE_CACHE_PRIVATE_KEY` instead of the
+    /// shared `TURBO_REMOTE_CACHE_SIGNATURE_KEY`. Machines without the
+    /// private key can verify artifacts but don't upload them.
+    ///
+    /// Documentation: https://turborepo.dev/docs/reference/configuration#publickey
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub public_key: Option<Spanned<String>>,
+
     /// When enabled, any HTTP request will be preceded by an OPTIONS request
     /// to determine if the request is supported by the endpoint.
     ///
@@ -221,6 +234,8 @@ pub struct RawObservabilityOtel {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metrics: Option<RawObservabilityOtelMetrics>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub traces: Option<Spanned<bool>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub use_remote_cache_token: Option<Spanned<bool>>,
 }
 
@@ -373,6 +388,17 @@ pub struct RawGlobalConfig {
     #[ts(optional)]
     pub cache_max_size: Option<Spanned<UnescapedString>>,
 
+    /// Store identical files in the local cache only once.
+    ///
+    /// When enabled, the contents of cached outputs are shared between
+    /// artifacts and unreferenced contents are removed during eviction.
+    /// Defaults to `false`.
+    ///
+    /// Documentation: https://turborepo.dev/docs/reference/configuration#cachededuplication
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cache_deduplication: Option<Spanned<bool>>,
+
     /// Experimental observability configuration for OpenTelemetry export.
     /// Excluded from JSON Schema and TypeScript types (`#[ts(skip)]`)
     /// because this is gated behind a separate `experimentalObservability`
@@ -411,6 +437,7 @@ pub struct RawRootTurboJson {
     pub cache_dir: Option<Spanned<UnescapedString>>,
     pub cache_max_age: Option<Spanned<UnescapedString>>,
     pub cache_max_size: Option<Spanned<UnescapedString>>,
+    pub cache_deduplication: Option<Spanned<bool>>,
     pub concurrency: Option<Spanned<String>>,
     pub tags: Option<Spanned<Vec<Spanned<String>>>>,
     pub boundaries: Option<Spanned<BoundariesConfig>>,
@@ -591,6 +618,17 @@ pub struct RawTurboJson {
     #[ts(optional)]
     pub cache_max_size: Option<Spanned<UnescapedString>>,
 
+    /// Store identical files in the local cache only once.
+    ///
+    /// When enabled, the contents of cached outputs are shared between
+    /// artifacts and unreferenced contents are removed during eviction.
+    /// Defaults to `false`.
+    ///
+    /// Documentation: https://turborepo.dev/docs/reference/configuration#cachededuplication
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub cache_deduplication: Option<Spanned<bool>>,
+
     /// When set to `true`, disables the update notification that appears when
     /// a new version of `turbo` is available.
     ///
@@ -711,6 +749,15 @@ pub enum RawTaskInput {
     Structured(RawStructuredInput),
 }
 
+#[derive(Serialize, Debug, PartialEq, Clone, JsonSchema, TS)]
+#[serde(untagged)]
+#[schemars(rename = "TaskCommand")]
+#[ts(export, rename = "TaskCommand")]
+pub enum RawTaskCommand {
+    String(UnescapedString),
+    Argv(Vec<UnescapedString>),
+}
+
 /// Configuration for a pipeline task.
 ///
 /// The name of a task that can be executed by turbo. If turbo finds a
@@ -737,6 +784,20 @@ pub struct RawTaskDefinition {
     #[ts(optional)]
     pub description: Option<Spanned<UnescapedString>>,
 
+    /// The command to run for this task, instead of the `package.json` script
+    /// with the same name.
+    ///
+    /// A string is run by the system shell, while an array is run as a
+    /// program and its arguments without a shell. `$TURBO_PACKAGE_DIR`,
+    /// `$TURBO_PACKAGE_NAME` and `$TURBO_ROOT` are replaced before the command
+    /// runs. The command is part of the task's hash, so editing it causes a
+    /// cache miss.
+    ///
+    /// Documentation: https://turborepo.dev/docs/reference/configuration#command
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub command: Option<Spanned<RawTaskCommand>>,
+
     /// Whether or not to cache the outputs of the task.
     ///
     /// Setting cache to false is useful for long-running "watch" or
@@ -844,6 +905,28 @@ pub struct RawTaskDefinition {
     #[ts(optional)]
     pub interactive: Option<Spanned<bool>>,
 
+    /// The maximum amount of time the task is allowed to run.
+    ///
+    /// Accepts a human-readable duration string (e.g. `"30s"`, `"15m"`,
+    /// `"1h"`). When the timeout elapses, turbo stops the task and marks it
+    /// as failed. Set to `"0"` to disable the timeout (the default).
+    ///
+    /// Documentation: https://turborepo.dev/docs/reference/configuration#timeout
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub timeout: Option<Spanned<UnescapedString>>,
+
+    /// The number of times to retry the task if it fails.
+    ///
+    /// A failed task is re-run up to this many times before turbo reports it
+    /// as failed. Only the final, successful attempt is written to the cache.
+    /// Defaults to `0`, which disables retries.
+    ///
+    /// Documentation: https://turborepo.dev/docs/reference/configuration#retries
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[ts(optional)]
+    pub retries: Option<Spanned<u32>>,
+
     // Internal field - excluded from schema
     #[serde(skip)]
     #[schemars(skip)]
@@ -894,6 +977,9 @@ impl HasConfigBeyondExtends for RawTaskDefinition {
             || self.outputs.is_some()
             || self.output_logs.is_some()
             || self.interactive.is_some()
+            || self.timeout.is_some()
+            || self.retries.is_some()
+            || self.command.is_some()
             || self.with.is_some()
             || self.incremental.is_some()
     }
@@ -935,6 +1021,7 @@ impl TryFrom<RawRootTurboJson> for RawTurboJson {
                 cache_dir: None,
                 cache_max_age: None,
                 cache_max_size: None,
+                cache_deduplication: None,
                 no_update_notifier: None,
                 concurrency: None,
                 experimental_observability: None,
@@ -961,6 +1048,7 @@ impl TryFrom<RawRootTurboJson> for RawTurboJson {
                 cache_dir: root.cache_dir,
                 cache_max_age: root.cache_max_age,
                 cache_max_size: root.cache_max_size,
+                cache_deduplication: root.cache_deduplication,
                 no_update_notifier: root.no_update_notifier,
                 tags: root.tags,
                 boundaries: root.boundaries,
@@ -1030,6 +1118,7 @@ fn validate_no_top_level_global_keys(root: &RawRootTurboJson) -> Result<(), Erro
     check_spanned!(root.cache_dir, "cacheDir", "");
     check_spanned!(root.cache_max_age, "cacheMaxAge", "");
     check_spanned!(root.cache_max_size, "cacheMaxSize", "");
+    check_spanned!(root.cache_deduplication, "cacheDeduplication", "");
     check_spanned!(root.no_update_notifier, "noUpdateNotifier", "");
     check_spanned!(root.concurrency, "concurrency", "");
     check_present!(root.remote_cache, "remoteCache", "");
@@ -1082,6 +1171,7 @@ impl RawTurboJson {
             self.cache_dir = g.cache_dir;
             self.cache_max_age = g.cache_max_age;
             self.cache_max_size = g.cache_max_size;
+            self.cache_deduplication = g.cache_deduplication;
             self.no_update_notifier = g.no_update_notifier;
             self.concurrency = g.concurrency;
             self.remote_cache = g.remote_cache;
@@ -16,7 +16,7 @@
 
 pub mod secret;
 pub mod task_input_matching;
-use std::{collections::HashMap, fmt, str::FromStr, sync::Arc};
+use std::{collections::HashMap, fmt, str::FromStr, sync::Arc, time::Duration};
 
 use biome_deserialize_macros::Deserializable;
 use clap::ValueEnum;
@@ -274,6 +274,53 @@ impl fmt::Display for ContinueMode {
     }
 }
 
+/// How files a task uses without declaring them are reported, either files it
+/// writes outside of its `outputs` or files it reads outside of its `inputs`.
+///
+/// - `Off`: Files aren't checked
+/// - `Warn`: A warning is printed at the end of the run
+/// - `Error`: The task fails
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UndeclaredFilesMode {
+    #[default]
+    Off,
+    Warn,
+    Error,
+}
+
+impl fmt::Display for UndeclaredFilesMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            UndeclaredFilesMode::Off => "off",
+            UndeclaredFilesMode::Warn => "warn",
+            UndeclaredFilesMode::Error => "error",
+        })
+    }
+}
+
+/// Formats the run summary can be written in.
+///
+/// - `Json`: Turbo's own summary, written to `.turbo/runs/<id>.json`
+/// - `Junit`: A JUnit XML report with a test case per task, written to
+///   `.turbo/runs/<id>.xml`
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SummaryFormat {
+    #[default]
+    Json,
+    Junit,
+}
+
+impl fmt::Display for SummaryFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            SummaryFormat::Json => "json",
+            SummaryFormat::Junit => "junit",
+        })
+    }
+}
+
 /// Log prefix mode for task output.
 ///
 /// Controls how task output lines are prefixed:
@@ -418,6 +465,8 @@ pub struct RunCacheOpts {
     /// complete successfully. Controlled by the `errorsOnlyShowHash` future
     /// flag.
     pub errors_only_show_hash: bool,
+    /// Report which hash inputs changed when a task misses the cache
+    pub explain_misses: bool,
 }
 
 /// Options for scope resolution.
@@ -431,6 +480,9 @@ pub struct ScopeOpts {
     pub filter_patterns: Vec<String>,
     /// Git range for affected detection (from_ref, to_ref)
     pub affected_range: Option<(Option<String>, Option<String>)>,
+    /// Maximum number of levels `...` walks in the graph (from
+    /// --filter-depth). `None` walks the full graph.
+    pub filter_depth: Option<usize>,
 }
 
 impl ScopeOpts {
@@ -522,6 +574,26 @@ pub struct TaskOutputs {
     pub exclusions: Vec<String>,
 }
 
+/// A command defined inline in turbo.json rather than as a package.json
+/// script. Substitutions like `$TURBO_PACKAGE_DIR` have not been applied yet.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum TaskCommand {
+    /// A command line run by the system shell
+    Shell(String),
+    /// A program and its arguments, run without a shell
+    Argv(Vec<String>),
+}
+
+impl fmt::Display for TaskCommand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskCommand::Shell(command) => f.write_str(command),
+            TaskCommand::Argv(argv) => f.write_str(&argv.join(" ")),
+        }
+    }
+}
+
 /// A single incremental cache partition. Each partition represents a distinct
 /// set of incremental artifacts with its own cache key.
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -851,6 +923,17 @@ pub struct TaskDefinition {
     // input.
     pub interactive: bool,
 
+    // Maximum wall-clock time the task may run before it is stopped and marked
+    // as failed. `None` means the task may run indefinitely.
+    pub timeout: Option<Duration>,
+
+    // Number of times a failed task is re-run before it is reported as failed.
+    pub retries: u32,
+
+    // Command run for this task instead of the package.json script with the
+    // same name.
+    pub command: Option<TaskCommand>,
+
     // Override for global env mode setting
     pub env_mode: Option<EnvMode>,
 
@@ -880,6 +963,9 @@ impl Default for TaskDefinition {
             persistent: Default::default(),
             interruptible: Default::default(),
             interactive: Default::default(),
+            timeout: Default::default(),
+            retries: Default::default(),
+            command: Default::default(),
             env_mode: Default::default(),
             with: Default::default(),
             incremental: Default::default(),
@@ -1112,6 +1198,8 @@ pub trait RunOptsInfo {
     fn single_package(&self) -> bool;
     /// Returns Some("true") if run summary should be saved to disk
     fn summarize(&self) -> Option<&str>;
+    /// Returns the formats the run summary is saved in
+    fn summary_formats(&self) -> &[SummaryFormat];
     /// Returns true if framework detection is enabled
     fn framework_inference(&self) -> bool;
     /// Returns arguments to pass through to task execution
@@ -1193,6 +1281,8 @@ pub trait TaskDefinitionHashInfo {
     fn outputs(&self) -> &TaskOutputs;
     /// Returns the hashable outputs for this task (includes log file)
     fn hashable_outputs(&self, task_id: &TaskId) -> TaskOutputs;
+    /// Returns the inline command defined for this task, if any
+    fn command(&self) -> Option<&TaskCommand>;
 }
 
 impl TaskDefinitionHashInfo for TaskDefinition {
@@ -1216,6 +1306,10 @@ impl TaskDefinitionHashInfo for TaskDefinition {
         // Delegate to the canonical implementation in TaskDefinition
         TaskDefinition::hashable_outputs(self, task_id.task())
     }
+
+    fn command(&self) -> Option<&TaskCommand> {
+        self.command.as_ref()
+    }
 }
 
 /// Trait for run options needed by the task hasher.
@@ -1,5 +1,5 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, HashSet},
     io::{self, Stdout, Write},
     mem,
     sync::Arc,
@@ -26,10 +26,12 @@ const RESIZE_DEBOUNCE_DELAY: Duration = Duration::from_millis(10);
 
 use super::{
     AppReceiver, Debouncer, Error, Event, InputOptions, SizeInfo, TaskTable, TerminalPane,
-    event::{CacheResult, Direction, OutputLogs, PaneSize, TaskResult},
+    event::{
+        CacheResult, Direction, OutputLogs, PaneSize, TaskAction, TaskActionHandler, TaskResult,
+    },
     input,
     preferences::PreferenceLoader,
-    search::SearchResults,
+    search::{OutputSearchResults, SearchResults, TaskMatches},
 };
 use crate::{
     ColorConfig,
@@ -51,6 +53,13 @@ pub enum LayoutSections {
     SearchLocked {
         results: SearchResults,
     },
+    OutputSearch {
+        previous_selection: String,
+        results: OutputSearchResults,
+    },
+    OutputSearchLocked {
+        results: OutputSearchResults,
+    },
 }
 
 pub struct App<W> {
@@ -68,6 +77,9 @@ pub struct App<W> {
     scroll_momentum: ScrollMomentum,
     log_events: Vec<turborepo_log::LogEvent>,
     showing_log_panel: bool,
+    task_action_handler: Option<TaskActionHandler>,
+    // Tasks the user asked to stop that haven't finished yet
+    stopping_tasks: HashSet<String>,
 }
 
 impl<W> App<W> {
@@ -126,6 +138,8 @@ impl<W> App<W> {
             scroll_momentum: ScrollMomentum::new(),
             log_events: Vec::new(),
             showing_log_panel: false,
+            task_action_handler: None,
+            stopping_tasks: HashSet::new(),
         }
     }
 
@@ -135,7 +149,9 @@ impl<W> App<W> {
             LayoutSections::Pane => true,
             LayoutSections::TaskList
             | LayoutSections::Search { .. }
-            | LayoutSections::SearchLocked { .. } => false,
+            | LayoutSections::SearchLocked { .. }
+            | LayoutSections::OutputSearch { .. }
+            | LayoutSections::OutputSearchLocked { .. } => false,
         }
     }
 
@@ -200,7 +216,7 @@ impl<W> App<W> {
 
     /// Navigate to the next matching task in locked search mode, with
     /// wrap-around
-    fn next_matching_task(&mut self, results: SearchResults) {
+    fn next_matching_task(&mut self, results: impl TaskMatches) {
         let next_task = results
             .first_match(
                 self.tasks_by_status
@@ -221,7 +237,7 @@ impl<W> App<W> {
 
     /// Navigate to the previous matching task in locked search mode, with
     /// wrap-around
-    fn previous_matching_task(&mut self, results: SearchResults) {
+    fn previous_matching_task(&mut self, results: impl TaskMatches) {
         let num_rows = self.tasks_by_status.count_all();
         let prev_task = results
             .first_match(
@@ -249,11 +265,15 @@ impl<W> App<W> {
             return;
         }
 
-        if let LayoutSections::SearchLocked { results } = &self.section_focus {
-            self.next_matching_task(results.clone());
-        } else {
-            self.selected_task_index = (self.selected_task_index + 1) % num_rows;
-            self.task_list_scroll.select(Some(self.selected_task_index));
+        match &self.section_focus {
+            LayoutSections::SearchLocked { results } => self.next_matching_task(results.clone()),
+            LayoutSections::OutputSearchLocked { results } => {
+                self.next_matching_task(results.clone())
+            }
+            _ => {
+                self.selected_task_index = (self.selected_task_index + 1) % num_rows;
+                self.task_list_scroll.select(Some(self.selected_task_index));
+            }
         }
 
         self.is_task_selection_pinned = true;
@@ -267,14 +287,20 @@ impl<W> App<W> {
             return;
         }
 
-        if let LayoutSections::SearchLocked { results } = &self.section_focus {
-            self.previous_matching_task(results.clone());
-        } else {
-            self.selected_task_index = self
-                .selected_task_index
-                .checked_sub(1)
-                .unwrap_or(num_rows - 1);
-            self.task_list_scroll.select(Some(self.selected_task_index));
+        match &self.section_focus {
+            LayoutSections::SearchLocked { results } => {
+                self.previous_matching_task(results.clone())
+            }
+            LayoutSections::OutputSearchLocked { results } => {
+                self.previous_matching_task(results.clone())
+            }
+            _ => {
+                self.selected_task_index = self
+                    .selected_task_index
+                    .checked_sub(1)
+                    .unwrap_or(num_rows - 1);
+                self.task_list_scroll.select(Some(self.selected_task_index));
+            }
         }
 
         self.is_task_selection_pinned = true;
@@ -324,12 +350,31 @@ impl<W> App<W> {
         Ok(())
     }
 
+    /// Enter a search of every task's output
+    pub fn enter_output_search(&mut self) -> Result<(), Error> {
+        // Ensure task list is visible when searching
+        if !self.preferences.is_task_list_visible() {
+            self.preferences.set_is_task_list_visible(Some(true));
+        }
+
+        self.section_focus = LayoutSections::OutputSearch {
+            previous_selection: self.active_task()?.to_string(),
+            results: OutputSearchResults::new(),
+        };
+        // We set scroll as we want to keep the current selection
+        self.is_task_selection_pinned = true;
+        Ok(())
+    }
+
     pub fn exit_search(&mut self, restore_scroll: bool) {
         let mut prev_focus = LayoutSections::TaskList;
         mem::swap(&mut self.section_focus, &mut prev_focus);
         match prev_focus {
             LayoutSections::Search {
                 previous_selection, ..
+            }
+            | LayoutSections::OutputSearch {
+                previous_selection, ..
             } if restore_scroll && self.select_task(&previous_selection).is_err() =>
             // If the task that was selected is no longer in the task list we reset
             // scrolling.
@@ -341,47 +386,72 @@ impl<W> App<W> {
     }
 
     pub fn lock_search(&mut self) {
-        if let LayoutSections::Search { results, .. } = &self.section_focus {
-            self.section_focus = LayoutSections::SearchLocked {
-                results: results.clone(),
-            };
+        match &self.section_focus {
+            LayoutSections::Search { results, .. } => {
+                self.section_focus = LayoutSections::SearchLocked {
+                    results: results.clone(),
+                };
+            }
+            LayoutSections::OutputSearch { results, .. } => {
+                self.section_focus = LayoutSections::OutputSearchLocked {
+                    results: results.clone(),
+                };
+            }
+            _ => {}
         }
     }
 
     pub fn search_scroll(&mut self, direction: Direction) -> Result<(), Error> {
-        let LayoutSections::Search { results, .. } = &self.section_focus else {
-            debug!("scrolling search while not searching");
-            return Ok(());
-        };
-        let results = results.clone();
-
-        match direction {
-            Direction::Down => self.next_matching_task(results),
-            Direction::Up => self.previous_matching_task(results),
+        match &self.section_focus {
+            LayoutSections::Search { results, .. } => {
+                let results = results.clone();
+                match direction {
+                    Direction::Down => self.next_matching_task(results),
+                    Direction::Up => self.previous_matching_task(results),
+                }
+            }
+            LayoutSections::OutputSearch { results, .. } => {
+                let results = results.clone();
+                match direction {
+                    Direction::Down => self.next_matching_task(results),
+                    Direction::Up => self.previous_matching_task(results),
+                }
+                self.jump_to_output_match(Direction::Down)?;
+            }
+            _ => {
+                debug!("scrolling search while not searching");
+            }
         }
 
         Ok(())
     }
 
+    fn modify_search_query(&mut self, modification: impl FnOnce(&mut String)) -> bool {
+        match &mut self.section_focus {
+            LayoutSections::Search { results, .. } => results.modify_query(modification),
+            LayoutSections::OutputSearch { results, .. } => results.modify_query(modification),
+            _ => return false,
+        }
+        true
+    }
+
     pub fn search_enter_char(&mut self, c: char) -> Result<(), Error> {
-        let LayoutSections::Search { results, .. } = &mut self.section_focus else {
+        if !self.modify_search_query(|s| s.push(c)) {
             debug!("modifying search query while not searching");
             return Ok(());
-        };
-        results.modify_query(|s| s.push(c));
+        }
         self.update_search_results();
         Ok(())
     }
 
     pub fn search_remove_char(&mut self) -> Result<(), Error> {
-        let LayoutSections::Search { results, .. } = &mut self.section_focus else {
-            debug!("modified search query while not searching");
-            return Ok(());
-        };
         let mut query_was_empty = false;
-        results.modify_query(|s| {
+        if !self.modify_search_query(|s| {
             query_was_empty = s.pop().is_none();
-        });
+        }) {
+            debug!("modified search query while not searching");
+            return Ok(());
+        }
         if query_was_empty {
             self.exit_search(true);
         } else {
@@ -390,26 +460,105 @@ impl<W> App<W> {
         Ok(())
     }
 
-    fn update_search_results(&mut self) {
-        let LayoutSections::Search { results, .. } = &self.section_focus else {
+    /// Switch an output search between literal and regex matching
+    pub fn toggle_search_regex(&mut self) {
+        let LayoutSections::OutputSearch { results, .. } = &mut self.section_focus else {
+            debug!("toggling regex while not searching output");
             return;
         };
+        results.toggle_regex();
+        self.update_search_results();
+    }
 
-        // if currently selected task is in results stay on it
-        // if not we go forward looking for a task in results
-        if let Some(result) = results
-            .first_match(
-                self.tasks_by_status
-                    .task_names_in_displayed_order()
-                    .skip(self.selected_task_index),
-            )
-            .or_else(|| results.first_match(self.tasks_by_status.task_names_in_displayed_order()))
-        {
-            let new_selection = result.to_owned();
+    /// Jump to the next or previous match of a locked output search.
+    ///
+    /// Moves through the matches of the selected task first and continues on
+    /// to the adjacent matching task once those are exhausted.
+    pub fn search_jump(&mut self, direction: Direction) -> Result<(), Error> {
+        let LayoutSections::OutputSearchLocked { results } = &self.section_focus else {
+            debug!("jumping to match while not searching output");
+            return Ok(());
+        };
+        if !results.has_matches() {
+            return Ok(());
+        }
+        let results = results.clone();
+        if self.jump_to_output_match(direction)? {
+            return Ok(());
+        }
+
+        match direction {
+            Direction::Down => self.next_matching_task(results),
+            Direction::Up => self.previous_matching_task(results),
+        }
+        self.jump_to_output_match(direction)?;
+        Ok(())
+    }
+
+    /// Scroll the selected task to its next or previous output match.
+    /// Returns false if there are no more matches in that direction.
+    fn jump_to_output_match(&mut self, direction: Direction) -> Result<bool, Error> {
+        let task_name = self.active_task()?.to_owned();
+        let (LayoutSections::OutputSearch { results, .. }
+        | LayoutSections::OutputSearchLocked { results }) = &mut self.section_focus
+        else {
+            return Ok(false);
+        };
+        let Some(line) = results.step_line(&task_name, direction) else {
+            return Ok(false);
+        };
+        // Keep a bit of context above the match
+        let offset = usize::from(self.size.pane_rows() / 3);
+        self.get_full_task_mut()?
+            .scroll_to_line(line.saturating_sub(offset))?;
+        Ok(true)
+    }
+
+    fn update_search_results(&mut self) {
+        let selection = match &mut self.section_focus {
+            LayoutSections::Search { results, .. } => {
+                // if currently selected task is in results stay on it
+                // if not we go forward looking for a task in results
+                results
+                    .first_match(
+                        self.tasks_by_status
+                            .task_names_in_displayed_order()
+                            .skip(self.selected_task_index),
+                    )
+                    .or_else(|| {
+                        results.first_match(self.tasks_by_status.task_names_in_displayed_order())
+                    })
+                    .map(str::to_owned)
+            }
+            LayoutSections::OutputSearch { results, .. } => {
+                results.update_matches(self.tasks.iter_mut().filter_map(|(name, term)| {
+                    term.search_update(true)
+                        .inspect_err(|error| debug!("unable to read output of {name}: {error}"))
+                        .ok()
+                        .map(|update| (name.as_str(), update))
+                }));
+                results
+                    .first_match(
+                        self.tasks_by_status
+                            .task_names_in_displayed_order()
+                            .skip(self.selected_task_index),
+                    )
+                    .or_else(|| {
+                        results.first_match(self.tasks_by_status.task_names_in_displayed_order())
+                    })
+                    .map(str::to_owned)
+            }
+            _ => return,
+        };
+
+        if let Some(new_selection) = selection {
             self.is_task_selection_pinned = true;
             if let Err(error) = self.select_task(&new_selection) {
                 debug!("failed to select search result: {error}");
             }
+            if let Err(error) = self.jump_to_output_match(Direction::Down) {
+                debug!("failed to jump to output match: {error}");
+            }
         }
     }
 
@@ -419,6 +568,7 @@ impl<W> App<W> {
     #[tracing::instrument(skip(self, output_logs))]
     pub fn start_task(&mut self, task: &str, output_logs: OutputLogs) -> Result<(), Error> {
         debug!("starting {task}");
+        self.stopping_tasks.remove(task);
         // Name of currently highlighted task.
         // We will use this after the order switches.
         let highlighted_task = self
@@ -487,6 +637,13 @@ impl<W> App<W> {
     #[tracing::instrument(skip(self, result))]
     pub fn finish_task(&mut self, task: &str, result: TaskResult) -> Result<(), Error> {
         debug!("finishing task {task}");
+        // A task killed at the user's request reports as a success, show that it was
+        // stopped instead.
+        let result = if self.stopping_tasks.remove(task) {
+            TaskResult::Stopped
+        } else {
+            result
+        };
         // Name of currently highlighted task.
         // We will use this after the order switches.
         let highlighted_task = self
@@ -695,6 +852,42 @@ impl<W> App<W> {
         Ok(())
     }
 
+    /// Request that an action is taken for the selected task.
+    ///
+    /// Requests that don't apply to the task's current state, e.g. stopping a
+    /// task that isn't running, are ignored.
+    pub fn task_action(&mut self, action: TaskAction) -> Result<(), Error> {
+        let Some(handler) = &self.task_action_handler else {
+            debug!("task actions are not supported for this run");
+            return Ok(());
+        };
+        let task = self.active_task()?.to_owned();
+        let is_running = self
+            .tasks_by_status
+            .running
+            .iter()
+            .any(|running| running.name() == task);
+        let is_applicable = match action {
+            TaskAction::Stop => is_running,
+            TaskAction::Restart => true,
+            TaskAction::Start => !is_running,
+        };
+        if !is_applicable {
+            debug!("ignoring {action:?} for {task}");
+            return Ok(());
+        }
+
+        if is_running && matches!(action, TaskAction::Stop | TaskAction::Restart) {
+            self.stopping_tasks.insert(task.clone());
+        }
+        handler(task, action);
+        Ok(())
+    }
+
+    pub fn set_task_action_handler(&mut self, handler: TaskActionHandler) {
+        self.task_action_handler = Some(handler);
+    }
+
     fn select_task(&mut self, task_name: &str) -> Result<(), Error> {
         if !self.is_task_selection_pinned {
             return Ok(());
@@ -793,6 +986,17 @@ impl<W: Write> App<W> {
                 name: task.to_owned(),
             })?;
         task_output.process(output);
+
+        // Search the new output so matches stay current, locked or not
+        if let LayoutSections::OutputSearch { results, .. }
+        | LayoutSections::OutputSearchLocked { results } = &mut self.section_focus
+            && results.is_searching()
+        {
+            match task_output.search_update(false) {
+                Ok(update) => results.extend_matches(task, update),
+                Err(error) => debug!("unable to search output of {task}: {error}"),
+            }
+        }
         Ok(())
     }
 }
@@ -806,6 +1010,7 @@ pub async fn run_app(
     repo_root: &AbsoluteSystemPathBuf,
     scrollback_len: u64,
     interrupt: Option<Arc<dyn Fn() + Send + Sync>>,
+    task_action_handler: Option<TaskActionHandler>,
 ) -> Result<(), Error> {
     // Get terminal size before potentially entering alternate screen
     let size = crossterm::terminal::size()?;
@@ -813,6 +1018,9 @@ pub async fn run_app(
 
     let mut app: App<Box<dyn io::Write + Send>> =
         App::new(size.1, size.0, tasks, preferences, scrollback_len);
+    if let Some(handler) = task_action_handler {
+        app.set_task_action_handler(handler);
+    }
     let (crossterm_tx, crossterm_rx) = mpsc::channel(1024);
     input::start_crossterm_stream(crossterm_tx);
 
@@ -1256,6 +1464,18 @@ fn update(
         Event::SearchBackspace => {
             app.search_remove_char()?;
         }
+        Event::OutputSearchEnter => {
+            app.enter_output_search()?;
+        }
+        Event::SearchToggleRegex => {
+            app.toggle_search_regex();
+        }
+        Event::SearchJump { direction } => {
+            app.search_jump(direction)?;
+        }
+        Event::TaskAction(action) => {
+            app.task_action(action)?;
+        }
         Event::PaneSizeQuery(callback) => {
             // If caller has already hung up do nothing
             callback
@@ -1776,6 +1996,60 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn test_task_actions() -> Result<(), Error> {
+        let repo_root_tmp = tempdir()?;
+        let repo_root = AbsoluteSystemPathBuf::try_from(repo_root_tmp.path())
+            .expect("Failed to create AbsoluteSystemPathBuf");
+
+        let mut app: App<()> = App::new(
+            100,
+            100,
+            vec!["a".to_string(), "b".to_string()],
+            PreferenceLoader::new(&repo_root),
+            2048,
+        );
+        let requested = Arc::new(std::sync::Mutex::new(Vec::new()));
+        app.set_task_action_handler({
+            let requested = requested.clone();
+            Arc::new(move |task: String, action: TaskAction| {
+                requested.lock().unwrap().push((task, action))
+            })
+        });
+
+        // Planned tasks can only be started
+        app.task_action(TaskAction::Stop)?;
+        app.task_action(TaskAction::Start)?;
+        app.start_task("a", OutputLogs::Full)?;
+        // Running tasks can't be started again
+        app.task_action(TaskAction::Start)?;
+        app.task_action(TaskAction::Stop)?;
+        assert_eq!(
+            requested.lock().unwrap().as_slice(),
+            &[
+                ("a".to_string(), TaskAction::Start),
+                ("a".to_string(), TaskAction::Stop)
+            ]
+        );
+
+        // The killed task reports success, but should be shown as stopped
+        app.finish_task("a", TaskResult::Success)?;
+        assert_eq!(
+            app.tasks_by_status.finished[0].result(),
+            TaskResult::Stopped
+        );
+
+        // Once restarted the task finishes normally
+        app.restart_tasks(vec!["a".to_string()])?;
+        app.start_task("a", OutputLogs::Full)?;
+        app.finish_task("a", TaskResult::Success)?;
+        assert_eq!(
+            app.tasks_by_status.finished[0].result(),
+            TaskResult::Success
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_search_backspace_exits_search() -> Result<(), Error> {
         let repo_root_tmp = tempdir()?;
@@ -2191,6 +2465,142 @@ mod test {
         Ok(())
     }
 
+    fn output_search_app(repo_root: &AbsoluteSystemPathBuf) -> Result<App<Vec<u8>>, Error> {
+        let mut app: App<Vec<u8>> = App::new(
+            100,
+            100,
+            vec!["a".to_string(), "b".to_string(), "c".to_string()],
+            PreferenceLoader::new(repo_root),
+            2048,
+        );
+        app.process_output("a", b"starting\r\n")?;
+        app.process_output("b", b"error: one\r\nok\r\nerror: two error\r\n")?;
+        app.process_output("c", b"error: three\r\n")?;
+        Ok(app)
+    }
+
+    fn output_hits<W>(app: &App<W>, task: &str) -> Option<usize> {
+        match &app.section_focus {
+            LayoutSections::OutputSearch { results, .. }
+            | LayoutSections::OutputSearchLocked { results } => results.hits(task),
+            _ => panic!("not searching output"),
+        }
+    }
+
+    #[test]
+    fn test_output_search_selects_first_match() -> Result<(), Error> {
+        let repo_root_tmp = tempdir()?;
+        let repo_root = AbsoluteSystemPathBuf::try_from(repo_root_tmp.path())
+            .expect("Failed to create AbsoluteSystemPathBuf");
+        let mut app = output_search_app(&repo_root)?;
+
+        app.enter_output_search()?;
+        assert!(matches!(
+            app.section_focus,
+            LayoutSections::OutputSearch { .. }
+        ));
+        for c in "error".chars() {
+            app.search_enter_char(c)?;
+        }
+        assert_eq!(app.active_task()?, "b");
+        assert_eq!(output_hits(&app, "a"), None);
+        assert_eq!(output_hits(&app, "b"), Some(3));
+        assert_eq!(output_hits(&app, "c"), Some(1));
+
+        app.search_scroll(Direction::Down)?;
+        assert_eq!(app.active_task()?, "c");
+
+        app.exit_search(true);
+        assert!(matches!(app.section_focus, LayoutSections::TaskList));
+        assert_eq!(app.active_task()?, "a", "should restore previous selection");
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_search_jump() -> Result<(), Error> {
+        let repo_root_tmp = tempdir()?;
+        let repo_root = AbsoluteSystemPathBuf::try_from(repo_root_tmp.path())
+            .expect("Failed to create AbsoluteSystemPathBuf");
+        let mut app = output_search_app(&repo_root)?;
+
+        app.enter_output_search()?;
+        for c in "error".chars() {
+            app.search_enter_char(c)?;
+        }
+        app.lock_search();
+        assert!(matches!(
+            app.section_focus,
+            LayoutSections::OutputSearchLocked { .. }
+        ));
+        assert_eq!(app.active_task()?, "b");
+
+        // Second match in b
+        app.search_jump(Direction::Down)?;
+        assert_eq!(app.active_task()?, "b");
+        // Out of matches in b, move on to c
+        app.search_jump(Direction::Down)?;
+        assert_eq!(app.active_task()?, "c");
+        // Wrap around back to b
+        app.search_jump(Direction::Down)?;
+        assert_eq!(app.active_task()?, "b");
+        app.search_jump(Direction::Up)?;
+        assert_eq!(app.active_task()?, "c");
+
+        // Task navigation only visits tasks with matches
+        app.next();
+        assert_eq!(app.active_task()?, "b");
+        app.next();
+        assert_eq!(app.active_task()?, "c");
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_search_regex() -> Result<(), Error> {
+        let repo_root_tmp = tempdir()?;
+        let repo_root = AbsoluteSystemPathBuf::try_from(repo_root_tmp.path())
+            .expect("Failed to create AbsoluteSystemPathBuf");
+        let mut app = output_search_app(&repo_root)?;
+
+        app.enter_output_search()?;
+        for c in "error: t.*".chars() {
+            app.search_enter_char(c)?;
+        }
+        assert_eq!(output_hits(&app, "b"), None);
+        assert_eq!(output_hits(&app, "c"), None);
+
+        app.toggle_search_regex();
+        assert_eq!(output_hits(&app, "b"), Some(1));
+        assert_eq!(output_hits(&app, "c"), Some(1));
+        assert_eq!(app.active_task()?, "b");
+        Ok(())
+    }
+
+    #[test]
+    fn test_output_search_includes_new_output() -> Result<(), Error> {
+        let repo_root_tmp = tempdir()?;
+        let repo_root = AbsoluteSystemPathBuf::try_from(repo_root_tmp.path())
+            .expect("Failed to create AbsoluteSystemPathBuf");
+        let mut app = output_search_app(&repo_root)?;
+
+        app.enter_output_search()?;
+        for c in "error".chars() {
+            app.search_enter_char(c)?;
+        }
+        app.process_output("a", b"error: four\r\n")?;
+        assert_eq!(output_hits(&app, "a"), Some(1));
+
+        app.lock_search();
+        app.process_output("c", b"still going\r\nerror: five\r\n")?;
+        assert_eq!(output_hits(&app, "c"), Some(2));
+
+        // Jumping reaches the new match
+        app.select_task("c")?;
+        app.search_jump(Direction::Down)?;
+        app.search_jump(Direction::Down)?;
+        assert_eq!(app.active_task()?, "c");
+        Ok(())
+    }
+
     #[test]
     fn test_locked_search_maintains_filter_after_task_changes() -> Result<(), Error> {
         let repo_root_tmp = tempdir()?;
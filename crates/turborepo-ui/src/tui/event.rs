@@ -1,3 +1,5 @@
+use std::sync::Arc;
+
 use async_graphql::Enum;
 use serde::Serialize;
 use tokio::sync::oneshot;
@@ -72,6 +74,12 @@ pub enum Event {
     },
     SearchEnterChar(char),
     SearchBackspace,
+    OutputSearchEnter,
+    SearchToggleRegex,
+    SearchJump {
+        direction: Direction,
+    },
+    TaskAction(TaskAction),
 }
 
 #[derive(Copy, Clone, PartialEq, Eq)]
@@ -85,8 +93,49 @@ pub enum TaskResult {
     Success,
     Failure,
     CacheHit,
+    // Stopped by the user from the TUI
+    Stopped,
+}
+
+/// An action the user requested for the selected task
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TaskAction {
+    /// Kill the running task
+    Stop,
+    /// Kill the task if it is running and run it again
+    Restart,
+    /// Run a task that is not currently running
+    Start,
+}
+
+/// Callback invoked with the name of a task and the action requested for it
+pub type TaskActionHandler = Arc<dyn Fn(String, TaskAction) + Send + Sync>;
+
+/// An event in the lifecycle of a task, for observers of the run. These are
+/// published in every UI mode, not only when the TUI is in use.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskEvent {
+    Start {
+        task: String,
+    },
+    Output {
+        task: String,
+        output: Vec<u8>,
+    },
+    Status {
+        task: String,
+        status: String,
+        result: CacheResult,
+    },
+    End {
+        task: String,
+        result: TaskResult,
+    },
 }
 
+/// Callback invoked with every task event of a run
+pub type TaskEventObserver = Arc<dyn Fn(TaskEvent) + Send + Sync>;
+
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Enum)]
 pub enum CacheResult {
     Hit,
@@ -6,7 +6,7 @@ use tokio::{sync::mpsc, task::JoinHandle};
 
 use super::{
     app::LayoutSections,
-    event::{Direction, Event},
+    event::{Direction, Event, TaskAction},
 };
 
 #[derive(Debug, Clone, Copy)]
@@ -86,43 +86,68 @@ fn translate_key_event(options: InputOptions, key_event: KeyEvent) -> Option<Eve
         KeyCode::Char('/') if matches!(options.focus, LayoutSections::TaskList) => {
             Some(Event::SearchEnter)
         }
+        // If we're on the list and user presses `f` enter output search mode
+        KeyCode::Char('f') if matches!(options.focus, LayoutSections::TaskList) => {
+            Some(Event::OutputSearchEnter)
+        }
         // If we're in locked search and user presses `/` clear the search
         KeyCode::Char('/') if matches!(options.focus, LayoutSections::SearchLocked { .. }) => {
             Some(Event::SearchExit {
                 restore_scroll: false,
             })
         }
-        KeyCode::Esc if options.is_help_popup_open => Some(Event::ToggleHelpPopup),
-        KeyCode::Esc if options.is_log_panel_open => Some(Event::ToggleLogPanel),
-        KeyCode::Esc if matches!(options.focus, LayoutSections::Search { .. }) => {
-            Some(Event::SearchExit {
-                restore_scroll: true,
-            })
-        }
-        KeyCode::Esc if matches!(options.focus, LayoutSections::SearchLocked { .. }) => {
+        // If we're in locked output search and user presses `f` clear the search
+        KeyCode::Char('f')
+            if matches!(options.focus, LayoutSections::OutputSearchLocked { .. }) =>
+        {
             Some(Event::SearchExit {
                 restore_scroll: false,
             })
         }
-        KeyCode::Enter if matches!(options.focus, LayoutSections::Search { .. }) => {
-            Some(Event::SearchLock)
+        KeyCode::Char('n')
+            if matches!(options.focus, LayoutSections::OutputSearchLocked { .. }) =>
+        {
+            Some(Event::SearchJump {
+                direction: Direction::Down,
+            })
         }
-        KeyCode::Up if matches!(options.focus, LayoutSections::Search { .. }) => {
-            Some(Event::SearchScroll {
+        KeyCode::Char('N')
+            if matches!(options.focus, LayoutSections::OutputSearchLocked { .. }) =>
+        {
+            Some(Event::SearchJump {
                 direction: Direction::Up,
             })
         }
-        KeyCode::Down if matches!(options.focus, LayoutSections::Search { .. }) => {
-            Some(Event::SearchScroll {
-                direction: Direction::Down,
+        KeyCode::Esc if options.is_help_popup_open => Some(Event::ToggleHelpPopup),
+        KeyCode::Esc if options.is_log_panel_open => Some(Event::ToggleLogPanel),
+        KeyCode::Esc if is_typing_search(options.focus) => Some(Event::SearchExit {
+            restore_scroll: true,
+        }),
+        KeyCode::Esc
+            if matches!(
+                options.focus,
+                LayoutSections::SearchLocked { .. } | LayoutSections::OutputSearchLocked { .. }
+            ) =>
+        {
+            Some(Event::SearchExit {
+                restore_scroll: false,
             })
         }
-        KeyCode::Backspace if matches!(options.focus, LayoutSections::Search { .. }) => {
-            Some(Event::SearchBackspace)
-        }
-        KeyCode::Char(c) if matches!(options.focus, LayoutSections::Search { .. }) => {
-            Some(Event::SearchEnterChar(c))
+        KeyCode::Enter if is_typing_search(options.focus) => Some(Event::SearchLock),
+        KeyCode::Up if is_typing_search(options.focus) => Some(Event::SearchScroll {
+            direction: Direction::Up,
+        }),
+        KeyCode::Down if is_typing_search(options.focus) => Some(Event::SearchScroll {
+            direction: Direction::Down,
+        }),
+        KeyCode::Backspace if is_typing_search(options.focus) => Some(Event::SearchBackspace),
+        KeyCode::Char('r')
+            if matches!(options.focus, LayoutSections::OutputSearch { .. })
+                && key_event.modifiers == crossterm::event::KeyModifiers::CONTROL =>
+        {
+            Some(Event::SearchToggleRegex)
         }
+        KeyCode::Char(c) if is_typing_search(options.focus) => Some(Event::SearchEnterChar(c)),
         // Fall through if we aren't in interactive mode
         KeyCode::Char('l') => Some(Event::ToggleLogPanel),
         KeyCode::Char('h') => Some(Event::ToggleSidebar),
@@ -135,6 +160,9 @@ fn translate_key_event(options: InputOptions, key_event: KeyEvent) -> Option<Eve
         KeyCode::Char('C') => Some(Event::ClearLogs),
         KeyCode::Char('m') => Some(Event::ToggleHelpPopup),
         KeyCode::Char('p') => Some(Event::TogglePinnedTask),
+        KeyCode::Char('x') => Some(Event::TaskAction(TaskAction::Stop)),
+        KeyCode::Char('r') => Some(Event::TaskAction(TaskAction::Restart)),
+        KeyCode::Char('s') => Some(Event::TaskAction(TaskAction::Start)),
         KeyCode::Up | KeyCode::Char('k') => Some(Event::Up),
         KeyCode::Down | KeyCode::Char('j') => Some(Event::Down),
         KeyCode::Enter | KeyCode::Char('i') => Some(Event::EnterInteractive),
@@ -142,6 +170,13 @@ fn translate_key_event(options: InputOptions, key_event: KeyEvent) -> Option<Eve
     }
 }
 
+fn is_typing_search(focus: &LayoutSections) -> bool {
+    matches!(
+        focus,
+        LayoutSections::Search { .. } | LayoutSections::OutputSearch { .. }
+    )
+}
+
 #[cfg(unix)]
 fn ctrl_c() -> Option<Event> {
     use nix::sys::signal;
@@ -440,7 +475,10 @@ mod test {
     use test_case::test_case;
 
     use super::*;
-    use crate::tui::{search::SearchResults, task::TasksByStatus};
+    use crate::tui::{
+        search::{OutputSearchResults, SearchResults},
+        task::TasksByStatus,
+    };
 
     fn search() -> &'static LayoutSections {
         static SEARCH: OnceLock<LayoutSections> = OnceLock::new();
@@ -450,18 +488,53 @@ mod test {
         })
     }
 
-    fn in_find() -> InputOptions<'static> {
+    fn output_search() -> &'static LayoutSections {
+        static SEARCH: OnceLock<LayoutSections> = OnceLock::new();
+        SEARCH.get_or_init(|| LayoutSections::OutputSearch {
+            previous_selection: "".into(),
+            results: OutputSearchResults::new(),
+        })
+    }
+
+    fn output_search_locked() -> &'static LayoutSections {
+        static SEARCH: OnceLock<LayoutSections> = OnceLock::new();
+        SEARCH.get_or_init(|| LayoutSections::OutputSearchLocked {
+            results: OutputSearchResults::new(),
+        })
+    }
+
+    fn with_focus(focus: &'static LayoutSections) -> InputOptions<'static> {
         InputOptions {
-            focus: search(),
+            focus,
             has_selection: false,
             is_help_popup_open: false,
             is_log_panel_open: false,
         }
     }
 
+    fn in_find() -> InputOptions<'static> {
+        with_focus(search())
+    }
+
+    static TASK_LIST: LayoutSections = LayoutSections::TaskList;
+
     const H: KeyEvent = KeyEvent::new(KeyCode::Char('h'), KeyModifiers::empty());
+    const R: KeyEvent = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::empty());
+    const F: KeyEvent = KeyEvent::new(KeyCode::Char('f'), KeyModifiers::empty());
+    const N: KeyEvent = KeyEvent::new(KeyCode::Char('n'), KeyModifiers::empty());
+    const SHIFT_N: KeyEvent = KeyEvent::new(KeyCode::Char('N'), KeyModifiers::SHIFT);
+    const CTRL_R: KeyEvent = KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL);
 
     #[test_case(in_find(), H, Some(Event::SearchEnterChar('h')) ; "h while searching")]
+    #[test_case(with_focus(&TASK_LIST), F, Some(Event::OutputSearchEnter) ; "f on task list")]
+    #[test_case(with_focus(&TASK_LIST), R, Some(Event::TaskAction(TaskAction::Restart)) ; "r on task list")]
+    #[test_case(in_find(), R, Some(Event::SearchEnterChar('r')) ; "r while searching")]
+    #[test_case(with_focus(output_search()), N, Some(Event::SearchEnterChar('n')) ; "n while searching output")]
+    #[test_case(with_focus(output_search()), CTRL_R, Some(Event::SearchToggleRegex) ; "ctrl-r while searching output")]
+    #[test_case(in_find(), CTRL_R, Some(Event::SearchEnterChar('r')) ; "ctrl-r while searching tasks")]
+    #[test_case(with_focus(output_search_locked()), N, Some(Event::SearchJump { direction: Direction::Down }) ; "n in locked output search")]
+    #[test_case(with_focus(output_search_locked()), SHIFT_N, Some(Event::SearchJump { direction: Direction::Up }) ; "shift-n in locked output search")]
+    #[test_case(with_focus(output_search_locked()), F, Some(Event::SearchExit { restore_scroll: false }) ; "f exits locked output search")]
     // Note: This only checks event variants not any data contained in the variant
     fn test_translate_key_event_variant(
         opts: InputOptions,
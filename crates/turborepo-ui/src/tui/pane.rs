@@ -15,6 +15,7 @@ const SCROLL_LOGS: &str = "u/d - Scroll logs";
 const PAGE_LOGS: &str = "U/D - Page logs";
 const JUMP_IN_LOGS: &str = "t/b - Jump to top/bottom";
 const TASK_LIST_HIDDEN: &str = "h - Show task list";
+const NEXT_MATCH: &str = "n/N - Next/previous match";
 
 pub struct TerminalPane<'a, W> {
     terminal_output: &'a mut TerminalOutput<W>,
@@ -71,9 +72,14 @@ impl<'a, W> TerminalPane<'a, W> {
                 build_message_vec(&[ENTER_INTERACTIVE_HINT, SCROLL_LOGS, PAGE_LOGS, JUMP_IN_LOGS])
             }
             LayoutSections::TaskList => build_message_vec(&[SCROLL_LOGS, PAGE_LOGS, JUMP_IN_LOGS]),
-            LayoutSections::Search { .. } | LayoutSections::SearchLocked { .. } => {
+            LayoutSections::Search { .. }
+            | LayoutSections::SearchLocked { .. }
+            | LayoutSections::OutputSearch { .. } => {
                 build_message_vec(&[SCROLL_LOGS, PAGE_LOGS, JUMP_IN_LOGS])
             }
+            LayoutSections::OutputSearchLocked { .. } => {
+                build_message_vec(&[NEXT_MATCH, SCROLL_LOGS, PAGE_LOGS, JUMP_IN_LOGS])
+            }
         }
     }
 }
@@ -14,8 +14,15 @@ const BIND_LIST: &[&str] = [
     "h       - Toggle task list",
     "p       - Toggle pinned task selection",
     "/       - Filter tasks to search term",
+    "f       - Search task output",
+    "Ctrl+r  - Toggle regex while searching output",
+    "n       - Jump to next output match",
+    "Shift+n - Jump to previous output match",
     "ESC     - Clear filter",
     "i       - Interact with task",
+    "x       - Stop task",
+    "r       - Restart task",
+    "s       - Start task",
     "Ctrl+z  - Stop interacting with task",
     "c       - Copy logs selection (Only when logs are selected)",
     "u       - Scroll logs up",
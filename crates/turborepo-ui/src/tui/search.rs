@@ -1,6 +1,18 @@
-use std::{collections::HashSet, sync::Arc};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+};
 
-use super::task::TasksByStatus;
+use regex::Regex;
+
+use super::{event::Direction, task::TasksByStatus, term_output::OutputUpdate};
+
+/// Search results that can be used to navigate between matching tasks
+pub trait TaskMatches {
+    /// Given an iterator it returns the first task that is in the search
+    /// results
+    fn first_match<'a>(&self, tasks: impl Iterator<Item = &'a str>) -> Option<&'a str>;
+}
 
 #[derive(Debug, Clone)]
 pub struct SearchResults {
@@ -49,21 +61,241 @@ impl SearchResults {
         }
     }
 
-    /// Given an iterator it returns the first task that is in the search
-    /// results
-    pub fn first_match<'a>(&self, mut tasks: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    /// Returns if there are any matches for the query
+    pub fn has_matches(&self) -> bool {
+        !self.matches.is_empty()
+    }
+
+    /// Returns query
+    pub fn query(&self) -> &str {
+        &self.query
+    }
+}
+
+impl TaskMatches for SearchResults {
+    fn first_match<'a>(&self, mut tasks: impl Iterator<Item = &'a str>) -> Option<&'a str> {
         tasks.find(|task| self.matches.contains(*task))
     }
+}
+
+/// Results of searching the output of every task for a query.
+///
+/// Queries are matched literally unless regex mode is enabled. Matches are
+/// tracked per line so the selected pane can jump between them. Output is
+/// searched once when the query changes and then extended as more arrives.
+#[derive(Debug, Clone, Default)]
+pub struct OutputSearchResults {
+    query: String,
+    is_regex: bool,
+    // Set when the query is a regex that fails to compile
+    is_invalid: bool,
+    // The compiled query, if it's valid and not empty
+    pattern: Option<Regex>,
+    matches: HashMap<Arc<str>, OutputMatches>,
+    // The task and index into its matching lines that was last jumped to
+    cursor: Option<(Arc<str>, usize)>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct OutputMatches {
+    // Lines containing at least one match, counted from the top of the
+    // scrollback, along with the number of matches on each
+    lines: Vec<(usize, usize)>,
+    // Total number of matches across all lines
+    hits: usize,
+    // Number of scrollback rows searched. These won't change so only the
+    // active screen below them is searched again on each update.
+    history_rows: usize,
+    // How many of `lines` are in the searched scrollback rows
+    history_lines: usize,
+}
+
+impl OutputMatches {
+    /// Applies an update to a task's output, returning how many entries were
+    /// removed from the front of `lines`.
+    fn update(&mut self, pattern: &Regex, update: OutputUpdate) -> usize {
+        let removed = match update.pruned {
+            Some(pruned) => {
+                // The active screen is searched again below
+                self.remove_lines(self.history_lines..);
+                let removed = self.lines.partition_point(|(line, _)| *line < pruned);
+                self.remove_lines(..removed);
+                for (line, _) in &mut self.lines {
+                    *line -= pruned;
+                }
+                self.history_rows = self.history_rows.saturating_sub(pruned);
+                removed
+            }
+            None => {
+                let removed = self.lines.len();
+                *self = Self::default();
+                removed
+            }
+        };
+
+        self.search(pattern, self.history_rows, &update.history);
+        self.history_rows += update.history.len();
+        self.history_lines = self.lines.len();
+        self.search(pattern, self.history_rows, &update.active);
+        removed
+    }
+
+    fn remove_lines(&mut self, range: impl std::ops::RangeBounds<usize>) {
+        for (_, hits) in self.lines.drain(range) {
+            self.hits -= hits;
+        }
+    }
+
+    fn search(&mut self, pattern: &Regex, first_row: usize, rows: &[String]) {
+        for (index, row) in rows.iter().enumerate() {
+            let hits = pattern.find_iter(row).count();
+            if hits > 0 {
+                self.lines.push((first_row + index, hits));
+                self.hits += hits;
+            }
+        }
+    }
+}
+
+impl OutputSearchResults {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Updates the query, matches need to be recomputed with
+    /// `update_matches`
+    pub fn modify_query(&mut self, modification: impl FnOnce(&mut String)) {
+        modification(&mut self.query);
+    }
+
+    /// Switches between literal and regex matching, matches need to be
+    /// recomputed with `update_matches`
+    pub fn toggle_regex(&mut self) {
+        self.is_regex = !self.is_regex;
+    }
+
+    /// Searches the full output of each task for the current query,
+    /// discarding previous matches
+    pub fn update_matches<'a>(&mut self, outputs: impl Iterator<Item = (&'a str, OutputUpdate)>) {
+        self.matches.clear();
+        self.cursor = None;
+        self.is_invalid = false;
+        self.pattern = None;
+        if self.query.is_empty() {
+            return;
+        }
+        let pattern = if self.is_regex {
+            Regex::new(&self.query)
+        } else {
+            Regex::new(&regex::escape(&self.query))
+        };
+        let Ok(pattern) = pattern else {
+            self.is_invalid = true;
+            return;
+        };
+        self.pattern = Some(pattern);
+
+        for (task, update) in outputs {
+            self.extend_matches(task, update);
+        }
+    }
+
+    /// Returns if there's a query to extend matches with as output arrives
+    pub fn is_searching(&self) -> bool {
+        self.pattern.is_some()
+    }
+
+    /// Searches output that a task wrote since its last update
+    pub fn extend_matches(&mut self, task: &str, update: OutputUpdate) {
+        let Some(pattern) = &self.pattern else {
+            return;
+        };
+        if !self.matches.contains_key(task) {
+            self.matches
+                .insert(Arc::from(task), OutputMatches::default());
+        }
+        let Some(matches) = self.matches.get_mut(task) else {
+            return;
+        };
+        let removed = matches.update(pattern, update);
+
+        // Keep the jump position on the same line if it's still there
+        if let Some((current, index)) = &mut self.cursor
+            && current.as_ref() == task
+        {
+            match index.checked_sub(removed) {
+                Some(shifted) if shifted < matches.lines.len() => *index = shifted,
+                _ => self.cursor = None,
+            }
+        }
+    }
+
+    /// Returns the number of matches in a task's output, if any
+    pub fn hits(&self, task: &str) -> Option<usize> {
+        self.matches
+            .get(task)
+            .map(|matches| matches.hits)
+            .filter(|hits| *hits > 0)
+    }
+
+    /// Moves to the next or previous matching line in the task's output and
+    /// returns it.
+    ///
+    /// Starts from the first (or last) match when the task differs from the
+    /// last one navigated. Returns `None` after stepping past either end so
+    /// the caller can move on to another task.
+    pub fn step_line(&mut self, task: &str, direction: Direction) -> Option<usize> {
+        let Some((task, matches)) = self.matches.get_key_value(task) else {
+            self.cursor = None;
+            return None;
+        };
+        let index = match (&self.cursor, direction) {
+            (Some((current, index)), Direction::Down) if current == task => index + 1,
+            (Some((current, index)), Direction::Up) if current == task => {
+                match index.checked_sub(1) {
+                    Some(index) => index,
+                    None => {
+                        self.cursor = None;
+                        return None;
+                    }
+                }
+            }
+            (_, Direction::Down) => 0,
+            (_, Direction::Up) => matches.lines.len().saturating_sub(1),
+        };
+        let Some((line, _)) = matches.lines.get(index).copied() else {
+            self.cursor = None;
+            return None;
+        };
+        self.cursor = Some((task.clone(), index));
+        Some(line)
+    }
 
     /// Returns if there are any matches for the query
     pub fn has_matches(&self) -> bool {
-        !self.matches.is_empty()
+        self.matches.values().any(|matches| matches.hits > 0)
     }
 
     /// Returns query
     pub fn query(&self) -> &str {
         &self.query
     }
+
+    /// Returns if the query is matched as a regex
+    pub fn is_regex(&self) -> bool {
+        self.is_regex
+    }
+
+    /// Returns if the query is a regex that failed to compile
+    pub fn is_invalid(&self) -> bool {
+        self.is_invalid
+    }
+}
+
+impl TaskMatches for OutputSearchResults {
+    fn first_match<'a>(&self, mut tasks: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+        tasks.find(|task| self.hits(task).is_some())
+    }
 }
 
 #[cfg(test)]
@@ -118,6 +350,181 @@ mod test {
         assert_eq!(result, Some("app-c"));
     }
 
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    fn output(history: &[&str]) -> OutputUpdate {
+        OutputUpdate {
+            pruned: None,
+            history: lines(history),
+            active: Vec::new(),
+        }
+    }
+
+    fn output_search(query: &str, is_regex: bool) -> OutputSearchResults {
+        let mut results = OutputSearchResults::new();
+        if is_regex {
+            results.toggle_regex();
+        }
+        results.modify_query(|s| s.push_str(query));
+        results.update_matches(
+            [
+                (
+                    "api#test",
+                    output(&[
+                        "connecting",
+                        "Error: connect ECONNREFUSED 127.0.0.1:5432",
+                        "retrying",
+                        "Error: connect ECONNREFUSED 127.0.0.1:5432 (ECONNREFUSED)",
+                    ]),
+                ),
+                ("web#test", output(&["all tests passed"])),
+                ("db#test", output(&["ECONNRESET"])),
+            ]
+            .into_iter(),
+        );
+        results
+    }
+
+    #[test]
+    fn test_output_search_counts_hits() {
+        let results = output_search("ECONNREFUSED", false);
+        assert!(results.has_matches());
+        assert_eq!(results.hits("api#test"), Some(3));
+        assert_eq!(results.hits("web#test"), None);
+        assert_eq!(results.hits("db#test"), None);
+        let task_list = ["db#test", "web#test", "api#test"];
+        assert_eq!(results.first_match(task_list.into_iter()), Some("api#test"));
+    }
+
+    #[test]
+    fn test_output_search_literal_escapes_regex() {
+        let results = output_search("127.0.0.1:5432 (", false);
+        assert_eq!(results.hits("api#test"), Some(1));
+        let results = output_search("tests.passed", false);
+        assert!(!results.has_matches());
+    }
+
+    #[test]
+    fn test_output_search_regex() {
+        let results = output_search("ECONN(REFUSED|RESET)", true);
+        assert_eq!(results.hits("api#test"), Some(3));
+        assert_eq!(results.hits("db#test"), Some(1));
+        assert!(!results.is_invalid());
+
+        let results = output_search("ECONN(", true);
+        assert!(results.is_invalid());
+        assert!(!results.has_matches());
+    }
+
+    #[test]
+    fn test_output_search_step_line() {
+        let mut results = output_search("ECONNREFUSED", false);
+        assert_eq!(results.step_line("api#test", Direction::Down), Some(1));
+        assert_eq!(results.step_line("api#test", Direction::Down), Some(3));
+        assert_eq!(results.step_line("api#test", Direction::Down), None);
+        // After running off the end we start again from the first match
+        assert_eq!(results.step_line("api#test", Direction::Down), Some(1));
+        assert_eq!(results.step_line("api#test", Direction::Up), None);
+        assert_eq!(results.step_line("api#test", Direction::Up), Some(3));
+        assert_eq!(results.step_line("web#test", Direction::Down), None);
+    }
+
+    #[test]
+    fn test_output_search_extends_with_new_output() {
+        let mut results = OutputSearchResults::new();
+        results.modify_query(|s| s.push_str("error"));
+        results.update_matches(std::iter::once((
+            "web#build",
+            OutputUpdate {
+                pruned: None,
+                history: Vec::new(),
+                active: lines(&["error one", "building"]),
+            },
+        )));
+        assert_eq!(results.hits("web#build"), Some(1));
+
+        // Rows scroll into the scrollback and new output arrives
+        results.extend_matches(
+            "web#build",
+            OutputUpdate {
+                pruned: Some(0),
+                history: lines(&["error one", "building"]),
+                active: lines(&["error two"]),
+            },
+        );
+        assert_eq!(results.hits("web#build"), Some(2));
+        assert_eq!(results.step_line("web#build", Direction::Down), Some(0));
+        assert_eq!(results.step_line("web#build", Direction::Down), Some(2));
+
+        // The active screen is rewritten, e.g. by a progress bar
+        results.extend_matches(
+            "web#build",
+            OutputUpdate {
+                pruned: Some(0),
+                history: Vec::new(),
+                active: lines(&["done"]),
+            },
+        );
+        assert_eq!(results.hits("web#build"), Some(1));
+
+        // Output from a task that had no matches yet
+        assert!(results.first_match(std::iter::once("api#build")).is_none());
+        results.extend_matches(
+            "api#build",
+            OutputUpdate {
+                pruned: Some(0),
+                history: Vec::new(),
+                active: lines(&["error: missing module"]),
+            },
+        );
+        assert_eq!(
+            results.first_match(std::iter::once("api#build")),
+            Some("api#build")
+        );
+    }
+
+    #[test]
+    fn test_output_search_shifts_pruned_rows() {
+        let mut results = OutputSearchResults::new();
+        results.modify_query(|s| s.push_str("error"));
+        results.update_matches(std::iter::once((
+            "web#build",
+            output(&["error a", "ok", "error b"]),
+        )));
+        assert_eq!(results.step_line("web#build", Direction::Down), Some(0));
+        assert_eq!(results.step_line("web#build", Direction::Down), Some(2));
+
+        // The first row falls out of the scrollback
+        results.extend_matches(
+            "web#build",
+            OutputUpdate {
+                pruned: Some(1),
+                history: lines(&["error c"]),
+                active: Vec::new(),
+            },
+        );
+        assert_eq!(results.hits("web#build"), Some(2));
+        // The cursor stays on "error b", which moved up a row
+        assert_eq!(results.step_line("web#build", Direction::Down), Some(2));
+        assert_eq!(results.step_line("web#build", Direction::Up), Some(1));
+
+        // Rows that can't be located anymore are searched again from scratch
+        results.extend_matches("web#build", output(&["error d"]));
+        assert_eq!(results.hits("web#build"), Some(1));
+        assert_eq!(results.step_line("web#build", Direction::Down), Some(0));
+    }
+
+    #[test]
+    fn test_output_search_without_query_ignores_output() {
+        let mut results = OutputSearchResults::new();
+        results.update_matches(std::iter::empty());
+        assert!(!results.is_searching());
+        results.extend_matches("web#build", output(&["error"]));
+        assert!(!results.has_matches());
+    }
+
     #[test]
     fn test_no_match_on_empty_list() {
         let task_list = basic_task_list();
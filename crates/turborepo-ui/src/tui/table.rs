@@ -5,7 +5,10 @@ use ratatui::{
     widgets::{Block, Borders, Cell, Row, StatefulWidget, Table, TableState},
 };
 
-use super::{app::LayoutSections, event::TaskResult, spinner::SpinnerState, task::TasksByStatus};
+use super::{
+    app::LayoutSections, event::TaskResult, search::TaskMatches, spinner::SpinnerState,
+    task::TasksByStatus,
+};
 
 /// A widget that renders a table of their tasks and their current status
 ///
@@ -60,10 +63,55 @@ impl<'b> TaskTable<'b> {
             | LayoutSections::SearchLocked { results, .. } => {
                 results.first_match(std::iter::once(task_name)).is_none()
             }
+            LayoutSections::OutputSearch { results, .. }
+            | LayoutSections::OutputSearchLocked { results, .. } => {
+                results.first_match(std::iter::once(task_name)).is_none()
+            }
             _ => false,
         }
     }
 
+    /// Number of output matches for a task, shown after its name while
+    /// searching output
+    fn hit_count(&self, task_name: &str) -> Option<Span<'static>> {
+        match self.section {
+            LayoutSections::OutputSearch { results, .. }
+            | LayoutSections::OutputSearchLocked { results, .. } => {
+                results.hits(task_name).map(|hits| {
+                    Span::styled(
+                        format!(" ({hits})"),
+                        Style::default().add_modifier(Modifier::DIM),
+                    )
+                })
+            }
+            _ => None,
+        }
+    }
+
+    fn header(&self) -> String {
+        match self.section {
+            LayoutSections::Search { results, .. }
+            | LayoutSections::SearchLocked { results, .. } => {
+                format!("/ {}", results.query())
+            }
+            LayoutSections::OutputSearch { results, .. }
+            | LayoutSections::OutputSearchLocked { results, .. } => {
+                let query = if results.is_regex() {
+                    format!("/{}/", results.query())
+                } else {
+                    results.query().to_string()
+                };
+                let invalid = if results.is_invalid() {
+                    " (invalid)"
+                } else {
+                    ""
+                };
+                format!("f {query}{invalid}")
+            }
+            _ => TASK_HEADER.to_string(),
+        }
+    }
+
     /// Get base style for a task (dimmed or normal)
     fn task_style(&self, task_name: &str) -> Style {
         if self.should_dim_task(task_name) {
@@ -102,6 +150,14 @@ impl<'b> TaskTable<'b> {
                     };
                     Span::styled(" ⨯ ", style)
                 }
+                TaskResult::Stopped => {
+                    let style = if self.should_dim_task(task.name()) {
+                        Style::default().yellow().add_modifier(Modifier::DIM)
+                    } else {
+                        Style::default().yellow()
+                    };
+                    Span::styled(" ■ ", style)
+                }
             };
 
             let name_style = if matches!(task.result(), TaskResult::CacheHit) {
@@ -112,6 +168,7 @@ impl<'b> TaskTable<'b> {
 
             let mut content = vec![icon];
             content.push(Span::styled(format!(" {}", task.name()), name_style));
+            content.extend(self.hit_count(task.name()));
 
             Row::new(vec![Cell::new(Line::from(content))])
         })
@@ -121,10 +178,11 @@ impl<'b> TaskTable<'b> {
         let spinner = self.spinner.current();
         self.tasks_by_type.running.iter().map(move |task| {
             let style = self.task_style(task.name());
-            let content = vec![
+            let mut content = vec![
                 Span::styled(format!(" {} ", spinner), style),
                 Span::styled(format!(" {}", task.name()), style),
             ];
+            content.extend(self.hit_count(task.name()));
             Row::new(vec![Cell::new(Line::from(content))])
         })
     }
@@ -132,10 +190,11 @@ impl<'b> TaskTable<'b> {
     fn planned_rows(&self) -> impl Iterator<Item = Row<'_>> + '_ {
         self.tasks_by_type.planned.iter().map(move |task| {
             let style = self.task_style(task.name());
-            let content = vec![
+            let mut content = vec![
                 Span::raw("   "),
                 Span::styled(format!(" {}", task.name()), style),
             ];
+            content.extend(self.hit_count(task.name()));
             Row::new(vec![Cell::new(Line::from(content))])
         })
     }
@@ -156,13 +215,7 @@ impl<'a> StatefulWidget for &'a TaskTable<'a> {
         .block(Block::new().borders(Borders::RIGHT))
         .header(
             Row::new(vec![Cell::from(Text::styled(
-                match self.section {
-                    LayoutSections::Search { results, .. }
-                    | LayoutSections::SearchLocked { results, .. } => {
-                        format!("/ {}", results.query())
-                    }
-                    _ => TASK_HEADER.to_string(),
-                },
+                self.header(),
                 Style::default().add_modifier(Modifier::DIM),
             ))])
             .height(1),
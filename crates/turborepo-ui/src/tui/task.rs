@@ -201,8 +201,8 @@ impl TasksByStatus {
     }
 
     /// Insert a finished task into the correct place in the finished section.
-    /// The order of `finished` is expected to be: failure, success, cached
-    /// with each subsection being sorted by finish time.
+    /// The order of `finished` is expected to be: failure, success, stopped,
+    /// cached with each subsection being sorted by finish time.
     /// Returns the index task was inserted at
     pub fn insert_finished_task(&mut self, task: Task<Finished>) -> usize {
         let index = match task.result() {
@@ -222,6 +222,13 @@ impl TasksByStatus {
                 })
                 .map(|(idx, _)| idx)
                 .next(),
+            TaskResult::Stopped => self
+                .finished
+                .iter()
+                .enumerate()
+                .skip_while(|(_, task)| task.result() != TaskResult::CacheHit)
+                .map(|(idx, _)| idx)
+                .next(),
             TaskResult::CacheHit => None,
         }
         .unwrap_or(self.finished.len());
@@ -309,6 +316,10 @@ mod test {
         .failed(&["fail"])
         .passed(&["passed"])
         .cached(&["cached"]);
+    const BASIC_STOPPED: TestCase = TestCase::new(TaskResult::Stopped, 2)
+        .failed(&["fail"])
+        .passed(&["passed"])
+        .cached(&["cached"]);
 
     #[test_case(EMPTY_FAIL)]
     #[test_case(EMPTY_PASS)]
@@ -316,6 +327,7 @@ mod test {
     #[test_case(BASIC_FAIL)]
     #[test_case(BASIC_PASS)]
     #[test_case(BASIC_CACHE)]
+    #[test_case(BASIC_STOPPED)]
     fn test_finished_task(test_case: TestCase) {
         let mut tasks = test_case.tasks();
         let actual = tasks.insert_finished_task(
@@ -19,6 +19,22 @@ pub struct TerminalOutput<W> {
     /// Pending selection start position (row, col) - set on mouse down, used on
     /// first drag
     selection_start: Option<(u16, u16)>,
+    /// Number of scrollback rows already handed out by `search_update`
+    searched_rows: usize,
+}
+
+/// Output to search since the last call to [`TerminalOutput::search_update`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutputUpdate {
+    /// Rows removed from the top of the scrollback since the last update.
+    /// `None` if earlier rows may have moved, in which case they are all
+    /// included again and previous results should be discarded.
+    pub pruned: Option<usize>,
+    /// Rows that moved into the scrollback since the last update. These won't
+    /// change again.
+    pub history: Vec<String>,
+    /// Rows of the active screen, which can still be rewritten by output
+    pub active: Vec<String>,
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -40,6 +56,7 @@ impl<W> TerminalOutput<W> {
             cache_result: None,
             scrollback_len,
             selection_start: None,
+            searched_rows: 0,
         }
     }
 
@@ -63,6 +80,8 @@ impl<W> TerminalOutput<W> {
     pub fn resize(&mut self, rows: u16, cols: u16) {
         if self.size() != (rows, cols) {
             let _ = self.parser.resize(rows, cols);
+            // Reflowing moves rows around so searches need to start over
+            self.parser.clear_row_mark();
         }
     }
 
@@ -86,6 +105,51 @@ impl<W> TerminalOutput<W> {
         Ok(())
     }
 
+    /// Plain text of the output written since the last call, for searching
+    /// output incrementally. Rows are counted from the top of the scrollback.
+    ///
+    /// With `restart`, or if earlier rows can no longer be located, all output
+    /// is returned.
+    pub fn search_update(&mut self, restart: bool) -> Result<OutputUpdate, Error> {
+        // The last row handed out is marked so we can tell how far the
+        // scrollback was pruned since
+        let pruned = if restart {
+            None
+        } else if self.searched_rows == 0 {
+            Some(0)
+        } else {
+            self.parser
+                .marked_row()?
+                .and_then(|row| (self.searched_rows - 1).checked_sub(row))
+        };
+        let start = match pruned {
+            Some(pruned) => self.searched_rows - pruned,
+            None => 0,
+        };
+
+        let history_rows = self.parser.scrollback_rows()?;
+        let total_rows = self.parser.total_rows()?;
+        let history = self.parser.screen_lines_range(start..history_rows)?;
+        let active = self.parser.screen_lines_range(history_rows..total_rows)?;
+
+        self.searched_rows = history_rows.max(start);
+        if self.searched_rows > 0 {
+            self.parser.mark_row(self.searched_rows - 1)?;
+        }
+
+        Ok(OutputUpdate {
+            pruned,
+            history,
+            active,
+        })
+    }
+
+    /// Scroll so that the given line of output is at the top of the pane
+    pub fn scroll_to_line(&mut self, line: usize) -> Result<(), Error> {
+        self.parser.scroll_to_row(line)?;
+        Ok(())
+    }
+
     fn persist_behavior(&self) -> LogBehavior {
         match self.output_logs.unwrap_or(OutputLogs::Full) {
             OutputLogs::Full => LogBehavior::Full,
@@ -17,8 +17,9 @@ use futures_util::StreamExt;
 use serde::{Deserialize, Serialize};
 use tokio::{net::TcpListener, sync::Mutex};
 use turborepo_vercel_api::{
-    AnalyticsEvent, CachingStatus, CachingStatusResponse, Membership, Role, Team, TeamsResponse,
-    User, UserResponse, telemetry::TelemetryEvent,
+    AnalyticsEvent, ArtifactInfo, ArtifactQueryRequest, ArtifactQueryResponse, ArtifactQueryResult,
+    CachingStatus, CachingStatusResponse, Membership, Role, Team, TeamsResponse, User,
+    UserResponse, telemetry::TelemetryEvent,
 };
 
 pub const EXPECTED_TOKEN: &str = "expected_token";
@@ -58,12 +59,15 @@ pub async fn start_test_server(
     let get_durations_ref = Arc::new(Mutex::new(HashMap::new()));
     let head_durations_ref = get_durations_ref.clone();
     let put_durations_ref = get_durations_ref.clone();
+    let query_durations_ref = get_durations_ref.clone();
 
     let get_metadata_ref: Arc<Mutex<ArtifactScmMetadata>> = Arc::new(Mutex::new(HashMap::new()));
     let head_metadata_ref = get_metadata_ref.clone();
     let put_metadata_ref = get_metadata_ref.clone();
+    let query_metadata_ref = get_metadata_ref.clone();
     let put_tempdir_ref = Arc::new(tempfile::tempdir()?);
     let get_tempdir_ref = put_tempdir_ref.clone();
+    let query_tempdir_ref = put_tempdir_ref.clone();
 
     let get_analytics_events_ref = Arc::new(Mutex::new(Vec::new()));
     let post_analytics_events_ref = get_analytics_events_ref.clone();
@@ -114,6 +118,7 @@ pub async fn start_test_server(
             get(|| async {
                 Json(CachingStatusResponse {
                     status: CachingStatus::Enabled,
+                    artifact_query: true,
                 })
             }),
         )
@@ -285,6 +290,34 @@ pub async fn start_test_server(
                 (StatusCode::OK, headers)
             }),
         )
+        .route(
+            "/v8/artifacts",
+            post(
+                |Json(ArtifactQueryRequest { hashes }): Json<ArtifactQueryRequest>| async move {
+                    let durations = query_durations_ref.lock().await;
+                    let metadata = query_metadata_ref.lock().await;
+                    let response: ArtifactQueryResponse = hashes
+                        .into_iter()
+                        .map(|hash| {
+                            let info = durations.get(&hash).map(|duration| {
+                                let (sha, dirty_hash) =
+                                    metadata.get(&hash).cloned().unwrap_or_default();
+                                ArtifactQueryResult::Found(ArtifactInfo {
+                                    size: std::fs::metadata(query_tempdir_ref.path().join(&hash))
+                                        .map_or(0, |m| m.len()),
+                                    task_duration_ms: (*duration).into(),
+                                    tag: None,
+                                    sha,
+                                    dirty_hash,
+                                })
+                            });
+                            (hash, info)
+                        })
+                        .collect();
+                    Json(response)
+                },
+            ),
+        )
         .route(
             "/v8/artifacts/events",
             post(
@@ -1,6 +1,8 @@
 //! Types for interacting with the Vercel API. Used for both
 //! the client (`turborepo-api-client`) and for the
 //! mock server (`turborepo-vercel-api-mock`)
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use turborepo_types::SecretString;
 use url::Url;
@@ -32,6 +34,10 @@ pub enum CachingStatus {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CachingStatusResponse {
     pub status: CachingStatus,
+    /// Whether the server supports querying many artifacts with a single
+    /// `POST /v8/artifacts` request
+    #[serde(default, rename = "artifactQuery")]
+    pub artifact_query: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +47,43 @@ pub struct ArtifactResponse {
     pub body: Vec<u8>,
 }
 
+/// The body of a `POST /v8/artifacts` request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactQueryRequest {
+    pub hashes: Vec<String>,
+}
+
+/// The response to a `POST /v8/artifacts` request, keyed by hash. Artifacts
+/// that don't exist map to `null`.
+pub type ArtifactQueryResponse = HashMap<String, Option<ArtifactQueryResult>>;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ArtifactQueryResult {
+    Found(ArtifactInfo),
+    Error { error: ArtifactQueryError },
+}
+
+/// The same information a `HEAD /v8/artifacts/{hash}` request returns in
+/// its headers
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ArtifactInfo {
+    pub size: u64,
+    pub task_duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dirty_hash: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArtifactQueryError {
+    pub message: String,
+}
+
 /// Membership is the relationship between the logged-in user and a particular
 /// team
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -153,7 +196,10 @@ mod tests {
     use test_case::test_case;
     use turborepo_types::SecretString;
 
-    use crate::{AnalyticsEvent, CacheEvent, CacheSource, VerificationResponse, VerifiedSsoUser};
+    use crate::{
+        AnalyticsEvent, ArtifactInfo, ArtifactQueryResponse, ArtifactQueryResult, CacheEvent,
+        CacheSource, CachingStatusResponse, VerificationResponse, VerifiedSsoUser,
+    };
 
     #[test]
     fn verified_sso_user_debug_redacts_token() {
@@ -219,4 +265,38 @@ mod tests {
         let json = serde_json::to_string(&event).unwrap();
         insta::assert_json_snapshot!(name, json);
     }
+
+    #[test]
+    fn test_deserialize_artifact_query_response() {
+        let response: ArtifactQueryResponse = serde_json::from_str(
+            r#"{
+                "found": { "size": 10, "taskDurationMs": 58, "sha": "abc" },
+                "missing": null,
+                "failed": { "error": { "message": "artifact is being uploaded" } }
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            &response["found"],
+            Some(ArtifactQueryResult::Found(ArtifactInfo {
+                size: 10,
+                task_duration_ms: 58,
+                sha: Some(sha),
+                ..
+            })) if sha == "abc"
+        ));
+        assert!(response["missing"].is_none());
+        assert!(matches!(
+            &response["failed"],
+            Some(ArtifactQueryResult::Error { .. })
+        ));
+    }
+
+    #[test]
+    fn test_caching_status_without_artifact_query() {
+        let response: CachingStatusResponse =
+            serde_json::from_str(r#"{ "status": "enabled" }"#).unwrap();
+        assert!(!response.artifact_query);
+    }
 }
@@ -65,6 +65,20 @@ impl turborepo_query_api::QueryServer for TurboQueryServer {
                 .map_err(Into::into)
         })
     }
+
+    fn serve_run(
+        &self,
+        run: Arc<dyn turborepo_query_api::QueryRun>,
+        signal: turborepo_signals::SignalHandler,
+        events: turborepo_query_api::RunEventSender,
+        listener: std::net::TcpListener,
+    ) -> Pin<Box<dyn Future<Output = Result<(), turborepo_query_api::Error>> + Send + '_>> {
+        Box::pin(async move {
+            turborepo_query::serve_run(run, signal, events, listener)
+                .await
+                .map_err(Into::into)
+        })
+    }
 }
 
 // This function should not expanded. Please add any logic to
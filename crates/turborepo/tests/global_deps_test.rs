@@ -53,3 +53,45 @@ fn test_global_deps_change_causes_cache_miss() {
         "expected cache hit after non-global-dep change, got: {stdout3}"
     );
 }
+
+#[test]
+fn test_undeclared_inputs_allows_reading_global_deps() {
+    // Reads are only traced on Linux with strace installed
+    if !cfg!(target_os = "linux")
+        || std::process::Command::new("strace")
+            .arg("-V")
+            .output()
+            .is_err()
+    {
+        return;
+    }
+
+    let tempdir = tempfile::tempdir().unwrap();
+    setup::setup_integration_test(tempdir.path(), "global_deps", "npm@10.5.0", false).unwrap();
+    fs::write(
+        tempdir.path().join("apps/my-app/package.json"),
+        r#"{
+  "name": "my-app",
+  "scripts": {
+    "build": "cat ../../global_deps/foo.txt"
+  },
+  "dependencies": {
+    "util": "*"
+  }
+}
+"#,
+    )
+    .unwrap();
+
+    // Global dependencies are declared for every task without --explain-miss
+    let output = run_turbo(
+        tempdir.path(),
+        &["build", "-F", "my-app", "--undeclared-inputs=error"],
+    );
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(
+        !stdout.contains("global_deps/foo.txt") && !stderr.contains("global_deps/foo.txt"),
+        "expected global dependency to be declared, got:\n{stdout}\n{stderr}"
+    );
+}
@@ -177,6 +177,18 @@ export interface RootSchema extends BaseSchema {
    */
   cacheMaxSize?: string;
 
+  /**
+   * Store identical files in the local cache only once.
+   *
+   * When enabled, the contents of cached outputs are shared between
+   * artifacts and unreferenced contents are removed during eviction.
+   *
+   * Documentation: https://turborepo.dev/docs/reference/configuration#cachededuplication
+   *
+   * @defaultValue `false`
+   */
+  cacheDeduplication?: boolean;
+
   /**
    * Deprecated: The daemon is no longer used for `turbo run` and this option will be removed in version 3.0.
    *
@@ -303,6 +315,15 @@ export interface FutureFlags {
    * @defaultValue `false`
    */
   globalConfiguration?: boolean;
+  /**
+   * Run package.json scripts through the shell instead of the package
+   * manager. `node_modules/.bin` directories are added to `PATH`, npm
+   * lifecycle environment variables are set, and `pre` and `post` scripts
+   * run when the package manager would run them.
+   *
+   * @defaultValue `false`
+   */
+  directScriptExecution?: boolean;
 }
 
 export interface GlobalConfig {
@@ -403,6 +424,18 @@ export interface GlobalConfig {
    */
   cacheMaxSize?: string;
 
+  /**
+   * Store identical files in the local cache only once.
+   *
+   * When enabled, the contents of cached outputs are shared between
+   * artifacts and unreferenced contents are removed during eviction.
+   *
+   * Documentation: https://turborepo.dev/docs/reference/configuration#cachededuplication
+   *
+   * @defaultValue `false`
+   */
+  cacheDeduplication?: boolean;
+
   /**
    * Deprecated: The daemon is no longer used for `turbo run`.
    *
@@ -454,6 +487,19 @@ export interface Pipeline {
    */
   description?: string;
 
+  /**
+   * The command to run for this task, instead of the package.json script
+   * with the same name.
+   *
+   * A string is run by the system shell, while an array is run as a program
+   * and its arguments without a shell. `$TURBO_PACKAGE_DIR`,
+   * `$TURBO_PACKAGE_NAME` and `$TURBO_ROOT` are replaced before the command
+   * runs. The command is part of the task's hash.
+   *
+   * Documentation: https://turborepo.dev/docs/reference/configuration#command
+   */
+  command?: string | Array<string>;
+
   /**
    * The list of tasks that this task depends on.
    *
@@ -592,6 +638,27 @@ export interface Pipeline {
    */
   interruptible?: boolean;
 
+  /**
+   * The maximum amount of time the task is allowed to run, as a duration
+   * string (e.g. "30s", "15m", "1h"). When the timeout elapses, turbo stops
+   * the task and marks it as failed. Set to "0" to disable the timeout.
+   *
+   * Documentation: https://turborepo.dev/docs/reference/configuration#timeout
+   *
+   * @defaultValue `"0"`
+   */
+  timeout?: string;
+
+  /**
+   * The number of times to retry the task if it fails. Only the final,
+   * successful attempt is written to the cache.
+   *
+   * Documentation: https://turborepo.dev/docs/reference/configuration#retries
+   *
+   * @defaultValue `0`
+   */
+  retries?: number;
+
   /**
    * A list of tasks that will run alongside this task.
    *
@@ -615,6 +682,16 @@ export interface RemoteCache {
    */
   signature?: boolean;
 
+  /**
+   * An Ed25519 public key used to verify artifacts when `signature` is enabled. When set,
+   * artifacts are signed with the private key in the environment variable
+   * `TURBO_REMOTE_CACHE_PRIVATE_KEY` instead of the shared `TURBO_REMOTE_CACHE_SIGNATURE_KEY`.
+   * Machines without the private key can verify artifacts but don't upload them.
+   *
+   * Documentation: https://turborepo.dev/docs/reference/configuration#publickey
+   */
+  publicKey?: string;
+
   /**
    * Indicates if the remote cache is enabled. When `false`, Turborepo will disable
    * all remote cache operations, even if the repo has a valid token. If true, remote caching
@@ -710,6 +787,11 @@ export interface BoundariesConfig {
    * These can include dependencies automatically injected by a framework or a testing library.
    */
   implicitDependencies?: Array<string>;
+  /**
+   * Reports packages in `dependencies` that no file in the package imports.
+   * When set in the root turbo.json, applies to every package that doesn't set it.
+   */
+  unusedDependencies?: boolean;
 }
 
 export interface RootBoundariesConfig extends BoundariesConfig {